@@ -0,0 +1,337 @@
+/*!
+Configuration Space Access
+
+[ConfigSpaceAccess] abstracts the source of configuration space: sysfs files, ECAM windows,
+hypervisor trap handlers or in-memory mocks. [ConfigSpaceReader] feeds [Header],
+[Capabilities] and [ExtendedCapabilities] from any such source, reading only regions that are
+actually requested.
+
+## Example
+```rust
+# use pcics::access::{ConfigSpaceAccess, ConfigSpaceReader, InMemory};
+let data =
+    include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/device/8086_2030/config"
+    ));
+let mut access = InMemory(*data);
+assert_eq!(0x8086, access.read_u16(0x00).unwrap());
+access.write_u16(0x04, 0x0000).unwrap();
+
+let mut reader = ConfigSpaceReader::new(access);
+let header = reader.header().unwrap();
+assert_eq!((0x8086, 0x2030), (header.vendor_id, header.device_id));
+assert_eq!(4, reader.capabilities().unwrap().count());
+assert_eq!(8, reader.extended_capabilities().unwrap().count());
+```
+*/
+
+use byte::{
+    ctx::*,
+    self,
+    BytesExt,
+};
+
+use super::{
+    DDR_OFFSET, ECS_OFFSET, Error,
+    builder::CONFIG_SPACE_LENGTH,
+    header::{Header, HEADER_TYPE_OFFSET},
+    capabilities::Capabilities,
+    extended_capabilities::ExtendedCapabilities,
+};
+
+
+/// Sized reads and writes of configuration space registers
+///
+/// Only byte access is mandatory, word and dword accessors are composed of byte accesses in
+/// little-endian order unless the implementation overrides them. Composed accessors fail with
+/// [Error::Truncated] if the register does not fit below offset FFFFh.
+pub trait ConfigSpaceAccess {
+    type Error: From<Error>;
+    /// Accessible configuration space size: 256 bytes for PCI and 4096 for PCI Express
+    fn size(&self) -> usize;
+    fn read_u8(&self, offset: u16) -> Result<u8, Self::Error>;
+    fn write_u8(&mut self, offset: u16, value: u8) -> Result<(), Self::Error>;
+    fn read_u16(&self, offset: u16) -> Result<u16, Self::Error> {
+        Ok(u16::from_le_bytes([
+            self.read_u8(offset)?,
+            self.read_u8(byte_offset(offset, 1)?)?,
+        ]))
+    }
+    fn read_u32(&self, offset: u16) -> Result<u32, Self::Error> {
+        Ok(u32::from_le_bytes([
+            self.read_u8(offset)?,
+            self.read_u8(byte_offset(offset, 1)?)?,
+            self.read_u8(byte_offset(offset, 2)?)?,
+            self.read_u8(byte_offset(offset, 3)?)?,
+        ]))
+    }
+    fn write_u16(&mut self, offset: u16, value: u16) -> Result<(), Self::Error> {
+        byte_offset(offset, 1)?;
+        for (n, byte) in (0..).zip(value.to_le_bytes()) {
+            self.write_u8(offset + n, byte)?;
+        }
+        Ok(())
+    }
+    fn write_u32(&mut self, offset: u16, value: u32) -> Result<(), Self::Error> {
+        byte_offset(offset, 3)?;
+        for (n, byte) in (0..).zip(value.to_le_bytes()) {
+            self.write_u8(offset + n, byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Offset of `n`-th byte of the register at `offset`
+fn byte_offset(offset: u16, n: u16) -> Result<u16, Error> {
+    offset.checked_add(n).ok_or(Error::Truncated { offset })
+}
+
+
+/// In-memory configuration space
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InMemory<T>(pub T);
+impl<T: AsRef<[u8]> + AsMut<[u8]>> ConfigSpaceAccess for InMemory<T> {
+    type Error = byte::Error;
+    fn size(&self) -> usize {
+        self.0.as_ref().len().min(CONFIG_SPACE_LENGTH)
+    }
+    fn read_u8(&self, offset: u16) -> Result<u8, Self::Error> {
+        self.0.as_ref().read_with(&mut offset.into(), LE)
+    }
+    fn write_u8(&mut self, offset: u16, value: u8) -> Result<(), Self::Error> {
+        self.0.as_mut().write_with(&mut offset.into(), value, LE)
+    }
+    fn read_u16(&self, offset: u16) -> Result<u16, Self::Error> {
+        self.0.as_ref().read_with(&mut offset.into(), LE)
+    }
+    fn read_u32(&self, offset: u16) -> Result<u32, Self::Error> {
+        self.0.as_ref().read_with(&mut offset.into(), LE)
+    }
+    fn write_u16(&mut self, offset: u16, value: u16) -> Result<(), Self::Error> {
+        self.0.as_mut().write_with(&mut offset.into(), value, LE)
+    }
+    fn write_u32(&mut self, offset: u16, value: u32) -> Result<(), Self::Error> {
+        self.0.as_mut().write_with(&mut offset.into(), value, LE)
+    }
+}
+
+
+/// Errors of reading configuration space through [ConfigSpaceAccess]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReadError<E> {
+    /// Underlying access failed
+    Access(E),
+    /// Header could not be parsed
+    Header(Error),
+}
+
+/// Lazily reads configuration space regions from [ConfigSpaceAccess] and parses them
+#[derive(Debug, Clone)]
+pub struct ConfigSpaceReader<A> {
+    access: A,
+    data: [u8; CONFIG_SPACE_LENGTH],
+    /// Number of bytes already read from the start of configuration space
+    read: usize,
+}
+impl<A: ConfigSpaceAccess> ConfigSpaceReader<A> {
+    pub fn new(access: A) -> Self {
+        Self { access, data: [0; CONFIG_SPACE_LENGTH], read: 0 }
+    }
+    /// Underlying access
+    pub fn access(&self) -> &A {
+        &self.access
+    }
+    pub fn into_inner(self) -> A {
+        self.access
+    }
+    /// Drops all previously read data, so next parsing reads registers again
+    pub fn invalidate(&mut self) {
+        self.read = 0;
+    }
+    pub fn header(&mut self) -> Result<Header, ReadError<A::Error>> {
+        let end = self.fill(HEADER_TYPE_OFFSET + 1)?;
+        let end = match self.data[..end].get(HEADER_TYPE_OFFSET) {
+            Some(&header_type) => self.fill(Header::length(header_type))?,
+            None => end,
+        };
+        Header::try_from(&self.data[..end]).map_err(ReadError::Header)
+    }
+    /// Registers are read as the list is followed, a capability beyond accessible configuration
+    /// space ends the list with [Error::Truncated]
+    pub fn capabilities(&mut self) -> Result<Capabilities<'_>, ReadError<A::Error>> {
+        let header = self.header()?;
+        let limit = self.access.size().min(ECS_OFFSET);
+        loop {
+            let end = self.read.min(ECS_OFFSET);
+            let truncated = self.capabilities_in(&header, end).checked()
+                .any(|cap| matches!(cap, Err(Error::Truncated { .. })));
+            if !truncated || end >= limit {
+                return Ok(self.capabilities_in(&header, end));
+            }
+            self.fill(end + 4)?;
+        }
+    }
+    fn capabilities_in(&self, header: &Header, end: usize) -> Capabilities<'_> {
+        Capabilities::new(&self.data[DDR_OFFSET..end], header.capabilities_pointer)
+            .with_header_type(&header.header_type)
+    }
+    /// Registers are read as the list is followed, like [capabilities](Self::capabilities).
+    /// Empty iterator if the accessible configuration space has no extended region
+    pub fn extended_capabilities(&mut self) -> Result<ExtendedCapabilities<'_>, ReadError<A::Error>> {
+        let limit = self.access.size().min(CONFIG_SPACE_LENGTH);
+        self.fill(ECS_OFFSET + 4)?;
+        loop {
+            let end = self.read;
+            let truncated = self.extended_capabilities_in(end).checked()
+                .any(|ecap| matches!(ecap, Err(Error::Truncated { .. })));
+            if !truncated || end >= limit {
+                return Ok(self.extended_capabilities_in(end));
+            }
+            self.fill(end + 4)?;
+        }
+    }
+    fn extended_capabilities_in(&self, end: usize) -> ExtendedCapabilities<'_> {
+        ExtendedCapabilities::new(self.data.get(ECS_OFFSET..end).unwrap_or_default())
+    }
+    /// Reads configuration space up to `end` offset, but not above accessible size. Returns
+    /// the end offset of read data.
+    fn fill(&mut self, end: usize) -> Result<usize, ReadError<A::Error>> {
+        let end = end.min(self.access.size()).min(CONFIG_SPACE_LENGTH);
+        while self.read < end {
+            let offset = self.read;
+            if end - offset >= 4 {
+                let dword = self.access.read_u32(offset as u16).map_err(ReadError::Access)?;
+                self.data[offset..offset + 4].copy_from_slice(&dword.to_le_bytes());
+                self.read += 4;
+            } else {
+                self.data[offset] = self.access.read_u8(offset as u16).map_err(ReadError::Access)?;
+                self.read += 1;
+            }
+        }
+        Ok(end)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use core::cell::Cell;
+    use pretty_assertions::assert_eq;
+    use super::*;
+
+    const DATA: &[u8; CONFIG_SPACE_LENGTH] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/device/8086_2030/config"
+    ));
+
+    /// Counts byte reads and implements only mandatory byte access
+    struct Counting<'a> {
+        data: &'a [u8],
+        reads: Cell<usize>,
+    }
+    impl<'a> ConfigSpaceAccess for Counting<'a> {
+        type Error = Error;
+        fn size(&self) -> usize { CONFIG_SPACE_LENGTH }
+        fn read_u8(&self, offset: u16) -> Result<u8, Self::Error> {
+            self.reads.set(self.reads.get() + 1);
+            self.data.get(usize::from(offset)).copied().ok_or(Error::Truncated { offset })
+        }
+        fn write_u8(&mut self, offset: u16, _: u8) -> Result<(), Self::Error> {
+            Err(Error::Truncated { offset })
+        }
+    }
+
+    #[test]
+    fn in_memory_sized_access() {
+        let mut access = InMemory([0u8; 8]);
+        access.write_u32(0, 0x11223344).unwrap();
+        access.write_u16(4, 0x5566).unwrap();
+        access.write_u8(6, 0x77).unwrap();
+        assert_eq!([0x44,0x33,0x22,0x11,0x66,0x55,0x77,0x00], access.0);
+        assert_eq!(0x3344, access.read_u16(0).unwrap());
+        assert_eq!(0x00775566, access.read_u32(4).unwrap());
+        assert!(access.read_u32(6).is_err());
+        assert!(access.write_u8(8, 0).is_err());
+    }
+
+    #[test]
+    fn default_sized_access() {
+        let access = Counting { data: &DATA[..], reads: Cell::new(0) };
+        assert_eq!(0x20308086, access.read_u32(0).unwrap());
+        assert_eq!(0x2030, access.read_u16(2).unwrap());
+        assert_eq!(6, access.reads.get());
+        assert_eq!(Err(Error::Truncated { offset: 0xfffe }), access.read_u32(0xfffe));
+        assert_eq!(Err(Error::Truncated { offset: 0xffff }), access.read_u16(0xffff));
+        let mut access = access;
+        assert_eq!(Err(Error::Truncated { offset: 0xfffd }), access.write_u32(0xfffd, 0));
+    }
+
+    #[test]
+    fn reads_lazily() {
+        let access = Counting { data: &DATA[..], reads: Cell::new(0) };
+        let mut reader = ConfigSpaceReader::new(access);
+        let header = reader.header().unwrap();
+        assert_eq!(DDR_OFFSET, reader.access().reads.get());
+        assert_eq!(Header::try_from(&DATA[..DDR_OFFSET]).unwrap(), header);
+
+        let result = reader.capabilities().unwrap().collect::<Vec<_>>();
+        let sample = Capabilities::new(&DATA[DDR_OFFSET..ECS_OFFSET], header.capabilities_pointer)
+            .collect::<Vec<_>>();
+        assert_eq!(sample, result);
+        // Registers are read up to the end of Power Management capability at E0h
+        assert_eq!(0xe8, reader.access().reads.get());
+
+        let result = reader.extended_capabilities().unwrap()
+            .map(|ecap| (ecap.offset, ecap.id()))
+            .collect::<Vec<_>>();
+        let sample = ExtendedCapabilities::new(&DATA[ECS_OFFSET..])
+            .map(|ecap| (ecap.offset, ecap.id()))
+            .collect::<Vec<_>>();
+        assert_eq!(sample, result);
+        // Up to the end of the last Vendor-Specific Extended Capability at 300h
+        assert_eq!(0x338, reader.access().reads.get());
+    }
+
+    #[test]
+    fn conventional_config_space() {
+        let mut reader = ConfigSpaceReader::new(InMemory(DATA[..ECS_OFFSET].to_vec()));
+        assert_eq!(4, reader.capabilities().unwrap().count());
+        assert_eq!(0, reader.extended_capabilities().unwrap().count());
+    }
+
+    #[test]
+    fn truncated_config_space() {
+        let mut reader = ConfigSpaceReader::new(InMemory(DATA[..0x48].to_vec()));
+        assert_eq!(0x8086, reader.header().unwrap().vendor_id);
+        let result = reader.capabilities().unwrap().checked()
+            .map(|cap| cap.map(|cap| (cap.pointer, cap.kind.id())))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![Ok((0x40, 0x0d)), Err(Error::Truncated { offset: 0x60 })], result);
+        assert_eq!(0, reader.extended_capabilities().unwrap().count());
+    }
+
+    #[test]
+    fn cardbus_header() {
+        let mut data = DATA[..0x48].to_vec();
+        data[HEADER_TYPE_OFFSET] = 0x02;
+        let access = Counting { data: &data, reads: Cell::new(0) };
+        let mut reader = ConfigSpaceReader::new(access);
+        let header = reader.header().unwrap();
+        assert!(matches!(header.header_type, crate::header::HeaderType::Cardbus(_)));
+        assert_eq!(0x48, reader.access().reads.get());
+        let mut reader = ConfigSpaceReader::new(InMemory(data[..0x44].to_vec()));
+        assert_eq!(Err(ReadError::Header(Error::Truncated { offset: 0 })), reader.header());
+    }
+
+    #[test]
+    fn access_error() {
+        let mut reader = ConfigSpaceReader::new(InMemory(DATA[..0x20].to_vec()));
+        assert_eq!(Err(ReadError::Header(Error::Truncated { offset: 0 })), reader.header());
+        let access = Counting { data: &DATA[..0x20], reads: Cell::new(0) };
+        let mut reader = ConfigSpaceReader::new(access);
+        assert_eq!(Err(ReadError::Access(Error::Truncated { offset: 0x20 })), reader.header());
+    }
+}
@@ -0,0 +1,109 @@
+/*!
+PCI Function Address
+
+Address of a function in the PCI hierarchy in the usual `DDDD:BB:DD.F` notation, where the PCI
+domain (segment) part is optional.
+
+```rust
+# use pcics::address::Address;
+let address: Address = "0000:00:1f.3".parse().unwrap();
+assert_eq!(Address { domain: 0, bus: 0, device: 0x1f, function: 3 }, address);
+assert_eq!(address, "00:1f.3".parse().unwrap());
+assert_eq!("0000:00:1f.3", format!("{}", address));
+```
+*/
+
+use core::{fmt, str::FromStr};
+
+use displaydoc::Display as DisplayDoc;
+
+
+/// Function address in `DDDD:BB:DD.F` notation
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Address {
+    /// PCI domain (segment group)
+    pub domain: u16,
+    pub bus: u8,
+    /// 5-bit device number
+    pub device: u8,
+    /// 3-bit function number
+    pub function: u8,
+}
+impl Address {
+    pub const DEVICE_MAX: u8 = 0x1f;
+    pub const FUNCTION_MAX: u8 = 0x07;
+}
+impl FromStr for Address {
+    type Err = ParseAddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (dbd, function) = s.rsplit_once('.').ok_or(ParseAddressError::Format)?;
+        let mut parts = dbd.rsplitn(3, ':');
+        let device = parts.next().ok_or(ParseAddressError::Format)?;
+        let bus = parts.next().ok_or(ParseAddressError::Format)?;
+        let domain = parts.next();
+        let hex = |s: &str, len: usize| {
+            if s.is_empty() || s.len() > len {
+                return Err(ParseAddressError::Format);
+            }
+            u16::from_str_radix(s, 16).map_err(|_| ParseAddressError::Format)
+        };
+        let address = Self {
+            domain: domain.map(|d| hex(d, 4)).transpose()?.unwrap_or(0),
+            bus: hex(bus, 2)? as u8,
+            device: hex(device, 2)? as u8,
+            function: hex(function, 1)? as u8,
+        };
+        if address.device > Self::DEVICE_MAX {
+            Err(ParseAddressError::Device)
+        } else if address.function > Self::FUNCTION_MAX {
+            Err(ParseAddressError::Function)
+        } else {
+            Ok(address)
+        }
+    }
+}
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04x}:{:02x}:{:02x}.{:x}", self.domain, self.bus, self.device, self.function)
+    }
+}
+
+/// [Address] parsing errors
+#[derive(DisplayDoc, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseAddressError {
+    /// address is not in [DDDD:]BB:DD.F format
+    Format,
+    /// device number is greater than 1fh
+    Device,
+    /// function number is greater than 7
+    Function,
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use pretty_assertions::assert_eq;
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let sample = Address { domain: 0x10, bus: 0xab, device: 0x1f, function: 7 };
+        assert_eq!(Ok(sample), "0010:ab:1f.7".parse());
+        assert_eq!(Ok(Address { domain: 0, ..sample }), "ab:1f.7".parse());
+        assert_eq!(Err(ParseAddressError::Format), "1f.7".parse::<Address>());
+        assert_eq!(Err(ParseAddressError::Format), "00:00:00:00.0".parse::<Address>());
+        assert_eq!(Err(ParseAddressError::Format), "00:00.g".parse::<Address>());
+        assert_eq!(Err(ParseAddressError::Device), "00:20.0".parse::<Address>());
+        assert_eq!(Err(ParseAddressError::Function), "00:00.8".parse::<Address>());
+    }
+
+    #[test]
+    fn display() {
+        let address = Address { domain: 0x10, bus: 0xab, device: 0x1f, function: 7 };
+        assert_eq!("0010:ab:1f.7", address.to_string());
+    }
+}
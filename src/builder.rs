@@ -0,0 +1,251 @@
+/*!
+Configuration Space Builder
+
+Lays out [Header], capabilities and extended capabilities into a complete 4096-byte
+configuration space image. Capabilities are placed one after another in the Device dependent
+region starting at [DDR_OFFSET](crate::DDR_OFFSET), extended capabilities are placed in the
+Extended configuration space starting at [ECS_OFFSET](crate::ECS_OFFSET). All structures are
+DWORD aligned and linked through their next pointers in the given order.
+
+## Example
+```rust
+# use pcics::{
+#     builder::ConfigSpaceBuilder,
+#     capabilities::{CapabilityKind, BridgeSubsystemVendorId},
+#     extended_capabilities::{ExtendedCapability, ExtendedCapabilityKind, DeviceSerialNumber},
+#     Capabilities, ExtendedCapabilities, Header, DDR_OFFSET, ECS_OFFSET,
+# };
+let conf_space =
+    include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/device/8086_2030/config"
+    ));
+let header = Header::try_from(&conf_space[..DDR_OFFSET]).unwrap();
+let caps = [
+    CapabilityKind::BridgeSubsystemVendorId(BridgeSubsystemVendorId {
+        reserved: 0,
+        subsystem_vendor_id: 0x8086,
+        subsystem_id: 0x0000,
+    }),
+];
+let ecaps = [
+    ExtendedCapability {
+        kind: ExtendedCapabilityKind::DeviceSerialNumber(DeviceSerialNumber {
+            lower_dword: 0x88776655,
+            upper_dword: 0x44332211,
+        }),
+        version: 1,
+        offset: 0,
+    },
+];
+let image = ConfigSpaceBuilder::new(header)
+    .capabilities(&caps)
+    .extended_capabilities(&ecaps)
+    .build()
+    .unwrap();
+
+let header = Header::try_from(&image[..DDR_OFFSET]).unwrap();
+assert!(header.status.capabilities_list);
+assert_eq!(0x40, header.capabilities_pointer);
+let mut caps = Capabilities::new(&image[DDR_OFFSET..ECS_OFFSET], header.capabilities_pointer);
+assert_eq!(Some(0x0d), caps.next().map(|cap| cap.kind.id()));
+let mut ecaps = ExtendedCapabilities::new(&image[ECS_OFFSET..]);
+assert_eq!(Some((0x100, 0x0003)), ecaps.next().map(|ecap| (ecap.offset, ecap.id())));
+```
+*/
+
+use byte::{
+    ctx::*,
+    self,
+    TryWrite,
+    BytesExt,
+};
+
+use super::{
+    DDR_OFFSET, ECS_OFFSET,
+    header::Header,
+    capabilities::CapabilityKind,
+    extended_capabilities::{ExtendedCapability, ExtendedCapabilityWriteCtx, ECH_BYTES},
+};
+
+/// Configuration space size
+pub const CONFIG_SPACE_LENGTH: usize = 4096;
+/// Capability Header (Capability ID and Next Capability Pointer) size
+const CH_BYTES: usize = 2;
+/// Capabilities and extended capabilities are DWORD aligned
+const ALIGNMENT: usize = 4;
+
+
+/// Builds 4096-byte configuration space image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigSpaceBuilder<'a, 'b> {
+    header: Header,
+    capabilities: &'b [CapabilityKind<'a>],
+    extended_capabilities: &'b [ExtendedCapability<'a>],
+}
+impl<'a, 'b> ConfigSpaceBuilder<'a, 'b> {
+    pub fn new(header: Header) -> Self {
+        Self { header, capabilities: &[], extended_capabilities: &[] }
+    }
+    /// Capabilities in the order they will be linked
+    pub fn capabilities(mut self, capabilities: &'b [CapabilityKind<'a>]) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+    /// Extended capabilities in the order they will be linked. The
+    /// [offset](ExtendedCapability::offset) field is ignored, offsets are allocated by the
+    /// builder.
+    pub fn extended_capabilities(mut self, extended_capabilities: &'b [ExtendedCapability<'a>]) -> Self {
+        self.extended_capabilities = extended_capabilities;
+        self
+    }
+    /// Lays out all structures. Status Capabilities List bit and Capabilities Pointer are
+    /// overridden according to capabilities presence.
+    pub fn build(self) -> byte::Result<[u8; CONFIG_SPACE_LENGTH]> {
+        let mut image = [0u8; CONFIG_SPACE_LENGTH];
+        let Self { mut header, capabilities, extended_capabilities } = self;
+
+        // Header size depends on Header Type, so calculate it before capabilities placing
+        let header_len = header.clone().try_write(&mut image, LE)?;
+        let ddr_start = header_len.max(DDR_OFFSET).next_multiple_of(ALIGNMENT);
+
+        header.status.capabilities_list = !capabilities.is_empty();
+        header.capabilities_pointer = if capabilities.is_empty() { 0 } else { ddr_start as u8 };
+        image.write_with(&mut 0, header, LE)?;
+
+        let ddr = &mut image[..ECS_OFFSET];
+        let mut pointer = ddr_start;
+        for (n, kind) in capabilities.iter().enumerate() {
+            let body = &mut pointer.clone();
+            *body += CH_BYTES;
+            ddr.write_with(body, kind.clone(), LE)?;
+            let next = if n + 1 < capabilities.len() {
+                body.next_multiple_of(ALIGNMENT)
+            } else {
+                0
+            };
+            let next = u8::try_from(next)
+                .map_err(|_| byte::Error::BadInput { err: "capabilities do not fit in DDR" })?;
+            ddr.write_with::<u8>(&mut pointer.clone(), kind.id(), LE)?;
+            ddr.write_with::<u8>(&mut (pointer + 1), next, LE)?;
+            pointer = next.into();
+        }
+
+        let ecs = &mut image[ECS_OFFSET..];
+        let mut offset = 0;
+        for (n, ecap) in extended_capabilities.iter().enumerate() {
+            let body = &mut (offset + ECH_BYTES);
+            ecs.write_with(body, ecap.kind.clone(), LE)?;
+            let next = if n + 1 < extended_capabilities.len() {
+                ECS_OFFSET + body.next_multiple_of(ALIGNMENT)
+            } else {
+                0
+            };
+            if next >= CONFIG_SPACE_LENGTH {
+                return Err(byte::Error::BadInput { err: "extended capabilities do not fit in ECS" });
+            }
+            let ctx = ExtendedCapabilityWriteCtx { endian: LE, next: next as u16 };
+            ecs.write_with(&mut offset.clone(), ecap.clone(), ctx)?;
+            offset = next.saturating_sub(ECS_OFFSET);
+        }
+
+        Ok(image)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use pretty_assertions::assert_eq;
+    use crate::{Capabilities, ExtendedCapabilities};
+    use super::*;
+
+    const DATA: &[u8; CONFIG_SPACE_LENGTH] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/device/8086_2030/config"
+    ));
+
+    #[test]
+    fn rebuild_device() {
+        let header = Header::try_from(&DATA[..DDR_OFFSET]).unwrap();
+        let caps = Capabilities::new(&DATA[DDR_OFFSET..ECS_OFFSET], header.capabilities_pointer)
+            .map(|cap| cap.kind)
+            .collect::<Vec<_>>();
+        let ecaps = ExtendedCapabilities::new(&DATA[ECS_OFFSET..]).collect::<Vec<_>>();
+
+        let image = ConfigSpaceBuilder::new(header.clone())
+            .capabilities(&caps)
+            .extended_capabilities(&ecaps)
+            .build()
+            .unwrap();
+
+        let result = Header::try_from(&image[..DDR_OFFSET]).unwrap();
+        let sample = Header { capabilities_pointer: 0x40, ..header };
+        assert_eq!(sample, result);
+
+        let result = Capabilities::new(&image[DDR_OFFSET..ECS_OFFSET], 0x40)
+            .map(|cap| (cap.pointer, cap.kind))
+            .collect::<Vec<_>>();
+        // 8086_2030 capabilities: SSVID (8 bytes), MSI (20 bytes), PCIe (60 bytes), PM (8 bytes)
+        let pointers = [0x40, 0x48, 0x5c, 0x98];
+        let sample = pointers.into_iter().zip(caps).collect::<Vec<_>>();
+        assert_eq!(sample, result);
+
+        let result = ExtendedCapabilities::new(&image[ECS_OFFSET..])
+            .map(|ecap| (ecap.offset, ecap.id(), ecap.version))
+            .collect::<Vec<_>>();
+        // VSEC (12 bytes), ACS (8 bytes), AER (72 bytes), VSEC (10 bytes), Secondary PCIe (12
+        // bytes), VSEC (24 bytes), VSEC (36 bytes), VSEC
+        let offsets = [0x100, 0x10c, 0x114, 0x15c, 0x168, 0x174, 0x18c, 0x1b0];
+        let sample = offsets.into_iter()
+            .zip(ecaps.iter())
+            .map(|(offset, ecap)| (offset, ecap.id(), ecap.version))
+            .collect::<Vec<_>>();
+        assert_eq!(sample, result);
+    }
+
+    #[test]
+    fn no_capabilities() {
+        let mut header = Header::try_from(&DATA[..DDR_OFFSET]).unwrap();
+        header.status.capabilities_list = true;
+        let image = ConfigSpaceBuilder::new(header).build().unwrap();
+        let result = Header::try_from(&image[..DDR_OFFSET]).unwrap();
+        assert_eq!((false, 0), (result.status.capabilities_list, result.capabilities_pointer));
+        assert_eq!([0u8; CONFIG_SPACE_LENGTH - DDR_OFFSET], image[DDR_OFFSET..]);
+    }
+
+    #[test]
+    fn capabilities_overflow() {
+        let header = Header::try_from(&DATA[..DDR_OFFSET]).unwrap();
+        let caps = Capabilities::new(&DATA[DDR_OFFSET..ECS_OFFSET], header.capabilities_pointer)
+            .map(|cap| cap.kind)
+            .collect::<Vec<_>>();
+        let caps = caps.iter().cycle().take(12).cloned().collect::<Vec<_>>();
+        let result = ConfigSpaceBuilder::new(header).capabilities(&caps).build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn extended_capabilities_overflow() {
+        use crate::extended_capabilities::{ExtendedCapabilityKind, DeviceSerialNumber};
+        let header = Header::try_from(&DATA[..DDR_OFFSET]).unwrap();
+        let dsn = ExtendedCapability {
+            kind: ExtendedCapabilityKind::DeviceSerialNumber(DeviceSerialNumber {
+                lower_dword: 0x11223344,
+                upper_dword: 0x55667788,
+            }),
+            version: 1,
+            offset: 0,
+        };
+        // (4096 - 256) / 12 = 320
+        let ecaps = vec![dsn; 320];
+        let image = ConfigSpaceBuilder::new(header.clone()).extended_capabilities(&ecaps).build()
+            .unwrap();
+        assert_eq!(320, ExtendedCapabilities::new(&image[ECS_OFFSET..]).count());
+        let ecaps = vec![ecaps[0].clone(); 321];
+        let result = ConfigSpaceBuilder::new(header).extended_capabilities(&ecaps).build();
+        assert_eq!(Err(byte::Error::BadInput { err: "extended capabilities do not fit in ECS" }), result);
+    }
+}
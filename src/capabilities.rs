@@ -6,12 +6,12 @@ Each Capability structure has a Capability ID assigned by the PCI-SIG.
 Capabilities list
 - [x] Null Capability (00h)
 - [x] [PCI Power Management Interface](power_management_interface) (01h)
-- [ ] AGP (02h)
+- [x] [AGP](accelerated_graphics_port) (02h)
 - [x] [VPD](vital_product_data) (03h)
 - [x] [Slot Identification](slot_identification) (04h)
 - [x] [Message Signaled Interrupts](message_signaled_interrups) (05h)
-- [x] CompactPCI Hot Swap (06h)
-- [ ] PCI-X (07h)
+- [x] [CompactPCI Hot Swap](compact_pci_hot_swap) (06h)
+- [x] [PCI-X](pci_x) (07h)
 - [x] [HyperTransport](hypertransport) (08h)
 - [x] [Vendor Specific](vendor_specific) (09h)
 - [x] [Debug port](debug_port) (0Ah)
@@ -19,13 +19,13 @@ Capabilities list
 - [x] PCI Hot-Plug (0Ch)
 - [x] [PCI Bridge Subsystem Vendor ID](bridge_subsystem_vendor_id) (0Dh)
 - [x] AGP 8x (0Eh)
-- [x] Secure Device (0Fh)
+- [x] [Secure Device](secure_device) (0Fh)
 - [x] [PCI Express](pci_express) (10h)
 - [x] [MSI-X](msi_x) (11h)
 - [x] [Serial ATA Data/Index Configuration](sata) (12h)
 - [x] [Advanced Features](advanced_features) (AF) (13h)
-- [ ] Enhanced Allocation (14h)
-- [ ] Flattening Portal Bridge (15h)
+- [x] [Enhanced Allocation](enhanced_allocation) (14h)
+- [x] [Flattening Portal Bridge](flattening_portal_bridge) (15h)
 
 Others Reserved
 
@@ -132,8 +132,10 @@ let sample = vec![
         pointer: 0xa8,
         kind: CapabilityKind::Sata(Sata {
             revision: sata::Revision { major: 1, minor: 0 },
+            reserved: 0,
             bar_offset: sata::BarOffset(0x00000004),
             bar_location: sata::BarLocation::Bar4,
+            rsvdp: 0,
         })
     },
 ];
@@ -145,13 +147,20 @@ assert_eq!(sample, result);
 
 
 use byte::{
-    ctx::LE,
+    ctx::{Endian, LE},
+    self,
     // TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
-use super::DDR_OFFSET;
+use super::{DDR_OFFSET, Error, header::HeaderType, visited::Visited};
+
+/// Capabilities Pointer register offset in Type 00h and Type 01h headers
+const CAPABILITIES_POINTER_OFFSET: u16 = 0x34;
+
+/// Upper bound of list length, dword aligned capabilities fit device dependent region 48 times
+const MAX_CAPABILITIES: u16 = 48;
 
 /// Each capability in the capability list consists of an 8-bit ID field assigned by the PCI SIG,
 /// an 8 bit pointer in configuration space to the next capability.
@@ -162,6 +171,8 @@ pub mod power_management_interface;
 pub use power_management_interface::PowerManagementInterface;
 
 // 02h AGP
+pub mod accelerated_graphics_port;
+pub use accelerated_graphics_port::AcceleratedGraphicsPort;
 
 // 03h VPD
 pub mod vital_product_data;
@@ -175,11 +186,13 @@ pub use slot_identification::SlotIdentification;
 pub mod message_signaled_interrups;
 pub use message_signaled_interrups::MessageSignaledInterrups;
 
-/// 06h CompactPCI Hot Swap
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct CompactPciHotSwap;
+// 06h CompactPCI Hot Swap
+pub mod compact_pci_hot_swap;
+pub use compact_pci_hot_swap::CompactPciHotSwap;
 
 // 07h PCI-X
+pub mod pci_x;
+pub use pci_x::PciX;
 
 // 08h HyperTransport
 pub mod hypertransport;
@@ -195,25 +208,26 @@ pub use debug_port::DebugPort;
 
 /// 0Bh CompactPCI central resource control
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CompactPciResourceControl;
 
 /// PCI Hot-Plug
 ///
 /// This ID indicates that the associated device conforms to the Standard Hot-Plug Controller model
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PciHotPlug;
 
 // 0Dh PCI Bridge Subsystem Vendor ID
 pub mod bridge_subsystem_vendor_id;
 pub use bridge_subsystem_vendor_id::BridgeSubsystemVendorId;
 
-/// 0Eh AGP 8x
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Agp8x;
+// 0Eh AGP 8x
+pub use accelerated_graphics_port::Agp8x;
 
-/// 0Fh Secure Device
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct SecureDevice;
+// 0Fh Secure Device
+pub mod secure_device;
+pub use secure_device::SecureDevice;
 
 // 10h PCI Express
 pub mod pci_express;
@@ -232,8 +246,13 @@ pub mod advanced_features;
 pub use advanced_features::AdvancedFeatures;
 
 // 14h Enhanced Allocation
+pub mod enhanced_allocation;
+pub use enhanced_allocation::EnhancedAllocation;
+use enhanced_allocation::EnhancedAllocationCtx;
 
 // 15h Flattening Portal Bridge
+pub mod flattening_portal_bridge;
+pub use flattening_portal_bridge::FlatteningPortalBridge;
 
 
 
@@ -246,61 +265,143 @@ pub use advanced_features::AdvancedFeatures;
 pub struct Capabilities<'a> {
     data: &'a [u8],
     pointer: u8,
+    /// Offset of register holding the pointer
+    pointer_offset: u16,
+    /// Bytes occupied by already parsed capabilities
+    visited: Visited<4, 0>,
+    /// Header Type register without multi-function bit
+    header_type: Option<u8>,
 }
 impl<'a> Capabilities<'a> {
     pub fn new(data: &'a [u8], pointer: u8) -> Self {
-        Self { data, pointer }
+        Self {
+            data,
+            pointer,
+            pointer_offset: CAPABILITIES_POINTER_OFFSET,
+            visited: Visited::new(MAX_CAPABILITIES),
+            header_type: None,
+        }
     }
-}
-impl<'a> Iterator for Capabilities<'a> {
-    type Item = Capability<'a>;
-
-    fn next(&mut self) -> Option<Self::Item> {
+    /// Provides header type of the function, which is needed to decode capabilities with
+    /// layout depending on it (e.g. [PciX]). Without it such capabilities are decoded as the
+    /// ones of Type 0 function.
+    pub fn with_header_type(self, header_type: &HeaderType) -> Self {
+        let header_type = match header_type {
+            HeaderType::Normal(_) => 0x00,
+            HeaderType::Bridge(_) => 0x01,
+            HeaderType::Cardbus(_) => 0x02,
+        };
+        Self { header_type: Some(header_type), ..self }
+    }
+    /// Iterator yielding parsing errors instead of silently ending the list
+    pub fn checked(self) -> CheckedCapabilities<'a> {
+        CheckedCapabilities(self)
+    }
+    /// Device dependent region the list resides in
+    pub(crate) fn data(&self) -> &'a [u8] {
+        self.data
+    }
+    fn try_next(&mut self) -> Option<Result<Capability<'a>, Error>> {
         // Stop iterating if next pointer is null
         if self.pointer == 0 {
             return None;
         }
         let pointer = self.pointer;
+        let result = self.read(pointer);
+        // Nothing can be read after a broken capability
+        self.pointer = match result {
+            Ok((_, next)) => next,
+            Err(_) => 0,
+        };
+        Some(result.map(|(cap, _)| cap))
+    }
+    fn read(&mut self, pointer: u8) -> Result<(Capability<'a>, u8), Error> {
         let data = &self.data;
+        let out_of_bounds = Error::PointerOutOfBounds {
+            offset: self.pointer_offset,
+            pointer: pointer.into(),
+        };
         // Capability data resides in Device dependent region (0x34 offset)
-        let offset = &mut usize::from(pointer).checked_sub(DDR_OFFSET)?;
+        let offset = &mut usize::from(pointer).checked_sub(DDR_OFFSET).ok_or(out_of_bounds)?;
+        let start = *offset;
+        self.visited.enter(self.pointer_offset, pointer.into())?;
+        let truncated = Error::Truncated { offset: pointer.into() };
         // 8-bit ID field assigned by the PCI SIG
-        let cap_id = data.read_with::<u8>(offset, LE).ok()?;
+        let cap_id = data.read_with::<u8>(offset, LE).map_err(|_| truncated)?;
         // an 8 bit pointer in configuration space to the next capability
-        self.pointer = data.read_with::<u8>(offset, LE).ok()?;
+        let next = data.read_with::<u8>(offset, LE).map_err(|_| truncated)?;
+        self.pointer_offset = u16::from(pointer) + 1;
         let kind = match cap_id {
-            0x00 => CapabilityKind::NullCapability,
-            0x01 => data.read_with(offset, LE).map(CapabilityKind::PowerManagementInterface).ok()?,
-            0x03 => data.read_with(offset, LE).map(CapabilityKind::VitalProductData).ok()?,
-            0x04 => data.read_with(offset, LE).map(CapabilityKind::SlotIdentification).ok()?,
-            0x05 => data.read_with(offset, LE).map(CapabilityKind::MessageSignaledInterrups).ok()?,
-            0x06 => CapabilityKind::CompactPciHotSwap(CompactPciHotSwap),
-            0x08 => data.read_with(offset, LE).map(CapabilityKind::Hypertransport).ok()?,
-            0x09 => data.read_with(offset, LE).map(CapabilityKind::VendorSpecific).ok()?,
-            0x0a => data.read_with(offset, LE).map(CapabilityKind::DebugPort).ok()?,
-            0x0b => CapabilityKind::CompactPciResourceControl(CompactPciResourceControl),
-            0x0c => CapabilityKind::PciHotPlug(PciHotPlug),
-            0x0d => data.read_with(offset, LE).map(CapabilityKind::BridgeSubsystemVendorId).ok()?,
-            0x0f => CapabilityKind::SecureDevice(SecureDevice),
-            0x10 => data.read_with(offset, LE).map(CapabilityKind::PciExpress).ok()?,
-            0x11 => data.read_with(offset, LE).map(CapabilityKind::MsiX).ok()?,
-            0x12 => data.read_with(offset, LE).map(CapabilityKind::Sata).ok()?,
-            0x13 => data.read_with(offset, LE).map(CapabilityKind::AdvancedFeatures).ok()?,
-            v => CapabilityKind::Reserved(v),
+            0x00 => Ok(CapabilityKind::NullCapability),
+            0x01 => data.read_with(offset, LE).map(CapabilityKind::PowerManagementInterface),
+            0x02 => data.read_with(offset, LE).map(CapabilityKind::AcceleratedGraphicsPort),
+            0x03 => data.read_with(offset, LE).map(CapabilityKind::VitalProductData),
+            0x04 => data.read_with(offset, LE).map(CapabilityKind::SlotIdentification),
+            0x05 => data.read_with(offset, LE).map(CapabilityKind::MessageSignaledInterrups),
+            0x06 => data.read_with(offset, LE).map(CapabilityKind::CompactPciHotSwap),
+            0x07 => match self.header_type {
+                None | Some(0x00) => data.read_with(offset, LE).map(PciX::Device).map(CapabilityKind::PciX),
+                Some(0x01) => data.read_with(offset, LE).map(PciX::Bridge).map(CapabilityKind::PciX),
+                _ => Ok(CapabilityKind::Reserved(cap_id)),
+            },
+            0x08 => data.read_with(offset, LE).map(CapabilityKind::Hypertransport),
+            0x09 => data.read_with(offset, LE).map(CapabilityKind::VendorSpecific),
+            0x0a => data.read_with(offset, LE).map(CapabilityKind::DebugPort),
+            0x0b => Ok(CapabilityKind::CompactPciResourceControl(CompactPciResourceControl)),
+            0x0c => Ok(CapabilityKind::PciHotPlug(PciHotPlug)),
+            0x0d => data.read_with(offset, LE).map(CapabilityKind::BridgeSubsystemVendorId),
+            0x0e => data.read_with(offset, LE).map(CapabilityKind::Agp8x),
+            0x0f => data.read_with(offset, LE).map(CapabilityKind::SecureDevice),
+            0x10 => data.read_with(offset, LE).map(CapabilityKind::PciExpress),
+            0x11 => data.read_with(offset, LE).map(CapabilityKind::MsiX),
+            0x12 => data.read_with(offset, LE).map(CapabilityKind::Sata),
+            0x13 => data.read_with(offset, LE).map(CapabilityKind::AdvancedFeatures),
+            0x14 => {
+                let ctx = EnhancedAllocationCtx { endian: LE, is_bridge: self.header_type == Some(0x01) };
+                data.read_with(offset, ctx).map(CapabilityKind::EnhancedAllocation)
+            },
+            0x15 => data.read_with(offset, LE).map(CapabilityKind::FlatteningPortalBridge),
+            v => Ok(CapabilityKind::Reserved(v)),
         };
-        Some(Capability { pointer, kind })
+        let kind = kind.map_err(|e| Error::from_byte(pointer.into(), e))?;
+        self.visited.claim(pointer.into(), *offset - start)?;
+        Ok((Capability { pointer, kind }, next))
+    }
+}
+impl<'a> Iterator for Capabilities<'a> {
+    type Item = Capability<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.try_next()?.ok()
+    }
+}
+
+/// An iterator through *Capabilities List* yielding [Error] on the first broken capability
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckedCapabilities<'a>(Capabilities<'a>);
+impl<'a> Iterator for CheckedCapabilities<'a> {
+    type Item = Result<Capability<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.try_next()
     }
 }
 
 /// Capability structure
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Capability<'a> {
     pub pointer: u8,
     pub kind: CapabilityKind<'a>,
 }
 
 /// Capability ID assigned by the PCI-SIG
-#[derive(Debug, PartialEq, Eq)]
+///
+/// With the `serde` feature, register blocks of variants borrowing configuration space
+/// ([VendorSpecific] and [EnhancedAllocation]) are serialized as hex strings and deserialized
+/// into owned [RawBytes](crate::RawBytes)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CapabilityKind<'a> {
     /// 00h Null Capability
     ///
@@ -309,12 +410,15 @@ pub enum CapabilityKind<'a> {
     NullCapability,
     /// 01h PCI Power Management Interface
     PowerManagementInterface(PowerManagementInterface),
-    // AcceleratedGraphicsPort(AcceleratedGraphicsPort),
+    /// 02h AGP
+    AcceleratedGraphicsPort(AcceleratedGraphicsPort),
     VitalProductData(VitalProductData),
     SlotIdentification(SlotIdentification),
     MessageSignaledInterrups(MessageSignaledInterrups),
     CompactPciHotSwap(CompactPciHotSwap),
-    // PciX(PciX),
+    /// 07h PCI-X, layout depends on the header type, see [Capabilities::with_header_type].
+    /// Not defined for CardBus bridges, these get [Reserved](CapabilityKind::Reserved).
+    PciX(PciX),
     Hypertransport(Hypertransport),
     VendorSpecific(VendorSpecific<'a>),
     DebugPort(DebugPort),
@@ -329,15 +433,81 @@ pub enum CapabilityKind<'a> {
     /// 12h Serial ATA Data/Index Configuration
     Sata(Sata),
     AdvancedFeatures(AdvancedFeatures),
+    /// 14h Enhanced Allocation, layout depends on the header type, see
+    /// [Capabilities::with_header_type]
+    EnhancedAllocation(EnhancedAllocation<'a>),
+    FlatteningPortalBridge(FlatteningPortalBridge),
     Reserved(u8),
 }
+impl<'a> CapabilityKind<'a> {
+    /// Capability ID assigned by the PCI-SIG
+    pub fn id(&self) -> u8 {
+        match self {
+            Self::NullCapability               => 0x00,
+            Self::PowerManagementInterface(_)  => 0x01,
+            Self::AcceleratedGraphicsPort(_)   => 0x02,
+            Self::VitalProductData(_)          => 0x03,
+            Self::SlotIdentification(_)        => 0x04,
+            Self::MessageSignaledInterrups(_)  => 0x05,
+            Self::CompactPciHotSwap(_)         => 0x06,
+            Self::PciX(_)                      => 0x07,
+            Self::Hypertransport(_)            => 0x08,
+            Self::VendorSpecific(_)            => 0x09,
+            Self::DebugPort(_)                 => 0x0a,
+            Self::CompactPciResourceControl(_) => 0x0b,
+            Self::PciHotPlug(_)                => 0x0c,
+            Self::BridgeSubsystemVendorId(_)   => 0x0d,
+            Self::Agp8x(_)                     => 0x0e,
+            Self::SecureDevice(_)              => 0x0f,
+            Self::PciExpress(_)                => 0x10,
+            Self::MsiX(_)                      => 0x11,
+            Self::Sata(_)                      => 0x12,
+            Self::AdvancedFeatures(_)          => 0x13,
+            Self::EnhancedAllocation(_)        => 0x14,
+            Self::FlatteningPortalBridge(_)    => 0x15,
+            Self::Reserved(v)                  => *v,
+        }
+    }
+}
+/// Writes capability registers following the ID and Next Pointer fields
+impl<'a> TryWrite<Endian> for CapabilityKind<'a> {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        match self {
+            Self::PowerManagementInterface(data) => bytes.write_with(offset, data, endian)?,
+            Self::AcceleratedGraphicsPort(data)  => bytes.write_with(offset, data, endian)?,
+            Self::VitalProductData(data)         => bytes.write_with(offset, data, endian)?,
+            Self::SlotIdentification(data)       => bytes.write_with(offset, data, endian)?,
+            Self::MessageSignaledInterrups(data) => bytes.write_with(offset, data, endian)?,
+            Self::CompactPciHotSwap(data)        => bytes.write_with(offset, data, endian)?,
+            Self::PciX(data)                     => bytes.write_with(offset, data, endian)?,
+            Self::Hypertransport(data)           => bytes.write_with(offset, data, endian)?,
+            Self::VendorSpecific(data)           => bytes.write_with(offset, data, endian)?,
+            Self::DebugPort(data)                => bytes.write_with(offset, data, endian)?,
+            Self::BridgeSubsystemVendorId(data)  => bytes.write_with(offset, data, endian)?,
+            Self::Agp8x(data)                    => bytes.write_with(offset, data, endian)?,
+            Self::SecureDevice(data)             => bytes.write_with(offset, data, endian)?,
+            Self::PciExpress(data)               => bytes.write_with(offset, data, endian)?,
+            Self::MsiX(data)                     => bytes.write_with(offset, data, endian)?,
+            Self::Sata(data)                     => bytes.write_with(offset, data, endian)?,
+            Self::AdvancedFeatures(data)         => bytes.write_with(offset, data, endian)?,
+            Self::EnhancedAllocation(data)       => bytes.write_with(offset, data, endian)?,
+            Self::FlatteningPortalBridge(data)   => bytes.write_with(offset, data, endian)?,
+            Self::NullCapability
+            | Self::CompactPciResourceControl(_)
+            | Self::PciHotPlug(_)
+            | Self::Reserved(_) => (),
+        }
+        Ok(*offset)
+    }
+}
 
 
 #[cfg(test)]
 mod tests {
     use std::prelude::v1::*;
     use pretty_assertions::assert_eq;
-    use crate::ECS_OFFSET;
+    use crate::{DDR_LENGTH, ECS_OFFSET};
     use super::*;
 
     #[test]
@@ -375,4 +545,174 @@ mod tests {
         ];
         assert_eq!(sample, result);
     }
+
+    #[test]
+    fn parse_then_write() {
+        for (name, data) in crate::test_data::device_configs() {
+            let header = crate::Header::try_from(&data[..DDR_OFFSET]).unwrap();
+            if !header.status.capabilities_list {
+                continue;
+            }
+            let ddr = &data[DDR_OFFSET..ECS_OFFSET];
+            let caps = Capabilities::new(ddr, header.capabilities_pointer)
+                .with_header_type(&header.header_type);
+            for Capability { pointer, kind } in caps {
+                let start = pointer as usize + CAP_HEADER_LEN;
+                let mut result = [0u8; 0x100];
+                let offset = &mut 0;
+                result.write_with(offset, kind, LE).unwrap();
+                assert_eq!(
+                    &data[start..start + *offset], &result[..*offset],
+                    "{}: capability at {:#x}", name, pointer
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn parse_then_write_random() {
+        let header_types = crate::test_data::device_configs().into_iter()
+            .map(|(_, data)| crate::Header::try_from(&data[..DDR_OFFSET]).unwrap().header_type)
+            .collect::<Vec<_>>();
+        let mut random = crate::test_data::Random::new(0x40);
+        let mut failures = std::collections::BTreeMap::new();
+        for i in 0..1_000 {
+            let mut ddr = [0u8; DDR_LENGTH];
+            random.fill(&mut ddr);
+            ddr[0] = (i % 0x16) as u8;
+            ddr[1] = 0;
+            let header_type = &header_types[i / 0x16 % header_types.len()];
+            let kind = match Capabilities::new(&ddr, 0x40).with_header_type(header_type).next() {
+                Some(Capability { kind, .. }) => kind,
+                None => continue,
+            };
+            let mut result = [0u8; DDR_LENGTH];
+            let offset = &mut 0;
+            let ok = match result.write_with(offset, kind, LE) {
+                Ok(()) => ddr[CAP_HEADER_LEN..CAP_HEADER_LEN + *offset] == result[..*offset],
+                Err(_) => false,
+            };
+            if !ok {
+                *failures.entry(ddr[0]).or_insert(0) += 1;
+            }
+        }
+        assert_eq!(std::collections::BTreeMap::<u8, i32>::new(), failures);
+    }
+
+    #[test]
+    fn checked() {
+        let mut ddr = [0u8; DDR_LENGTH];
+        // Power Management pointing below device dependent region
+        ddr[..8].copy_from_slice(&[0x01, 0x20, 0x03, 0x00, 0x08, 0x00, 0x00, 0x00]);
+        let pointers = |caps: Capabilities| {
+            caps.checked().map(|cap| cap.map(|cap| cap.pointer)).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec![Ok(0x40), Err(Error::PointerOutOfBounds { offset: 0x41, pointer: 0x20 })],
+            pointers(Capabilities::new(&ddr, 0x40))
+        );
+        assert_eq!(1, Capabilities::new(&ddr, 0x40).count(), "Unchecked iterator silently stops");
+        assert_eq!(
+            vec![Err(Error::PointerOutOfBounds { offset: 0x34, pointer: 0x3c })],
+            pointers(Capabilities::new(&ddr, 0x3c))
+        );
+
+        // Vendor Specific with zero length
+        ddr[1] = 0x48;
+        ddr[8..11].copy_from_slice(&[0x09, 0x00, 0x00]);
+        assert_eq!(
+            vec![Ok(0x40), Err(Error::InvalidLength { offset: 0x48 })],
+            pointers(Capabilities::new(&ddr, 0x40))
+        );
+
+        // Power Management body does not fit
+        assert_eq!(
+            vec![Err(Error::Truncated { offset: 0x40 })],
+            pointers(Capabilities::new(&ddr[..0x04], 0x40))
+        );
+        // Capability header does not fit
+        assert_eq!(
+            vec![Err(Error::Truncated { offset: 0x50 })],
+            pointers(Capabilities::new(&ddr[..0x0c], 0x50))
+        );
+    }
+
+    #[test]
+    fn loops_and_overlaps() {
+        let pointers = |ddr: &[u8]| {
+            Capabilities::new(ddr, 0x40).checked()
+                .map(|cap| cap.map(|cap| cap.pointer))
+                .collect::<Vec<_>>()
+        };
+        let mut ddr = [0u8; DDR_LENGTH];
+        // Power Management followed by reserved capability pointing back
+        ddr[..10].copy_from_slice(&[0x01, 0x48, 0x03, 0x00, 0x08, 0x00, 0x00, 0x00, 0xff, 0x40]);
+        assert_eq!(
+            vec![Ok(0x40), Ok(0x48), Err(Error::PointerLoop { offset: 0x49, pointer: 0x40 })],
+            pointers(&ddr)
+        );
+        assert_eq!(2, Capabilities::new(&ddr, 0x40).count(), "Unchecked iterator terminates");
+
+        // Pointer into Power Management registers
+        ddr[9] = 0x44;
+        assert_eq!(
+            vec![Ok(0x40), Ok(0x48), Err(Error::Overlap { offset: 0x44 })],
+            pointers(&ddr)
+        );
+
+        // Power Management registers covering reserved capability
+        let mut ddr = [0u8; DDR_LENGTH];
+        ddr[..2].copy_from_slice(&[0xff, 0x4c]);
+        ddr[0x08..0x0c].copy_from_slice(&[0x01, 0x00, 0x03, 0x00]);
+        ddr[0x0c..0x0e].copy_from_slice(&[0xff, 0x48]);
+        assert_eq!(
+            vec![Ok(0x40), Ok(0x4c), Err(Error::Overlap { offset: 0x48 })],
+            pointers(&ddr)
+        );
+
+        // Chain of 2 bytes long reserved capabilities
+        let mut ddr = [0u8; DDR_LENGTH];
+        for (cap, next) in ddr.chunks_exact_mut(2).zip((0x42..0xc0).step_by(2)) {
+            cap.copy_from_slice(&[0xff, next]);
+        }
+        let result = pointers(&ddr);
+        assert_eq!(49, result.len());
+        assert_eq!(Some(&Err(Error::IterationLimit { offset: 0xa0 })), result.last());
+    }
+
+    #[test]
+    fn pci_x_header_type() {
+        let data = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"),
+            "/tests/data/device/8086_2030/config"
+        ));
+        let bridge = crate::Header::try_from(&data[..DDR_OFFSET]).unwrap().header_type;
+        let mut ddr = [0u8; DDR_LENGTH];
+        ddr[..16].copy_from_slice(&[
+            0x07, 0x00, 0xc3, 0x00, 0xe0, 0x00, 0x03, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        ]);
+        // Type 0 layout by default
+        let result = Capabilities::new(&ddr, 0x40).map(|cap| cap.kind).collect::<Vec<_>>();
+        assert!(matches!(result[..], [CapabilityKind::PciX(PciX::Device(_))]), "{:?}", result);
+        let result = Capabilities::new(&ddr, 0x40).with_header_type(&bridge)
+            .map(|cap| cap.kind)
+            .collect::<Vec<_>>();
+        assert!(matches!(result[..], [CapabilityKind::PciX(PciX::Bridge(_))]), "{:?}", result);
+    }
+
+    #[test]
+    fn enhanced_allocation_header_type() {
+        let data = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"),
+            "/tests/data/device/8086_2030/config"
+        ));
+        let bridge = crate::Header::try_from(&data[..DDR_OFFSET]).unwrap().header_type;
+        let mut ddr = [0u8; DDR_LENGTH];
+        ddr[..8].copy_from_slice(&[0x14, 0x00, 0x00, 0x00, 0x01, 0x02, 0x00, 0x00]);
+        let fixed_bus_numbers = |caps: Capabilities| match caps.map(|cap| cap.kind).next() {
+            Some(CapabilityKind::EnhancedAllocation(ea)) => Some(ea.fixed_bus_numbers.is_some()),
+            _ => None,
+        };
+        // Type 0 layout by default
+        assert_eq!(Some(false), fixed_bus_numbers(Capabilities::new(&ddr, 0x40)));
+        assert_eq!(Some(true), fixed_bus_numbers(Capabilities::new(&ddr, 0x40).with_header_type(&bridge)));
+    }
 }
@@ -0,0 +1,628 @@
+//! Accelerated Graphics Port
+//!
+//! AGP is a dedicated point-to-point channel between the core logic (AGP target) and a graphics
+//! device (AGP master). The same register set describes both sides of the port: master and target
+//! report their capabilities in the Status register and software programs the negotiated subset
+//! into the Command register of both. AGP 3.0 adds the 8x transfer mode and isochronous
+//! transactions.
+
+use modular_bitfield::prelude::*;
+use byte::{
+    ctx::*,
+    self,
+    TryRead,
+    TryWrite,
+    BytesExt,
+};
+
+
+/// Target-only registers (AGPCTRL, APSIZE, NEPG, GARTLO and GARTHI) between NISTAT and NICMD
+const TARGET_REGISTERS_LEN: usize = 0x10;
+
+/// AGP capability structure
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AcceleratedGraphicsPort {
+    /// Revision of the AGP interface specification the device conforms to
+    pub version: Version,
+    /// Reserved byte following the revision
+    pub reserved: u8,
+    pub status: Status,
+    pub command: Command,
+    /// Registers following AGP Command, present since AGP 3.0 regardless of isochronous
+    /// transactions support
+    pub isochronous: Option<Isochronous>,
+}
+impl AcceleratedGraphicsPort {
+    /// Device operates in AGP 3.0 mode, which changes the meaning of [DataRate] bits
+    pub fn is_agp3_mode(&self) -> bool {
+        self.version.major >= 3 && self.status.agp3_mode
+    }
+}
+impl<'a> TryRead<'a, Endian> for AcceleratedGraphicsPort {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let version: Version = bytes.read_with::<u8>(offset, endian)?.into();
+        let reserved = bytes.read_with::<u8>(offset, endian)?;
+        let status: Status = bytes.read_with::<u32>(offset, endian)?.into();
+        let command = bytes.read_with::<u32>(offset, endian)?.into();
+        let isochronous = if version.major >= 3 {
+            let status = bytes.read_with::<u32>(offset, endian)?.into();
+            let target_registers = bytes.read_with::<&[u8]>(offset, Bytes::Len(TARGET_REGISTERS_LEN))?
+                .try_into().unwrap_or_default();
+            let command = bytes.read_with::<u16>(offset, endian)?.into();
+            Some(Isochronous { status, target_registers, command })
+        } else {
+            None
+        };
+        let agp = AcceleratedGraphicsPort { version, reserved, status, command, isochronous };
+        Ok((agp, *offset))
+    }
+}
+impl TryWrite<Endian> for AcceleratedGraphicsPort {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u8>(offset, self.version.into(), endian)?;
+        bytes.write_with::<u8>(offset, self.reserved, endian)?;
+        bytes.write_with::<u32>(offset, self.status.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.command.into(), endian)?;
+        if let Some(isochronous) = self.isochronous {
+            bytes.write_with::<u32>(offset, isochronous.status.into(), endian)?;
+            bytes.write::<&[u8]>(offset, &isochronous.target_registers)?;
+            bytes.write_with::<u16>(offset, isochronous.command.into(), endian)?;
+        }
+        Ok(*offset)
+    }
+}
+
+/// AGP 8x
+///
+/// AGP 3.0 register set located in the AGP Target PCI-PCI Bridge
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Agp8x(pub AcceleratedGraphicsPort);
+impl<'a> TryRead<'a, Endian> for Agp8x {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let agp = bytes.read_with(offset, endian)?;
+        Ok((Self(agp), *offset))
+    }
+}
+impl TryWrite<Endian> for Agp8x {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with(offset, self.0, endian)?;
+        Ok(*offset)
+    }
+}
+
+
+#[bitfield(bits = 8)]
+#[repr(u8)]
+pub struct VersionProto {
+    minor: B4,
+    major: B4,
+}
+
+/// AGP interface specification revision
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Version {
+    /// Minor Revision
+    pub minor: u8,
+    /// Major Revision
+    pub major: u8,
+}
+impl From<VersionProto> for Version {
+    fn from(proto: VersionProto) -> Self {
+        Self {
+            minor: proto.minor(),
+            major: proto.major(),
+        }
+    }
+}
+impl From<u8> for Version {
+    fn from(byte: u8) -> Self { VersionProto::from(byte).into() }
+}
+impl From<Version> for VersionProto {
+    fn from(data: Version) -> Self {
+        Self::new()
+            .with_minor(data.minor)
+            .with_major(data.major)
+    }
+}
+impl From<Version> for u8 {
+    fn from(data: Version) -> Self { VersionProto::from(data).into() }
+}
+
+
+/// Supported (in Status) or selected (in Command) data transfer rates
+///
+/// Bits 0, 1 and 2 stand for 1x, 2x and 4x rates in AGP 2.0 mode and for 4x, 8x and reserved
+/// rates in AGP 3.0 mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DataRate(pub u8);
+impl DataRate {
+    /// Transfer rate multipliers in the given mode
+    pub fn multipliers(self, agp3_mode: bool) -> impl Iterator<Item = u8> {
+        let shift = if agp3_mode { 2 } else { 0 };
+        (0..3).filter(move |bit| self.0 & (1 << bit) != 0).map(move |bit| 1 << (bit + shift))
+    }
+}
+
+
+/// Calibration cycle period
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CalibrationCycle {
+    /// 4 ms
+    Ms4,
+    /// 16 ms
+    Ms16,
+    /// 64 ms
+    Ms64,
+    /// 256 ms
+    Ms256,
+    /// Calibration cycle is not needed
+    NotNeeded,
+    Reserved(u8),
+}
+impl From<u8> for CalibrationCycle {
+    fn from(byte: u8) -> Self {
+        match byte {
+            0b000 => Self::Ms4,
+            0b001 => Self::Ms16,
+            0b010 => Self::Ms64,
+            0b011 => Self::Ms256,
+            0b111 => Self::NotNeeded,
+            v => Self::Reserved(v),
+        }
+    }
+}
+impl From<CalibrationCycle> for u8 {
+    fn from(data: CalibrationCycle) -> Self {
+        match data {
+            CalibrationCycle::Ms4 => 0b000,
+            CalibrationCycle::Ms16 => 0b001,
+            CalibrationCycle::Ms64 => 0b010,
+            CalibrationCycle::Ms256 => 0b011,
+            CalibrationCycle::NotNeeded => 0b111,
+            CalibrationCycle::Reserved(v) => v,
+        }
+    }
+}
+
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct StatusProto {
+    data_rate: B3,
+    agp3_mode: bool,
+    fast_writes: bool,
+    over_4g: bool,
+    no_host_translation: bool,
+    gart64: bool,
+    ita_coherent: bool,
+    side_band_addressing: bool,
+    calibration_cycle: B3,
+    async_request_size: B3,
+    rsvdp_0: bool,
+    isochronous: bool,
+    rsvdp_1: B6,
+    request_queue: B8,
+}
+
+/// AGP Status
+///
+/// Reports capabilities of the device
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Status {
+    /// Supported data transfer rates
+    pub data_rate: DataRate,
+    /// Device operates in AGP 3.0 mode
+    pub agp3_mode: bool,
+    /// Supports fast write transactions
+    pub fast_writes: bool,
+    /// Supports addresses above 4 GB
+    pub over_4g: bool,
+    /// HTRANS#: core logic does not translate host processor accesses through the aperture
+    pub no_host_translation: bool,
+    /// Supports 64-bit GART entries
+    pub gart64: bool,
+    /// Core logic provides coherent translation of ITA accesses
+    pub ita_coherent: bool,
+    /// Supports side band addressing
+    pub side_band_addressing: bool,
+    /// Required period of calibration cycles
+    pub calibration_cycle: CalibrationCycle,
+    /// Optimum asynchronous request size, 2<sup>(n + 4)</sup> bytes
+    pub async_request_size: u8,
+    /// Reserved bit 16
+    pub rsvdp_0: bool,
+    /// Supports isochronous transactions
+    pub isochronous: bool,
+    /// Reserved bits 23:18
+    pub rsvdp_1: u8,
+    /// Maximum number of queued AGP command requests minus one
+    pub request_queue: u8,
+}
+impl From<StatusProto> for Status {
+    fn from(proto: StatusProto) -> Self {
+        Self {
+            data_rate: DataRate(proto.data_rate()),
+            agp3_mode: proto.agp3_mode(),
+            fast_writes: proto.fast_writes(),
+            over_4g: proto.over_4g(),
+            no_host_translation: proto.no_host_translation(),
+            gart64: proto.gart64(),
+            ita_coherent: proto.ita_coherent(),
+            side_band_addressing: proto.side_band_addressing(),
+            calibration_cycle: proto.calibration_cycle().into(),
+            async_request_size: proto.async_request_size(),
+            rsvdp_0: proto.rsvdp_0(),
+            isochronous: proto.isochronous(),
+            rsvdp_1: proto.rsvdp_1(),
+            request_queue: proto.request_queue(),
+        }
+    }
+}
+impl From<u32> for Status {
+    fn from(dword: u32) -> Self { StatusProto::from(dword).into() }
+}
+impl From<Status> for StatusProto {
+    fn from(data: Status) -> Self {
+        Self::new()
+            .with_data_rate(data.data_rate.0)
+            .with_agp3_mode(data.agp3_mode)
+            .with_fast_writes(data.fast_writes)
+            .with_over_4g(data.over_4g)
+            .with_no_host_translation(data.no_host_translation)
+            .with_gart64(data.gart64)
+            .with_ita_coherent(data.ita_coherent)
+            .with_side_band_addressing(data.side_band_addressing)
+            .with_calibration_cycle(data.calibration_cycle.into())
+            .with_async_request_size(data.async_request_size)
+            .with_rsvdp_0(data.rsvdp_0)
+            .with_isochronous(data.isochronous)
+            .with_rsvdp_1(data.rsvdp_1)
+            .with_request_queue(data.request_queue)
+    }
+}
+impl From<Status> for u32 {
+    fn from(data: Status) -> Self { StatusProto::from(data).into() }
+}
+
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct CommandProto {
+    data_rate: B3,
+    rsvdp_0: bool,
+    fast_writes_enable: bool,
+    over_4g_enable: bool,
+    rsvdp_1: bool,
+    gart64_enable: bool,
+    agp_enable: bool,
+    side_band_addressing_enable: bool,
+    calibration_cycle: B3,
+    async_request_size: B3,
+    rsvdp_2: B8,
+    request_queue: B8,
+}
+
+/// AGP Command
+///
+/// Controls operation of the device
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Command {
+    /// Selected data transfer rate, only one bit may be set
+    pub data_rate: DataRate,
+    /// Reserved bit 3
+    pub rsvdp_0: bool,
+    /// Fast write transactions are enabled
+    pub fast_writes_enable: bool,
+    /// Addresses above 4 GB are enabled
+    pub over_4g_enable: bool,
+    /// Reserved bit 6
+    pub rsvdp_1: bool,
+    /// 64-bit GART entries are enabled
+    pub gart64_enable: bool,
+    /// AGP operation is enabled
+    pub agp_enable: bool,
+    /// Side band addressing is enabled
+    pub side_band_addressing_enable: bool,
+    /// Programmed period of calibration cycles
+    pub calibration_cycle: CalibrationCycle,
+    /// Programmed asynchronous request size, 2<sup>(n + 4)</sup> bytes
+    pub async_request_size: u8,
+    /// Reserved bits 23:16
+    pub rsvdp_2: u8,
+    /// Maximum number of AGP command requests the master may enqueue minus one
+    pub request_queue: u8,
+}
+impl From<CommandProto> for Command {
+    fn from(proto: CommandProto) -> Self {
+        Self {
+            data_rate: DataRate(proto.data_rate()),
+            rsvdp_0: proto.rsvdp_0(),
+            fast_writes_enable: proto.fast_writes_enable(),
+            over_4g_enable: proto.over_4g_enable(),
+            rsvdp_1: proto.rsvdp_1(),
+            gart64_enable: proto.gart64_enable(),
+            agp_enable: proto.agp_enable(),
+            side_band_addressing_enable: proto.side_band_addressing_enable(),
+            calibration_cycle: proto.calibration_cycle().into(),
+            async_request_size: proto.async_request_size(),
+            rsvdp_2: proto.rsvdp_2(),
+            request_queue: proto.request_queue(),
+        }
+    }
+}
+impl From<u32> for Command {
+    fn from(dword: u32) -> Self { CommandProto::from(dword).into() }
+}
+impl From<Command> for CommandProto {
+    fn from(data: Command) -> Self {
+        Self::new()
+            .with_data_rate(data.data_rate.0)
+            .with_rsvdp_0(data.rsvdp_0)
+            .with_fast_writes_enable(data.fast_writes_enable)
+            .with_over_4g_enable(data.over_4g_enable)
+            .with_rsvdp_1(data.rsvdp_1)
+            .with_gart64_enable(data.gart64_enable)
+            .with_agp_enable(data.agp_enable)
+            .with_side_band_addressing_enable(data.side_band_addressing_enable)
+            .with_calibration_cycle(data.calibration_cycle.into())
+            .with_async_request_size(data.async_request_size)
+            .with_rsvdp_2(data.rsvdp_2)
+            .with_request_queue(data.request_queue)
+    }
+}
+impl From<Command> for u32 {
+    fn from(data: Command) -> Self { CommandProto::from(data).into() }
+}
+
+
+/// AGP 3.0 isochronous registers and target-only registers between them
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Isochronous {
+    /// NISTAT
+    pub status: IsochronousStatus,
+    /// Target-only registers between NISTAT and NICMD
+    pub target_registers: [u8; TARGET_REGISTERS_LEN],
+    /// NICMD
+    pub command: IsochronousCommand,
+}
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct IsochronousStatusProto {
+    rsvdp_0: B3,
+    latency: B3,
+    payload_size: B2,
+    transactions: B8,
+    max_bandwidth: B8,
+    rsvdp_1: B8,
+}
+
+/// Isochronous Status (NISTAT)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IsochronousStatus {
+    /// Reserved bits 2:0
+    pub rsvdp_0: u8,
+    /// ISOCH_L: maximum isochronous transaction latency in isochronous periods
+    pub latency: u8,
+    /// ISOCH_Y: supported isochronous payload size
+    pub payload_size: u8,
+    /// ISOCH_N: maximum number of isochronous transactions per isochronous period
+    pub transactions: u8,
+    /// MAXBW: maximum bandwidth of the device in 32 byte units per microsecond
+    pub max_bandwidth: u8,
+    /// Reserved bits 31:24
+    pub rsvdp_1: u8,
+}
+impl From<IsochronousStatusProto> for IsochronousStatus {
+    fn from(proto: IsochronousStatusProto) -> Self {
+        Self {
+            rsvdp_0: proto.rsvdp_0(),
+            latency: proto.latency(),
+            payload_size: proto.payload_size(),
+            transactions: proto.transactions(),
+            max_bandwidth: proto.max_bandwidth(),
+            rsvdp_1: proto.rsvdp_1(),
+        }
+    }
+}
+impl From<u32> for IsochronousStatus {
+    fn from(dword: u32) -> Self { IsochronousStatusProto::from(dword).into() }
+}
+impl From<IsochronousStatus> for IsochronousStatusProto {
+    fn from(data: IsochronousStatus) -> Self {
+        Self::new()
+            .with_rsvdp_0(data.rsvdp_0)
+            .with_latency(data.latency)
+            .with_payload_size(data.payload_size)
+            .with_transactions(data.transactions)
+            .with_max_bandwidth(data.max_bandwidth)
+            .with_rsvdp_1(data.rsvdp_1)
+    }
+}
+impl From<IsochronousStatus> for u32 {
+    fn from(data: IsochronousStatus) -> Self { IsochronousStatusProto::from(data).into() }
+}
+
+#[bitfield(bits = 16)]
+#[repr(u16)]
+pub struct IsochronousCommandProto {
+    rsvdp: B6,
+    payload_size: B2,
+    transactions: B8,
+}
+
+/// Isochronous Command (NICMD)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IsochronousCommand {
+    /// Reserved bits 5:0
+    pub rsvdp: u8,
+    /// ISOCH_Y: programmed isochronous payload size
+    pub payload_size: u8,
+    /// ISOCH_N: programmed number of isochronous transactions per isochronous period
+    pub transactions: u8,
+}
+impl From<IsochronousCommandProto> for IsochronousCommand {
+    fn from(proto: IsochronousCommandProto) -> Self {
+        Self {
+            rsvdp: proto.rsvdp(),
+            payload_size: proto.payload_size(),
+            transactions: proto.transactions(),
+        }
+    }
+}
+impl From<u16> for IsochronousCommand {
+    fn from(word: u16) -> Self { IsochronousCommandProto::from(word).into() }
+}
+impl From<IsochronousCommand> for IsochronousCommandProto {
+    fn from(data: IsochronousCommand) -> Self {
+        Self::new()
+            .with_rsvdp(data.rsvdp)
+            .with_payload_size(data.payload_size)
+            .with_transactions(data.transactions)
+    }
+}
+impl From<IsochronousCommand> for u16 {
+    fn from(data: IsochronousCommand) -> Self { IsochronousCommandProto::from(data).into() }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use pretty_assertions::assert_eq;
+    use super::*;
+
+    #[test]
+    fn agp2() {
+        // Capabilities: [58] AGP version 2.0
+        //         Status: RQ=32 Iso- ArqSz=0 Cal=0 SBA+ ITACoh- GART64- HTrans- 64bit- FW+ AGP3- Rate=x1,x2,x4
+        //         Command: RQ=1 ArqSz=0 Cal=0 SBA+ AGP+ GART64- 64bit- FW- Rate=x4
+        let data = [0x20, 0x00, 0x17, 0x02, 0x00, 0x1f, 0x04, 0x03, 0x00, 0x00];
+        let result: AcceleratedGraphicsPort = data.read_with(&mut 0, LE).unwrap();
+        let sample = AcceleratedGraphicsPort {
+            version: Version { major: 2, minor: 0 },
+            reserved: 0,
+            status: Status {
+                data_rate: DataRate(0b111),
+                agp3_mode: false,
+                fast_writes: true,
+                over_4g: false,
+                no_host_translation: false,
+                gart64: false,
+                ita_coherent: false,
+                side_band_addressing: true,
+                calibration_cycle: CalibrationCycle::Ms4,
+                async_request_size: 0,
+                rsvdp_0: false,
+                isochronous: false,
+                rsvdp_1: 0,
+                request_queue: 31,
+            },
+            command: Command {
+                data_rate: DataRate(0b100),
+                rsvdp_0: false,
+                fast_writes_enable: false,
+                over_4g_enable: false,
+                rsvdp_1: false,
+                gart64_enable: false,
+                agp_enable: true,
+                side_band_addressing_enable: true,
+                calibration_cycle: CalibrationCycle::Ms4,
+                async_request_size: 0,
+                rsvdp_2: 0,
+                request_queue: 0,
+            },
+            isochronous: None,
+        };
+        assert_eq!(sample, result);
+        assert!(!result.is_agp3_mode());
+        assert_eq!(vec![1, 2, 4], result.status.data_rate.multipliers(false).collect::<Vec<_>>());
+
+        let mut buf = [0u8; 10];
+        let len = &mut 0;
+        buf.write_with(len, result, LE).unwrap();
+        assert_eq!(data, buf[..*len]);
+    }
+
+    #[test]
+    fn agp3_isochronous() {
+        let mut data = [0u8; 0x20];
+        // Version 3.5
+        data[0] = 0x35;
+        // Status: RQ=32 Iso+ ArqSz=2 Cal=3 SBA+ AGP3+ Rate=x4,x8
+        data[2..6].copy_from_slice(&0x1f024e0bu32.to_le_bytes());
+        // Command: RQ=16 ArqSz=2 Cal=3 SBA+ AGP+ Rate=x8
+        data[6..10].copy_from_slice(&0x0f004f02u32.to_le_bytes());
+        // NISTAT: MAXBW=16 N=8 Y=1 L=2
+        data[10..14].copy_from_slice(&0x00100850u32.to_le_bytes());
+        // Target-only registers
+        data[14..30].fill(0xff);
+        // NICMD: N=4 Y=1
+        data[30..32].copy_from_slice(&0x0440u16.to_le_bytes());
+
+        let result: AcceleratedGraphicsPort = data.read_with(&mut 0, LE).unwrap();
+        assert!(result.is_agp3_mode());
+        assert_eq!(CalibrationCycle::Ms256, result.status.calibration_cycle);
+        assert_eq!(vec![4, 8], result.status.data_rate.multipliers(true).collect::<Vec<_>>());
+        assert_eq!(vec![8], result.command.data_rate.multipliers(true).collect::<Vec<_>>());
+        let sample = Isochronous {
+            status: IsochronousStatus {
+                rsvdp_0: 0,
+                latency: 2,
+                payload_size: 1,
+                transactions: 8,
+                max_bandwidth: 16,
+                rsvdp_1: 0,
+            },
+            target_registers: [0xff; TARGET_REGISTERS_LEN],
+            command: IsochronousCommand {
+                rsvdp: 0,
+                payload_size: 1,
+                transactions: 4,
+            },
+        };
+        assert_eq!(Some(sample), result.isochronous);
+
+        let mut buf = [0u8; 0x20];
+        let len = &mut 0;
+        buf.write_with(len, Agp8x(result), LE).unwrap();
+        assert_eq!(0x20, *len);
+        assert_eq!(data, buf);
+    }
+
+    #[test]
+    fn agp3_without_isochronous() {
+        let mut data = [0u8; 0x20];
+        data[0] = 0x30;
+        // Status: RQ=32 Iso- ArqSz=2 Cal=3 SBA+ AGP3+ Rate=x4,x8
+        data[2..6].copy_from_slice(&0x1f004e0bu32.to_le_bytes());
+        // Target-only registers
+        data[14..30].copy_from_slice(&[0x5a; TARGET_REGISTERS_LEN]);
+        let result: AcceleratedGraphicsPort = data.read_with(&mut 0, LE).unwrap();
+        assert!(!result.status.isochronous);
+        let isochronous = result.isochronous.as_ref().unwrap();
+        assert_eq!([0x5a; TARGET_REGISTERS_LEN], isochronous.target_registers);
+
+        let mut buf = [0u8; 0x20];
+        let len = &mut 0;
+        buf.write_with(len, result, LE).unwrap();
+        assert_eq!(0x20, *len);
+        assert_eq!(data, buf);
+        assert!(data[..0x1e].read_with::<AcceleratedGraphicsPort>(&mut 0, LE).is_err());
+    }
+}
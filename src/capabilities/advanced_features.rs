@@ -9,12 +9,13 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AdvancedFeatures {
     /// AF Structure Length (Bytes). Shall return a value of 06h.
     pub length: u8,
@@ -34,6 +35,16 @@ impl<'a> TryRead<'a, Endian> for AdvancedFeatures {
         Ok((af, *offset))
     }
 }
+impl TryWrite<Endian> for AdvancedFeatures {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u8>(offset, self.length, endian)?;
+        bytes.write_with::<u8>(offset, self.capabilities.into(), endian)?;
+        bytes.write_with::<u8>(offset, self.control.into(), endian)?;
+        bytes.write_with::<u8>(offset, self.status.into(), endian)?;
+        Ok(*offset)
+    }
+}
 
 #[bitfield(bits = 8)]
 #[repr(u8)]
@@ -45,25 +56,39 @@ pub struct CapabilitiesProto {
 
 /// AF Capabilities
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Capabilities {
     /// Indicate support for the Transactions Pending (TP) bit. TP must be supported if FLR is
     /// supported.
     pub transactions_pending: bool,
     /// indicate support for Function Level Reset (FLR).
     pub function_level_reset: bool,
+    /// Reserved bits 7:2
+    pub reserved: u8,
 }
 impl From<CapabilitiesProto> for Capabilities {
     fn from(proto: CapabilitiesProto) -> Self {
-        let _ = proto.reserved();
         Self {
             transactions_pending: proto.transactions_pending(),
             function_level_reset: proto.function_level_reset(),
+            reserved: proto.reserved(),
         }
     }
 }
 impl From<u8> for Capabilities {
     fn from(byte: u8) -> Self { CapabilitiesProto::from(byte).into() }
 }
+impl From<Capabilities> for CapabilitiesProto {
+    fn from(data: Capabilities) -> Self {
+        Self::new()
+            .with_transactions_pending(data.transactions_pending)
+            .with_function_level_reset(data.function_level_reset)
+            .with_reserved(data.reserved)
+    }
+}
+impl From<Capabilities> for u8 {
+    fn from(data: Capabilities) -> Self { CapabilitiesProto::from(data).into() }
+}
 
 #[bitfield(bits = 8)]
 #[repr(u8)]
@@ -74,22 +99,35 @@ pub struct ControlProto {
 
 /// AF Control
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Control {
     /// A write of 1b initiates Function Level Reset (FLR). The value read by software from this
     /// bit shall always be 0b.
     pub initiate_flr: bool,
+    /// Reserved bits 7:1
+    pub reserved: u8,
 }
 impl From<ControlProto> for Control {
     fn from(proto: ControlProto) -> Self {
-        let _ = proto.reserved();
         Self {
             initiate_flr: proto.initiate_flr(),
+            reserved: proto.reserved(),
         }
     }
 }
 impl From<u8> for Control {
     fn from(byte: u8) -> Self { ControlProto::from(byte).into() }
 }
+impl From<Control> for ControlProto {
+    fn from(data: Control) -> Self {
+        Self::new()
+            .with_initiate_flr(data.initiate_flr)
+            .with_reserved(data.reserved)
+    }
+}
+impl From<Control> for u8 {
+    fn from(data: Control) -> Self { ControlProto::from(data).into() }
+}
 
 
 #[bitfield(bits = 8)]
@@ -101,19 +139,32 @@ pub struct StatusProto {
 
 /// AF Status
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Status {
     /// Indicates that the Function has issued one or more non-posted transactions which have not
     /// been completed, including non-posted transactions that a target has terminated with Retry
     pub transactions_pending: bool,
+    /// Reserved bits 7:1
+    pub reserved: u8,
 }
 impl From<StatusProto> for Status {
     fn from(proto: StatusProto) -> Self {
-        let _ = proto.reserved();
         Self {
             transactions_pending: proto.transactions_pending(),
+            reserved: proto.reserved(),
         }
     }
 }
 impl From<u8> for Status {
     fn from(byte: u8) -> Self { StatusProto::from(byte).into() }
 }
+impl From<Status> for StatusProto {
+    fn from(data: Status) -> Self {
+        Self::new()
+            .with_transactions_pending(data.transactions_pending)
+            .with_reserved(data.reserved)
+    }
+}
+impl From<Status> for u8 {
+    fn from(data: Status) -> Self { StatusProto::from(data).into() }
+}
@@ -4,6 +4,7 @@ use byte::{
     ctx::*,
     self,
     TryRead,
+    TryWrite,
     BytesExt,
 };
 
@@ -26,6 +27,7 @@ use byte::{
 
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BridgeSubsystemVendorId {
     pub reserved: u16,
     /// PCI Bridge Subsystem Vendor ID
@@ -45,3 +47,12 @@ impl<'a> TryRead<'a, Endian> for BridgeSubsystemVendorId {
         Ok((bsv, *offset))
     }
 }
+impl TryWrite<Endian> for BridgeSubsystemVendorId {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u16>(offset, self.reserved, endian)?;
+        bytes.write_with::<u16>(offset, self.subsystem_vendor_id, endian)?;
+        bytes.write_with::<u16>(offset, self.subsystem_id, endian)?;
+        Ok(*offset)
+    }
+}
@@ -0,0 +1,122 @@
+//! CompactPCI Hot Swap
+//!
+//! Hot Swap Control/Status Register (HS_CSR) defined by PICMG 2.1 CompactPCI Hot Swap
+//! Specification. System host watches ENUM# status bits to find boards being inserted or about to
+//! be extracted and drives the blue LED through LOO bit.
+
+use modular_bitfield::prelude::*;
+use byte::{
+    ctx::*,
+    self,
+    TryRead,
+    TryWrite,
+    BytesExt,
+};
+
+
+#[bitfield(bits = 8)]
+#[repr(u8)]
+pub struct CompactPciHotSwapProto {
+    device_hiding_arm: bool,
+    enum_interrupt_mask: bool,
+    pending_insert_or_extract: bool,
+    led_on_off: bool,
+    programming_interface: B2,
+    enum_extraction_status: bool,
+    enum_insertion_status: bool,
+}
+
+/// Hot Swap Control/Status Register
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CompactPciHotSwap {
+    /// Device Hiding Arm (DHA), hardware connection control of R2.0 devices is armed
+    pub device_hiding_arm: bool,
+    /// ENUM# Signal Mask (EIM), board does not assert ENUM# signal
+    pub enum_interrupt_mask: bool,
+    /// Pending Insertion or Extraction (PIE), R2.0 devices report insertion or extraction is
+    /// in process
+    pub pending_insert_or_extract: bool,
+    /// LED On/Off (LOO), blue LED is lit
+    pub led_on_off: bool,
+    /// Programming Interface (PI), 00b – INS, EXT, LOO and EIM bits are implemented
+    pub programming_interface: u8,
+    /// ENUM# Status - Extraction (EXT), board is about to be extracted
+    pub enum_extraction_status: bool,
+    /// ENUM# Status - Insertion (INS), board has been inserted
+    pub enum_insertion_status: bool,
+}
+impl From<CompactPciHotSwapProto> for CompactPciHotSwap {
+    fn from(proto: CompactPciHotSwapProto) -> Self {
+        Self {
+            device_hiding_arm: proto.device_hiding_arm(),
+            enum_interrupt_mask: proto.enum_interrupt_mask(),
+            pending_insert_or_extract: proto.pending_insert_or_extract(),
+            led_on_off: proto.led_on_off(),
+            programming_interface: proto.programming_interface(),
+            enum_extraction_status: proto.enum_extraction_status(),
+            enum_insertion_status: proto.enum_insertion_status(),
+        }
+    }
+}
+impl From<u8> for CompactPciHotSwap {
+    fn from(byte: u8) -> Self { CompactPciHotSwapProto::from(byte).into() }
+}
+impl From<CompactPciHotSwap> for CompactPciHotSwapProto {
+    fn from(data: CompactPciHotSwap) -> Self {
+        Self::new()
+            .with_device_hiding_arm(data.device_hiding_arm)
+            .with_enum_interrupt_mask(data.enum_interrupt_mask)
+            .with_pending_insert_or_extract(data.pending_insert_or_extract)
+            .with_led_on_off(data.led_on_off)
+            .with_programming_interface(data.programming_interface)
+            .with_enum_extraction_status(data.enum_extraction_status)
+            .with_enum_insertion_status(data.enum_insertion_status)
+    }
+}
+impl From<CompactPciHotSwap> for u8 {
+    fn from(data: CompactPciHotSwap) -> Self { CompactPciHotSwapProto::from(data).into() }
+}
+impl<'a> TryRead<'a, Endian> for CompactPciHotSwap {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let hs = bytes.read_with::<u8>(offset, endian)?.into();
+        Ok((hs, *offset))
+    }
+}
+impl TryWrite<Endian> for CompactPciHotSwap {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u8>(offset, self.into(), endian)?;
+        Ok(*offset)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use super::*;
+
+    #[test]
+    fn insertion() {
+        // INS set, LED lit, ENUM# masked
+        let data = [0x8a];
+        let result: CompactPciHotSwap = data.read_with(&mut 0, LE).unwrap();
+        let sample = CompactPciHotSwap {
+            device_hiding_arm: false,
+            enum_interrupt_mask: true,
+            pending_insert_or_extract: false,
+            led_on_off: true,
+            programming_interface: 0,
+            enum_extraction_status: false,
+            enum_insertion_status: true,
+        };
+        assert_eq!(sample, result);
+
+        let mut buf = [0u8; 1];
+        buf.write_with(&mut 0, result, LE).unwrap();
+        assert_eq!(data, buf);
+    }
+}
@@ -4,13 +4,14 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
 
 /// Debug port
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DebugPort {
     /// Indicates the byte offset (up to 4K) within the BAR indicated by BAR#
     pub offset: u16,
@@ -29,3 +30,11 @@ impl<'a> TryRead<'a, Endian> for DebugPort {
         Ok((dp, *offset))
     }
 }
+impl TryWrite<Endian> for DebugPort {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        let word = (self.offset & 0x1fff) | ((self.bar_number as u16) << 13);
+        bytes.write_with::<u16>(offset, word, endian)?;
+        Ok(*offset)
+    }
+}
@@ -0,0 +1,401 @@
+//! Enhanced Allocation
+//!
+//! The Enhanced Allocation (EA) Capability is an optional Capability that allows the allocation
+//! of I/O, Memory and Bus Number resources in ways not possible with the BAR and Base/Limit
+//! mechanisms in the Type 0 and Type 1 Configuration Headers. Each resource is described by a
+//! variable-size entry.
+
+use modular_bitfield::prelude::*;
+use byte::{
+    ctx::*,
+    self,
+    TryRead,
+    TryWrite,
+    BytesExt,
+};
+
+use crate::RawBytes;
+
+
+/// Context for reading [EnhancedAllocation]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnhancedAllocationCtx {
+    pub endian: Endian,
+    /// Function has Type 01h Configuration Space header
+    pub is_bridge: bool,
+}
+
+/// Enhanced Allocation capability structure
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EnhancedAllocation<'a> {
+    /// Number of entries following the first DW of the capability (or the second DW for Type 1
+    /// functions)
+    pub num_entries: u8,
+    /// Reserved bits 15:6 following Num Entries
+    pub reserved: u16,
+    /// Present in Type 01h functions only
+    pub fixed_bus_numbers: Option<FixedBusNumbers>,
+    /// Raw entries, see [EnhancedAllocation::entries]
+    pub entries_data: RawBytes<'a>,
+}
+impl<'a> EnhancedAllocation<'a> {
+    pub fn entries(&self) -> Entries<'_> {
+        Entries::new(&self.entries_data)
+    }
+}
+impl<'a> TryRead<'a, EnhancedAllocationCtx> for EnhancedAllocation<'a> {
+    fn try_read(bytes: &'a [u8], ctx: EnhancedAllocationCtx) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let word = bytes.read_with::<u16>(offset, ctx.endian)?;
+        let num_entries = word as u8 & 0x3f;
+        let fixed_bus_numbers = if ctx.is_bridge {
+            Some(bytes.read_with::<u32>(offset, ctx.endian)?.into())
+        } else {
+            None
+        };
+        let start = *offset;
+        for _ in 0..num_entries {
+            let _: Entry = bytes.read_with(offset, ctx.endian)?;
+        }
+        let ea = EnhancedAllocation {
+            num_entries,
+            reserved: word >> 6,
+            fixed_bus_numbers,
+            entries_data: RawBytes::new(&bytes[start..*offset]),
+        };
+        Ok((ea, *offset))
+    }
+}
+impl<'a> TryWrite<Endian> for EnhancedAllocation<'a> {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        let word = self.reserved << 6 | u16::from(self.num_entries & 0x3f);
+        bytes.write_with::<u16>(offset, word, endian)?;
+        if let Some(fixed_bus_numbers) = self.fixed_bus_numbers {
+            bytes.write_with::<u32>(offset, fixed_bus_numbers.into(), endian)?;
+        }
+        bytes.write::<&[u8]>(offset, &self.entries_data)?;
+        Ok(*offset)
+    }
+}
+
+/// Bus numbers fixed by Type 1 function
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FixedBusNumbers {
+    /// Fixed Secondary Bus Number
+    pub secondary: u8,
+    /// Fixed Subordinate Bus Number
+    pub subordinate: u8,
+    /// Reserved bits 31:16
+    pub reserved: u16,
+}
+impl From<u32> for FixedBusNumbers {
+    fn from(dword: u32) -> Self {
+        Self {
+            secondary: dword as u8,
+            subordinate: (dword >> 8) as u8,
+            reserved: (dword >> 16) as u16,
+        }
+    }
+}
+impl From<FixedBusNumbers> for u32 {
+    fn from(data: FixedBusNumbers) -> Self {
+        (data.reserved as u32) << 16 | (data.subordinate as u32) << 8 | data.secondary as u32
+    }
+}
+
+
+/// An iterator through EA entries
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entries<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+impl<'a> Entries<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+}
+impl<'a> Iterator for Entries<'a> {
+    type Item = Entry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.data.read_with(&mut self.offset, LE).ok()
+    }
+}
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct EntryHeaderProto {
+    entry_size: B3,
+    rsvdp_0: B1,
+    bar_equivalent_indicator: B4,
+    primary_properties: B8,
+    secondary_properties: B8,
+    rsvdp_1: B6,
+    writable: bool,
+    enable: bool,
+}
+
+/// EA entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Entry {
+    /// Number of DW following the initial DW in this entry
+    pub entry_size: u8,
+    pub bar_equivalent_indicator: BarEquivalentIndicator,
+    pub primary_properties: EntryProperties,
+    /// Used if Primary Properties are not understood by software
+    pub secondary_properties: EntryProperties,
+    /// Base and MaxOffset are writable
+    pub writable: bool,
+    /// Entry is enabled
+    pub enable: bool,
+    /// Base address of the resource, DW aligned
+    pub base: u64,
+    /// Base is 64-bit
+    pub is_base_64bit: bool,
+    /// Offset of the last byte of the resource from the Base
+    pub max_offset: u64,
+    /// MaxOffset is 64-bit
+    pub is_max_offset_64bit: bool,
+}
+impl<'a> TryRead<'a, Endian> for Entry {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let header: EntryHeaderProto = bytes.read_with::<u32>(offset, endian)?.into();
+        let _ = header.rsvdp_0();
+        let _ = header.rsvdp_1();
+        let entry_size = header.entry_size();
+        let data: &[u8] = bytes.read_with(offset, Bytes::Len(usize::from(entry_size) * 4))?;
+        let too_small = byte::Error::BadInput { err: "EA entry size is too small" };
+        if entry_size < 2 {
+            return Err(too_small);
+        }
+        let data_offset = &mut 0;
+        let base_lo = data.read_with::<u32>(data_offset, endian)?;
+        let max_offset_lo = data.read_with::<u32>(data_offset, endian)?;
+        let is_base_64bit = base_lo & 0b10 != 0;
+        let is_max_offset_64bit = max_offset_lo & 0b10 != 0;
+        if entry_size < 2 + is_base_64bit as u8 + is_max_offset_64bit as u8 {
+            return Err(too_small);
+        }
+        let base_hi = if is_base_64bit { data.read_with::<u32>(data_offset, endian)? } else { 0 };
+        let max_offset_hi =
+            if is_max_offset_64bit { data.read_with::<u32>(data_offset, endian)? } else { 0 };
+        let entry = Entry {
+            entry_size,
+            bar_equivalent_indicator: header.bar_equivalent_indicator().into(),
+            primary_properties: header.primary_properties().into(),
+            secondary_properties: header.secondary_properties().into(),
+            writable: header.writable(),
+            enable: header.enable(),
+            base: (base_hi as u64) << 32 | (base_lo & !0b11) as u64,
+            is_base_64bit,
+            max_offset: (max_offset_hi as u64) << 32 | (max_offset_lo | 0b11) as u64,
+            is_max_offset_64bit,
+        };
+        Ok((entry, *offset))
+    }
+}
+
+/// BAR Equivalent Indicator (BEI)
+///
+/// Indicates the equivalent BAR for this entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BarEquivalentIndicator {
+    /// Entry is equivalent to BAR at location 10h + n * 4
+    Bar(u8),
+    /// Entry for resource behind the function (Type 1 functions only)
+    ResourceBehindFunction,
+    /// Equivalent not indicated
+    NotIndicated,
+    /// Expansion ROM Base Address
+    ExpansionRom,
+    /// Entry is equivalent to VF BAR n in SR-IOV Capability
+    VfBar(u8),
+    Reserved(u8),
+}
+impl From<u8> for BarEquivalentIndicator {
+    fn from(byte: u8) -> Self {
+        match byte {
+            0..=5 => Self::Bar(byte),
+            6 => Self::ResourceBehindFunction,
+            7 => Self::NotIndicated,
+            8 => Self::ExpansionRom,
+            9..=14 => Self::VfBar(byte - 9),
+            v => Self::Reserved(v),
+        }
+    }
+}
+impl From<BarEquivalentIndicator> for u8 {
+    fn from(data: BarEquivalentIndicator) -> Self {
+        match data {
+            BarEquivalentIndicator::Bar(n) => n,
+            BarEquivalentIndicator::ResourceBehindFunction => 6,
+            BarEquivalentIndicator::NotIndicated => 7,
+            BarEquivalentIndicator::ExpansionRom => 8,
+            BarEquivalentIndicator::VfBar(n) => n + 9,
+            BarEquivalentIndicator::Reserved(v) => v,
+        }
+    }
+}
+
+/// Primary / Secondary Properties
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EntryProperties {
+    /// Memory Space, Non-Prefetchable
+    MemoryNonPrefetchable,
+    /// Memory Space, Prefetchable
+    MemoryPrefetchable,
+    /// I/O Space
+    Io,
+    /// Resource for Virtual Function use, Memory Space, Prefetchable
+    VfMemoryPrefetchable,
+    /// Resource for Virtual Function use, Memory Space, Non-Prefetchable
+    VfMemoryNonPrefetchable,
+    /// Memory behind Type 1 function, Non-Prefetchable
+    BridgeMemoryNonPrefetchable,
+    /// Memory behind Type 1 function, Prefetchable
+    BridgeMemoryPrefetchable,
+    /// I/O Space behind Type 1 function
+    BridgeIo,
+    Reserved(u8),
+    /// Memory Resource is Unavailable for use
+    MemoryUnavailable,
+    /// I/O Resource is Unavailable for use
+    IoUnavailable,
+    /// Entry Unavailable for use
+    Unavailable,
+}
+impl From<u8> for EntryProperties {
+    fn from(byte: u8) -> Self {
+        match byte {
+            0x00 => Self::MemoryNonPrefetchable,
+            0x01 => Self::MemoryPrefetchable,
+            0x02 => Self::Io,
+            0x03 => Self::VfMemoryPrefetchable,
+            0x04 => Self::VfMemoryNonPrefetchable,
+            0x05 => Self::BridgeMemoryNonPrefetchable,
+            0x06 => Self::BridgeMemoryPrefetchable,
+            0x07 => Self::BridgeIo,
+            0xfd => Self::MemoryUnavailable,
+            0xfe => Self::IoUnavailable,
+            0xff => Self::Unavailable,
+            v => Self::Reserved(v),
+        }
+    }
+}
+impl From<EntryProperties> for u8 {
+    fn from(data: EntryProperties) -> Self {
+        match data {
+            EntryProperties::MemoryNonPrefetchable => 0x00,
+            EntryProperties::MemoryPrefetchable => 0x01,
+            EntryProperties::Io => 0x02,
+            EntryProperties::VfMemoryPrefetchable => 0x03,
+            EntryProperties::VfMemoryNonPrefetchable => 0x04,
+            EntryProperties::BridgeMemoryNonPrefetchable => 0x05,
+            EntryProperties::BridgeMemoryPrefetchable => 0x06,
+            EntryProperties::BridgeIo => 0x07,
+            EntryProperties::Reserved(v) => v,
+            EntryProperties::MemoryUnavailable => 0xfd,
+            EntryProperties::IoUnavailable => 0xfe,
+            EntryProperties::Unavailable => 0xff,
+        }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use pretty_assertions::assert_eq;
+    use super::*;
+
+    // Capabilities: [40] Enhanced Allocation (EA): NumEntries=2
+    //         Entry 0: Enable+ Writable- EntrySize=3
+    //                  BAR Equivalent Indicator: BAR 0
+    //                  PrimaryProperties: memory space, non-prefetchable
+    //                  SecondaryProperties: entry unavailable for use, PrimaryProperties should be used
+    //                  Base: 872000000
+    //                  MaxOffset: 000fffff
+    //         Entry 1: Enable+ Writable- EntrySize=4
+    //                  BAR Equivalent Indicator: VF-BAR 0
+    //                  PrimaryProperties: VF memory space, non-prefetchable
+    //                  SecondaryProperties: entry unavailable for use, PrimaryProperties should be used
+    //                  Base: 840000000
+    //                  MaxOffset: 0001fffff
+    const DATA: [u8; 38] = [
+        0x02, 0x00,
+        0x03, 0x00, 0xff, 0x80, 0x02, 0x00, 0x00, 0x72, 0xfc, 0xff, 0x0f, 0x00, 0x08, 0x00, 0x00, 0x00,
+        0x94, 0x04, 0xff, 0x80, 0x02, 0x00, 0x00, 0x40, 0xfe, 0xff, 0x1f, 0x00, 0x08, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn entries() {
+        let ctx = EnhancedAllocationCtx { endian: LE, is_bridge: false };
+        let ea: EnhancedAllocation = DATA.read_with(&mut 0, ctx).unwrap();
+        assert_eq!(2, ea.num_entries);
+        assert_eq!(None, ea.fixed_bus_numbers);
+        let result = ea.entries().collect::<Vec<_>>();
+        let sample = vec![
+            Entry {
+                entry_size: 3,
+                bar_equivalent_indicator: BarEquivalentIndicator::Bar(0),
+                primary_properties: EntryProperties::MemoryNonPrefetchable,
+                secondary_properties: EntryProperties::Unavailable,
+                writable: false,
+                enable: true,
+                base: 0x8_7200_0000,
+                is_base_64bit: true,
+                max_offset: 0x000f_ffff,
+                is_max_offset_64bit: false,
+            },
+            Entry {
+                entry_size: 4,
+                bar_equivalent_indicator: BarEquivalentIndicator::VfBar(0),
+                primary_properties: EntryProperties::VfMemoryNonPrefetchable,
+                secondary_properties: EntryProperties::Unavailable,
+                writable: false,
+                enable: true,
+                base: 0x8_4000_0000,
+                is_base_64bit: true,
+                max_offset: 0x001f_ffff,
+                is_max_offset_64bit: true,
+            },
+        ];
+        assert_eq!(sample, result);
+
+        let mut buf = [0u8; 38];
+        buf.write_with(&mut 0, ea, LE).unwrap();
+        assert_eq!(DATA, buf);
+    }
+
+    #[test]
+    fn bridge() {
+        let data = [0x00, 0x00, 0x01, 0x05, 0x00, 0x00];
+        let ctx = EnhancedAllocationCtx { endian: LE, is_bridge: true };
+        let (ea, len) = EnhancedAllocation::try_read(&data, ctx).unwrap();
+        assert_eq!(6, len);
+        let sample = FixedBusNumbers { secondary: 1, subordinate: 5, reserved: 0 };
+        assert_eq!(Some(sample), ea.fixed_bus_numbers);
+        assert_eq!(0, ea.entries().count());
+    }
+
+    #[test]
+    fn entry_too_small() {
+        // Entry size 2 with 64-bit Base
+        let data = [0x01, 0x00, 0x02, 0x00, 0x00, 0x80, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+        let ctx = EnhancedAllocationCtx { endian: LE, is_bridge: false };
+        assert_eq!(
+            Err(byte::Error::BadInput { err: "EA entry size is too small" }),
+            EnhancedAllocation::try_read(&data, ctx).map(|_| ())
+        );
+    }
+}
@@ -0,0 +1,637 @@
+//! Flattening Portal Bridge
+//!
+//! The Flattening Portal Bridge (FPB) is an optional mechanism which can be used to improve the
+//! scalability and runtime reallocation of Routing IDs and Memory Space resources. A bridge with
+//! enabled FPB decode mechanism claims resources described by bit vectors in addition to the Bus
+//! Number and Memory window registers of its Type 01h Configuration Space header.
+//!
+//! Each vector bit stands for a block of [granularity](RidVectorControl::vector_granularity)
+//! resources, blocks are contiguous starting at the vector start. Vectors themselves are accessed
+//! indirectly through [VectorAccessControl] and [FlatteningPortalBridge::vector_access_data].
+
+use core::ops::Range;
+use modular_bitfield::prelude::*;
+use byte::{
+    ctx::*,
+    self,
+    TryRead,
+    TryWrite,
+    BytesExt,
+};
+
+/// Routing IDs are 16-bit: Bus, Device and Function Numbers
+const RID_SPACE_END: u32 = 0x1_0000;
+/// MEM Low vector covers 32-bit address space only
+const MEM_LOW_END: u64 = 1 << 32;
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlatteningPortalBridge {
+    /// Reserved bits 31:16 of the capability header
+    pub reserved: u16,
+    pub capabilities: Capabilities,
+    pub rid_vector_control: RidVectorControl,
+    pub mem_low_vector_control: MemLowVectorControl,
+    pub mem_high_vector_control: MemHighVectorControl,
+    pub vector_access_control: VectorAccessControl,
+    /// DWORD of the selected vector at [VectorAccessControl::vector_access_offset]
+    pub vector_access_data: u32,
+}
+impl FlatteningPortalBridge {
+    /// Routing IDs covered by the RID vector, if RID decode mechanism is enabled, limited to the
+    /// RID space
+    pub fn rid_range(&self) -> Option<Range<u32>> {
+        if !self.rid_vector_control.decode_mechanism_enable {
+            return None;
+        }
+        self.rid_vector_control.range(self.capabilities.rid_vector_size_supported)
+    }
+    /// Memory addresses below 4 GB covered by the MEM Low vector, if MEM Low decode mechanism is
+    /// enabled, limited to 32-bit address space
+    pub fn mem_low_range(&self) -> Option<Range<u64>> {
+        if !self.mem_low_vector_control.decode_mechanism_enable {
+            return None;
+        }
+        self.mem_low_vector_control.range(self.capabilities.mem_low_vector_size_supported)
+    }
+    /// Memory addresses covered by the MEM High vector, if MEM High decode mechanism is enabled
+    pub fn mem_high_range(&self) -> Option<Range<u64>> {
+        if !self.mem_high_vector_control.decode_mechanism_enable {
+            return None;
+        }
+        self.mem_high_vector_control.range(self.capabilities.mem_high_vector_size_supported)
+    }
+}
+impl<'a> TryRead<'a, Endian> for FlatteningPortalBridge {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let reserved = bytes.read_with::<u16>(offset, endian)?;
+        let capabilities = bytes.read_with::<u32>(offset, endian)?.into();
+        let rid_1: RidVectorControl1Proto = bytes.read_with::<u32>(offset, endian)?.into();
+        let rid_2: RidVectorControl2Proto = bytes.read_with::<u32>(offset, endian)?.into();
+        let mem_low: MemLowVectorControlProto = bytes.read_with::<u32>(offset, endian)?.into();
+        let mem_high_1: MemHighVectorControl1Proto = bytes.read_with::<u32>(offset, endian)?.into();
+        let mem_high_2 = bytes.read_with::<u32>(offset, endian)?;
+        let vector_access_control = bytes.read_with::<u32>(offset, endian)?.into();
+        let vector_access_data = bytes.read_with::<u32>(offset, endian)?;
+        let fpb = FlatteningPortalBridge {
+            reserved,
+            capabilities,
+            rid_vector_control: RidVectorControl {
+                decode_mechanism_enable: rid_1.decode_mechanism_enable(),
+                rsvdp: rid_1.rsvdp(),
+                vector_granularity: RidVectorGranularity(rid_1.vector_granularity()),
+                rsvdp_1: rid_1.rsvdp_1(),
+                vector_start: rid_1.vector_start() << 3,
+                rsvdp_2: rid_2.rsvdp(),
+                secondary_start: rid_2.secondary_start() << 3,
+                rsvdp_3: rid_2.rsvdp_1(),
+            },
+            mem_low_vector_control: MemLowVectorControl {
+                decode_mechanism_enable: mem_low.decode_mechanism_enable(),
+                rsvdp: mem_low.rsvdp(),
+                vector_granularity: MemLowVectorGranularity(mem_low.vector_granularity()),
+                rsvdp_1: mem_low.rsvdp_1(),
+                vector_start: u32::from(mem_low.vector_start()) << 20,
+            },
+            mem_high_vector_control: MemHighVectorControl {
+                decode_mechanism_enable: mem_high_1.decode_mechanism_enable(),
+                rsvdp: mem_high_1.rsvdp(),
+                vector_granularity: MemHighVectorGranularity(mem_high_1.vector_granularity()),
+                rsvdp_1: mem_high_1.rsvdp_1(),
+                vector_start: (u64::from(mem_high_2) << 32)
+                    | (u64::from(mem_high_1.vector_start_lower()) << 28),
+            },
+            vector_access_control,
+            vector_access_data,
+        };
+        Ok((fpb, *offset))
+    }
+}
+impl TryWrite<Endian> for FlatteningPortalBridge {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        let rid = self.rid_vector_control;
+        let rid_1 = RidVectorControl1Proto::new()
+            .with_decode_mechanism_enable(rid.decode_mechanism_enable)
+            .with_rsvdp(rid.rsvdp)
+            .with_vector_granularity(rid.vector_granularity.0)
+            .with_rsvdp_1(rid.rsvdp_1)
+            .with_vector_start(rid.vector_start >> 3);
+        let rid_2 = RidVectorControl2Proto::new()
+            .with_rsvdp(rid.rsvdp_2)
+            .with_secondary_start(rid.secondary_start >> 3)
+            .with_rsvdp_1(rid.rsvdp_3);
+        let mem_low = self.mem_low_vector_control;
+        let mem_low = MemLowVectorControlProto::new()
+            .with_decode_mechanism_enable(mem_low.decode_mechanism_enable)
+            .with_rsvdp(mem_low.rsvdp)
+            .with_vector_granularity(mem_low.vector_granularity.0)
+            .with_rsvdp_1(mem_low.rsvdp_1)
+            .with_vector_start((mem_low.vector_start >> 20) as u16);
+        let mem_high = self.mem_high_vector_control;
+        let mem_high_1 = MemHighVectorControl1Proto::new()
+            .with_decode_mechanism_enable(mem_high.decode_mechanism_enable)
+            .with_rsvdp(mem_high.rsvdp)
+            .with_vector_granularity(mem_high.vector_granularity.0)
+            .with_rsvdp_1(mem_high.rsvdp_1)
+            .with_vector_start_lower((mem_high.vector_start >> 28) as u8 & 0x0f);
+        bytes.write_with::<u16>(offset, self.reserved, endian)?;
+        bytes.write_with::<u32>(offset, self.capabilities.into(), endian)?;
+        bytes.write_with::<u32>(offset, rid_1.into(), endian)?;
+        bytes.write_with::<u32>(offset, rid_2.into(), endian)?;
+        bytes.write_with::<u32>(offset, mem_low.into(), endian)?;
+        bytes.write_with::<u32>(offset, mem_high_1.into(), endian)?;
+        bytes.write_with::<u32>(offset, (mem_high.vector_start >> 32) as u32, endian)?;
+        bytes.write_with::<u32>(offset, self.vector_access_control.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.vector_access_data, endian)?;
+        Ok(*offset)
+    }
+}
+
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct CapabilitiesProto {
+    rid_decode_mechanism_supported: bool,
+    mem_low_decode_mechanism_supported: bool,
+    mem_high_decode_mechanism_supported: bool,
+    num_sec_dev: B5,
+    rid_vector_size_supported: B3,
+    rsvdp: B5,
+    mem_low_vector_size_supported: B3,
+    rsvdp_1: B5,
+    mem_high_vector_size_supported: B3,
+    rsvdp_2: B5,
+}
+
+/// FPB Capabilities
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Capabilities {
+    /// FPB RID Decode Mechanism Supported
+    pub rid_decode_mechanism_supported: bool,
+    /// FPB MEM Low Decode Mechanism Supported
+    pub mem_low_decode_mechanism_supported: bool,
+    /// FPB MEM High Decode Mechanism Supported
+    pub mem_high_decode_mechanism_supported: bool,
+    /// For Upstream Ports, quantity of Device Numbers associated with the Secondary Side of the
+    /// Upstream Port bridge minus one
+    pub num_sec_dev: u8,
+    pub rid_vector_size_supported: RidVectorSize,
+    /// Reserved bits 15:11
+    pub rsvdp: u8,
+    pub mem_low_vector_size_supported: MemLowVectorSize,
+    /// Reserved bits 23:19
+    pub rsvdp_1: u8,
+    pub mem_high_vector_size_supported: MemHighVectorSize,
+    /// Reserved bits 31:27
+    pub rsvdp_2: u8,
+}
+impl From<CapabilitiesProto> for Capabilities {
+    fn from(proto: CapabilitiesProto) -> Self {
+        Self {
+            rid_decode_mechanism_supported: proto.rid_decode_mechanism_supported(),
+            mem_low_decode_mechanism_supported: proto.mem_low_decode_mechanism_supported(),
+            mem_high_decode_mechanism_supported: proto.mem_high_decode_mechanism_supported(),
+            num_sec_dev: proto.num_sec_dev(),
+            rid_vector_size_supported: RidVectorSize(proto.rid_vector_size_supported()),
+            rsvdp: proto.rsvdp(),
+            mem_low_vector_size_supported: MemLowVectorSize(proto.mem_low_vector_size_supported()),
+            rsvdp_1: proto.rsvdp_1(),
+            mem_high_vector_size_supported: MemHighVectorSize(proto.mem_high_vector_size_supported()),
+            rsvdp_2: proto.rsvdp_2(),
+        }
+    }
+}
+impl From<u32> for Capabilities {
+    fn from(dword: u32) -> Self { CapabilitiesProto::from(dword).into() }
+}
+impl From<Capabilities> for CapabilitiesProto {
+    fn from(data: Capabilities) -> Self {
+        Self::new()
+            .with_rid_decode_mechanism_supported(data.rid_decode_mechanism_supported)
+            .with_mem_low_decode_mechanism_supported(data.mem_low_decode_mechanism_supported)
+            .with_mem_high_decode_mechanism_supported(data.mem_high_decode_mechanism_supported)
+            .with_num_sec_dev(data.num_sec_dev)
+            .with_rid_vector_size_supported(data.rid_vector_size_supported.0)
+            .with_rsvdp(data.rsvdp)
+            .with_mem_low_vector_size_supported(data.mem_low_vector_size_supported.0)
+            .with_rsvdp_1(data.rsvdp_1)
+            .with_mem_high_vector_size_supported(data.mem_high_vector_size_supported.0)
+            .with_rsvdp_2(data.rsvdp_2)
+    }
+}
+impl From<Capabilities> for u32 {
+    fn from(data: Capabilities) -> Self { CapabilitiesProto::from(data).into() }
+}
+
+/// Encoded number of bits in the RID vector
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RidVectorSize(pub u8);
+impl RidVectorSize {
+    /// 000b – 256 bits, 010b – 1K bits, 101b – 8K bits, other encodings are reserved
+    pub fn bits(&self) -> Option<u32> {
+        matches!(self.0, 0b000 | 0b010 | 0b101).then(|| 256 << self.0)
+    }
+}
+
+/// Encoded number of bits in the MEM Low vector
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemLowVectorSize(pub u8);
+impl MemLowVectorSize {
+    /// 000b – 256 bits, 001b – 512 bits, … 100b – 4K bits, other encodings are reserved
+    pub fn bits(&self) -> Option<u32> {
+        (self.0 <= 0b100).then(|| 256 << self.0)
+    }
+}
+
+/// Encoded number of bits in the MEM High vector
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemHighVectorSize(pub u8);
+impl MemHighVectorSize {
+    /// 000b – 256 bits, 001b – 512 bits, … 101b – 8K bits, other encodings are reserved
+    pub fn bits(&self) -> Option<u32> {
+        (self.0 <= 0b101).then(|| 256 << self.0)
+    }
+}
+
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct RidVectorControl1Proto {
+    decode_mechanism_enable: bool,
+    rsvdp: B3,
+    vector_granularity: B4,
+    rsvdp_1: B11,
+    vector_start: B13,
+}
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct RidVectorControl2Proto {
+    rsvdp: B3,
+    secondary_start: B13,
+    rsvdp_1: B16,
+}
+
+/// FPB RID Vector Control 1 and 2
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RidVectorControl {
+    /// FPB RID Decode Mechanism Enable
+    pub decode_mechanism_enable: bool,
+    /// Reserved bits 3:1 of RID Vector Control 1
+    pub rsvdp: u8,
+    pub vector_granularity: RidVectorGranularity,
+    /// Reserved bits 18:8 of RID Vector Control 1
+    pub rsvdp_1: u16,
+    /// RID of the first function covered by the vector, aligned to 8
+    pub vector_start: u16,
+    /// Reserved bits 2:0 of RID Vector Control 2
+    pub rsvdp_2: u8,
+    /// RID of the first function on the secondary side, aligned to 8
+    pub secondary_start: u16,
+    /// Reserved bits 31:16 of RID Vector Control 2
+    pub rsvdp_3: u16,
+}
+impl RidVectorControl {
+    /// Routing IDs covered by the vector of `size`, the vector part beyond RID space is ignored
+    pub fn range(&self, size: RidVectorSize) -> Option<Range<u32>> {
+        let len = size.bits()? * u32::from(self.vector_granularity.rids()?);
+        let start = u32::from(self.vector_start);
+        Some(start..(start + len).min(RID_SPACE_END))
+    }
+    /// Routing IDs claimed by the vector bit `n`, `None` if the bit is beyond RID space
+    pub fn bit_range(&self, n: u32) -> Option<Range<u32>> {
+        let granularity = u32::from(self.vector_granularity.rids()?);
+        let start = u32::from(self.vector_start).checked_add(n.checked_mul(granularity)?)?;
+        (start < RID_SPACE_END).then(|| start..(start + granularity).min(RID_SPACE_END))
+    }
+}
+
+/// Encoded number of Routing IDs each bit of the RID vector covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RidVectorGranularity(pub u8);
+impl RidVectorGranularity {
+    /// 0000b – 8 RIDs, 0001b – 64 RIDs, 0010b – 256 RIDs, other encodings are reserved
+    pub fn rids(&self) -> Option<u16> {
+        match self.0 {
+            0b0000 => Some(8),
+            0b0001 => Some(64),
+            0b0010 => Some(256),
+            _ => None,
+        }
+    }
+}
+
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct MemLowVectorControlProto {
+    decode_mechanism_enable: bool,
+    rsvdp: B3,
+    vector_granularity: B4,
+    rsvdp_1: B12,
+    vector_start: B12,
+}
+
+/// FPB MEM Low Vector Control
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemLowVectorControl {
+    /// FPB MEM Low Decode Mechanism Enable
+    pub decode_mechanism_enable: bool,
+    /// Reserved bits 3:1
+    pub rsvdp: u8,
+    pub vector_granularity: MemLowVectorGranularity,
+    /// Reserved bits 19:8
+    pub rsvdp_1: u16,
+    /// Address of the first byte covered by the vector, aligned to 1 MB
+    pub vector_start: u32,
+}
+impl MemLowVectorControl {
+    /// Memory addresses covered by the vector of `size`, the vector part beyond 4 GB is ignored
+    pub fn range(&self, size: MemLowVectorSize) -> Option<Range<u64>> {
+        let len = u64::from(size.bits()?) * u64::from(self.vector_granularity.bytes()?);
+        let start = u64::from(self.vector_start);
+        Some(start..(start + len).min(MEM_LOW_END))
+    }
+    /// Memory addresses claimed by the vector bit `n`, `None` if the bit is beyond 4 GB
+    pub fn bit_range(&self, n: u32) -> Option<Range<u64>> {
+        let granularity = u64::from(self.vector_granularity.bytes()?);
+        let start = u64::from(self.vector_start) + u64::from(n) * granularity;
+        (start < MEM_LOW_END).then(|| start..(start + granularity).min(MEM_LOW_END))
+    }
+}
+
+/// Encoded size of memory block each bit of the MEM Low vector covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemLowVectorGranularity(pub u8);
+impl MemLowVectorGranularity {
+    /// 0000b – 1 MB, 0001b – 2 MB, … 0100b – 16 MB, other encodings are reserved
+    pub fn bytes(&self) -> Option<u32> {
+        (self.0 <= 0b0100).then(|| (1 << 20) << self.0)
+    }
+}
+
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct MemHighVectorControl1Proto {
+    decode_mechanism_enable: bool,
+    rsvdp: B3,
+    vector_granularity: B4,
+    rsvdp_1: B20,
+    vector_start_lower: B4,
+}
+
+/// FPB MEM High Vector Control 1 and 2
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemHighVectorControl {
+    /// FPB MEM High Decode Mechanism Enable
+    pub decode_mechanism_enable: bool,
+    /// Reserved bits 3:1 of MEM High Vector Control 1
+    pub rsvdp: u8,
+    pub vector_granularity: MemHighVectorGranularity,
+    /// Reserved bits 27:8 of MEM High Vector Control 1
+    pub rsvdp_1: u32,
+    /// Address of the first byte covered by the vector, aligned to 256 MB
+    pub vector_start: u64,
+}
+impl MemHighVectorControl {
+    /// Memory addresses covered by the vector of `size`, `None` if the range does not fit into
+    /// 64-bit address space
+    pub fn range(&self, size: MemHighVectorSize) -> Option<Range<u64>> {
+        let len = u64::from(size.bits()?).checked_mul(self.vector_granularity.bytes()?)?;
+        Some(self.vector_start..self.vector_start.checked_add(len)?)
+    }
+    /// Memory addresses claimed by the vector bit `n`
+    pub fn bit_range(&self, n: u32) -> Option<Range<u64>> {
+        let granularity = self.vector_granularity.bytes()?;
+        let start = self.vector_start.checked_add(u64::from(n).checked_mul(granularity)?)?;
+        Some(start..start.checked_add(granularity)?)
+    }
+}
+
+/// Encoded size of memory block each bit of the MEM High vector covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemHighVectorGranularity(pub u8);
+impl MemHighVectorGranularity {
+    /// 0000b – 256 MB, 0001b – 512 MB, … 0111b – 32 GB, other encodings are reserved
+    pub fn bytes(&self) -> Option<u64> {
+        (self.0 <= 0b0111).then(|| (1 << 28) << self.0)
+    }
+}
+
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct VectorAccessControlProto {
+    vector_access_offset: B8,
+    rsvdp: B6,
+    vector_select: B2,
+    rsvdp_1: B16,
+}
+
+/// FPB Vector Access Control
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VectorAccessControl {
+    /// Offset in DWORDs of the vector portion accessed through FPB Vector Access Data
+    pub vector_access_offset: u8,
+    /// Reserved bits 13:8
+    pub rsvdp: u8,
+    pub vector_select: VectorSelect,
+    /// Reserved bits 31:16
+    pub rsvdp_1: u16,
+}
+impl VectorAccessControl {
+    /// Number of the first vector bit accessed through FPB Vector Access Data
+    pub fn first_bit(&self) -> u32 {
+        u32::from(self.vector_access_offset) * 32
+    }
+}
+impl From<VectorAccessControlProto> for VectorAccessControl {
+    fn from(proto: VectorAccessControlProto) -> Self {
+        Self {
+            vector_access_offset: proto.vector_access_offset(),
+            rsvdp: proto.rsvdp(),
+            vector_select: proto.vector_select().into(),
+            rsvdp_1: proto.rsvdp_1(),
+        }
+    }
+}
+impl From<u32> for VectorAccessControl {
+    fn from(dword: u32) -> Self { VectorAccessControlProto::from(dword).into() }
+}
+impl From<VectorAccessControl> for VectorAccessControlProto {
+    fn from(data: VectorAccessControl) -> Self {
+        Self::new()
+            .with_vector_access_offset(data.vector_access_offset)
+            .with_rsvdp(data.rsvdp)
+            .with_vector_select(data.vector_select.into())
+            .with_rsvdp_1(data.rsvdp_1)
+    }
+}
+impl From<VectorAccessControl> for u32 {
+    fn from(data: VectorAccessControl) -> Self { VectorAccessControlProto::from(data).into() }
+}
+
+/// Vector accessed through FPB Vector Access Data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VectorSelect {
+    Rid,
+    MemLow,
+    MemHigh,
+    Reserved,
+}
+impl From<u8> for VectorSelect {
+    fn from(byte: u8) -> Self {
+        match byte {
+            0b00 => Self::Rid,
+            0b01 => Self::MemLow,
+            0b10 => Self::MemHigh,
+            _ => Self::Reserved,
+        }
+    }
+}
+impl From<VectorSelect> for u8 {
+    fn from(data: VectorSelect) -> Self {
+        match data {
+            VectorSelect::Rid => 0b00,
+            VectorSelect::MemLow => 0b01,
+            VectorSelect::MemHigh => 0b10,
+            VectorSelect::Reserved => 0b11,
+        }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use pretty_assertions::assert_eq;
+    use super::*;
+
+    const DATA: [u8; 34] = [
+        0x00, 0x00,
+        // Capabilities: RID, MEM Low, MEM High; Num Sec Dev 3; sizes 8K, 4K, 8K bits
+        0x1f, 0x05, 0x04, 0x05,
+        // RID Vector Control 1: enabled, 64 RIDs, start 20:00.0
+        0x11, 0x00, 0x00, 0x20,
+        // RID Vector Control 2: secondary start 20:00.0
+        0x00, 0x20, 0x00, 0x00,
+        // MEM Low Vector Control: enabled, 2 MB, start 0x8000_0000
+        0x11, 0x00, 0x00, 0x80,
+        // MEM High Vector Control 1: disabled, 1 GB, start 0x40_3000_0000
+        0x20, 0x00, 0x00, 0x30,
+        // MEM High Vector Control 2
+        0x40, 0x00, 0x00, 0x00,
+        // Vector Access Control: MEM Low, offset 2
+        0x02, 0x40, 0x00, 0x00,
+        // Vector Access Data
+        0x0f, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn parse_full_struct() {
+        let result: FlatteningPortalBridge = DATA.read_with(&mut 0, LE).unwrap();
+        let sample = FlatteningPortalBridge {
+            capabilities: Capabilities {
+                rid_decode_mechanism_supported: true,
+                mem_low_decode_mechanism_supported: true,
+                mem_high_decode_mechanism_supported: true,
+                num_sec_dev: 3,
+                rid_vector_size_supported: RidVectorSize(5),
+                mem_low_vector_size_supported: MemLowVectorSize(4),
+                mem_high_vector_size_supported: MemHighVectorSize(5),
+                rsvdp: 0,
+                rsvdp_1: 0,
+                rsvdp_2: 0,
+            },
+            rid_vector_control: RidVectorControl {
+                decode_mechanism_enable: true,
+                vector_granularity: RidVectorGranularity(1),
+                vector_start: 0x2000,
+                secondary_start: 0x2000,
+                rsvdp: 0,
+                rsvdp_1: 0,
+                rsvdp_2: 0,
+                rsvdp_3: 0,
+            },
+            mem_low_vector_control: MemLowVectorControl {
+                decode_mechanism_enable: true,
+                vector_granularity: MemLowVectorGranularity(1),
+                vector_start: 0x8000_0000,
+                rsvdp: 0,
+                rsvdp_1: 0,
+            },
+            mem_high_vector_control: MemHighVectorControl {
+                decode_mechanism_enable: false,
+                vector_granularity: MemHighVectorGranularity(2),
+                vector_start: 0x40_3000_0000,
+                rsvdp: 0,
+                rsvdp_1: 0,
+            },
+            vector_access_control: VectorAccessControl {
+                vector_access_offset: 2,
+                vector_select: VectorSelect::MemLow,
+                rsvdp: 0,
+                rsvdp_1: 0,
+            },
+            vector_access_data: 0x0f,
+            reserved: 0,
+        };
+        assert_eq!(sample, result);
+
+        let mut buf = [0xffu8; 34];
+        buf.write_with(&mut 0, result, LE).unwrap();
+        assert_eq!(DATA, buf);
+    }
+
+    #[test]
+    fn claimed_ranges() {
+        let fpb: FlatteningPortalBridge = DATA.read_with(&mut 0, LE).unwrap();
+        // 8K bits of 64 RIDs exceed RID space
+        assert_eq!(Some(0x2000..0x1_0000), fpb.rid_range());
+        assert_eq!(Some(0x2040..0x2080), fpb.rid_vector_control.bit_range(1));
+        assert_eq!(None, fpb.rid_vector_control.bit_range(0x380));
+        // 4K bits of 2 MB exceed 4 GB
+        assert_eq!(Some(0x8000_0000..0x1_0000_0000), fpb.mem_low_range());
+        assert_eq!(None, fpb.mem_low_vector_control.bit_range(0x400));
+        let first = fpb.vector_access_control.first_bit();
+        assert_eq!(64, first);
+        assert_eq!(
+            Some(0x8880_0000..0x88a0_0000),
+            fpb.mem_low_vector_control.bit_range(first + 4)
+        );
+        assert_eq!(None, fpb.mem_high_range());
+        assert_eq!(
+            Some(0x40_3000_0000..0x840_3000_0000),
+            fpb.mem_high_vector_control.range(MemHighVectorSize(5))
+        );
+        let overflow = MemHighVectorControl {
+            vector_start: 0xffff_ffff_f000_0000,
+            ..fpb.mem_high_vector_control
+        };
+        assert_eq!(None, overflow.range(MemHighVectorSize(0)));
+        assert_eq!(None, RidVectorGranularity(3).rids());
+        assert_eq!(Some(1024), RidVectorSize(0b010).bits());
+        assert_eq!(None, RidVectorSize(0b001).bits());
+        assert_eq!(None, RidVectorSize(0b011).bits());
+        assert_eq!(Some(4096), MemLowVectorSize(0b100).bits());
+        assert_eq!(None, MemLowVectorSize(0b101).bits());
+        assert_eq!(None, MemHighVectorSize(6).bits());
+    }
+}
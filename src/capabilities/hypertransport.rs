@@ -28,7 +28,7 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
@@ -42,6 +42,7 @@ const HT_CAP_TYPE_MASK: u16 = 0xf800;
 /// The layout of the capabilities block is determined by the value in the Capability Type field in
 /// the Command register
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Hypertransport {
     /// Slave or Primary Interface
     SlaveOrPrimaryInterface(SlaveOrPrimaryInterface),
@@ -53,8 +54,8 @@ pub enum Hypertransport {
     ReservedHost(ReservedHost),
     /// Interrupt Discovery and Configuration
     InterruptDiscoveryAndConfiguration(InterruptDiscoveryAndConfiguration),
-    /// Revision ID
-    RevisionId(RevisionId),
+    /// Revision ID and reserved bits 10:8 of the Command register
+    RevisionId(RevisionId, u8),
     /// UnitID Clumping
     UnitIdClumping(UnitIdClumping),
     /// Extended Configuration Space Access
@@ -79,13 +80,14 @@ pub enum Hypertransport {
     PowerManagement(PowerManagement),
     /// High Node Count
     HighNodeCount(HighNodeCount),
-    /// Reserved
-    Reserved(u8),
+    /// Reserved capability type, raw Command register
+    Reserved(u16),
 }
 impl<'a> TryRead<'a, Endian> for Hypertransport {
     fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
         let word = bytes.read_with::<u16>(&mut 0, endian)?;
         let capability_type = (word & HT_CAP_TYPE_MASK) >> 11;
+        let command = word & !HT_CAP_TYPE_MASK;
         let offset = &mut 0;
         let ht = match capability_type {
             0b00000..=0b00011 => {
@@ -98,31 +100,32 @@ impl<'a> TryRead<'a, Endian> for Hypertransport {
             },
             0b01000           => {
                 // let data = bytes.read_with::<Switch>(offset, endian)?;
-                Self::Switch(Switch {})
+                Self::Switch(Switch { command })
             },
             0b01001           => {
                 // let data = bytes.read_with::<ReservedHost>(offset, endian)?;
-                Self::ReservedHost(ReservedHost {})
+                Self::ReservedHost(ReservedHost { command })
             },
             0b10000           => {
                 // let data = bytes.read_with::<InterruptDiscoveryAndConfiguration>(offset, endian)?;
-                Self::InterruptDiscoveryAndConfiguration(InterruptDiscoveryAndConfiguration {})
+                Self::InterruptDiscoveryAndConfiguration(InterruptDiscoveryAndConfiguration { command })
             },
             0b10001           => {
                 let data = bytes.read_with::<u8>(offset, endian)?.into();
-                Self::RevisionId(data)
+                let reserved = bytes.read_with::<u8>(offset, endian)? & 0b111;
+                Self::RevisionId(data, reserved)
             },
             0b10010           => {
                 // let data = bytes.read_with::<UnitIdClumping>(offset, endian)?;
-                Self::UnitIdClumping(UnitIdClumping {})
+                Self::UnitIdClumping(UnitIdClumping { command })
             },
             0b10011           => {
                 // let data = bytes.read_with::<ExtendedConfigurationSpaceAccess>(offset, endian)?;
-                Self::ExtendedConfigurationSpaceAccess(ExtendedConfigurationSpaceAccess {})
+                Self::ExtendedConfigurationSpaceAccess(ExtendedConfigurationSpaceAccess { command })
             },
             0b10100           => {
                 // let data = bytes.read_with::<AddressMapping>(offset, endian)?;
-                Self::AddressMapping(AddressMapping {})
+                Self::AddressMapping(AddressMapping { command })
             },
             0b10101           => {
                 let data = bytes.read_with::<MsiMapping>(offset, endian)?;
@@ -130,39 +133,88 @@ impl<'a> TryRead<'a, Endian> for Hypertransport {
             },
             0b10110           => {
                 // let data = bytes.read_with::<DirectRoute>(offset, endian)?;
-                Self::DirectRoute(DirectRoute {})
+                Self::DirectRoute(DirectRoute { command })
             },
             0b10111           => {
                 // let data = bytes.read_with::<VCSet>(offset, endian)?;
-                Self::VCSet(VCSet {})
+                Self::VCSet(VCSet { command })
             },
             0b11000           => {
                 // let data = bytes.read_with::<RetryMode>(offset, endian)?;
-                Self::RetryMode(RetryMode {})
+                Self::RetryMode(RetryMode { command })
             },
             0b11001           => {
                 // let data = bytes.read_with::<X86Encoding>(offset, endian)?;
-                Self::X86Encoding(X86Encoding {})
+                Self::X86Encoding(X86Encoding { command })
             },
             0b11010           => {
                 // let data = bytes.read_with::<Gen3>(offset, endian)?;
-                Self::Gen3(Gen3 {})
+                Self::Gen3(Gen3 { command })
             },
             0b11011           => {
                 // let data = bytes.read_with::<FunctionLevelExtension>(offset, endian)?;
-                Self::FunctionLevelExtension(FunctionLevelExtension {})
+                Self::FunctionLevelExtension(FunctionLevelExtension { command })
             },
             0b11100           => {
                 // let data = bytes.read_with::<PowerManagement>(offset, endian)?;
-                Self::PowerManagement(PowerManagement {})
+                Self::PowerManagement(PowerManagement { command })
             },
             0b11101           => {
                 // let data = bytes.read_with::<HighNodeCount>(offset, endian)?;
-                Self::HighNodeCount(HighNodeCount {})
+                Self::HighNodeCount(HighNodeCount { command })
             },
-            v => Self::Reserved(v as u8),
+            _ => Self::Reserved(word),
         };
-        Ok((ht, *offset))
+        // Only the Command register of undecoded blocks is read
+        let size = (*offset).max(2);
+        Ok((ht, size))
+    }
+}
+impl TryWrite<Endian> for Hypertransport {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        // Capability type bits in the upper 5 bits of the command register
+        let command = (u8::from(&self) as u16) << 11;
+        match self {
+            Self::SlaveOrPrimaryInterface(data) => {
+                bytes.write_with::<SlaveOrPrimaryInterface>(offset, data, endian)?;
+            },
+            Self::HostOrSecondaryInterface(data) => {
+                bytes.write_with::<HostOrSecondaryInterface>(offset, data, endian)?;
+            },
+            Self::RevisionId(data, reserved) => {
+                bytes.write_with::<u8>(offset, (&data).into(), endian)?;
+                bytes.write_with::<u8>(offset, (command >> 8) as u8 | (reserved & 0b111), endian)?;
+            },
+            Self::MsiMapping(data) => {
+                bytes.write_with::<MsiMapping>(offset, data, endian)?;
+            },
+            Self::Switch(Switch { command: bits })
+            | Self::ReservedHost(ReservedHost { command: bits })
+            | Self::InterruptDiscoveryAndConfiguration(
+                InterruptDiscoveryAndConfiguration { command: bits }
+            )
+            | Self::UnitIdClumping(UnitIdClumping { command: bits })
+            | Self::ExtendedConfigurationSpaceAccess(
+                ExtendedConfigurationSpaceAccess { command: bits }
+            )
+            | Self::AddressMapping(AddressMapping { command: bits })
+            | Self::DirectRoute(DirectRoute { command: bits })
+            | Self::VCSet(VCSet { command: bits })
+            | Self::RetryMode(RetryMode { command: bits })
+            | Self::X86Encoding(X86Encoding { command: bits })
+            | Self::Gen3(Gen3 { command: bits })
+            | Self::FunctionLevelExtension(FunctionLevelExtension { command: bits })
+            | Self::PowerManagement(PowerManagement { command: bits })
+            | Self::HighNodeCount(HighNodeCount { command: bits }) => {
+                let bits = bits & !HT_CAP_TYPE_MASK;
+                bytes.write_with::<u16>(offset, command | bits, endian)?;
+            },
+            Self::Reserved(word) => {
+                bytes.write_with::<u16>(offset, word, endian)?;
+            },
+        }
+        Ok(*offset)
     }
 }
 impl<'a> From<&'a Hypertransport> for u8 {
@@ -173,7 +225,7 @@ impl<'a> From<&'a Hypertransport> for u8 {
             Hypertransport::Switch(_)                             => 0b01000,
             Hypertransport::ReservedHost(_)                       => 0b01001,
             Hypertransport::InterruptDiscoveryAndConfiguration(_) => 0b10000,
-            Hypertransport::RevisionId(_)                         => 0b10001,
+            Hypertransport::RevisionId(..)                        => 0b10001,
             Hypertransport::UnitIdClumping(_)                     => 0b10010,
             Hypertransport::ExtendedConfigurationSpaceAccess(_)   => 0b10011,
             Hypertransport::AddressMapping(_)                     => 0b10100,
@@ -186,7 +238,7 @@ impl<'a> From<&'a Hypertransport> for u8 {
             Hypertransport::FunctionLevelExtension(_)             => 0b11011,
             Hypertransport::PowerManagement(_)                    => 0b11100,
             Hypertransport::HighNodeCount(_)                      => 0b11101,
-            Hypertransport::Reserved(v)                           => *v,
+            Hypertransport::Reserved(word)                        => (*word >> 11) as u8,
         }
     }
 }
@@ -195,6 +247,7 @@ impl<'a> From<&'a Hypertransport> for u8 {
 
 /// Slave/Primary Interface
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SlaveOrPrimaryInterface {
     /// Command
     pub command: SlaveOrPrimaryCommand,
@@ -277,6 +330,32 @@ impl<'a> TryRead<'a, Endian> for SlaveOrPrimaryInterface {
         Ok((sopi, *offset))
     }
 }
+impl TryWrite<Endian> for SlaveOrPrimaryInterface {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        let link_freq_err_proto_0 = LinkFreqErrProto::from(self.link_error_0)
+            .with_link_freq(self.link_freq_0);
+        let link_freq_err_proto_1 = LinkFreqErrProto::from(self.link_error_1)
+            .with_link_freq(self.link_freq_1);
+        bytes.write_with::<u16>(offset, self.command.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.link_control_0.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.link_config_0.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.link_control_1.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.link_config_1.into(), endian)?;
+        bytes.write_with::<u8>(offset, (&self.revision_id).into(), endian)?;
+        bytes.write_with::<u8>(offset, link_freq_err_proto_0.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.link_freq_cap_0.into(), endian)?;
+        bytes.write_with::<u8>(offset, u16::from(self.feature) as u8, endian)?;
+        bytes.write_with::<u8>(offset, link_freq_err_proto_1.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.link_freq_cap_1.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.enumeration_scratchpad, endian)?;
+        bytes.write_with::<u16>(offset, self.error_handling.into(), endian)?;
+        bytes.write_with::<u8>(offset, self.mem_base_upper, endian)?;
+        bytes.write_with::<u8>(offset, self.mem_limit_upper, endian)?;
+        bytes.write_with::<u8>(offset, self.bus_number, endian)?;
+        Ok(*offset)
+    }
+}
 
 
 #[bitfield(bits = 16)]
@@ -291,6 +370,7 @@ pub struct SlaveOrPrimaryCommandProto {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SlaveOrPrimaryCommand {
     /// Base UnitID
     pub base_unitid: u8,
@@ -318,6 +398,20 @@ impl From<SlaveOrPrimaryCommandProto> for SlaveOrPrimaryCommand {
 impl From<u16> for SlaveOrPrimaryCommand {
     fn from(word: u16) -> Self { SlaveOrPrimaryCommandProto::from(word).into() }
 }
+impl From<SlaveOrPrimaryCommand> for SlaveOrPrimaryCommandProto {
+    fn from(data: SlaveOrPrimaryCommand) -> Self {
+        Self::new()
+            .with_base_unitid(data.base_unitid)
+            .with_unit_count(data.unit_count)
+            .with_master_host(data.master_host)
+            .with_default_direction(data.default_direction)
+            .with_drop_on_uninitialized_link(data.drop_on_uninitialized_link)
+            .with_capability_type(0b000)
+    }
+}
+impl From<SlaveOrPrimaryCommand> for u16 {
+    fn from(data: SlaveOrPrimaryCommand) -> Self { SlaveOrPrimaryCommandProto::from(data).into() }
+}
 
 
 #[bitfield(bits = 16)]
@@ -339,6 +433,7 @@ pub struct LinkControlProto {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LinkControl {
     /// Source ID Enable
     pub source_id_enable: bool,
@@ -389,6 +484,27 @@ impl From<LinkControlProto> for LinkControl {
 impl From<u16> for LinkControl {
     fn from(word: u16) -> Self { LinkControlProto::from(word).into() }
 }
+impl From<LinkControl> for LinkControlProto {
+    fn from(data: LinkControl) -> Self {
+        Self::new()
+            .with_source_id_enable(data.source_id_enable)
+            .with_crc_flood_enable(data.crc_flood_enable)
+            .with_crc_start_test(data.crc_start_test)
+            .with_crc_force_error(data.crc_force_error)
+            .with_link_failure(data.link_failure)
+            .with_initialization_complete(data.initialization_complete)
+            .with_end_of_chain(data.end_of_chain)
+            .with_transmitter_off(data.transmitter_off)
+            .with_crc_error(data.crc_error)
+            .with_isochronous_flow_control_enable(data.isochronous_flow_control_enable)
+            .with_ldtstop_tristate_enable(data.ldtstop_tristate_enable)
+            .with_extended_ctl_time(data.extended_ctl_time)
+            .with_enable_64_bit_addressing(data.enable_64_bit_addressing)
+    }
+}
+impl From<LinkControl> for u16 {
+    fn from(data: LinkControl) -> Self { LinkControlProto::from(data).into() }
+}
 
 
 #[bitfield(bits = 16)]
@@ -405,6 +521,7 @@ pub struct LinkConfigurationProto {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LinkConfiguration {
     /// Max Link Width In
     pub max_link_width_in: LinkWidth,
@@ -440,11 +557,28 @@ impl From<LinkConfigurationProto> for LinkConfiguration {
 impl From<u16> for LinkConfiguration {
     fn from(word: u16) -> Self { LinkConfigurationProto::from(word).into() }
 }
+impl From<LinkConfiguration> for LinkConfigurationProto {
+    fn from(data: LinkConfiguration) -> Self {
+        Self::new()
+            .with_max_link_width_in(data.max_link_width_in.into())
+            .with_doubleword_flow_control_in(data.doubleword_flow_control_in)
+            .with_max_link_width_out(data.max_link_width_out.into())
+            .with_doubleword_flow_control_out(data.doubleword_flow_control_out)
+            .with_link_width_in(data.link_width_in.into())
+            .with_doubleword_flow_control_in_enable(data.doubleword_flow_control_in_enable)
+            .with_link_width_out(data.link_width_out.into())
+            .with_doubleword_flow_control_out_enable(data.doubleword_flow_control_out_enable)
+    }
+}
+impl From<LinkConfiguration> for u16 {
+    fn from(data: LinkConfiguration) -> Self { LinkConfigurationProto::from(data).into() }
+}
 
 
 /// Indicate the physical width of the incoming side of the HyperTransport link implemented by this
 /// device. Unganged links indicate a maximum width of 8 bits.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LinkWidth {
     /// 8 bits
     Width8bits,
@@ -474,6 +608,19 @@ impl From<u8> for LinkWidth {
         }
     }
 }
+impl From<LinkWidth> for u8 {
+    fn from(data: LinkWidth) -> Self {
+        match data {
+            LinkWidth::Width8bits   => 0b000,
+            LinkWidth::Width16bits  => 0b001,
+            LinkWidth::Width32bits  => 0b011,
+            LinkWidth::Width2bits   => 0b100,
+            LinkWidth::Width4bits   => 0b101,
+            LinkWidth::NotConnected => 0b111,
+            LinkWidth::Reserved(v)  => v,
+        }
+    }
+}
 impl Default for LinkWidth {
     fn default() -> Self {
         Self::Width8bits
@@ -496,6 +643,7 @@ impl<'a> From<&'a RevisionId> for RevisionIdProto {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RevisionId {
     pub minor: u8,
     pub major: u8,
@@ -521,6 +669,7 @@ impl<'a> From<&'a RevisionId> for u8 {
 #[bitfield(bits = 8)]
 #[repr(u8)]
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LinkFreqErrProto {
     link_freq: B4,
     protocol_error: bool,
@@ -532,6 +681,7 @@ pub struct LinkFreqErrProto {
 /// The Link Frequency register specifies the operating frequency of the link’s transmitter
 /// clock—the data rate is twice this value.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LinkFrequency {
     Rate200MHz,
     Rate300MHz,
@@ -588,6 +738,7 @@ impl Default for LinkFrequency {
 
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LinkError {
     /// Protocol Error
     pub protocol_error: bool,
@@ -608,6 +759,17 @@ impl From<LinkFreqErrProto> for LinkError {
         }
     }
 }
+/// Link frequency bits are not a part of [LinkError] and are set to 0
+impl From<LinkError> for LinkFreqErrProto {
+    fn from(data: LinkError) -> Self {
+        Self::new()
+            .with_link_freq(0)
+            .with_protocol_error(data.protocol_error)
+            .with_overflow_error(data.overflow_error)
+            .with_end_of_chain_error(data.end_of_chain_error)
+            .with_ctl_timeout(data.ctl_timeout)
+    }
+}
 
 
 #[bitfield(bits = 16)]
@@ -632,6 +794,7 @@ pub struct LinkFrequencyCapabilityProto {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LinkFrequencyCapability {
     pub supports_200mhz: bool,
     pub supports_300mhz: bool,
@@ -675,6 +838,30 @@ impl From<LinkFrequencyCapabilityProto> for LinkFrequencyCapability {
 impl From<u16> for LinkFrequencyCapability {
     fn from(word: u16) -> Self { LinkFrequencyCapabilityProto::from(word).into() }
 }
+impl From<LinkFrequencyCapability> for LinkFrequencyCapabilityProto {
+    fn from(data: LinkFrequencyCapability) -> Self {
+        Self::new()
+            .with_supports_200mhz(data.supports_200mhz)
+            .with_supports_300mhz(data.supports_300mhz)
+            .with_supports_400mhz(data.supports_400mhz)
+            .with_supports_500mhz(data.supports_500mhz)
+            .with_supports_600mhz(data.supports_600mhz)
+            .with_supports_800mhz(data.supports_800mhz)
+            .with_supports_1000mhz(data.supports_1000mhz)
+            .with_supports_1200mhz(data.supports_1200mhz)
+            .with_supports_1400mhz(data.supports_1400mhz)
+            .with_supports_1600mhz(data.supports_1600mhz)
+            .with_supports_1800mhz(data.supports_1800mhz)
+            .with_supports_2000mhz(data.supports_2000mhz)
+            .with_supports_2200mhz(data.supports_2200mhz)
+            .with_supports_2400mhz(data.supports_2400mhz)
+            .with_supports_2600mhz(data.supports_2600mhz)
+            .with_supports_vendor_specific(data.supports_vendor_specific)
+    }
+}
+impl From<LinkFrequencyCapability> for u16 {
+    fn from(data: LinkFrequencyCapability) -> Self { LinkFrequencyCapabilityProto::from(data).into() }
+}
 
 
 #[bitfield(bits = 16)]
@@ -694,6 +881,7 @@ pub struct FeatureCapabilityProto {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FeatureCapability {
     /// Isochronous Flow Control Mode
     pub isochronous_flow_control_mode: bool,
@@ -709,15 +897,17 @@ pub struct FeatureCapability {
     pub unitid_reorder_disable: bool,
     /// Source Identification Extension
     pub source_identification_extension: bool,
+    /// Reserved bit 7
+    pub rsvdp: u8,
     /// Extended Register Set
     pub extended_register_set: bool,
     /// Upstream Configuration Enable
     pub upstream_configuration_enable: bool,
+    /// Reserved bits 15:10
+    pub rsvdp_2: u8,
 }
 impl From<FeatureCapabilityProto> for FeatureCapability {
     fn from(proto: FeatureCapabilityProto) -> Self {
-        let _ = proto.rsvdp();
-        let _ = proto.rsvdp_2();
         Self {
             isochronous_flow_control_mode: proto.isochronous_flow_control_mode(),
             ldtstop: proto.ldtstop(),
@@ -726,14 +916,35 @@ impl From<FeatureCapabilityProto> for FeatureCapability {
             qword_addressing: proto.qword_addressing(),
             unitid_reorder_disable: proto.unitid_reorder_disable(),
             source_identification_extension: proto.source_identification_extension(),
+            rsvdp: proto.rsvdp(),
             extended_register_set: proto.extended_register_set(),
             upstream_configuration_enable: proto.upstream_configuration_enable(),
+            rsvdp_2: proto.rsvdp_2(),
         }
     }
 }
 impl From<u16> for FeatureCapability {
     fn from(word: u16) -> Self { FeatureCapabilityProto::from(word).into() }
 }
+impl From<FeatureCapability> for FeatureCapabilityProto {
+    fn from(data: FeatureCapability) -> Self {
+        Self::new()
+            .with_isochronous_flow_control_mode(data.isochronous_flow_control_mode)
+            .with_ldtstop(data.ldtstop)
+            .with_crc_test_mode(data.crc_test_mode)
+            .with_extended_ctl_time_required(data.extended_ctl_time_required)
+            .with_qword_addressing(data.qword_addressing)
+            .with_unitid_reorder_disable(data.unitid_reorder_disable)
+            .with_source_identification_extension(data.source_identification_extension)
+            .with_rsvdp(data.rsvdp)
+            .with_extended_register_set(data.extended_register_set)
+            .with_upstream_configuration_enable(data.upstream_configuration_enable)
+            .with_rsvdp_2(data.rsvdp_2)
+    }
+}
+impl From<FeatureCapability> for u16 {
+    fn from(data: FeatureCapability) -> Self { FeatureCapabilityProto::from(data).into() }
+}
 
 
 #[bitfield(bits = 16)]
@@ -758,6 +969,7 @@ pub struct ErrorHandlingProto {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ErrorHandling {
     /// Protocol Error Flood Enable
     pub protocol_error_flood_enable: bool,
@@ -817,11 +1029,36 @@ impl From<ErrorHandlingProto> for ErrorHandling {
 impl From<u16> for ErrorHandling {
     fn from(word: u16) -> Self { ErrorHandlingProto::from(word).into() }
 }
+impl From<ErrorHandling> for ErrorHandlingProto {
+    fn from(data: ErrorHandling) -> Self {
+        Self::new()
+            .with_protocol_error_flood_enable(data.protocol_error_flood_enable)
+            .with_overflow_error_flood_enable(data.overflow_error_flood_enable)
+            .with_protocol_error_fatal_enable(data.protocol_error_fatal_enable)
+            .with_overflow_error_fatal_enable(data.overflow_error_fatal_enable)
+            .with_end_of_chain_error_fatal_enable(data.end_of_chain_error_fatal_enable)
+            .with_response_error_fatal_enable(data.response_error_fatal_enable)
+            .with_crc_error_fatal_enable(data.crc_error_fatal_enable)
+            .with_system_error_fatal_enable(data.system_error_fatal_enable)
+            .with_chain_fail(data.chain_fail)
+            .with_response_error(data.response_error)
+            .with_protocol_error_nonfatal_enable(data.protocol_error_nonfatal_enable)
+            .with_overflow_error_nonfatal_enable(data.overflow_error_nonfatal_enable)
+            .with_end_of_chain_error_nonfatal_enable(data.end_of_chain_error_nonfatal_enable)
+            .with_response_error_nonfatal_enable(data.response_error_nonfatal_enable)
+            .with_crc_error_nonfatal_enable(data.crc_error_nonfatal_enable)
+            .with_system_error_nonfatal_enable(data.system_error_nonfatal_enable)
+    }
+}
+impl From<ErrorHandling> for u16 {
+    fn from(data: ErrorHandling) -> Self { ErrorHandlingProto::from(data).into() }
+}
 
 
 
 /// Host/Secondary Interface
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HostOrSecondaryInterface {
     /// Command
     pub command: HostOrSecondaryCommand,
@@ -839,6 +1076,8 @@ pub struct HostOrSecondaryInterface {
     pub link_freq_cap: LinkFrequencyCapability,
     /// Feature
     pub feature: FeatureCapability,
+    /// Reserved word following Feature
+    pub reserved: u16,
     /// Enumeration Scratchpad
     pub enumeration_scratchpad: u16,
     /// Error Handling
@@ -871,10 +1110,8 @@ impl<'a> TryRead<'a, Endian> for HostOrSecondaryInterface {
                 bytes.read_with::<u16>(offset, endian)?.into()
             },
             feature: bytes.read_with::<u16>(offset, endian)?.into(),
-            enumeration_scratchpad: {
-                let _reserved = bytes.read_with::<u16>(offset, endian)?;
-                bytes.read_with::<u16>(offset, endian)?
-            },
+            reserved: bytes.read_with::<u16>(offset, endian)?,
+            enumeration_scratchpad: bytes.read_with::<u16>(offset, endian)?,
             error_handling: bytes.read_with::<u16>(offset, endian)?.into(),
             mem_base_upper: bytes.read_with::<u8>(offset, endian)?,
             mem_limit_upper: bytes.read_with::<u8>(offset, endian)?,
@@ -882,6 +1119,26 @@ impl<'a> TryRead<'a, Endian> for HostOrSecondaryInterface {
         Ok((hosi, *offset))
     }
 }
+impl TryWrite<Endian> for HostOrSecondaryInterface {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        let link_freq_err_proto = LinkFreqErrProto::from(self.link_error)
+            .with_link_freq(self.link_freq);
+        bytes.write_with::<u16>(offset, self.command.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.link_control.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.link_config.into(), endian)?;
+        bytes.write_with::<u8>(offset, (&self.revision_id).into(), endian)?;
+        bytes.write_with::<u8>(offset, link_freq_err_proto.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.link_freq_cap.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.feature.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.reserved, endian)?;
+        bytes.write_with::<u16>(offset, self.enumeration_scratchpad, endian)?;
+        bytes.write_with::<u16>(offset, self.error_handling.into(), endian)?;
+        bytes.write_with::<u8>(offset, self.mem_base_upper, endian)?;
+        bytes.write_with::<u8>(offset, self.mem_limit_upper, endian)?;
+        Ok(*offset)
+    }
+}
 
 
 #[bitfield(bits = 16)]
@@ -900,6 +1157,7 @@ pub struct HostOrSecondaryCommandProto {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HostOrSecondaryCommand {
     /// Warm Reset
     pub warm_reset: bool,
@@ -911,6 +1169,8 @@ pub struct HostOrSecondaryCommand {
     pub chain_side: bool,
     /// Host Hide
     pub host_hide: bool,
+    /// Reserved bit 9
+    pub rsvdp: u8,
     /// Act as Slave
     pub act_as_slave: bool,
     /// Host Inbound End of Chain Error
@@ -920,7 +1180,6 @@ pub struct HostOrSecondaryCommand {
 }
 impl From<HostOrSecondaryCommandProto> for HostOrSecondaryCommand {
     fn from(proto: HostOrSecondaryCommandProto) -> Self {
-        let _ = proto.rsvdp();
         let _ = proto.capability_type();
         Self {
             warm_reset: proto.warm_reset(),
@@ -928,6 +1187,7 @@ impl From<HostOrSecondaryCommandProto> for HostOrSecondaryCommand {
             device_number: proto.device_number(),
             chain_side: proto.chain_side(),
             host_hide: proto.host_hide(),
+            rsvdp: proto.rsvdp(),
             act_as_slave: proto.act_as_slave(),
             host_inbound_end_of_chain_error: proto.host_inbound_end_of_chain_error(),
             drop_on_uninitialized_link: proto.drop_on_uninitialized_link(),
@@ -937,31 +1197,67 @@ impl From<HostOrSecondaryCommandProto> for HostOrSecondaryCommand {
 impl From<u16> for HostOrSecondaryCommand {
     fn from(word: u16) -> Self { HostOrSecondaryCommandProto::from(word).into() }
 }
+impl From<HostOrSecondaryCommand> for HostOrSecondaryCommandProto {
+    fn from(data: HostOrSecondaryCommand) -> Self {
+        Self::new()
+            .with_warm_reset(data.warm_reset)
+            .with_double_ended(data.double_ended)
+            .with_device_number(data.device_number)
+            .with_chain_side(data.chain_side)
+            .with_host_hide(data.host_hide)
+            .with_rsvdp(data.rsvdp)
+            .with_act_as_slave(data.act_as_slave)
+            .with_host_inbound_end_of_chain_error(data.host_inbound_end_of_chain_error)
+            .with_drop_on_uninitialized_link(data.drop_on_uninitialized_link)
+            .with_capability_type(0b001)
+    }
+}
+impl From<HostOrSecondaryCommand> for u16 {
+    fn from(data: HostOrSecondaryCommand) -> Self { HostOrSecondaryCommandProto::from(data).into() }
+}
 
 
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Switch {
+    /// Command register bits 10:0, the rest of the block is not decoded
+    pub command: u16,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReservedHost {
+    /// Command register bits 10:0, the rest of the block is not decoded
+    pub command: u16,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InterruptDiscoveryAndConfiguration {
+    /// Command register bits 10:0, the rest of the block is not decoded
+    pub command: u16,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnitIdClumping {
+    /// Command register bits 10:0, the rest of the block is not decoded
+    pub command: u16,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtendedConfigurationSpaceAccess {
+    /// Command register bits 10:0, the rest of the block is not decoded
+    pub command: u16,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AddressMapping {
+    /// Command register bits 10:0, the rest of the block is not decoded
+    pub command: u16,
 }
 
 
@@ -976,12 +1272,15 @@ pub struct MsiMappingProto {
 
 /// MSI Mapping Capability
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MsiMapping {
     /// Indicating if the mapping is active
     pub enabled: bool,
     /// Indicating if the next two doublewords for programming address are present in the
     /// capability
     pub fixed: bool,
+    /// Reserved bits 10:2
+    pub rsvdp: u16,
     /// Holds the lower portion of the base address where the mapping of MSIs takes place. It is
     /// set to FEEh upon warm reset
     pub base_address_lower: u32,
@@ -1000,48 +1299,86 @@ impl<'a> TryRead<'a, Endian> for MsiMapping {
         let offset = &mut 0;
         let msi_mapping_proto: MsiMappingProto =
             bytes.read_with::<u16>(offset, endian)?.into();
-        let _ = msi_mapping_proto.rsvdp();
         let _ = msi_mapping_proto.capability_type();
         let msim = MsiMapping {
             enabled: msi_mapping_proto.enabled(),
             fixed: msi_mapping_proto.fixed(),
+            rsvdp: msi_mapping_proto.rsvdp(),
             base_address_lower: bytes.read_with::<u32>(offset, endian)?,
             base_address_upper: bytes.read_with::<u32>(offset, endian)?,
         };
         Ok((msim, *offset))
     }
 }
+impl TryWrite<Endian> for MsiMapping {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        let msi_mapping_proto = MsiMappingProto::new()
+            .with_enabled(self.enabled)
+            .with_fixed(self.fixed)
+            .with_rsvdp(self.rsvdp)
+            .with_capability_type(0b10101);
+        bytes.write_with::<u16>(offset, msi_mapping_proto.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.base_address_lower, endian)?;
+        bytes.write_with::<u32>(offset, self.base_address_upper, endian)?;
+        Ok(*offset)
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DirectRoute {
+    /// Command register bits 10:0, the rest of the block is not decoded
+    pub command: u16,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VCSet {
+    /// Command register bits 10:0, the rest of the block is not decoded
+    pub command: u16,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RetryMode {
+    /// Command register bits 10:0, the rest of the block is not decoded
+    pub command: u16,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct X86Encoding {
+    /// Command register bits 10:0, the rest of the block is not decoded
+    pub command: u16,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Gen3 {
+    /// Command register bits 10:0, the rest of the block is not decoded
+    pub command: u16,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FunctionLevelExtension {
+    /// Command register bits 10:0, the rest of the block is not decoded
+    pub command: u16,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PowerManagement {
+    /// Command register bits 10:0, the rest of the block is not decoded
+    pub command: u16,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HighNodeCount {
+    /// Command register bits 10:0, the rest of the block is not decoded
+    pub command: u16,
 }
 
 
@@ -1172,6 +1509,8 @@ mod tests {
                 source_identification_extension: false,
                 extended_register_set: false,
                 upstream_configuration_enable: false,
+                rsvdp: 0,
+                rsvdp_2: 0,
             },
             link_freq_1: 0, // 0 -> 200MHz (default)
             link_error_1: LinkError {
@@ -1253,6 +1592,7 @@ mod tests {
                 act_as_slave: false,
                 host_inbound_end_of_chain_error: false,
                 drop_on_uninitialized_link: false,
+                rsvdp: 0,
             },
             link_control: LinkControl {
                 source_id_enable: false,
@@ -1318,6 +1658,8 @@ mod tests {
                 source_identification_extension: false,
                 extended_register_set: false,
                 upstream_configuration_enable: false,
+                rsvdp: 0,
+                rsvdp_2: 0,
             },
             enumeration_scratchpad: 0x02ee,
             error_handling: ErrorHandling {
@@ -1340,6 +1682,7 @@ mod tests {
             },
             mem_base_upper: 0,
             mem_limit_upper: 0,
+            reserved: 0,
         };
         assert_eq!(Hypertransport::HostOrSecondaryInterface(sample), result);
     }
@@ -1354,7 +1697,7 @@ mod tests {
             major: 1,
             minor: 5,
         };
-        assert_eq!(Hypertransport::RevisionId(sample), result);
+        assert_eq!(Hypertransport::RevisionId(sample, 0), result);
     }
 
     #[test]
@@ -1371,6 +1714,7 @@ mod tests {
             fixed: true,
             base_address_lower: 0,
             base_address_upper: 0x11da1734,
+            rsvdp: 0,
         };
         assert_eq!(Hypertransport::MsiMapping(sample), result);
         
@@ -1380,4 +1724,33 @@ mod tests {
             _ => unreachable!(),
         }
     }
+
+    #[test]
+    fn write_hypertransport() {
+        let slave_or_primary_interface = [
+            0x08, 0x54, 0x80, 0x01, 0x20, 0x00, 0x11, 0x11, 0xd0, 0x00, 0x00, 0x00, 0x60, 0x0c,
+            0x75, 0x1e, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let host_or_secondary_interface = [
+            0x08, 0x00, 0x01, 0x21, 0x20, 0xa0, 0x11, 0x11, 0x60, 0x0c, 0xf5, 0xff, 0x13, 0x00,
+            0x00, 0x00, 0xee, 0x02, 0x84, 0x80, 0x00, 0x00,
+        ];
+        let revision_id = [0x08, 0x00, 0x25, 0b10001000];
+        let msi_mapping = [
+            0x08, 0xb0, 0x03, 0xa8, 0x00, 0x00, 0x00, 0x00, 0x34, 0x17, 0xda, 0x11,
+        ];
+        let samples: [&[u8]; 4] = [
+            &slave_or_primary_interface,
+            &host_or_secondary_interface,
+            &revision_id,
+            &msi_mapping,
+        ];
+        for data in samples {
+            let ht = data[2..].read_with::<Hypertransport>(&mut 0, LE).unwrap();
+            let mut result = [0u8; 0x20];
+            let offset = &mut 0;
+            result.write_with(offset, ht, LE).unwrap();
+            assert_eq!(&data[2..], &result[..*offset]);
+        }
+    }
 }
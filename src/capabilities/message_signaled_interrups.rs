@@ -11,7 +11,7 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
@@ -20,7 +20,8 @@ use byte::{
 /// address specified by the contents of the Message Address register (and, optionally, the Message
 /// Upper Address register for a 64-bit message address). A read of the address specified by the
 /// contents of the Message Address register produces undefined results. 
-#[derive(Default, Debug, PartialEq, Eq,)] 
+#[derive(Default, Debug, Clone, PartialEq, Eq,)] 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MessageSignaledInterrups {
     pub message_control: MessageControl,
     pub message_address: MessageAddress,
@@ -86,9 +87,37 @@ impl<'a> TryRead<'a, Endian> for MessageSignaledInterrups {
         Ok((msi, *offset))
     }
 }
+impl TryWrite<Endian> for MessageSignaledInterrups {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        let is_64bit_address_capable = matches!(self.message_address, MessageAddress::Qword(_));
+        let mcp = MessageControlProto::from(self.message_control)
+            .with_is_64bit_address_capable(is_64bit_address_capable);
+        bytes.write_with::<u16>(offset, mcp.into(), endian)?;
+        match self.message_address {
+            MessageAddress::Dword(dword) => {
+                bytes.write_with::<u32>(offset, dword, endian)?;
+            },
+            MessageAddress::Qword(qword) => {
+                bytes.write_with::<u32>(offset, qword as u32, endian)?;
+                bytes.write_with::<u32>(offset, (qword >> 32) as u32, endian)?;
+            },
+        }
+        bytes.write_with::<u16>(offset, self.message_data, endian)?;
+        bytes.write_with::<u16>(offset, self.reserved, endian)?;
+        if let Some(mask_bits) = self.mask_bits {
+            bytes.write_with::<u32>(offset, mask_bits, endian)?;
+        }
+        if let Some(pending_bits) = self.pending_bits {
+            bytes.write_with::<u32>(offset, pending_bits, endian)?;
+        }
+        Ok(*offset)
+    }
+}
 
 /// Provides system software control over MSI.
-#[derive(Default, Debug, PartialEq, Eq,)] 
+#[derive(Default, Debug, Clone, PartialEq, Eq,)] 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MessageControl {
     pub enable: bool,
     pub multiple_message_capable: NumberOfVectors,
@@ -99,7 +128,8 @@ pub struct MessageControl {
 }
 
 /// System-specified message address
-#[derive(Debug, PartialEq, Eq,)] 
+#[derive(Debug, Clone, PartialEq, Eq,)] 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MessageAddress {
     Dword(u32),
     Qword(u64),
@@ -114,6 +144,7 @@ impl Default for MessageAddress {
 /// The number of requested vectors must be aligned to a power of two (if a function requires three
 /// vectors, it requests four by initializing this field to “010”).
 #[derive(Clone, Copy, Debug, PartialEq, Eq,)] 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum NumberOfVectors {
     One = 1,
@@ -122,6 +153,9 @@ pub enum NumberOfVectors {
     Eight = 8,
     Sixteen = 16,
     ThirtyTwo = 32,
+    /// Reserved encoding 110b
+    Reserved6 = u8::MAX - 1,
+    /// Reserved encoding 111b
     Reserved = u8::MAX,
 }
 
@@ -139,10 +173,25 @@ impl From<u8> for NumberOfVectors {
             3 => Self::Eight,
             4 => Self::Sixteen,
             5 => Self::ThirtyTwo,
+            6 => Self::Reserved6,
             _ => Self::Reserved,
         }
     }
 }
+impl From<NumberOfVectors> for u8 {
+    fn from(data: NumberOfVectors) -> Self {
+        match data {
+            NumberOfVectors::One       => 0,
+            NumberOfVectors::Two       => 1,
+            NumberOfVectors::Four      => 2,
+            NumberOfVectors::Eight     => 3,
+            NumberOfVectors::Sixteen   => 4,
+            NumberOfVectors::ThirtyTwo => 5,
+            NumberOfVectors::Reserved6 => 6,
+            NumberOfVectors::Reserved  => 7,
+        }
+    }
+}
 
 
 /// Common Capability Structure for Message Address
@@ -168,6 +217,19 @@ impl From<MessageControlProto> for MessageControl {
         }
     }
 }
+/// 64 bit address capable bit is not a part of [MessageControl], it is derived from
+/// [MessageAddress] variant
+impl From<MessageControl> for MessageControlProto {
+    fn from(data: MessageControl) -> Self {
+        Self::new()
+            .with_enable(data.enable)
+            .with_multiple_message_capable(data.multiple_message_capable.into())
+            .with_multiple_message_enable(data.multiple_message_enable.into())
+            .with_is_64bit_address_capable(false)
+            .with_per_vector_masking_capable(data.per_vector_masking_capable)
+            .with_reserved(data.reserved)
+    }
+}
 
 
 
@@ -300,4 +362,19 @@ mod tests {
         };
         assert_eq!(sample, result);
     }
+    #[test]
+    fn write_message_address() {
+        let mut data =
+            *include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/random/4k"));
+        for control in [0b0_0000_0000u16, 0b0_1000_0000, 0b1_0000_0000, 0b1_1000_0000] {
+            let control = control.to_le_bytes();
+            data[2] = control[0];
+            data[3] = control[1];
+            let msi: MessageSignaledInterrups = data[2..].read_with(&mut 0, LE).unwrap();
+            let mut result = [0u8; 24];
+            let offset = &mut 0;
+            result.write_with(offset, msi, LE).unwrap();
+            assert_eq!(&data[2..2 + *offset], &result[..*offset]);
+        }
+    }
 }
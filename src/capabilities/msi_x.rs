@@ -11,7 +11,7 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
@@ -21,6 +21,7 @@ use byte::{
 /// instead points to an (MSI-X Table)[Table] structure and a (MSI-X Pending Bit Array
 /// (PBA))[PendingBitArray] structure, each residing in Memory Space. 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MsiX {
     pub message_control: MessageControl,
     pub table: Table,
@@ -37,6 +38,15 @@ impl<'a> TryRead<'a, Endian> for MsiX {
         Ok((msix, *offset))
     }
 }
+impl TryWrite<Endian> for MsiX {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u16>(offset, self.message_control.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.table.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.pending_bit_array.into(), endian)?;
+        Ok(*offset)
+    }
+}
 
 
 #[bitfield(bits = 16)]
@@ -50,9 +60,12 @@ pub struct MessageControlProto {
 
 /// Message Control for MSI-X 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MessageControl {
     /// Table Size
     pub table_size: u16,
+    /// Reserved bits 13:11
+    pub reserved: u8,
     /// Function Mask
     pub function_mask: bool,
     /// MSI-X Enable
@@ -60,9 +73,9 @@ pub struct MessageControl {
 }
 impl From<MessageControlProto> for MessageControl {
     fn from(proto: MessageControlProto) -> Self {
-        let _ = proto.reserved();
         Self {
             table_size: proto.table_size(),
+            reserved: proto.reserved(),
             function_mask: proto.function_mask(),
             msi_x_enable: proto.msi_x_enable(),
         }
@@ -71,10 +84,23 @@ impl From<MessageControlProto> for MessageControl {
 impl From<u16> for MessageControl {
     fn from(word: u16) -> Self { MessageControlProto::from(word).into() }
 }
+impl From<MessageControl> for MessageControlProto {
+    fn from(data: MessageControl) -> Self {
+        Self::new()
+            .with_table_size(data.table_size)
+            .with_reserved(data.reserved)
+            .with_function_mask(data.function_mask)
+            .with_msi_x_enable(data.msi_x_enable)
+    }
+}
+impl From<MessageControl> for u16 {
+    fn from(data: MessageControl) -> Self { MessageControlProto::from(data).into() }
+}
 
 /// BAR Indicator register (BIR) indicates which BAR, and a QWORD-aligned Offset indicates where
 /// the structure begins relative to the base address associated with the BAR
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Bir {
     Bar10h,
     Bar14h,
@@ -97,6 +123,19 @@ impl From<u8> for Bir {
         }
     }
 }
+impl From<Bir> for u8 {
+    fn from(data: Bir) -> Self {
+        match data {
+            Bir::Bar10h      => 0,
+            Bir::Bar14h      => 1,
+            Bir::Bar18h      => 2,
+            Bir::Bar1Ch      => 3,
+            Bir::Bar20h      => 4,
+            Bir::Bar24h      => 5,
+            Bir::Reserved(v) => v,
+        }
+    }
+}
 
 #[bitfield(bits = 32)]
 #[repr(u32)]
@@ -107,6 +146,7 @@ pub struct TableProto {
 
 /// Table Offset/Table BIR for MSI-X
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Table {
     pub bir: Bir,
     /// Used as an offset from the address contained by one of the function’s Base Address
@@ -124,6 +164,16 @@ impl From<TableProto> for Table {
 impl From<u32> for Table {
     fn from(word: u32) -> Self { TableProto::from(word).into() }
 }
+impl From<Table> for TableProto {
+    fn from(data: Table) -> Self {
+        Self::new()
+            .with_bir(data.bir.into())
+            .with_offset(data.offset >> 3)
+    }
+}
+impl From<Table> for u32 {
+    fn from(data: Table) -> Self { TableProto::from(data).into() }
+}
 
 
 
@@ -136,6 +186,7 @@ pub struct PendingBitArrayProto {
 
 /// PBA Offset/PBA BIR for MSI-X
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PendingBitArray {
     pub bir: Bir,
     /// Used as an offset from the address contained by one of the function’s Base Address
@@ -153,3 +204,13 @@ impl From<PendingBitArrayProto> for PendingBitArray {
 impl From<u32> for PendingBitArray {
     fn from(word: u32) -> Self { PendingBitArrayProto::from(word).into() }
 }
+impl From<PendingBitArray> for PendingBitArrayProto {
+    fn from(data: PendingBitArray) -> Self {
+        Self::new()
+            .with_bir(data.bir.into())
+            .with_offset(data.offset >> 3)
+    }
+}
+impl From<PendingBitArray> for u32 {
+    fn from(data: PendingBitArray) -> Self { PendingBitArrayProto::from(data).into() }
+}
@@ -16,7 +16,7 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
@@ -24,6 +24,7 @@ use byte::{
 
 /// PCI Express Capability Structure
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PciExpress {
     pub capabilities: Capabilities,
     pub device: Device,
@@ -58,6 +59,24 @@ impl<'a> TryRead<'a, Endian> for PciExpress {
         Ok((PciExpress { capabilities, device, link, slot, root, device_2, link_2, slot_2 }, *offset))
     }
 }
+impl TryWrite<Endian> for PciExpress {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        let version = self.capabilities.version;
+        bytes.write_with::<u16>(offset, self.capabilities.into(), endian)?;
+        bytes.write_with(offset, self.device, endian)?;
+        bytes.write_with(offset, LinkOption(self.link), endian)?;
+        bytes.write_with(offset, SlotOption(self.slot), endian)?;
+        bytes.write_with(offset, RootOption(self.root), endian)?;
+        // Since PCI Express® Base Specification Revision 2
+        if version > 1 {
+            bytes.write_with(offset, Device2Option(self.device_2), endian)?;
+            bytes.write_with(offset, Link2Option(self.link_2), endian)?;
+            bytes.write_with(offset, Slot2Option(self.slot_2), endian)?;
+        }
+        Ok(*offset)
+    }
+}
 
 
 #[bitfield(bits = 16)]
@@ -74,6 +93,7 @@ pub struct CapabilitiesProto {
 /// The PCI Express Capabilities register identifies PCI Express device Function type and
 /// associated capabilities
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Capabilities {
     /// Indicates PCI-SIG defined PCI Express Capability structure version number
     pub version: u8,
@@ -85,25 +105,42 @@ pub struct Capabilities {
     pub interrupt_message_number: u8,
     /// Indicate support for TCS Routing
     pub tcs_routing_support: bool,
+    /// Reserved bit 15
+    pub rsvdp: u8,
 }
 impl From<CapabilitiesProto> for Capabilities {
     fn from(proto: CapabilitiesProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             version: proto.version(),
             device_type: proto.device_type().into(),
             slot_implemented: proto.slot_implemented(),
             interrupt_message_number: proto.interrupt_message_number(),
             tcs_routing_support: proto.tcs_routing_support(),
+            rsvdp: proto.rsvdp(),
         }
     }
 }
 impl From<u16> for Capabilities {
     fn from(word: u16) -> Self { CapabilitiesProto::from(word).into() }
 }
+impl From<Capabilities> for CapabilitiesProto {
+    fn from(data: Capabilities) -> Self {
+        Self::new()
+            .with_version(data.version)
+            .with_device_type(data.device_type.into())
+            .with_slot_implemented(data.slot_implemented)
+            .with_interrupt_message_number(data.interrupt_message_number)
+            .with_tcs_routing_support(data.tcs_routing_support)
+            .with_rsvdp(data.rsvdp)
+    }
+}
+impl From<Capabilities> for u16 {
+    fn from(data: Capabilities) -> Self { CapabilitiesProto::from(data).into() }
+}
 
 /// Indicates the specific type of this PCI Express Function
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DeviceType {
     /// PCI Express Endpoint
     Endpoint,
@@ -142,10 +179,27 @@ impl From<u8> for DeviceType {
         }
     }
 }
+impl From<DeviceType> for u8 {
+    fn from(data: DeviceType) -> Self {
+        match data {
+            DeviceType::Endpoint                      => 0b0000,
+            DeviceType::LegacyEndpoint                => 0b0001,
+            DeviceType::RootPort                      => 0b0100,
+            DeviceType::UpstreamPort                  => 0b0101,
+            DeviceType::DownstreamPort                => 0b0110,
+            DeviceType::PcieToPciBridge               => 0b0111,
+            DeviceType::PciToPcieBridge               => 0b1000,
+            DeviceType::RootComplexIntegratedEndpoint => 0b1001,
+            DeviceType::RootComplexEventCollector     => 0b1010,
+            DeviceType::Reserved(v)                   => v,
+        }
+    }
+}
 
 /// The Device Capabilities, Device Status, and Device Control registers are required for all PCI
 /// Express device Functions
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Device {
     pub capabilities: DeviceCapabilities,
     pub control: DeviceControl,
@@ -162,6 +216,15 @@ impl<'a> TryRead<'a, Endian> for Device {
         Ok((result, *offset))
     }
 }
+impl TryWrite<Endian> for Device {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u32>(offset, self.capabilities.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.control.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.status.into(), endian)?;
+        Ok(*offset)
+    }
+}
 
 #[bitfield(bits = 32)]
 #[repr(u32)]
@@ -183,6 +246,7 @@ pub struct DeviceCapabilitiesProto {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DeviceCapabilities {
     /// Max_Payload_Size Supported
     pub max_payload_size_supported: MaxSize,
@@ -204,14 +268,16 @@ pub struct DeviceCapabilities {
     /// Function implements the functionality originally defined in the Error Reporting ECN for PCI
     /// Express Base Specification
     pub role_based_error_reporting: bool,
+    /// Reserved bits 17:16
+    pub rsvdp: u8,
     pub captured_slot_power_limit: SlotPowerLimit,
     /// Function supports the optional Function Level Reset mechanism
     pub function_level_reset_capability: bool,
+    /// Reserved bits 31:29
+    pub rsvdp_2: u8,
 }
 impl From<DeviceCapabilitiesProto> for DeviceCapabilities {
     fn from(proto: DeviceCapabilitiesProto) -> Self {
-        let _ = proto.rsvdp();
-        let _ = proto.rsvdp_2();
         Self {
             max_payload_size_supported: proto.max_payload_size_supported().into(),
             phantom_functions_supported: proto.phantom_functions_supported().into(),
@@ -222,21 +288,46 @@ impl From<DeviceCapabilitiesProto> for DeviceCapabilities {
             attention_indicator_present: proto.attention_indicator_present(),
             power_indicator_present: proto.power_indicator_present(),
             role_based_error_reporting: proto.role_based_error_reporting(),
+            rsvdp: proto.rsvdp(),
             captured_slot_power_limit: SlotPowerLimit::new(
                 proto.captured_slot_power_limit_value(),
                 proto.captured_slot_power_limit_scale(),
             ),
             function_level_reset_capability: proto.function_level_reset_capability(),
+            rsvdp_2: proto.rsvdp_2(),
         }
     }
 }
 impl From<u32> for DeviceCapabilities {
     fn from(dword: u32) -> Self { DeviceCapabilitiesProto::from(dword).into() }
 }
+impl From<DeviceCapabilities> for DeviceCapabilitiesProto {
+    fn from(data: DeviceCapabilities) -> Self {
+        Self::new()
+            .with_max_payload_size_supported(data.max_payload_size_supported as u8)
+            .with_phantom_functions_supported(data.phantom_functions_supported as u8)
+            .with_extended_tag_field_supported(data.extended_tag_field_supported.into())
+            .with_endpoint_l0s_acceptable_latency(data.endpoint_l0s_acceptable_latency as u8)
+            .with_endpoint_l1_acceptable_latency(data.endpoint_l1_acceptable_latency as u8)
+            .with_attention_button_present(data.attention_button_present)
+            .with_attention_indicator_present(data.attention_indicator_present)
+            .with_power_indicator_present(data.power_indicator_present)
+            .with_role_based_error_reporting(data.role_based_error_reporting)
+            .with_rsvdp(data.rsvdp)
+            .with_captured_slot_power_limit_value(data.captured_slot_power_limit.value)
+            .with_captured_slot_power_limit_scale(data.captured_slot_power_limit.scale_encoding())
+            .with_function_level_reset_capability(data.function_level_reset_capability)
+            .with_rsvdp_2(data.rsvdp_2)
+    }
+}
+impl From<DeviceCapabilities> for u32 {
+    fn from(data: DeviceCapabilities) -> Self { DeviceCapabilitiesProto::from(data).into() }
+}
 
 
 /// Max_Payload_Size Supported / Max_Payload_Size / Max_Read_Request_Size 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MaxSize {
     /// 128 bytes max size 
     B128,
@@ -275,6 +366,7 @@ impl From<u8> for MaxSize {
 /// allowed by logically combining unclaimed Function Numbers (called Phantom Functions) with the
 /// Tag identifier
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PhantomFunctionsSupported {
     /// No Function Number bits are used
     NoBits,
@@ -301,6 +393,7 @@ impl From<u8> for PhantomFunctionsSupported {
 
 /// Maximum supported size of the Tag field as a Requester
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExtendedTagFieldSupported {
     /// 5-bit Tag field supported
     Five,
@@ -316,10 +409,19 @@ impl From<bool> for ExtendedTagFieldSupported {
         }
     }
 }
+impl From<ExtendedTagFieldSupported> for bool {
+    fn from(data: ExtendedTagFieldSupported) -> Self {
+        match data {
+            ExtendedTagFieldSupported::Five  => false,
+            ExtendedTagFieldSupported::Eight => true,
+        }
+    }
+}
 
 /// Acceptable total latency that an Endpoint can withstand due to the transition from L0s state to
 /// the L0 state
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EndpointL0sAcceptableLatency {
     /// Maximum of 64 ns
     Max64ns,
@@ -357,6 +459,7 @@ impl From<u8> for EndpointL0sAcceptableLatency {
 /// Aacceptable latency that an Endpoint can withstand due to the transition from L1 state to the
 /// L0 state
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EndpointL1AcceptableLatency {
     /// Maximum of 1 µs
     Max1us,
@@ -395,6 +498,7 @@ impl From<u8> for EndpointL1AcceptableLatency {
 /// Slot Power Limit (Captured)
 /// Specifies the upper limit on power available/supplied to the adapter
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SlotPowerLimit {
     /// Slot Power Limit Value
     pub value: u8,
@@ -411,6 +515,12 @@ impl SlotPowerLimit {
                _ => unreachable!(),
         }
     }
+    /// Two bit Slot Power Limit Scale encoding
+    pub fn scale_encoding(&self) -> u8 {
+        [1.0, 0.1, 0.01, 0.001].iter()
+            .position(|&scale| scale == self.scale)
+            .unwrap_or(0) as u8
+    }
 }
 impl Eq for SlotPowerLimit {}
 impl From<SlotPowerLimit> for f32 {
@@ -444,6 +554,7 @@ pub struct DeviceControlProto {
 
 /// The Device Control register controls PCI Express device specific parameters
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DeviceControl {
     /// Correctable Error Reporting Enable
     pub correctable_error_reporting_enable: bool,
@@ -495,6 +606,26 @@ impl From<DeviceControlProto> for DeviceControl {
 impl From<u16> for DeviceControl {
     fn from(word: u16) -> Self { DeviceControlProto::from(word).into() }
 }
+impl From<DeviceControl> for DeviceControlProto {
+    fn from(data: DeviceControl) -> Self {
+        Self::new()
+            .with_correctable_error_reporting_enable(data.correctable_error_reporting_enable)
+            .with_non_fatal_error_reporting_enable(data.non_fatal_error_reporting_enable)
+            .with_fatal_error_reporting_enable(data.fatal_error_reporting_enable)
+            .with_unsupported_request_reporting_enable(data.unsupported_request_reporting_enable)
+            .with_enable_relaxed_ordering(data.enable_relaxed_ordering)
+            .with_max_payload_size(data.max_payload_size as u8)
+            .with_extended_tag_field_enable(data.extended_tag_field_enable)
+            .with_phantom_functions_enable(data.phantom_functions_enable)
+            .with_aux_power_pm_enable(data.aux_power_pm_enable)
+            .with_enable_no_snoop(data.enable_no_snoop)
+            .with_max_read_request_size(data.max_read_request_size as u8)
+            .with_bcre_or_flreset(data.bcre_or_flreset)
+    }
+}
+impl From<DeviceControl> for u16 {
+    fn from(data: DeviceControl) -> Self { DeviceControlProto::from(data).into() }
+}
 
 
 #[bitfield(bits = 16)]
@@ -511,6 +642,7 @@ pub struct DeviceStatusProto {
 
 /// Provides information about PCI Express device (Function) specific parameters
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DeviceStatus {
     /// Correctable Error Detected
     pub correctable_error_detected: bool,
@@ -529,10 +661,11 @@ pub struct DeviceStatus {
     /// - Root and Switch pub Ports: indicates that a Port has issued Non-Posted Requests on its own
     ///   behalf (using the Port’s own Requester ID) which have not been completed
     pub transactions_pending: bool,
+    /// Reserved bits 15:6
+    pub rsvdz: u16,
 }
 impl From<DeviceStatusProto> for DeviceStatus {
     fn from(proto: DeviceStatusProto) -> Self {
-        let _ = proto.rsvdz();
         Self {
             correctable_error_detected: proto.correctable_error_detected(),
             non_fatal_error_detected: proto.non_fatal_error_detected(),
@@ -540,17 +673,34 @@ impl From<DeviceStatusProto> for DeviceStatus {
             unsupported_request_detected: proto.unsupported_request_detected(),
             aux_power_detected: proto.aux_power_detected(),
             transactions_pending: proto.transactions_pending(),
+            rsvdz: proto.rsvdz(),
         }
     }
 }
 impl From<u16> for DeviceStatus {
     fn from(word: u16) -> Self { DeviceStatusProto::from(word).into() }
 }
+impl From<DeviceStatus> for DeviceStatusProto {
+    fn from(data: DeviceStatus) -> Self {
+        Self::new()
+            .with_correctable_error_detected(data.correctable_error_detected)
+            .with_non_fatal_error_detected(data.non_fatal_error_detected)
+            .with_fatal_error_detected(data.fatal_error_detected)
+            .with_unsupported_request_detected(data.unsupported_request_detected)
+            .with_aux_power_detected(data.aux_power_detected)
+            .with_transactions_pending(data.transactions_pending)
+            .with_rsvdz(data.rsvdz)
+    }
+}
+impl From<DeviceStatus> for u16 {
+    fn from(data: DeviceStatus) -> Self { DeviceStatusProto::from(data).into() }
+}
 
 
 /// The Link Capabilities, Link Status, and Link Control registers are required for all Root Ports,
 /// Switch Ports, Bridges, and Endpoints that are not Root Complex Integrated Endpoints
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Link {
     pub capabilities: LinkCapabilities,
     pub control: LinkControl,
@@ -581,6 +731,18 @@ impl<'a> TryRead<'a, Endian> for LinkOption {
         }
     }
 }
+impl TryWrite<Endian> for LinkOption {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let (capabilities, control, status): (u32, u16, u16) = self.0
+            .map(|link| (link.capabilities.into(), link.control.into(), link.status.into()))
+            .unwrap_or_default();
+        let offset = &mut 0;
+        bytes.write_with::<u32>(offset, capabilities, endian)?;
+        bytes.write_with::<u16>(offset, control, endian)?;
+        bytes.write_with::<u16>(offset, status, endian)?;
+        Ok(*offset)
+    }
+}
 
 
 #[bitfield(bits = 32)]
@@ -612,13 +774,14 @@ impl From<LinkCapabilities> for LinkCapabilitiesProto {
             .with_data_link_layer_link_active_reporting_capable(data.data_link_layer_link_active_reporting_capable)
             .with_link_bandwidth_notification_capability(data.link_bandwidth_notification_capability)
             .with_aspm_optionality_compliance(data.aspm_optionality_compliance)
-            .with_rsvdp(0)
+            .with_rsvdp(data.rsvdp)
             .with_port_number(data.port_number)
     }
 }
 
 /// The Link Capabilities register identifies PCI Express Link specific capabilities
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LinkCapabilities {
     pub max_link_speed: LinkSpeed,
     pub maximum_link_width: LinkWidth,
@@ -636,12 +799,13 @@ pub struct LinkCapabilities {
     pub link_bandwidth_notification_capability: bool,
     /// ASPM Optionality Compliance
     pub aspm_optionality_compliance: bool,
+    /// Reserved bit 23
+    pub rsvdp: u8,
     /// Port Number
     pub port_number: u8,
 }
 impl From<LinkCapabilitiesProto> for LinkCapabilities {
     fn from(proto: LinkCapabilitiesProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             max_link_speed: proto.max_link_speed().into(),
             maximum_link_width: proto.maximum_link_width().into(),
@@ -653,6 +817,7 @@ impl From<LinkCapabilitiesProto> for LinkCapabilities {
             data_link_layer_link_active_reporting_capable: proto.data_link_layer_link_active_reporting_capable(),
             link_bandwidth_notification_capability: proto.link_bandwidth_notification_capability(),
             aspm_optionality_compliance: proto.aspm_optionality_compliance(),
+            rsvdp: proto.rsvdp(),
             port_number: proto.port_number(),
         }
     }
@@ -668,6 +833,7 @@ impl From<LinkCapabilities> for u32 {
 /// Max/Current/Target Link Speed
 /// Speeds should be taken from [SupportedLinkSpeedsVector]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LinkSpeed {
     /// 2.5 GT/s
     Rate2GTps,
@@ -718,6 +884,7 @@ impl From<LinkSpeed> for u8 {
 
 /// Maximum/Negotiated Link Width
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LinkWidth {
     Reserved(u8),
     X1,
@@ -759,13 +926,13 @@ impl From<u8> for LinkWidth {
 impl From<LinkWidth> for u8 {
     fn from(data: LinkWidth) -> Self {
         match data {
-            LinkWidth::X1  => 0,
-            LinkWidth::X2  => 1,
-            LinkWidth::X4  => 2,
-            LinkWidth::X8  => 3,
-            LinkWidth::X12 => 4,
-            LinkWidth::X16 => 5,
-            LinkWidth::X32 => 6,
+            LinkWidth::X1  => 0b00_0001,
+            LinkWidth::X2  => 0b00_0010,
+            LinkWidth::X4  => 0b00_0100,
+            LinkWidth::X8  => 0b00_1000,
+            LinkWidth::X12 => 0b00_1100,
+            LinkWidth::X16 => 0b01_0000,
+            LinkWidth::X32 => 0b10_0000,
             LinkWidth::Reserved(v) => v,
         }
     }
@@ -774,6 +941,7 @@ impl From<LinkWidth> for u8 {
 
 /// Active State Power Management (ASPM) Support/Control
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ActiveStatePowerManagement {
     NoAspm,
     L0s,
@@ -795,6 +963,7 @@ impl From<u8> for ActiveStatePowerManagement {
 
 /// L0s Exit Latency
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum L0sExitLatency {
     /// Less than 64 ns
     Lt64ns,
@@ -832,6 +1001,7 @@ impl From<u8> for L0sExitLatency {
 
 /// L1 Exit Latency
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum L1ExitLatency {
     /// Less than 1 µs
     Lt1us,
@@ -885,9 +1055,12 @@ pub struct LinkControlProto {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LinkControl {
     /// Active State Power Management (ASPM) Control
     pub active_state_power_management_control: ActiveStatePowerManagement,
+    /// Reserved bit 2
+    pub rsvdp: u8,
     /// Read Completion Boundary (RCB)
     pub read_completion_boundary: ReadCompletionBoundary,
     /// Link Disable
@@ -906,13 +1079,14 @@ pub struct LinkControl {
     pub link_bandwidth_management_interrupt_enable: bool,
     /// Link Autonomous Bandwidth Interrupt Enable
     pub link_autonomous_bandwidth_interrupt_enable: bool,
+    /// Reserved bits 15:12
+    pub rsvdp_2: u8,
 }
 impl From<LinkControlProto> for LinkControl {
     fn from(proto: LinkControlProto) -> Self {
-        let _ = proto.rsvdp();
-        let _ = proto.rsvdp_2();
         Self {
             active_state_power_management_control: proto.active_state_power_management_control().into(),
+            rsvdp: proto.rsvdp(),
             read_completion_boundary: proto.read_completion_boundary().into(),
             link_disable: proto.link_disable(),
             retrain_link: proto.retrain_link(),
@@ -922,15 +1096,37 @@ impl From<LinkControlProto> for LinkControl {
             hardware_autonomous_width_disable: proto.hardware_autonomous_width_disable(),
             link_bandwidth_management_interrupt_enable: proto.link_bandwidth_management_interrupt_enable(),
             link_autonomous_bandwidth_interrupt_enable: proto.link_autonomous_bandwidth_interrupt_enable(),
+            rsvdp_2: proto.rsvdp_2(),
         }
     }
 }
 impl From<u16> for LinkControl {
     fn from(word: u16) -> Self { LinkControlProto::from(word).into() }
 }
+impl From<LinkControl> for LinkControlProto {
+    fn from(data: LinkControl) -> Self {
+        Self::new()
+            .with_active_state_power_management_control(data.active_state_power_management_control as u8)
+            .with_rsvdp(data.rsvdp)
+            .with_read_completion_boundary(data.read_completion_boundary.into())
+            .with_link_disable(data.link_disable)
+            .with_retrain_link(data.retrain_link)
+            .with_common_clock_configuration(data.common_clock_configuration)
+            .with_extended_synch(data.extended_synch)
+            .with_enable_clock_power_management(data.enable_clock_power_management)
+            .with_hardware_autonomous_width_disable(data.hardware_autonomous_width_disable)
+            .with_link_bandwidth_management_interrupt_enable(data.link_bandwidth_management_interrupt_enable)
+            .with_link_autonomous_bandwidth_interrupt_enable(data.link_autonomous_bandwidth_interrupt_enable)
+            .with_rsvdp_2(data.rsvdp_2)
+    }
+}
+impl From<LinkControl> for u16 {
+    fn from(data: LinkControl) -> Self { LinkControlProto::from(data).into() }
+}
 
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ReadCompletionBoundary {
     B64 = 64,
     B128 = 128,
@@ -938,6 +1134,9 @@ pub enum ReadCompletionBoundary {
 impl From<bool> for ReadCompletionBoundary {
     fn from(b: bool) -> Self { if b { Self::B128 } else { Self::B64 } }
 }
+impl From<ReadCompletionBoundary> for bool {
+    fn from(data: ReadCompletionBoundary) -> Self { data == ReadCompletionBoundary::B128 }
+}
 
 
 #[bitfield(bits = 16)]
@@ -955,6 +1154,7 @@ pub struct LinkStatusProto {
 
 /// The Link Status register provides information about PCI Express Link specific parameters
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LinkStatus {
     /// Current Link Speed
     pub current_link_speed: LinkSpeed,
@@ -990,12 +1190,29 @@ impl From<LinkStatusProto> for LinkStatus {
 impl From<u16> for LinkStatus {
     fn from(word: u16) -> Self { LinkStatusProto::from(word).into() }
 }
+impl From<LinkStatus> for LinkStatusProto {
+    fn from(data: LinkStatus) -> Self {
+        Self::new()
+            .with_current_link_speed(data.current_link_speed.into())
+            .with_negotiated_link_width(data.negotiated_link_width.into())
+            .with_link_training_error(data.link_training_error)
+            .with_link_training(data.link_training)
+            .with_slot_clock_configuration(data.slot_clock_configuration)
+            .with_data_link_layer_link_active(data.data_link_layer_link_active)
+            .with_link_bandwidth_management_status(data.link_bandwidth_management_status)
+            .with_link_autonomous_bandwidth_status(data.link_autonomous_bandwidth_status)
+    }
+}
+impl From<LinkStatus> for u16 {
+    fn from(data: LinkStatus) -> Self { LinkStatusProto::from(data).into() }
+}
 
 
 /// Slot Capabilities, Slot Status, and Slot Control registers are required for Switch Downstream
 /// and Root Ports if a slot is implemented on the Port (indicated by the Slot Implemented bit in
 /// the PCI Express Capabilities register)
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Slot {
     pub capabilities: SlotCapabilities,
     pub control: SlotControl,
@@ -1026,6 +1243,18 @@ impl<'a> TryRead<'a, Endian> for SlotOption {
         }
     }
 }
+impl TryWrite<Endian> for SlotOption {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let (capabilities, control, status): (u32, u16, u16) = self.0
+            .map(|slot| (slot.capabilities.into(), slot.control.into(), slot.status.into()))
+            .unwrap_or_default();
+        let offset = &mut 0;
+        bytes.write_with::<u32>(offset, capabilities, endian)?;
+        bytes.write_with::<u16>(offset, control, endian)?;
+        bytes.write_with::<u16>(offset, status, endian)?;
+        Ok(*offset)
+    }
+}
 
 
 #[bitfield(bits = 32)]
@@ -1047,6 +1276,7 @@ pub struct SlotCapabilitiesProto {
 
 /// The Slot Capabilities register identifies PCI Express slot specific capabilities
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SlotCapabilities {
     /// Attention Button Present
     pub attention_button_present: bool,
@@ -1094,6 +1324,26 @@ impl From<SlotCapabilitiesProto> for SlotCapabilities {
 impl From<u32> for SlotCapabilities {
     fn from(dword: u32) -> Self { SlotCapabilitiesProto::from(dword).into() }
 }
+impl From<SlotCapabilities> for SlotCapabilitiesProto {
+    fn from(data: SlotCapabilities) -> Self {
+        Self::new()
+            .with_attention_button_present(data.attention_button_present)
+            .with_power_controller_present(data.power_controller_present)
+            .with_mrl_sensor_present(data.mrl_sensor_present)
+            .with_attention_indicator_present(data.attention_indicator_present)
+            .with_power_indicator_present(data.power_indicator_present)
+            .with_hot_plug_surprise(data.hot_plug_surprise)
+            .with_hot_plug_capable(data.hot_plug_capable)
+            .with_slot_power_limit_value(data.slot_power_limit.value)
+            .with_slot_power_limit_scale(data.slot_power_limit.scale_encoding())
+            .with_electromechanical_interlock_present(data.electromechanical_interlock_present)
+            .with_no_command_completed_support(data.no_command_completed_support)
+            .with_physical_slot_number(data.physical_slot_number)
+    }
+}
+impl From<SlotCapabilities> for u32 {
+    fn from(data: SlotCapabilities) -> Self { SlotCapabilitiesProto::from(data).into() }
+}
 
 
 #[bitfield(bits = 16)]
@@ -1114,6 +1364,7 @@ pub struct SlotControlProto {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SlotControl {
     /// Attention Button Pressed Enable
     pub attention_button_pressed_enable: bool,
@@ -1137,10 +1388,11 @@ pub struct SlotControl {
     pub electromechanical_interlock_control: bool,
     /// Data Link Layer State Changed Enable
     pub data_link_layer_state_changed_enable: bool,
+    /// Reserved bits 15:13
+    pub rsvdp: u8,
 }
 impl From<SlotControlProto> for SlotControl {
     fn from(proto: SlotControlProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             attention_button_pressed_enable: proto.attention_button_pressed_enable(),
             power_fault_detected_enable: proto.power_fault_detected_enable(),
@@ -1153,16 +1405,38 @@ impl From<SlotControlProto> for SlotControl {
             power_controller_control: proto.power_controller_control(),
             electromechanical_interlock_control: proto.electromechanical_interlock_control(),
             data_link_layer_state_changed_enable: proto.data_link_layer_state_changed_enable(),
+            rsvdp: proto.rsvdp(),
         }
     }
 }
 impl From<u16> for SlotControl {
     fn from(word: u16) -> Self { SlotControlProto::from(word).into() }
 }
+impl From<SlotControl> for SlotControlProto {
+    fn from(data: SlotControl) -> Self {
+        Self::new()
+            .with_attention_button_pressed_enable(data.attention_button_pressed_enable)
+            .with_power_fault_detected_enable(data.power_fault_detected_enable)
+            .with_mrl_sensor_changed_enable(data.mrl_sensor_changed_enable)
+            .with_presence_detect_changed_enable(data.presence_detect_changed_enable)
+            .with_command_completed_interrupt_enable(data.command_completed_interrupt_enable)
+            .with_hot_plug_interrupt_enable(data.hot_plug_interrupt_enable)
+            .with_attention_indicator_control(data.attention_indicator_control as u8)
+            .with_power_indicator_control(data.power_indicator_control as u8)
+            .with_power_controller_control(data.power_controller_control)
+            .with_electromechanical_interlock_control(data.electromechanical_interlock_control)
+            .with_data_link_layer_state_changed_enable(data.data_link_layer_state_changed_enable)
+            .with_rsvdp(data.rsvdp)
+    }
+}
+impl From<SlotControl> for u16 {
+    fn from(data: SlotControl) -> Self { SlotControlProto::from(data).into() }
+}
 
 
 /// Attention/Power Indicator Control 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IndicatorControl {
     Reserved,
     On,
@@ -1198,6 +1472,7 @@ pub struct SlotStatusProto {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SlotStatus {
     /// Attention Button Pressed
     pub attention_button_pressed: bool,
@@ -1217,10 +1492,11 @@ pub struct SlotStatus {
     pub electromechanical_interlock_status: bool,
     /// Data Link Layer State Changed
     pub data_link_layer_state_changed: bool,
+    /// Reserved bits 15:9
+    pub rsvdz: u8,
 }
 impl From<SlotStatusProto> for SlotStatus {
     fn from(proto: SlotStatusProto) -> Self {
-        let _ = proto.rsvdz();
         Self {
             attention_button_pressed: proto.attention_button_pressed(),
             power_fault_detected: proto.power_fault_detected(),
@@ -1231,17 +1507,37 @@ impl From<SlotStatusProto> for SlotStatus {
             presence_detect_state: proto.presence_detect_state(),
             electromechanical_interlock_status: proto.electromechanical_interlock_status(),
             data_link_layer_state_changed: proto.data_link_layer_state_changed(),
+            rsvdz: proto.rsvdz(),
         }
     }
 }
 impl From<u16> for SlotStatus {
     fn from(word: u16) -> Self { SlotStatusProto::from(word).into() }
 }
+impl From<SlotStatus> for SlotStatusProto {
+    fn from(data: SlotStatus) -> Self {
+        Self::new()
+            .with_attention_button_pressed(data.attention_button_pressed)
+            .with_power_fault_detected(data.power_fault_detected)
+            .with_mrl_sensor_changed(data.mrl_sensor_changed)
+            .with_presence_detect_changed(data.presence_detect_changed)
+            .with_command_completed(data.command_completed)
+            .with_mrl_sensor_state(data.mrl_sensor_state)
+            .with_presence_detect_state(data.presence_detect_state)
+            .with_electromechanical_interlock_status(data.electromechanical_interlock_status)
+            .with_data_link_layer_state_changed(data.data_link_layer_state_changed)
+            .with_rsvdz(data.rsvdz)
+    }
+}
+impl From<SlotStatus> for u16 {
+    fn from(data: SlotStatus) -> Self { SlotStatusProto::from(data).into() }
+}
 
 
 /// Root Ports and Root Complex Event Collectors must implement the Root Capabilities, Root Status,
 /// and Root Control registers
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Root {
     pub control: RootControl,
     pub capabilities: RootCapabilities,
@@ -1273,6 +1569,18 @@ impl<'a> TryRead<'a, Endian> for RootOption {
         }
     }
 }
+impl TryWrite<Endian> for RootOption {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let (control, capabilities, status): (u16, u16, u32) = self.0
+            .map(|root| (root.control.into(), root.capabilities.into(), root.status.into()))
+            .unwrap_or_default();
+        let offset = &mut 0;
+        bytes.write_with::<u16>(offset, control, endian)?;
+        bytes.write_with::<u16>(offset, capabilities, endian)?;
+        bytes.write_with::<u32>(offset, status, endian)?;
+        Ok(*offset)
+    }
+}
 
 
 #[bitfield(bits = 16)]
@@ -1288,6 +1596,7 @@ pub struct RootControlProto {
 
 /// The Root Control register controls PCI Express Root Complex specific parameters
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RootControl {
     /// System Error on Correctable Error Enable
     pub system_error_on_correctable_error_enable: bool,
@@ -1299,22 +1608,38 @@ pub struct RootControl {
     pub pme_interrupt_enable: bool,
     /// CRS Software Visibility Enable
     pub crs_software_visibility_enable: bool,
+    /// Reserved bits 15:5
+    pub rsvdp: u16,
 }
 impl From<RootControlProto> for RootControl {
     fn from(proto: RootControlProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             system_error_on_correctable_error_enable: proto.system_error_on_correctable_error_enable(),
             system_error_on_non_fatal_error_enable: proto.system_error_on_non_fatal_error_enable(),
             system_error_on_fatal_error_enable: proto.system_error_on_fatal_error_enable(),
             pme_interrupt_enable: proto.pme_interrupt_enable(),
             crs_software_visibility_enable: proto.crs_software_visibility_enable(),
+            rsvdp: proto.rsvdp(),
         }
     }
 }
 impl From<u16> for RootControl {
     fn from(word: u16) -> Self { RootControlProto::from(word).into() }
 }
+impl From<RootControl> for RootControlProto {
+    fn from(data: RootControl) -> Self {
+        Self::new()
+            .with_system_error_on_correctable_error_enable(data.system_error_on_correctable_error_enable)
+            .with_system_error_on_non_fatal_error_enable(data.system_error_on_non_fatal_error_enable)
+            .with_system_error_on_fatal_error_enable(data.system_error_on_fatal_error_enable)
+            .with_pme_interrupt_enable(data.pme_interrupt_enable)
+            .with_crs_software_visibility_enable(data.crs_software_visibility_enable)
+            .with_rsvdp(data.rsvdp)
+    }
+}
+impl From<RootControl> for u16 {
+    fn from(data: RootControl) -> Self { RootControlProto::from(data).into() }
+}
 
 
 #[bitfield(bits = 16)]
@@ -1326,21 +1651,34 @@ pub struct RootCapabilitiesProto {
 
 /// The Root Capabilities register identifies PCI Express Root Port specific capabilities
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RootCapabilities {
     /// CRS Software Visibility
     pub crs_software_visibility: bool,
+    /// Reserved bits 15:1
+    pub rsvdp: u16,
 }
 impl From<RootCapabilitiesProto> for RootCapabilities {
     fn from(proto: RootCapabilitiesProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             crs_software_visibility: proto.crs_software_visibility(),
+            rsvdp: proto.rsvdp(),
         }
     }
 }
 impl From<u16> for RootCapabilities {
     fn from(word: u16) -> Self { RootCapabilitiesProto::from(word).into() }
 }
+impl From<RootCapabilities> for RootCapabilitiesProto {
+    fn from(data: RootCapabilities) -> Self {
+        Self::new()
+            .with_crs_software_visibility(data.crs_software_visibility)
+            .with_rsvdp(data.rsvdp)
+    }
+}
+impl From<RootCapabilities> for u16 {
+    fn from(data: RootCapabilities) -> Self { RootCapabilitiesProto::from(data).into() }
+}
 
 
 #[bitfield(bits = 32)]
@@ -1354,6 +1692,7 @@ pub struct RootStatusProto {
 
 /// The Root Status register provides information about PCI Express device specific parameters
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RootStatus {
     /// PME Requester ID
     pub pme_requester_id: u16,
@@ -1361,22 +1700,37 @@ pub struct RootStatus {
     pub pme_status: bool,
     /// PME Pending
     pub pme_pending: bool,
+    /// Reserved bits 31:18
+    pub rsvdz: u16,
 }
 impl From<RootStatusProto> for RootStatus {
     fn from(proto: RootStatusProto) -> Self {
-        let _ = proto.rsvdz();
         Self {
             pme_requester_id: proto.pme_requester_id(),
             pme_status: proto.pme_status(),
             pme_pending: proto.pme_pending(),
+            rsvdz: proto.rsvdz(),
         }
     }
 }
 impl From<u32> for RootStatus {
     fn from(dword: u32) -> Self { RootStatusProto::from(dword).into() }
 }
+impl From<RootStatus> for RootStatusProto {
+    fn from(data: RootStatus) -> Self {
+        Self::new()
+            .with_pme_requester_id(data.pme_requester_id)
+            .with_pme_status(data.pme_status)
+            .with_pme_pending(data.pme_pending)
+            .with_rsvdz(data.rsvdz)
+    }
+}
+impl From<RootStatus> for u32 {
+    fn from(data: RootStatus) -> Self { RootStatusProto::from(data).into() }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Device2 {
     pub capabilities: DeviceCapabilities2,
     pub control: DeviceControl2,
@@ -1407,6 +1761,18 @@ impl<'a> TryRead<'a, Endian> for Device2Option {
         }
     }
 }
+impl TryWrite<Endian> for Device2Option {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let (capabilities, control, status): (u32, u16, u16) = self.0
+            .map(|device| ((&device.capabilities).into(), (&device.control).into(), device.status.into()))
+            .unwrap_or_default();
+        let offset = &mut 0;
+        bytes.write_with::<u32>(offset, capabilities, endian)?;
+        bytes.write_with::<u16>(offset, control, endian)?;
+        bytes.write_with::<u16>(offset, status, endian)?;
+        Ok(*offset)
+    }
+}
 
 
 #[bitfield(bits = 32)]
@@ -1458,12 +1824,13 @@ impl<'a> From<&'a DeviceCapabilities2> for DeviceCapabilities2Proto {
             .with_emergency_power_reduction_initialization_required(
                 data.emergency_power_reduction_initialization_required
             )
-            .with_rsvdp(0)
+            .with_rsvdp(data.rsvdp)
             .with_frs_supported(data.frs_supported)
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DeviceCapabilities2  {
     /// Completion Timeout Ranges Supported
     pub completion_timeout_ranges_supported: CompletionTimeoutRanges,
@@ -1503,12 +1870,13 @@ pub struct DeviceCapabilities2  {
     pub emergency_power_reduction_supported: EmergencyPowerReduction,
     /// Emergency Power Reduction Initialization Required
     pub emergency_power_reduction_initialization_required: bool,
+    /// Reserved bits 30:27
+    pub rsvdp: u8,
     /// FRS Supported
     pub frs_supported: bool,
 }
 impl From<DeviceCapabilities2Proto> for DeviceCapabilities2 {
     fn from(proto: DeviceCapabilities2Proto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             completion_timeout_ranges_supported: proto.completion_timeout_ranges_supported().into(),
             completion_timeout_disable_supported: proto.completion_timeout_disable_supported(),
@@ -1530,6 +1898,7 @@ impl From<DeviceCapabilities2Proto> for DeviceCapabilities2 {
             emergency_power_reduction_supported: proto.emergency_power_reduction_supported().into(),
             emergency_power_reduction_initialization_required:
                 proto.emergency_power_reduction_initialization_required(),
+            rsvdp: proto.rsvdp(),
             frs_supported: proto.frs_supported(),
         }
     }
@@ -1544,6 +1913,7 @@ impl<'a> From<&'a DeviceCapabilities2> for u32 {
 
 /// Indicates device Function support for the optional Completion Timeout programmability mechanism
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CompletionTimeoutRanges {
     /// Completion Timeout programming not supported – the Function must implement a timeout value
     /// in the range 50 µs to 50 ms.
@@ -1604,6 +1974,7 @@ impl From<CompletionTimeoutRanges> for u8 {
 
 /// Value indicates Completer support for TPH or Extended TPH
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TphCompleter {
     /// TPH and Extended TPH Completer not supported.
     NotSupported,
@@ -1639,6 +2010,7 @@ impl From<TphCompleter> for u8 {
 /// Indicates if the Root Port or RCRB supports LN protocol as an LN Completer, and if so, what
 /// cacheline size is in effect
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LnSystemCls {
     /// LN Completer either not supported or not in effect
     NotSupported,
@@ -1673,6 +2045,7 @@ impl From<LnSystemCls> for u8 {
 
 /// Indicates if OBFF is supported and, if so, what signaling mechanism is used
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Obff {
     /// OBFF Not Supported
     NotSupported,
@@ -1707,6 +2080,7 @@ impl From<Obff> for u8 {
 
 /// Indicates the maximum number of End-End TLP Prefixes supported by this Function
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MaxEndEndTlpPrefixes {
     /// 4 End-End TLP Prefixes
     Max4,
@@ -1741,6 +2115,7 @@ impl From<MaxEndEndTlpPrefixes> for u8 {
 
 /// Indicates support level of the optional Emergency Power Reduction State feature
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EmergencyPowerReduction {
     /// Emergency Power Reduction State not supported
     NotSupported,
@@ -1812,6 +2187,7 @@ impl<'a> From<&'a DeviceControl2> for DeviceControl2Proto {
 
 /// Device Control 2 Register
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DeviceControl2  {
     /// Completion Timeout Value
     pub completion_timeout_value: CompletionTimeoutValue,
@@ -1864,6 +2240,7 @@ impl<'a> From<&'a DeviceControl2> for u16 {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CompletionTimeoutValue {
     /// Default range: 50 µs to 50 ms
     DefaultRange50usTo50ms,
@@ -1919,6 +2296,7 @@ impl From<CompletionTimeoutValue> for u8 {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ObffEnable {
     /// Disabled
     Disabled,
@@ -1961,22 +2339,35 @@ pub struct DeviceStatus2Proto {
 /// Device Status 2 Register is a placeholder
 /// There are no capabilities that require this register
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DeviceStatus2  {
+    /// Reserved bits 15:0
+    pub rsvdz: u16,
 }
 impl From<DeviceStatus2Proto> for DeviceStatus2 {
     fn from(proto: DeviceStatus2Proto) -> Self {
-        let _ = proto.rsvdz();
         Self {
+            rsvdz: proto.rsvdz(),
         }
     }
 }
 impl From<u16> for DeviceStatus2 {
     fn from(word: u16) -> Self { DeviceStatus2Proto::from(word).into() }
 }
+impl From<DeviceStatus2> for DeviceStatus2Proto {
+    fn from(data: DeviceStatus2) -> Self {
+        Self::new()
+            .with_rsvdz(data.rsvdz)
+    }
+}
+impl From<DeviceStatus2> for u16 {
+    fn from(data: DeviceStatus2) -> Self { DeviceStatus2Proto::from(data).into() }
+}
 
 /// Controls whether the routing function is permitted to forward TLPs containing an End-End TLP
 /// Prefix
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EndEndTlpPrefixBlocking {
     /// – Function is permitted to send TLPs with End-End TLP Prefixes
     ForwardingEnabled,
@@ -2002,6 +2393,7 @@ impl From<EndEndTlpPrefixBlocking> for bool {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Link2 {
     pub capabilities: LinkCapabilities2,
     pub control: LinkControl2,
@@ -2032,6 +2424,18 @@ impl<'a> TryRead<'a, Endian> for Link2Option {
         }
     }
 }
+impl TryWrite<Endian> for Link2Option {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let (capabilities, control, status): (u32, u16, u16) = self.0
+            .map(|link| (link.capabilities.into(), link.control.into(), link.status.into()))
+            .unwrap_or_default();
+        let offset = &mut 0;
+        bytes.write_with::<u32>(offset, capabilities, endian)?;
+        bytes.write_with::<u16>(offset, control, endian)?;
+        bytes.write_with::<u16>(offset, status, endian)?;
+        Ok(*offset)
+    }
+}
 
 
 #[bitfield(bits = 32)]
@@ -2050,21 +2454,24 @@ pub struct LinkCapabilities2Proto {
 impl From<LinkCapabilities2> for LinkCapabilities2Proto {
     fn from(data: LinkCapabilities2) -> Self {
         Self::new()
-            .with_rsvdp(0)
+            .with_rsvdp(data.rsvdp)
             .with_supported_link_speeds_vector(data.supported_link_speeds_vector.into())
             .with_crosslink_supported(data.crosslink_supported)
             .with_lower_skp_os_generation_supported_speeds_vector(data.lower_skp_os_generation_supported_speeds_vector.into())
             .with_lower_skp_os_reception_supported_speeds_vector(data.lower_skp_os_reception_supported_speeds_vector.into())
             .with_retimer_presence_detect_supported(data.retimer_presence_detect_supported)
             .with_two_retimers_presence_detect_supported(data.two_retimers_presence_detect_supported)
-            .with_rsvdp_2(0)
+            .with_rsvdp_2(data.rsvdp_2)
             .with_drs_supported(data.drs_supported)
     }
 }
 
 /// Link Capabilities 2 Register
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LinkCapabilities2  {
+    /// Reserved bit 0
+    pub rsvdp: u8,
     /// Supported Link Speeds Vector
     pub supported_link_speeds_vector: SupportedLinkSpeedsVector,
     /// Crosslink Supported
@@ -2077,14 +2484,15 @@ pub struct LinkCapabilities2  {
     pub retimer_presence_detect_supported: bool,
     /// Two Retimers Presence Detect Supported
     pub two_retimers_presence_detect_supported: bool,
+    /// Reserved bits 30:25
+    pub rsvdp_2: u8,
     /// DRS Supported
     pub drs_supported: bool,
 }
 impl From<LinkCapabilities2Proto> for LinkCapabilities2 {
     fn from(proto: LinkCapabilities2Proto) -> Self {
-        let _ = proto.rsvdp();
-        let _ = proto.rsvdp_2();
         Self {
+            rsvdp: proto.rsvdp(),
             supported_link_speeds_vector: proto.supported_link_speeds_vector().into(),
             crosslink_supported: proto.crosslink_supported(),
             lower_skp_os_generation_supported_speeds_vector:
@@ -2093,6 +2501,7 @@ impl From<LinkCapabilities2Proto> for LinkCapabilities2 {
                 proto.lower_skp_os_reception_supported_speeds_vector().into(),
             retimer_presence_detect_supported: proto.retimer_presence_detect_supported(),
             two_retimers_presence_detect_supported: proto.two_retimers_presence_detect_supported(),
+            rsvdp_2: proto.rsvdp_2(),
             drs_supported: proto.drs_supported(),
         }
     }
@@ -2123,12 +2532,13 @@ impl From<SupportedLinkSpeedsVector> for SupportedLinkSpeedsVectorProto {
             .with_speed_16_0_gtps(data.speed_16_0_gtps)
             .with_speed_32_0_gtps(data.speed_32_0_gtps)
             .with_speed_64_0_gtps(data.speed_64_0_gtps)
-            .with_rsvdp(0)
+            .with_rsvdp(data.rsvdp)
     }
 }
 
 /// Indicates the supported Link speed(s) of the associated Port
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SupportedLinkSpeedsVector  {
     /// 2.5 GT/s
     pub speed_2_5_gtps: bool,
@@ -2142,10 +2552,11 @@ pub struct SupportedLinkSpeedsVector  {
     pub speed_32_0_gtps: bool,
     /// 64.0 GT/s
     pub speed_64_0_gtps: bool,
+    /// Reserved bit 6
+    pub rsvdp: u8,
 }
 impl From<SupportedLinkSpeedsVectorProto> for SupportedLinkSpeedsVector {
     fn from(proto: SupportedLinkSpeedsVectorProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             speed_2_5_gtps: proto.speed_2_5_gtps(),
             speed_5_0_gtps: proto.speed_5_0_gtps(),
@@ -2153,6 +2564,7 @@ impl From<SupportedLinkSpeedsVectorProto> for SupportedLinkSpeedsVector {
             speed_16_0_gtps: proto.speed_16_0_gtps(),
             speed_32_0_gtps: proto.speed_32_0_gtps(),
             speed_64_0_gtps: proto.speed_64_0_gtps(),
+            rsvdp: proto.rsvdp(),
         }
     }
 }
@@ -2182,6 +2594,7 @@ pub struct LinkControl2Proto {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LinkControl2  {
     /// Target Link Speed
     pub target_link_speed: LinkSpeed,
@@ -2216,11 +2629,28 @@ impl From<LinkControl2Proto> for LinkControl2 {
 impl From<u16> for LinkControl2 {
     fn from(word: u16) -> Self { LinkControl2Proto::from(word).into() }
 }
+impl From<LinkControl2> for LinkControl2Proto {
+    fn from(data: LinkControl2) -> Self {
+        Self::new()
+            .with_target_link_speed(data.target_link_speed.into())
+            .with_enter_compliance(data.enter_compliance)
+            .with_hardware_autonomous_speed_disable(data.hardware_autonomous_speed_disable)
+            .with_selectable_de_emphasis(data.selectable_de_emphasis.into())
+            .with_transmit_margin(data.transmit_margin.0)
+            .with_enter_modified_compliance(data.enter_modified_compliance)
+            .with_compliance_sos(data.compliance_sos)
+            .with_compliance_preset_or_de_emphasis(data.compliance_preset_or_de_emphasis.0)
+    }
+}
+impl From<LinkControl2> for u16 {
+    fn from(data: LinkControl2) -> Self { LinkControl2Proto::from(data).into() }
+}
 
 /// Selectable De-emphasis
 ///
 /// Used to control the transmit deemphasis of the link in specific situations
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DeEmphasis {
     Minus3_5dB,
     Minus6dB,
@@ -2234,11 +2664,20 @@ impl From<bool> for DeEmphasis {
         }
     }
 }
+impl From<DeEmphasis> for bool {
+    fn from(data: DeEmphasis) -> Self {
+        match data {
+            DeEmphasis::Minus3_5dB => true,
+            DeEmphasis::Minus6dB   => false,
+        }
+    }
+}
 
 /// Controls the value of the nondeemphasized voltage level at the Transmitter pins
 ///
 /// 0b000 - Normal operating range
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransmitMargin(pub u8);
 impl From<u8> for TransmitMargin {
     fn from(byte: u8) -> Self {
@@ -2255,6 +2694,7 @@ impl From<u8> for TransmitMargin {
 ///   if the entry occurred due to the Enter Compliance bit being 1b. 
 /// - **2.5 GT/s Data Rate:** The setting of this field has no effect.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CompliancePresetOrDeEmphasis(pub u8);
 impl From<u8> for CompliancePresetOrDeEmphasis {
     fn from(byte: u8) -> Self {
@@ -2280,6 +2720,7 @@ pub struct LinkStatus2Proto {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LinkStatus2  {
     /// Current De-emphasis Level
     pub current_de_emphasis_level: DeEmphasis,
@@ -2299,6 +2740,8 @@ pub struct LinkStatus2  {
     pub two_retimers_presence_detected: bool,
     /// Crosslink Resolution
     pub crosslink_resolution: CrosslinkResolution,
+    /// Reserved bits 11:10
+    pub rsvdz: u8,
     /// Downstream Component Presence
     pub downstream_component_presence: DownstreamComponentPresence,
     /// DRS Message Received
@@ -2306,7 +2749,6 @@ pub struct LinkStatus2  {
 }
 impl From<LinkStatus2Proto> for LinkStatus2 {
     fn from(proto: LinkStatus2Proto) -> Self {
-        let _ = proto.rsvdz();
         Self {
             current_de_emphasis_level: proto.current_de_emphasis_level().into(),
             equalization_complete: proto.equalization_complete(),
@@ -2317,6 +2759,7 @@ impl From<LinkStatus2Proto> for LinkStatus2 {
             retimer_presence_detected: proto.retimer_presence_detected(),
             two_retimers_presence_detected: proto.two_retimers_presence_detected(),
             crosslink_resolution: proto.crosslink_resolution().into(),
+            rsvdz: proto.rsvdz(),
             downstream_component_presence: proto.downstream_component_presence().into(),
             drs_message_received: proto.drs_message_received(),
         }
@@ -2325,10 +2768,31 @@ impl From<LinkStatus2Proto> for LinkStatus2 {
 impl From<u16> for LinkStatus2 {
     fn from(word: u16) -> Self { LinkStatus2Proto::from(word).into() }
 }
+impl From<LinkStatus2> for LinkStatus2Proto {
+    fn from(data: LinkStatus2) -> Self {
+        Self::new()
+            .with_current_de_emphasis_level(data.current_de_emphasis_level.into())
+            .with_equalization_complete(data.equalization_complete)
+            .with_equalization_phase_1_successful(data.equalization_phase_1_successful)
+            .with_equalization_phase_2_successful(data.equalization_phase_2_successful)
+            .with_equalization_phase_3_successful(data.equalization_phase_3_successful)
+            .with_link_equalization_request(data.link_equalization_request)
+            .with_retimer_presence_detected(data.retimer_presence_detected)
+            .with_two_retimers_presence_detected(data.two_retimers_presence_detected)
+            .with_crosslink_resolution(data.crosslink_resolution as u8)
+            .with_rsvdz(data.rsvdz)
+            .with_downstream_component_presence(data.downstream_component_presence.into())
+            .with_drs_message_received(data.drs_message_received)
+    }
+}
+impl From<LinkStatus2> for u16 {
+    fn from(data: LinkStatus2) -> Self { LinkStatus2Proto::from(data).into() }
+}
 
 
 /// Indicates the state of the Crosslink negotiation
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CrosslinkResolution {
     /// Crosslink Resolution is not supported
     NotSupported,
@@ -2355,6 +2819,7 @@ impl From<u8> for CrosslinkResolution {
 /// Indicates the presence and DRS status for the Downstream Component, if any, connected to the
 /// Link
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DownstreamComponentPresence {
     /// Link Down – Presence Not Determined
     DownNotDetermined,
@@ -2381,9 +2846,22 @@ impl From<u8> for DownstreamComponentPresence {
         }
     }
 }
+impl From<DownstreamComponentPresence> for u8 {
+    fn from(data: DownstreamComponentPresence) -> Self {
+        match data {
+            DownstreamComponentPresence::DownNotDetermined       => 0b000,
+            DownstreamComponentPresence::DownNotPresent          => 0b001,
+            DownstreamComponentPresence::DownPresent             => 0b010,
+            DownstreamComponentPresence::UpPresent               => 0b100,
+            DownstreamComponentPresence::UpPresentAndDrsReceived => 0b101,
+            DownstreamComponentPresence::Reserved(v)             => v,
+        }
+    }
+}
 
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Slot2 {
     pub capabilities: SlotCapabilities2,
     pub control: SlotControl2,
@@ -2414,6 +2892,18 @@ impl<'a> TryRead<'a, Endian> for Slot2Option {
         }
     }
 }
+impl TryWrite<Endian> for Slot2Option {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let (capabilities, control, status): (u32, u16, u16) = self.0
+            .map(|slot| (slot.capabilities.into(), slot.control.into(), slot.status.into()))
+            .unwrap_or_default();
+        let offset = &mut 0;
+        bytes.write_with::<u32>(offset, capabilities, endian)?;
+        bytes.write_with::<u16>(offset, control, endian)?;
+        bytes.write_with::<u16>(offset, status, endian)?;
+        Ok(*offset)
+    }
+}
 
 
 #[bitfield(bits = 32)]
@@ -2426,18 +2916,30 @@ pub struct SlotCapabilities2Proto {
 ///
 /// This section is a placeholder. There are no capabilities that require this register
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SlotCapabilities2  {
+    /// Reserved bits 31:0
+    pub rsvdp: u32,
 }
 impl From<SlotCapabilities2Proto> for SlotCapabilities2 {
     fn from(proto: SlotCapabilities2Proto) -> Self {
-        let _ = proto.rsvdp();
         Self {
+            rsvdp: proto.rsvdp(),
         }
     }
 }
 impl From<u32> for SlotCapabilities2 {
     fn from(dword: u32) -> Self { SlotCapabilities2Proto::from(dword).into() }
 }
+impl From<SlotCapabilities2> for SlotCapabilities2Proto {
+    fn from(data: SlotCapabilities2) -> Self {
+        Self::new()
+            .with_rsvdp(data.rsvdp)
+    }
+}
+impl From<SlotCapabilities2> for u32 {
+    fn from(data: SlotCapabilities2) -> Self { SlotCapabilities2Proto::from(data).into() }
+}
 
 
 #[bitfield(bits = 16)]
@@ -2450,18 +2952,30 @@ pub struct SlotControl2Proto {
 ///
 /// This section is a placeholder. There are no capabilities that require this register
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SlotControl2  {
+    /// Reserved bits 15:0
+    pub rsvdp: u16,
 }
 impl From<SlotControl2Proto> for SlotControl2 {
     fn from(proto: SlotControl2Proto) -> Self {
-        let _ = proto.rsvdp();
         Self {
+            rsvdp: proto.rsvdp(),
         }
     }
 }
 impl From<u16> for SlotControl2 {
     fn from(word: u16) -> Self { SlotControl2Proto::from(word).into() }
 }
+impl From<SlotControl2> for SlotControl2Proto {
+    fn from(data: SlotControl2) -> Self {
+        Self::new()
+            .with_rsvdp(data.rsvdp)
+    }
+}
+impl From<SlotControl2> for u16 {
+    fn from(data: SlotControl2) -> Self { SlotControl2Proto::from(data).into() }
+}
 
 
 #[bitfield(bits = 16)]
@@ -2474,22 +2988,35 @@ pub struct SlotStatus2Proto {
 ///
 /// This section is a placeholder. There are no capabilities that require this register
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SlotStatus2  {
+    /// Reserved bits 15:0
+    pub rsvdz: u16,
 }
 impl From<SlotStatus2Proto> for SlotStatus2 {
     fn from(proto: SlotStatus2Proto) -> Self {
-        let _ = proto.rsvdz();
         Self {
+            rsvdz: proto.rsvdz(),
         }
     }
 }
 impl From<u16> for SlotStatus2 {
     fn from(word: u16) -> Self { SlotStatus2Proto::from(word).into() }
 }
+impl From<SlotStatus2> for SlotStatus2Proto {
+    fn from(data: SlotStatus2) -> Self {
+        Self::new()
+            .with_rsvdz(data.rsvdz)
+    }
+}
+impl From<SlotStatus2> for u16 {
+    fn from(data: SlotStatus2) -> Self { SlotStatus2Proto::from(data).into() }
+}
 
 
 /// Transmitter Preset
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TransmitterPreset {
     P0,
     P1,
@@ -2522,9 +3049,28 @@ impl From<u8> for TransmitterPreset {
         }
     }
 }
+impl From<TransmitterPreset> for u8 {
+    fn from(data: TransmitterPreset) -> Self {
+        match data {
+            TransmitterPreset::P0          => 0b0000,
+            TransmitterPreset::P1          => 0b0001,
+            TransmitterPreset::P2          => 0b0010,
+            TransmitterPreset::P3          => 0b0011,
+            TransmitterPreset::P4          => 0b0100,
+            TransmitterPreset::P5          => 0b0101,
+            TransmitterPreset::P6          => 0b0110,
+            TransmitterPreset::P7          => 0b0111,
+            TransmitterPreset::P8          => 0b1000,
+            TransmitterPreset::P9          => 0b1001,
+            TransmitterPreset::P10         => 0b1010,
+            TransmitterPreset::Reserved(v) => v,
+        }
+    }
+}
 
 /// Receiver Preset Hint
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ReceiverPresetHint {
     Minus6dB = -6,
     Minus7dB = -7,
@@ -2549,6 +3095,20 @@ impl From<u8> for ReceiverPresetHint {
         }
     }
 }
+impl From<ReceiverPresetHint> for u8 {
+    fn from(data: ReceiverPresetHint) -> Self {
+        match data {
+            ReceiverPresetHint::Minus6dB  => 0b000,
+            ReceiverPresetHint::Minus7dB  => 0b001,
+            ReceiverPresetHint::Minus8dB  => 0b010,
+            ReceiverPresetHint::Minus9dB  => 0b011,
+            ReceiverPresetHint::Minus10dB => 0b100,
+            ReceiverPresetHint::Minus11dB => 0b101,
+            ReceiverPresetHint::Minus12dB => 0b110,
+            ReceiverPresetHint::Reserved  => 0b111,
+        }
+    }
+}
 
 
 #[cfg(test)]
@@ -2599,6 +3159,7 @@ mod tests {
                 slot_implemented: false,
                 interrupt_message_number: 0,
                 tcs_routing_support: false,
+                rsvdp: 0,
             },
             device: Device {
                 capabilities: DeviceCapabilities {
@@ -2613,6 +3174,8 @@ mod tests {
                     role_based_error_reporting: true,
                     captured_slot_power_limit: SlotPowerLimit { value: 0, scale: 1.0 },
                     function_level_reset_capability: true,
+                    rsvdp: 0,
+                    rsvdp_2: 0,
                 },
                 control: DeviceControl {
                     correctable_error_reporting_enable: false,
@@ -2635,6 +3198,7 @@ mod tests {
                     unsupported_request_detected: true,
                     aux_power_detected: false,
                     transactions_pending: false,
+                    rsvdz: 0,
                 },
             },
             link: Some(Link {
@@ -2650,6 +3214,7 @@ mod tests {
                     link_bandwidth_notification_capability: false,
                     aspm_optionality_compliance: true,
                     port_number: 0,
+                    rsvdp: 0,
                 },
                 control: LinkControl {
                     active_state_power_management_control: ActiveStatePowerManagement::NoAspm,
@@ -2662,6 +3227,8 @@ mod tests {
                     hardware_autonomous_width_disable: false,
                     link_bandwidth_management_interrupt_enable: false,
                     link_autonomous_bandwidth_interrupt_enable: false,
+                    rsvdp: 0,
+                    rsvdp_2: 0,
                 },
                 status: LinkStatus {
                     current_link_speed: LinkSpeed::Rate8GTps,
@@ -2698,6 +3265,7 @@ mod tests {
                     extended_fmt_field_supported: false,
                     end_end_tlp_prefix_supported: false,
                     max_end_end_tlp_prefixes: MaxEndEndTlpPrefixes::Max4,
+                    rsvdp: 0,
                 },
                 control: DeviceControl2 {
                     emergency_power_reduction_request: false,
@@ -2713,7 +3281,7 @@ mod tests {
                     obff_enable: ObffEnable::Disabled,
                     end_end_tlp_prefix_blocking: EndEndTlpPrefixBlocking::ForwardingEnabled,
                 },
-                status: DeviceStatus2 {},
+                status: DeviceStatus2 { rsvdz: 0 },
             }),
             link_2: Some(Link2 {
                 capabilities: LinkCapabilities2 {
@@ -2724,6 +3292,7 @@ mod tests {
                         speed_16_0_gtps: false,
                         speed_32_0_gtps: false,
                         speed_64_0_gtps: false,
+                        rsvdp: 0,
                     },
                     lower_skp_os_reception_supported_speeds_vector: SupportedLinkSpeedsVector {
                         speed_2_5_gtps: false,
@@ -2732,6 +3301,7 @@ mod tests {
                         speed_16_0_gtps: false,
                         speed_32_0_gtps: false,
                         speed_64_0_gtps: false,
+                        rsvdp: 0,
                     },
                     retimer_presence_detect_supported: false,
                     two_retimers_presence_detect_supported: false,
@@ -2743,8 +3313,11 @@ mod tests {
                         speed_16_0_gtps: false,
                         speed_32_0_gtps: false,
                         speed_64_0_gtps: false,
+                        rsvdp: 0,
                     },
                     crosslink_supported: false,
+                    rsvdp: 0,
+                    rsvdp_2: 0,
                 },
                 control: LinkControl2 {
                     target_link_speed: LinkSpeed::Rate2GTps,
@@ -2768,10 +3341,59 @@ mod tests {
                     crosslink_resolution: CrosslinkResolution::NotSupported,
                     downstream_component_presence: DownstreamComponentPresence::DownNotDetermined,
                     drs_message_received: false,
+                    rsvdz: 0,
                 },
             }),
             slot_2: None,
         };
         assert_eq!(sample, result);
     }
+
+    #[test]
+    fn write_endpoint() {
+        let data = [
+            0x10, 0xe0, 0x02, 0x00, 0xc2, 0x8c, 0x00, 0x10, 0x3e, 0x20, 0x09, 0x00, 0x43, 0x5c, 0x42, 0x00,
+            0x40, 0x00, 0x43, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x1f, 0x08, 0x00, 0x00, 0x06, 0x00, 0x00, 0x00, 0x0e, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0x1f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let sample: PciExpress = data[2..].read_with(&mut 0, LE).unwrap();
+        let mut result = [0u8; 58];
+        let offset = &mut 0;
+        result.write_with(offset, sample, LE).unwrap();
+        assert_eq!(data.len() - 2, *offset);
+        assert_eq!(&data[2..], &result[..]);
+    }
+
+    #[test]
+    fn write_root_port() {
+        // Capabilities: [90] Express (v2) Root Port (Slot+), MSI 00
+        let data = &include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"),
+            "/tests/data/device/8086_2030/config"
+        ))[0x92..0x90 + 0x3c];
+        let sample: PciExpress = data.read_with(&mut 0, LE).unwrap();
+        assert!(sample.slot.is_some() && sample.root.is_some());
+        let mut result = [0u8; 58];
+        result.write_with(&mut 0, sample, LE).unwrap();
+        assert_eq!(data, &result[..]);
+    }
+
+    #[test]
+    fn write_truncated() {
+        // Capability at the end of device dependent region without Slot 2 registers
+        let data = &include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"),
+            "/tests/data/device/8086_2030/config"
+        ))[0x92..0x90 + 0x34];
+        let (sample, len) = PciExpress::try_read(data, LE).unwrap();
+        assert_eq!((&None, data.len()), (&sample.slot_2, len));
+        // Slot 2 registers are always written for version 2 capability
+        let mut result = [0u8; 0x32];
+        assert_eq!(Err(byte::Error::Incomplete), result.write_with(&mut 0, sample.clone(), LE));
+        let mut result = [0xffu8; 0x3a];
+        let offset = &mut 0;
+        result.write_with(offset, sample, LE).unwrap();
+        assert_eq!(result.len(), *offset);
+        assert_eq!(data, &result[..data.len()]);
+        assert_eq!([0; 8], result[data.len()..]);
+    }
 }
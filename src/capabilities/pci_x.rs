@@ -0,0 +1,923 @@
+//! PCI-X
+//!
+//! PCI-X capability layout depends on the Configuration Space header type: Type 00h functions
+//! implement [Device] registers and Type 01h PCI-X bridges implement [Bridge] registers. PCI-X 2.0
+//! devices additionally implement ECC registers, presence of those is reported by capability
+//! version.
+
+use modular_bitfield::prelude::*;
+use byte::{
+    ctx::*,
+    self,
+    TryRead,
+    TryWrite,
+    BytesExt,
+};
+
+
+/// PCI-X capability structure
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PciX {
+    /// Type 00h Configuration Space header function
+    Device(Device),
+    /// Type 01h Configuration Space header PCI-X bridge
+    Bridge(Bridge),
+}
+impl TryWrite<Endian> for PciX {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        match self {
+            Self::Device(data) => bytes.write_with(offset, data, endian)?,
+            Self::Bridge(data) => bytes.write_with(offset, data, endian)?,
+        }
+        Ok(*offset)
+    }
+}
+
+
+/// PCI-X capability of Type 00h function
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Device {
+    pub command: Command,
+    pub status: Status,
+    /// Present if [Command::version] is not 0
+    pub ecc: Option<Ecc>,
+}
+impl<'a> TryRead<'a, Endian> for Device {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let command: Command = bytes.read_with::<u16>(offset, endian)?.into();
+        let status = bytes.read_with::<u32>(offset, endian)?.into();
+        let ecc = if command.version != 0 {
+            Some(bytes.read_with(offset, endian)?)
+        } else {
+            None
+        };
+        Ok((Device { command, status, ecc }, *offset))
+    }
+}
+impl TryWrite<Endian> for Device {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u16>(offset, self.command.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.status.into(), endian)?;
+        if let Some(ecc) = self.ecc {
+            bytes.write_with(offset, ecc, endian)?;
+        }
+        Ok(*offset)
+    }
+}
+
+#[bitfield(bits = 16)]
+#[repr(u16)]
+pub struct CommandProto {
+    data_parity_error_recovery_enable: bool,
+    enable_relaxed_ordering: bool,
+    max_memory_read_byte_count: B2,
+    max_outstanding_split_transactions: B3,
+    rsvdp: B5,
+    version: B2,
+    rsvdp_1: B2,
+}
+
+/// PCI-X Command
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Command {
+    /// Device attempts to recover from data parity errors
+    pub data_parity_error_recovery_enable: bool,
+    /// Device may set the Relaxed Ordering bit in requests it initiates
+    pub enable_relaxed_ordering: bool,
+    /// Maximum byte count the device uses when initiating a Sequence with one of the burst
+    /// memory read commands
+    pub max_memory_read_byte_count: MaxMemoryReadByteCount,
+    /// Maximum number of Split Transactions the device is permitted to have outstanding at one
+    /// time
+    pub max_outstanding_split_transactions: MaxOutstandingSplitTransactions,
+    /// Reserved bits 11:7
+    pub rsvdp: u8,
+    /// PCI-X Capability version, 0 means the device does not implement ECC registers
+    pub version: u8,
+    /// Reserved bits 15:14
+    pub rsvdp_1: u8,
+}
+impl From<CommandProto> for Command {
+    fn from(proto: CommandProto) -> Self {
+        Self {
+            data_parity_error_recovery_enable: proto.data_parity_error_recovery_enable(),
+            enable_relaxed_ordering: proto.enable_relaxed_ordering(),
+            max_memory_read_byte_count: proto.max_memory_read_byte_count().into(),
+            max_outstanding_split_transactions: proto.max_outstanding_split_transactions().into(),
+            rsvdp: proto.rsvdp(),
+            version: proto.version(),
+            rsvdp_1: proto.rsvdp_1(),
+        }
+    }
+}
+impl From<u16> for Command {
+    fn from(word: u16) -> Self { CommandProto::from(word).into() }
+}
+impl From<Command> for CommandProto {
+    fn from(data: Command) -> Self {
+        Self::new()
+            .with_data_parity_error_recovery_enable(data.data_parity_error_recovery_enable)
+            .with_enable_relaxed_ordering(data.enable_relaxed_ordering)
+            .with_max_memory_read_byte_count(data.max_memory_read_byte_count.into())
+            .with_max_outstanding_split_transactions(data.max_outstanding_split_transactions.into())
+            .with_rsvdp(data.rsvdp)
+            .with_version(data.version)
+            .with_rsvdp_1(data.rsvdp_1)
+    }
+}
+impl From<Command> for u16 {
+    fn from(data: Command) -> Self { CommandProto::from(data).into() }
+}
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct StatusProto {
+    function_number: B3,
+    device_number: B5,
+    bus_number: B8,
+    device_64bit: bool,
+    capable_133mhz: bool,
+    split_completion_discarded: bool,
+    unexpected_split_completion: bool,
+    device_complexity: bool,
+    designed_max_memory_read_byte_count: B2,
+    designed_max_outstanding_split_transactions: B3,
+    designed_max_cumulative_read_size: B3,
+    received_split_completion_error_message: bool,
+    capable_266mhz: bool,
+    capable_533mhz: bool,
+}
+
+/// PCI-X Status
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Status {
+    /// Function Number of the device, captured on configuration writes
+    pub function_number: u8,
+    /// Device Number of the device, captured on configuration writes
+    pub device_number: u8,
+    /// Bus Number of the segment the device resides on, captured on configuration writes
+    pub bus_number: u8,
+    /// Width of the AD interface is 64 bits
+    pub device_64bit: bool,
+    /// Device is capable of 133 MHz operation in PCI-X mode
+    pub capable_133mhz: bool,
+    /// Split Completion has been discarded because the requester would not accept it
+    pub split_completion_discarded: bool,
+    /// Unexpected Split Completion with the device's Requester ID was received
+    pub unexpected_split_completion: bool,
+    pub device_complexity: DeviceComplexity,
+    /// Maximum memory read byte count the device is designed to use
+    pub designed_max_memory_read_byte_count: MaxMemoryReadByteCount,
+    /// Number of outstanding Split Transactions the device is designed to use
+    pub designed_max_outstanding_split_transactions: MaxOutstandingSplitTransactions,
+    /// Size of memory reads the device is designed to have outstanding at one time
+    pub designed_max_cumulative_read_size: MaxCumulativeReadSize,
+    /// Split Completion Message with error was received
+    pub received_split_completion_error_message: bool,
+    /// Device is capable of PCI-X 266 operation
+    pub capable_266mhz: bool,
+    /// Device is capable of PCI-X 533 operation
+    pub capable_533mhz: bool,
+}
+impl From<StatusProto> for Status {
+    fn from(proto: StatusProto) -> Self {
+        Self {
+            function_number: proto.function_number(),
+            device_number: proto.device_number(),
+            bus_number: proto.bus_number(),
+            device_64bit: proto.device_64bit(),
+            capable_133mhz: proto.capable_133mhz(),
+            split_completion_discarded: proto.split_completion_discarded(),
+            unexpected_split_completion: proto.unexpected_split_completion(),
+            device_complexity: proto.device_complexity().into(),
+            designed_max_memory_read_byte_count: proto.designed_max_memory_read_byte_count().into(),
+            designed_max_outstanding_split_transactions:
+                proto.designed_max_outstanding_split_transactions().into(),
+            designed_max_cumulative_read_size: proto.designed_max_cumulative_read_size().into(),
+            received_split_completion_error_message: proto.received_split_completion_error_message(),
+            capable_266mhz: proto.capable_266mhz(),
+            capable_533mhz: proto.capable_533mhz(),
+        }
+    }
+}
+impl From<u32> for Status {
+    fn from(dword: u32) -> Self { StatusProto::from(dword).into() }
+}
+impl From<Status> for StatusProto {
+    fn from(data: Status) -> Self {
+        Self::new()
+            .with_function_number(data.function_number)
+            .with_device_number(data.device_number)
+            .with_bus_number(data.bus_number)
+            .with_device_64bit(data.device_64bit)
+            .with_capable_133mhz(data.capable_133mhz)
+            .with_split_completion_discarded(data.split_completion_discarded)
+            .with_unexpected_split_completion(data.unexpected_split_completion)
+            .with_device_complexity(data.device_complexity.into())
+            .with_designed_max_memory_read_byte_count(data.designed_max_memory_read_byte_count.into())
+            .with_designed_max_outstanding_split_transactions(
+                data.designed_max_outstanding_split_transactions.into()
+            )
+            .with_designed_max_cumulative_read_size(data.designed_max_cumulative_read_size.into())
+            .with_received_split_completion_error_message(data.received_split_completion_error_message)
+            .with_capable_266mhz(data.capable_266mhz)
+            .with_capable_533mhz(data.capable_533mhz)
+    }
+}
+impl From<Status> for u32 {
+    fn from(data: Status) -> Self { StatusProto::from(data).into() }
+}
+
+/// Indicates whether the device is a simple device or a bridge device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DeviceComplexity {
+    Simple,
+    /// Device is a bridge, which has a Type 00h header (e.g. host bridge)
+    Bridge,
+}
+impl From<bool> for DeviceComplexity {
+    fn from(b: bool) -> Self {
+        if b { Self::Bridge } else { Self::Simple }
+    }
+}
+impl From<DeviceComplexity> for bool {
+    fn from(data: DeviceComplexity) -> Self {
+        matches!(data, DeviceComplexity::Bridge)
+    }
+}
+
+
+/// PCI-X capability of Type 01h PCI-X bridge
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bridge {
+    pub secondary_status: SecondaryStatus,
+    pub bridge_status: BridgeStatus,
+    /// Split Transactions forwarded from the secondary interface to the primary interface
+    pub upstream_split_transaction_control: SplitTransactionControl,
+    /// Split Transactions forwarded from the primary interface to the secondary interface
+    pub downstream_split_transaction_control: SplitTransactionControl,
+    /// Present if [SecondaryStatus::version] is not 0
+    pub ecc: Option<Ecc>,
+}
+impl<'a> TryRead<'a, Endian> for Bridge {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let secondary_status: SecondaryStatus = bytes.read_with::<u16>(offset, endian)?.into();
+        let bridge_status = bytes.read_with::<u32>(offset, endian)?.into();
+        let upstream_split_transaction_control = bytes.read_with::<u32>(offset, endian)?.into();
+        let downstream_split_transaction_control = bytes.read_with::<u32>(offset, endian)?.into();
+        let ecc = if secondary_status.version != 0 {
+            Some(bytes.read_with(offset, endian)?)
+        } else {
+            None
+        };
+        let bridge = Bridge {
+            secondary_status,
+            bridge_status,
+            upstream_split_transaction_control,
+            downstream_split_transaction_control,
+            ecc,
+        };
+        Ok((bridge, *offset))
+    }
+}
+impl TryWrite<Endian> for Bridge {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u16>(offset, self.secondary_status.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.bridge_status.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.upstream_split_transaction_control.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.downstream_split_transaction_control.into(), endian)?;
+        if let Some(ecc) = self.ecc {
+            bytes.write_with(offset, ecc, endian)?;
+        }
+        Ok(*offset)
+    }
+}
+
+#[bitfield(bits = 16)]
+#[repr(u16)]
+pub struct SecondaryStatusProto {
+    is_64bit: bool,
+    capable_133mhz: bool,
+    split_completion_discarded: bool,
+    unexpected_split_completion: bool,
+    split_completion_overrun: bool,
+    split_request_delayed: bool,
+    secondary_bus_mode_and_frequency: B4,
+    rsvdp: B2,
+    version: B2,
+    capable_266mhz: bool,
+    capable_533mhz: bool,
+}
+
+/// PCI-X Secondary Status
+///
+/// Reports status information about the secondary interface
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SecondaryStatus {
+    /// Width of the secondary AD interface is 64 bits
+    pub is_64bit: bool,
+    /// Secondary interface is capable of 133 MHz operation in PCI-X mode
+    pub capable_133mhz: bool,
+    /// Split Completion moving toward the secondary bus has been discarded
+    pub split_completion_discarded: bool,
+    /// Unexpected Split Completion has been received on the secondary bus
+    pub unexpected_split_completion: bool,
+    /// Bridge terminated a Split Completion on the secondary bus with Retry or Disconnect
+    /// because its buffers were full
+    pub split_completion_overrun: bool,
+    /// Bridge delayed a Split Request because of insufficient Split Completion commitment limit
+    pub split_request_delayed: bool,
+    /// Mode and frequency the secondary bus was initialized to: 0 is conventional PCI, 1, 2
+    /// and 3 are PCI-X 66 MHz, 100 MHz and 133 MHz
+    pub secondary_bus_mode_and_frequency: u8,
+    /// Reserved bits 11:10
+    pub rsvdp: u8,
+    /// PCI-X Capability version, 0 means the bridge does not implement ECC registers
+    pub version: u8,
+    /// Secondary interface is capable of PCI-X 266 operation
+    pub capable_266mhz: bool,
+    /// Secondary interface is capable of PCI-X 533 operation
+    pub capable_533mhz: bool,
+}
+impl From<SecondaryStatusProto> for SecondaryStatus {
+    fn from(proto: SecondaryStatusProto) -> Self {
+        Self {
+            is_64bit: proto.is_64bit(),
+            capable_133mhz: proto.capable_133mhz(),
+            split_completion_discarded: proto.split_completion_discarded(),
+            unexpected_split_completion: proto.unexpected_split_completion(),
+            split_completion_overrun: proto.split_completion_overrun(),
+            split_request_delayed: proto.split_request_delayed(),
+            secondary_bus_mode_and_frequency: proto.secondary_bus_mode_and_frequency(),
+            rsvdp: proto.rsvdp(),
+            version: proto.version(),
+            capable_266mhz: proto.capable_266mhz(),
+            capable_533mhz: proto.capable_533mhz(),
+        }
+    }
+}
+impl From<u16> for SecondaryStatus {
+    fn from(word: u16) -> Self { SecondaryStatusProto::from(word).into() }
+}
+impl From<SecondaryStatus> for SecondaryStatusProto {
+    fn from(data: SecondaryStatus) -> Self {
+        Self::new()
+            .with_is_64bit(data.is_64bit)
+            .with_capable_133mhz(data.capable_133mhz)
+            .with_split_completion_discarded(data.split_completion_discarded)
+            .with_unexpected_split_completion(data.unexpected_split_completion)
+            .with_split_completion_overrun(data.split_completion_overrun)
+            .with_split_request_delayed(data.split_request_delayed)
+            .with_secondary_bus_mode_and_frequency(data.secondary_bus_mode_and_frequency)
+            .with_rsvdp(data.rsvdp)
+            .with_version(data.version)
+            .with_capable_266mhz(data.capable_266mhz)
+            .with_capable_533mhz(data.capable_533mhz)
+    }
+}
+impl From<SecondaryStatus> for u16 {
+    fn from(data: SecondaryStatus) -> Self { SecondaryStatusProto::from(data).into() }
+}
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct BridgeStatusProto {
+    function_number: B3,
+    device_number: B5,
+    bus_number: B8,
+    is_64bit: bool,
+    capable_133mhz: bool,
+    split_completion_discarded: bool,
+    unexpected_split_completion: bool,
+    split_completion_overrun: bool,
+    split_request_delayed: bool,
+    rsvdp: B8,
+    capable_266mhz: bool,
+    capable_533mhz: bool,
+}
+
+/// PCI-X Bridge Status
+///
+/// Reports status information about the primary interface
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BridgeStatus {
+    /// Function Number of the bridge, captured on configuration writes
+    pub function_number: u8,
+    /// Device Number of the bridge, captured on configuration writes
+    pub device_number: u8,
+    /// Number of the primary bus, captured on configuration writes
+    pub bus_number: u8,
+    /// Width of the primary AD interface is 64 bits
+    pub is_64bit: bool,
+    /// Primary interface is capable of 133 MHz operation in PCI-X mode
+    pub capable_133mhz: bool,
+    /// Split Completion moving toward the primary bus has been discarded
+    pub split_completion_discarded: bool,
+    /// Unexpected Split Completion has been received on the primary bus
+    pub unexpected_split_completion: bool,
+    /// Bridge terminated a Split Completion on the primary bus with Retry or Disconnect because
+    /// its buffers were full
+    pub split_completion_overrun: bool,
+    /// Bridge delayed a Split Request because of insufficient Split Completion commitment limit
+    pub split_request_delayed: bool,
+    /// Reserved bits 29:22
+    pub rsvdp: u8,
+    /// Primary interface is capable of PCI-X 266 operation
+    pub capable_266mhz: bool,
+    /// Primary interface is capable of PCI-X 533 operation
+    pub capable_533mhz: bool,
+}
+impl From<BridgeStatusProto> for BridgeStatus {
+    fn from(proto: BridgeStatusProto) -> Self {
+        Self {
+            function_number: proto.function_number(),
+            device_number: proto.device_number(),
+            bus_number: proto.bus_number(),
+            is_64bit: proto.is_64bit(),
+            capable_133mhz: proto.capable_133mhz(),
+            split_completion_discarded: proto.split_completion_discarded(),
+            unexpected_split_completion: proto.unexpected_split_completion(),
+            split_completion_overrun: proto.split_completion_overrun(),
+            split_request_delayed: proto.split_request_delayed(),
+            rsvdp: proto.rsvdp(),
+            capable_266mhz: proto.capable_266mhz(),
+            capable_533mhz: proto.capable_533mhz(),
+        }
+    }
+}
+impl From<u32> for BridgeStatus {
+    fn from(dword: u32) -> Self { BridgeStatusProto::from(dword).into() }
+}
+impl From<BridgeStatus> for BridgeStatusProto {
+    fn from(data: BridgeStatus) -> Self {
+        Self::new()
+            .with_function_number(data.function_number)
+            .with_device_number(data.device_number)
+            .with_bus_number(data.bus_number)
+            .with_is_64bit(data.is_64bit)
+            .with_capable_133mhz(data.capable_133mhz)
+            .with_split_completion_discarded(data.split_completion_discarded)
+            .with_unexpected_split_completion(data.unexpected_split_completion)
+            .with_split_completion_overrun(data.split_completion_overrun)
+            .with_split_request_delayed(data.split_request_delayed)
+            .with_rsvdp(data.rsvdp)
+            .with_capable_266mhz(data.capable_266mhz)
+            .with_capable_533mhz(data.capable_533mhz)
+    }
+}
+impl From<BridgeStatus> for u32 {
+    fn from(data: BridgeStatus) -> Self { BridgeStatusProto::from(data).into() }
+}
+
+/// Upstream / Downstream Split Transaction Control
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SplitTransactionControl {
+    /// Size of the buffer (in ADQs) for storing Split Completions for memory reads
+    pub split_transaction_capacity: u16,
+    /// Cumulative sequence size (in ADQs) of the commitment limit
+    pub split_transaction_commitment_limit: u16,
+}
+impl From<u32> for SplitTransactionControl {
+    fn from(dword: u32) -> Self {
+        Self {
+            split_transaction_capacity: dword as u16,
+            split_transaction_commitment_limit: (dword >> 16) as u16,
+        }
+    }
+}
+impl From<SplitTransactionControl> for u32 {
+    fn from(data: SplitTransactionControl) -> Self {
+        (data.split_transaction_commitment_limit as u32) << 16
+            | data.split_transaction_capacity as u32
+    }
+}
+
+
+/// PCI-X 2.0 ECC registers
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ecc {
+    pub control_and_status: EccControlAndStatus,
+    /// ECC First Address
+    pub first_address: u32,
+    /// ECC Second Address
+    pub second_address: u32,
+    /// ECC Attribute
+    pub attribute: u32,
+}
+impl<'a> TryRead<'a, Endian> for Ecc {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let ecc = Ecc {
+            control_and_status: bytes.read_with::<u32>(offset, endian)?.into(),
+            first_address: bytes.read_with::<u32>(offset, endian)?,
+            second_address: bytes.read_with::<u32>(offset, endian)?,
+            attribute: bytes.read_with::<u32>(offset, endian)?,
+        };
+        Ok((ecc, *offset))
+    }
+}
+impl TryWrite<Endian> for Ecc {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u32>(offset, self.control_and_status.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.first_address, endian)?;
+        bytes.write_with::<u32>(offset, self.second_address, endian)?;
+        bytes.write_with::<u32>(offset, self.attribute, endian)?;
+        Ok(*offset)
+    }
+}
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct EccControlAndStatusProto {
+    select_secondary_ecc_registers: bool,
+    ecc_mode: bool,
+    ecc_error_phase: B3,
+    error_corrected: bool,
+    additional_correctable_ecc_error: bool,
+    additional_uncorrectable_ecc_error: bool,
+    ecc_error_syndrome: u8,
+    rsvdp: u16,
+}
+
+/// ECC Control and Status
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EccControlAndStatus {
+    /// Bridge ECC registers report errors of the secondary bus instead of the primary one
+    pub select_secondary_ecc_registers: bool,
+    /// ECC is used in PCI-X Mode 1 instead of parity
+    pub ecc_mode: bool,
+    /// Bus phase in which the captured error was detected
+    pub ecc_error_phase: EccErrorPhase,
+    /// Captured error was a single-bit error corrected by ECC
+    pub error_corrected: bool,
+    /// Correctable error occurred after the error was captured
+    pub additional_correctable_ecc_error: bool,
+    /// Uncorrectable error occurred after the error was captured
+    pub additional_uncorrectable_ecc_error: bool,
+    /// ECC syndrome of the captured error
+    pub ecc_error_syndrome: u8,
+    /// Reserved bits 31:16
+    pub rsvdp: u16,
+}
+impl From<EccControlAndStatusProto> for EccControlAndStatus {
+    fn from(proto: EccControlAndStatusProto) -> Self {
+        Self {
+            select_secondary_ecc_registers: proto.select_secondary_ecc_registers(),
+            ecc_mode: proto.ecc_mode(),
+            ecc_error_phase: proto.ecc_error_phase().into(),
+            error_corrected: proto.error_corrected(),
+            additional_correctable_ecc_error: proto.additional_correctable_ecc_error(),
+            additional_uncorrectable_ecc_error: proto.additional_uncorrectable_ecc_error(),
+            ecc_error_syndrome: proto.ecc_error_syndrome(),
+            rsvdp: proto.rsvdp(),
+        }
+    }
+}
+impl From<u32> for EccControlAndStatus {
+    fn from(dword: u32) -> Self { EccControlAndStatusProto::from(dword).into() }
+}
+impl From<EccControlAndStatus> for EccControlAndStatusProto {
+    fn from(data: EccControlAndStatus) -> Self {
+        Self::new()
+            .with_select_secondary_ecc_registers(data.select_secondary_ecc_registers)
+            .with_ecc_mode(data.ecc_mode)
+            .with_ecc_error_phase(data.ecc_error_phase.into())
+            .with_error_corrected(data.error_corrected)
+            .with_additional_correctable_ecc_error(data.additional_correctable_ecc_error)
+            .with_additional_uncorrectable_ecc_error(data.additional_uncorrectable_ecc_error)
+            .with_ecc_error_syndrome(data.ecc_error_syndrome)
+            .with_rsvdp(data.rsvdp)
+    }
+}
+impl From<EccControlAndStatus> for u32 {
+    fn from(data: EccControlAndStatus) -> Self { EccControlAndStatusProto::from(data).into() }
+}
+
+/// Bus phase of the error captured in ECC registers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EccErrorPhase {
+    NoError,
+    /// First 32 bits of address
+    FirstAddress,
+    /// Second 32 bits of address
+    SecondAddress,
+    AttributePhase,
+    DataPhase,
+    Reserved(u8),
+}
+impl From<u8> for EccErrorPhase {
+    fn from(byte: u8) -> Self {
+        match byte {
+            0b000 => Self::NoError,
+            0b001 => Self::FirstAddress,
+            0b010 => Self::SecondAddress,
+            0b011 => Self::AttributePhase,
+            0b100 => Self::DataPhase,
+            v => Self::Reserved(v),
+        }
+    }
+}
+impl From<EccErrorPhase> for u8 {
+    fn from(data: EccErrorPhase) -> Self {
+        match data {
+            EccErrorPhase::NoError => 0b000,
+            EccErrorPhase::FirstAddress => 0b001,
+            EccErrorPhase::SecondAddress => 0b010,
+            EccErrorPhase::AttributePhase => 0b011,
+            EccErrorPhase::DataPhase => 0b100,
+            EccErrorPhase::Reserved(v) => v,
+        }
+    }
+}
+
+
+/// Maximum Memory Read Byte Count
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MaxMemoryReadByteCount {
+    B512,
+    B1024,
+    B2048,
+    B4096,
+}
+impl MaxMemoryReadByteCount {
+    pub fn value(&self) -> u16 {
+        512 << u8::from(*self)
+    }
+}
+impl From<u8> for MaxMemoryReadByteCount {
+    fn from(byte: u8) -> Self {
+        match byte & 0b11 {
+            0b00 => Self::B512,
+            0b01 => Self::B1024,
+            0b10 => Self::B2048,
+            _ => Self::B4096,
+        }
+    }
+}
+impl From<MaxMemoryReadByteCount> for u8 {
+    fn from(data: MaxMemoryReadByteCount) -> Self {
+        match data {
+            MaxMemoryReadByteCount::B512 => 0b00,
+            MaxMemoryReadByteCount::B1024 => 0b01,
+            MaxMemoryReadByteCount::B2048 => 0b10,
+            MaxMemoryReadByteCount::B4096 => 0b11,
+        }
+    }
+}
+
+/// Maximum Outstanding Split Transactions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MaxOutstandingSplitTransactions {
+    One,
+    Two,
+    Three,
+    Four,
+    Eight,
+    Twelve,
+    Sixteen,
+    ThirtyTwo,
+}
+impl MaxOutstandingSplitTransactions {
+    pub fn value(&self) -> u8 {
+        [1, 2, 3, 4, 8, 12, 16, 32][usize::from(u8::from(*self))]
+    }
+}
+impl From<u8> for MaxOutstandingSplitTransactions {
+    fn from(byte: u8) -> Self {
+        match byte & 0b111 {
+            0b000 => Self::One,
+            0b001 => Self::Two,
+            0b010 => Self::Three,
+            0b011 => Self::Four,
+            0b100 => Self::Eight,
+            0b101 => Self::Twelve,
+            0b110 => Self::Sixteen,
+            _ => Self::ThirtyTwo,
+        }
+    }
+}
+impl From<MaxOutstandingSplitTransactions> for u8 {
+    fn from(data: MaxOutstandingSplitTransactions) -> Self {
+        match data {
+            MaxOutstandingSplitTransactions::One => 0b000,
+            MaxOutstandingSplitTransactions::Two => 0b001,
+            MaxOutstandingSplitTransactions::Three => 0b010,
+            MaxOutstandingSplitTransactions::Four => 0b011,
+            MaxOutstandingSplitTransactions::Eight => 0b100,
+            MaxOutstandingSplitTransactions::Twelve => 0b101,
+            MaxOutstandingSplitTransactions::Sixteen => 0b110,
+            MaxOutstandingSplitTransactions::ThirtyTwo => 0b111,
+        }
+    }
+}
+
+/// Designed Maximum Cumulative Read Size in ADQs (128 bytes)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MaxCumulativeReadSize {
+    Adq8,
+    Adq16,
+    Adq32,
+    Adq64,
+    Adq128,
+    Adq256,
+    Adq512,
+    Adq1024,
+}
+impl MaxCumulativeReadSize {
+    pub fn value(&self) -> u16 {
+        8 << u8::from(*self)
+    }
+}
+impl From<u8> for MaxCumulativeReadSize {
+    fn from(byte: u8) -> Self {
+        match byte & 0b111 {
+            0b000 => Self::Adq8,
+            0b001 => Self::Adq16,
+            0b010 => Self::Adq32,
+            0b011 => Self::Adq64,
+            0b100 => Self::Adq128,
+            0b101 => Self::Adq256,
+            0b110 => Self::Adq512,
+            _ => Self::Adq1024,
+        }
+    }
+}
+impl From<MaxCumulativeReadSize> for u8 {
+    fn from(data: MaxCumulativeReadSize) -> Self {
+        match data {
+            MaxCumulativeReadSize::Adq8 => 0b000,
+            MaxCumulativeReadSize::Adq16 => 0b001,
+            MaxCumulativeReadSize::Adq32 => 0b010,
+            MaxCumulativeReadSize::Adq64 => 0b011,
+            MaxCumulativeReadSize::Adq128 => 0b100,
+            MaxCumulativeReadSize::Adq256 => 0b101,
+            MaxCumulativeReadSize::Adq512 => 0b110,
+            MaxCumulativeReadSize::Adq1024 => 0b111,
+        }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use super::*;
+
+    #[test]
+    fn device() {
+        // Capabilities: [e0] PCI-X non-bridge device
+        //         Command: DPERE- ERO+ RBC=2048 OST=8
+        //         Status: Dev=03:01.0 64bit+ 133MHz+ SCD- USC- DC=simple DMMRBC=4096 DMOST=8 DMCRS=32 RSCEM- 266MHz- 533MHz-
+        let data = [0x4a, 0x00, 0x08, 0x03, 0x63, 0x0a];
+        let result: Device = data.read_with(&mut 0, LE).unwrap();
+        let sample = Device {
+            command: Command {
+                data_parity_error_recovery_enable: false,
+                enable_relaxed_ordering: true,
+                max_memory_read_byte_count: MaxMemoryReadByteCount::B2048,
+                max_outstanding_split_transactions: MaxOutstandingSplitTransactions::Eight,
+                version: 0,
+                rsvdp: 0,
+                rsvdp_1: 0,
+            },
+            status: Status {
+                function_number: 0,
+                device_number: 1,
+                bus_number: 3,
+                device_64bit: true,
+                capable_133mhz: true,
+                split_completion_discarded: false,
+                unexpected_split_completion: false,
+                device_complexity: DeviceComplexity::Simple,
+                designed_max_memory_read_byte_count: MaxMemoryReadByteCount::B4096,
+                designed_max_outstanding_split_transactions: MaxOutstandingSplitTransactions::Eight,
+                designed_max_cumulative_read_size: MaxCumulativeReadSize::Adq32,
+                received_split_completion_error_message: false,
+                capable_266mhz: false,
+                capable_533mhz: false,
+            },
+            ecc: None,
+        };
+        assert_eq!(sample, result);
+        assert_eq!(2048, result.command.max_memory_read_byte_count.value());
+        assert_eq!(8, result.status.designed_max_outstanding_split_transactions.value());
+        assert_eq!(32, result.status.designed_max_cumulative_read_size.value());
+
+        let mut buf = [0u8; 6];
+        buf.write_with(&mut 0, result, LE).unwrap();
+        assert_eq!(data, buf);
+    }
+
+    #[test]
+    fn device_ecc() {
+        let mut data = [0u8; 22];
+        // Version 2, 266 MHz and 533 MHz capable
+        data[..6].copy_from_slice(&[0x00, 0x20, 0x00, 0x00, 0x00, 0xc0]);
+        // ECC mode, corrected data phase error with syndrome a5h followed by uncorrectable one
+        data[6..10].copy_from_slice(&0x0001_a5b2u32.to_le_bytes());
+        data[18..22].copy_from_slice(&0xdeadbeefu32.to_le_bytes());
+        let result: Device = data.read_with(&mut 0, LE).unwrap();
+        assert_eq!(2, result.command.version);
+        assert!(result.status.capable_266mhz && result.status.capable_533mhz);
+        let sample = Ecc {
+            control_and_status: EccControlAndStatus {
+                select_secondary_ecc_registers: false,
+                ecc_mode: true,
+                ecc_error_phase: EccErrorPhase::DataPhase,
+                error_corrected: true,
+                additional_correctable_ecc_error: false,
+                additional_uncorrectable_ecc_error: true,
+                ecc_error_syndrome: 0xa5,
+                rsvdp: 0x0001,
+            },
+            first_address: 0,
+            second_address: 0,
+            attribute: 0xdeadbeef,
+        };
+        assert_eq!(Some(sample), result.ecc);
+
+        let mut buf = [0u8; 22];
+        let len = &mut 0;
+        buf.write_with(len, result, LE).unwrap();
+        assert_eq!(22, *len);
+        assert_eq!(data, buf);
+    }
+
+    #[test]
+    fn bridge() {
+        // Capabilities: [d8] PCI-X bridge device
+        //         Secondary Status: 64bit+ 133MHz+ SCD- USC- SCO- SRD- Freq=133MHz
+        //         Status: Dev=00:1c.0 64bit+ 133MHz+ SCD- USC- SCO- SRD-
+        //         Upstream: Capacity=65535 CommitmentLimit=65535
+        //         Downstream: Capacity=65535 CommitmentLimit=65535
+        let data = [
+            0xc3, 0x00, 0xe0, 0x00, 0x03, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        ];
+        let result: Bridge = data.read_with(&mut 0, LE).unwrap();
+        let sample = Bridge {
+            secondary_status: SecondaryStatus {
+                is_64bit: true,
+                capable_133mhz: true,
+                split_completion_discarded: false,
+                unexpected_split_completion: false,
+                split_completion_overrun: false,
+                split_request_delayed: false,
+                secondary_bus_mode_and_frequency: 3,
+                version: 0,
+                capable_266mhz: false,
+                capable_533mhz: false,
+                rsvdp: 0,
+            },
+            bridge_status: BridgeStatus {
+                function_number: 0,
+                device_number: 0x1c,
+                bus_number: 0,
+                is_64bit: true,
+                capable_133mhz: true,
+                split_completion_discarded: false,
+                unexpected_split_completion: false,
+                split_completion_overrun: false,
+                split_request_delayed: false,
+                capable_266mhz: false,
+                capable_533mhz: false,
+                rsvdp: 0,
+            },
+            upstream_split_transaction_control: SplitTransactionControl {
+                split_transaction_capacity: 0xffff,
+                split_transaction_commitment_limit: 0xffff,
+            },
+            downstream_split_transaction_control: SplitTransactionControl {
+                split_transaction_capacity: 0xffff,
+                split_transaction_commitment_limit: 0xffff,
+            },
+            ecc: None,
+        };
+        assert_eq!(sample, result);
+
+        let mut buf = [0u8; 14];
+        buf.write_with(&mut 0, PciX::Bridge(result), LE).unwrap();
+        assert_eq!(data, buf);
+    }
+}
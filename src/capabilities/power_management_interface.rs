@@ -10,13 +10,14 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
 
 
-#[derive(Debug, PartialEq, Eq,)]
+#[derive(Debug, Clone, PartialEq, Eq,)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PowerManagementInterface {
     pub capabilities: Capabilities,
     pub control: Control,
@@ -49,6 +50,16 @@ impl<'a> TryRead<'a, Endian> for PowerManagementInterface {
         Ok((pmi, *offset))
     }
 }
+impl TryWrite<Endian> for PowerManagementInterface {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u16>(offset, self.capabilities.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.control.into(), endian)?;
+        bytes.write_with::<u8>(offset, self.bridge.into(), endian)?;
+        bytes.write_with::<u8>(offset, self.data, endian)?;
+        Ok(*offset)
+    }
+}
 
 
 
@@ -71,7 +82,8 @@ pub struct CapabilitiesProto {
 }
 
 /// Provides information on the capabilities of the function related to power management
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Capabilities {
     /// Default value of 0b10 indicates that this function complies with Revision 1.1 of the PCI
     /// Power Management Interface Specification.
@@ -114,10 +126,31 @@ impl From<CapabilitiesProto> for Capabilities {
 impl From<u16> for Capabilities {
     fn from(word: u16) -> Self { CapabilitiesProto::from(word).into() }
 }
+impl From<Capabilities> for CapabilitiesProto {
+    fn from(data: Capabilities) -> Self {
+        Self::new()
+            .with_version(data.version)
+            .with_pme_clock(data.pme_clock)
+            .with_reserved(data.reserved)
+            .with_device_specific_initialization(data.device_specific_initialization)
+            .with_aux_current(data.aux_current)
+            .with_d1_support(data.d1_support)
+            .with_d2_support(data.d2_support)
+            .with_pme_support_d0(data.pme_support.d0)
+            .with_pme_support_d1(data.pme_support.d1)
+            .with_pme_support_d2(data.pme_support.d2)
+            .with_pme_support_d3_hot(data.pme_support.d3_hot)
+            .with_pme_support_d3_cold(data.pme_support.d3_cold)
+    }
+}
+impl From<Capabilities> for u16 {
+    fn from(data: Capabilities) -> Self { CapabilitiesProto::from(data).into() }
+}
 
 /// This 3 bit field reports the 3.3Vaux auxiliary current requirements for the PCI function.
 /// he [Data] Register takes precedence over this field for 3.3Vaux current and value must be 0.
-#[derive(DisplayDoc, BitfieldSpecifier, Debug, PartialEq, Eq)]
+#[derive(DisplayDoc, BitfieldSpecifier, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[bits = 3]
 pub enum AuxCurrent {
     /// 0mA
@@ -139,7 +172,8 @@ pub enum AuxCurrent {
 }
 
 /// Indicates the power states in which the function may assert PME#.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PmeSupport {
     /// PME# can be asserted from D0
     pub d0: bool,
@@ -165,7 +199,8 @@ pub struct ControlProto {
 }
 
 /// Used to manage the PCI function’s power management state as well as to enable/monitor PMEs.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Control {
     pub power_state: PowerState,
     /// Reserved bits 07:02
@@ -196,9 +231,26 @@ impl From<ControlProto> for Control {
 impl From<u16> for Control {
     fn from(word: u16) -> Self { ControlProto::from(word).into() }
 }
+impl From<Control> for ControlProto {
+    fn from(data: Control) -> Self {
+        // No_Soft_Reset is bit 3 of the register, that is bit 1 of the reserved field
+        let reserved = (data.reserved & !0b10) | ((data.no_soft_reset as u8) << 1);
+        Self::new()
+            .with_power_state(data.power_state.into())
+            .with_reserved(reserved)
+            .with_pme_enabled(data.pme_enabled)
+            .with_data_select(data.data_select.into())
+            .with_data_scale(data.data_scale.into())
+            .with_pme_status(data.pme_status)
+    }
+}
+impl From<Control> for u16 {
+    fn from(data: Control) -> Self { ControlProto::from(data).into() }
+}
 
 /// Current power state.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PowerState {
     D0,
     D1,
@@ -216,6 +268,16 @@ impl From<u8> for PowerState {
         }
     }
 }
+impl From<PowerState> for u8 {
+    fn from(data: PowerState) -> Self {
+        match data {
+            PowerState::D0    => 0b00,
+            PowerState::D1    => 0b01,
+            PowerState::D2    => 0b10,
+            PowerState::D3Hot => 0b11,
+        }
+    }
+}
 
 
 #[bitfield(bits = 8)]
@@ -227,7 +289,8 @@ pub struct BridgeProto {
 }
 
 /// PCI bridge specific functionality and is required for all PCI-toPCI bridges
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Bridge {
     /// Value at reset 0b000000
     pub reserved: u8,
@@ -254,10 +317,22 @@ impl From<BridgeProto> for Bridge {
 impl From<u8> for Bridge {
     fn from(byte: u8) -> Self { BridgeProto::from(byte).into() }
 }
+impl From<Bridge> for BridgeProto {
+    fn from(data: Bridge) -> Self {
+        Self::new()
+            .with_reserved(data.reserved)
+            .with_b2_b3(data.b2_b3)
+            .with_bpcc_enabled(data.bpcc_enabled)
+    }
+}
+impl From<Bridge> for u8 {
+    fn from(data: Bridge) -> Self { BridgeProto::from(data).into() }
+}
 
 /// Register that provides a mechanism for the function to report state dependent operating data
 /// such as power consumed or heat dissipation
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Data {
     pub value: u8,
     pub select: DataSelect,
@@ -266,6 +341,7 @@ pub struct Data {
 
 /// Used to select which data is to be reported through the [Data] register and [DataScale].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DataSelect {
     /// D0 Power Consumed
     PowerConsumedD0,
@@ -323,6 +399,7 @@ impl From<DataSelect> for u8 {
 
 /// Scaling factor indicated to arrive at the value for the desired measurement.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DataScale {
     Unknown,
     /// 0.1x
@@ -343,6 +420,16 @@ impl From<u8> for DataScale {
         }
     }
 }
+impl From<DataScale> for u8 {
+    fn from(data: DataScale) -> Self {
+        match data {
+            DataScale::Unknown    => 0b00,
+            DataScale::Tenth      => 0b01,
+            DataScale::Hundredth  => 0b10,
+            DataScale::Thousandth => 0b11,
+        }
+    }
+}
 
 
 
@@ -394,5 +481,14 @@ mod tests {
         };
         assert_eq!(sample, result);
     }
+
+    #[test]
+    fn write_power_management_interface() {
+        let data = [0x03,0xc8,0x08,0x01,0x40,0x00];
+        let pmi = data.read_with::<PowerManagementInterface>(&mut 0, LE).unwrap();
+        let mut result = [0; 6];
+        result.write_with(&mut 0, pmi, LE).unwrap();
+        assert_eq!(data, result);
+    }
 }
 
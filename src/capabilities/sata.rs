@@ -5,36 +5,55 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
 
 /// Slave/Primary Interface
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sata {
     pub revision: Revision,
+    /// Reserved byte following the Revision register
+    pub reserved: u8,
     /// BAR Offset
     pub bar_offset: BarOffset,
     /// BAR Location
     pub bar_location: BarLocation,
+    /// Reserved bits 31:24 of SATA Capability Register 1
+    pub rsvdp: u8,
 }
 impl<'a> TryRead<'a, Endian> for Sata {
     fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
         let offset = &mut 0;
         let revision = bytes.read_with::<u8>(offset, endian)?.into();
-        let _reserved = bytes.read_with::<u8>(offset, endian)?;
+        let reserved = bytes.read_with::<u8>(offset, endian)?;
         let sata_cap1_proto: SataCapability1Proto =
             bytes.read_with::<u32>(offset, endian)?.into();
-        let _ = sata_cap1_proto.rsvdp();
         let sata = Sata {
             revision,
+            reserved,
             bar_location: sata_cap1_proto.bar_location().into(),
             bar_offset: BarOffset(sata_cap1_proto.bar_offset()),
+            rsvdp: sata_cap1_proto.rsvdp(),
         };
         Ok((sata, *offset))
     }
 }
+impl TryWrite<Endian> for Sata {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        let sata_cap1_proto = SataCapability1Proto::new()
+            .with_bar_location(self.bar_location.into())
+            .with_bar_offset(self.bar_offset.0)
+            .with_rsvdp(self.rsvdp);
+        bytes.write_with::<u8>(offset, self.revision.into(), endian)?;
+        bytes.write_with::<u8>(offset, self.reserved, endian)?;
+        bytes.write_with::<u32>(offset, sata_cap1_proto.into(), endian)?;
+        Ok(*offset)
+    }
+}
 
 #[bitfield(bits = 8)]
 #[repr(u8)]
@@ -44,6 +63,7 @@ pub struct RevisionProto {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Revision {
     /// Minor Revision
     pub minor: u8,
@@ -61,6 +81,16 @@ impl From<RevisionProto> for Revision {
 impl From<u8> for Revision {
     fn from(byte: u8) -> Self { RevisionProto::from(byte).into() }
 }
+impl From<Revision> for RevisionProto {
+    fn from(data: Revision) -> Self {
+        Self::new()
+            .with_minor(data.minor)
+            .with_major(data.major)
+    }
+}
+impl From<Revision> for u8 {
+    fn from(data: Revision) -> Self { RevisionProto::from(data).into() }
+}
 
 #[bitfield(bits = 32)]
 #[repr(u32)]
@@ -74,6 +104,7 @@ pub struct SataCapability1Proto {
 /// Indicates the absolute PCI Configuration Register address of the BAR containing the Index-Data
 /// Pair in Dword granularity
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BarLocation {
     /// 10h (BAR0)
     Bar0,
@@ -107,6 +138,20 @@ impl From<u8> for BarLocation {
         }
     }
 }
+impl From<BarLocation> for u8 {
+    fn from(data: BarLocation) -> Self {
+        match data {
+            BarLocation::Bar0            => 0b0100,
+            BarLocation::Bar1            => 0b0101,
+            BarLocation::Bar2            => 0b0110,
+            BarLocation::Bar3            => 0b0111,
+            BarLocation::Bar4            => 0b1000,
+            BarLocation::Bar5            => 0b1001,
+            BarLocation::SataCapability1 => 0b1111,
+            BarLocation::Reserved(v)     => v,
+        }
+    }
+}
 
 
 ///  Indicates the offset into the BAR where the Index-Data Pair are located in Dword granularity
@@ -114,6 +159,7 @@ impl From<u8> for BarLocation {
 ///  - Maximum if Index-Data Pair is implemented in IO Space from 0 – 64 KB
 ///  - Maximum if Index-Data Pair is memory mapped in the 0 – (1MB – 4) range)
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BarOffset(pub u32);
 impl BarOffset {
     pub fn value(&self) -> u32 {
@@ -0,0 +1,376 @@
+//! Secure Device
+//!
+//! On AMD platforms the Secure Device capability block carries the IOMMU capability registers.
+//! Unit ID, base address and the device range reported here correspond to the IVHD entries of
+//! the ACPI IVRS table.
+
+use modular_bitfield::prelude::*;
+use byte::{
+    ctx::*,
+    self,
+    TryRead,
+    TryWrite,
+    BytesExt,
+};
+
+
+/// IOMMU Capability Block
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SecureDevice {
+    pub capabilities: Capabilities,
+    /// IOMMU is enabled, its register set is mapped at [SecureDevice::base_address]
+    pub enable: bool,
+    /// Reserved bits 13:1 of IOMMU Base Address Low register
+    pub reserved: u16,
+    /// IOMMU Base Address, 16 Kbyte aligned address of the IOMMU control registers
+    pub base_address: u64,
+    pub range: Range,
+    pub misc_information_0: MiscInformation0,
+    /// Present if [Capabilities::capability_extension] is set
+    pub misc_information_1: Option<MiscInformation1>,
+}
+impl<'a> TryRead<'a, Endian> for SecureDevice {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let capabilities: Capabilities = bytes.read_with::<u16>(offset, endian)?.into();
+        let base_address_low = bytes.read_with::<u32>(offset, endian)?;
+        let base_address_high = bytes.read_with::<u32>(offset, endian)?;
+        let range = bytes.read_with::<u32>(offset, endian)?.into();
+        let misc_information_0 = bytes.read_with::<u32>(offset, endian)?.into();
+        let misc_information_1 = if capabilities.capability_extension {
+            Some(bytes.read_with::<u32>(offset, endian)?.into())
+        } else {
+            None
+        };
+        let sd = SecureDevice {
+            capabilities,
+            enable: base_address_low & 1 != 0,
+            reserved: ((base_address_low & 0x3fff) >> 1) as u16,
+            base_address: (u64::from(base_address_high) << 32)
+                | u64::from(base_address_low & !0x3fff),
+            range,
+            misc_information_0,
+            misc_information_1,
+        };
+        Ok((sd, *offset))
+    }
+}
+impl TryWrite<Endian> for SecureDevice {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        let base_address_low = (self.base_address as u32 & !0x3fff)
+            | (u32::from(self.reserved) & 0x1fff) << 1
+            | self.enable as u32;
+        bytes.write_with::<u16>(offset, self.capabilities.into(), endian)?;
+        bytes.write_with::<u32>(offset, base_address_low, endian)?;
+        bytes.write_with::<u32>(offset, (self.base_address >> 32) as u32, endian)?;
+        bytes.write_with::<u32>(offset, self.range.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.misc_information_0.into(), endian)?;
+        if let Some(misc_information_1) = self.misc_information_1 {
+            bytes.write_with::<u32>(offset, misc_information_1.into(), endian)?;
+        }
+        Ok(*offset)
+    }
+}
+
+
+#[bitfield(bits = 16)]
+#[repr(u16)]
+pub struct CapabilitiesProto {
+    capability_type: B3,
+    capability_revision: B5,
+    iotlb_support: bool,
+    hypertransport_tunnel: bool,
+    not_present_cache: bool,
+    extended_feature_register_support: bool,
+    capability_extension: bool,
+    rsvdp: B3,
+}
+
+/// IOMMU Capability Header bits following the Capability ID and Next Pointer fields
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Capabilities {
+    /// CapType, 011b – IOMMU
+    pub capability_type: u8,
+    /// CapRev, IOMMU specification revision
+    pub capability_revision: u8,
+    /// IotlbSup, IOMMU supports remote IOTLB caching of translations
+    pub iotlb_support: bool,
+    /// HtTunnel, IOMMU is in a HyperTransport tunnel
+    pub hypertransport_tunnel: bool,
+    /// NpCache, IOMMU caches page table entries that are marked as not present
+    pub not_present_cache: bool,
+    /// EFRSup, IOMMU Extended Feature Register is supported
+    pub extended_feature_register_support: bool,
+    /// CapExt, IOMMU Miscellaneous Information Register 1 is implemented
+    pub capability_extension: bool,
+    /// Reserved bits 15:13
+    pub rsvdp: u8,
+}
+impl From<CapabilitiesProto> for Capabilities {
+    fn from(proto: CapabilitiesProto) -> Self {
+        Self {
+            capability_type: proto.capability_type(),
+            capability_revision: proto.capability_revision(),
+            iotlb_support: proto.iotlb_support(),
+            hypertransport_tunnel: proto.hypertransport_tunnel(),
+            not_present_cache: proto.not_present_cache(),
+            extended_feature_register_support: proto.extended_feature_register_support(),
+            capability_extension: proto.capability_extension(),
+            rsvdp: proto.rsvdp(),
+        }
+    }
+}
+impl From<u16> for Capabilities {
+    fn from(word: u16) -> Self { CapabilitiesProto::from(word).into() }
+}
+impl From<Capabilities> for CapabilitiesProto {
+    fn from(data: Capabilities) -> Self {
+        Self::new()
+            .with_capability_type(data.capability_type)
+            .with_capability_revision(data.capability_revision)
+            .with_iotlb_support(data.iotlb_support)
+            .with_hypertransport_tunnel(data.hypertransport_tunnel)
+            .with_not_present_cache(data.not_present_cache)
+            .with_extended_feature_register_support(data.extended_feature_register_support)
+            .with_capability_extension(data.capability_extension)
+            .with_rsvdp(data.rsvdp)
+    }
+}
+impl From<Capabilities> for u16 {
+    fn from(data: Capabilities) -> Self { CapabilitiesProto::from(data).into() }
+}
+
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct RangeProto {
+    unit_id: B5,
+    rsvdp: B2,
+    range_valid: bool,
+    bus_number: u8,
+    first_device: u8,
+    last_device: u8,
+}
+
+/// IOMMU Range
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Range {
+    /// UnitID, HyperTransport Unit ID of the IOMMU
+    pub unit_id: u8,
+    /// Reserved bits 6:5
+    pub rsvdp: u8,
+    /// RngValid, [Range::bus_number], [Range::first_device] and [Range::last_device] are valid
+    pub range_valid: bool,
+    /// BusNumber of the devices controlled by the IOMMU
+    pub bus_number: u8,
+    /// FirstDevice, device and function number of the first device controlled by the IOMMU
+    pub first_device: u8,
+    /// LastDevice, device and function number of the last device controlled by the IOMMU
+    pub last_device: u8,
+}
+impl From<RangeProto> for Range {
+    fn from(proto: RangeProto) -> Self {
+        Self {
+            unit_id: proto.unit_id(),
+            rsvdp: proto.rsvdp(),
+            range_valid: proto.range_valid(),
+            bus_number: proto.bus_number(),
+            first_device: proto.first_device(),
+            last_device: proto.last_device(),
+        }
+    }
+}
+impl From<u32> for Range {
+    fn from(dword: u32) -> Self { RangeProto::from(dword).into() }
+}
+impl From<Range> for RangeProto {
+    fn from(data: Range) -> Self {
+        Self::new()
+            .with_unit_id(data.unit_id)
+            .with_rsvdp(data.rsvdp)
+            .with_range_valid(data.range_valid)
+            .with_bus_number(data.bus_number)
+            .with_first_device(data.first_device)
+            .with_last_device(data.last_device)
+    }
+}
+impl From<Range> for u32 {
+    fn from(data: Range) -> Self { RangeProto::from(data).into() }
+}
+
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct MiscInformation0Proto {
+    msi_number: B5,
+    guest_virtual_address_size: B3,
+    physical_address_size: B7,
+    virtual_address_size: B7,
+    ht_ats_reserved: bool,
+    rsvdp: B4,
+    msi_number_ppr: B5,
+}
+
+/// IOMMU Miscellaneous Information Register 0
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MiscInformation0 {
+    /// MsiNum, MSI message number used for event and command completion interrupts
+    pub msi_number: u8,
+    /// GVAsize, 010b – 48 bits of guest virtual address, other encodings are reserved
+    pub guest_virtual_address_size: u8,
+    /// PAsize, maximum number of physical address bits supported
+    pub physical_address_size: u8,
+    /// VAsize, maximum number of virtual address bits supported
+    pub virtual_address_size: u8,
+    /// HtAtsResv, HyperTransport ATS address range is reserved and can not be translated
+    pub ht_ats_reserved: bool,
+    /// Reserved bits 26:23
+    pub rsvdp: u8,
+    /// MsiNumPPR, MSI message number used for peripheral page request interrupts
+    pub msi_number_ppr: u8,
+}
+impl From<MiscInformation0Proto> for MiscInformation0 {
+    fn from(proto: MiscInformation0Proto) -> Self {
+        Self {
+            msi_number: proto.msi_number(),
+            guest_virtual_address_size: proto.guest_virtual_address_size(),
+            physical_address_size: proto.physical_address_size(),
+            virtual_address_size: proto.virtual_address_size(),
+            ht_ats_reserved: proto.ht_ats_reserved(),
+            rsvdp: proto.rsvdp(),
+            msi_number_ppr: proto.msi_number_ppr(),
+        }
+    }
+}
+impl From<u32> for MiscInformation0 {
+    fn from(dword: u32) -> Self { MiscInformation0Proto::from(dword).into() }
+}
+impl From<MiscInformation0> for MiscInformation0Proto {
+    fn from(data: MiscInformation0) -> Self {
+        Self::new()
+            .with_msi_number(data.msi_number)
+            .with_guest_virtual_address_size(data.guest_virtual_address_size)
+            .with_physical_address_size(data.physical_address_size)
+            .with_virtual_address_size(data.virtual_address_size)
+            .with_ht_ats_reserved(data.ht_ats_reserved)
+            .with_rsvdp(data.rsvdp)
+            .with_msi_number_ppr(data.msi_number_ppr)
+    }
+}
+impl From<MiscInformation0> for u32 {
+    fn from(data: MiscInformation0) -> Self { MiscInformation0Proto::from(data).into() }
+}
+
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct MiscInformation1Proto {
+    msi_number_ga: B5,
+    rsvdp: B27,
+}
+
+/// IOMMU Miscellaneous Information Register 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MiscInformation1 {
+    /// MsiNumGA, MSI message number used for guest virtual APIC log interrupts
+    pub msi_number_ga: u8,
+    /// Reserved bits 31:5
+    pub rsvdp: u32,
+}
+impl From<MiscInformation1Proto> for MiscInformation1 {
+    fn from(proto: MiscInformation1Proto) -> Self {
+        Self {
+            msi_number_ga: proto.msi_number_ga(),
+            rsvdp: proto.rsvdp(),
+        }
+    }
+}
+impl From<u32> for MiscInformation1 {
+    fn from(dword: u32) -> Self { MiscInformation1Proto::from(dword).into() }
+}
+impl From<MiscInformation1> for MiscInformation1Proto {
+    fn from(data: MiscInformation1) -> Self {
+        Self::new()
+            .with_msi_number_ga(data.msi_number_ga)
+            .with_rsvdp(data.rsvdp)
+    }
+}
+impl From<MiscInformation1> for u32 {
+    fn from(data: MiscInformation1) -> Self { MiscInformation1Proto::from(data).into() }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use super::*;
+
+    // Capabilities: [40] Secure device <?>
+    const DATA: [u8; 22] = [
+        0x0b, 0x1d,
+        0x01, 0x00, 0xb8, 0xfe,
+        0x00, 0x00, 0x00, 0x00,
+        0x80, 0x00, 0x08, 0xff,
+        0x40, 0x30, 0x20, 0x00,
+        0x02, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn parse_full_struct() {
+        let result: SecureDevice = DATA.read_with(&mut 0, LE).unwrap();
+        let sample = SecureDevice {
+            capabilities: Capabilities {
+                capability_type: 0b011,
+                capability_revision: 1,
+                iotlb_support: true,
+                hypertransport_tunnel: false,
+                not_present_cache: true,
+                extended_feature_register_support: true,
+                capability_extension: true,
+                rsvdp: 0,
+            },
+            enable: true,
+            base_address: 0xfeb8_0000,
+            range: Range {
+                unit_id: 0,
+                range_valid: true,
+                bus_number: 0,
+                first_device: 0x08,
+                last_device: 0xff,
+                rsvdp: 0,
+            },
+            misc_information_0: MiscInformation0 {
+                msi_number: 0,
+                guest_virtual_address_size: 0b010,
+                physical_address_size: 48,
+                virtual_address_size: 64,
+                ht_ats_reserved: false,
+                msi_number_ppr: 0,
+                rsvdp: 0,
+            },
+            misc_information_1: Some(MiscInformation1 { msi_number_ga: 2, rsvdp: 0 }),
+            reserved: 0,
+        };
+        assert_eq!(sample, result);
+
+        let mut buf = [0u8; 22];
+        buf.write_with(&mut 0, result, LE).unwrap();
+        assert_eq!(DATA, buf);
+    }
+
+    #[test]
+    fn without_capability_extension() {
+        let mut data = DATA;
+        data[1] &= !0x10;
+        let (result, len) = SecureDevice::try_read(&data[..18], LE).unwrap();
+        assert_eq!(18, len);
+        assert_eq!(None, result.misc_information_1);
+    }
+}
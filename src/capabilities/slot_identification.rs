@@ -7,13 +7,14 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
 
 /// Slot Identification
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SlotIdentification {
     pub expansion_slot: ExpansionSlot,
     /// Contains the physical chassis number for the slots on this bridge’s secondary interface
@@ -29,6 +30,14 @@ impl<'a> TryRead<'a, Endian> for SlotIdentification {
         Ok((si, *offset))
     }
 }
+impl TryWrite<Endian> for SlotIdentification {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u8>(offset, self.expansion_slot.into(), endian)?;
+        bytes.write_with::<u8>(offset, self.chassis_number, endian)?;
+        Ok(*offset)
+    }
+}
 
 #[bitfield(bits = 8)]
 #[repr(u8)]
@@ -41,21 +50,35 @@ pub struct ExpansionSlotProto {
 /// Provides information used by system software in calculating the slot number of a device plugged
 /// into a PCI slot in an expansion chassis
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExpansionSlot {
     /// Number of PCI expansion slots located directly on the secondary interface of this bridge
     pub expansion_slots_provided: u8,
     /// Indicates that this bridge is the first in an expansion chassis
     pub first_in_chassis: bool,
+    /// Reserved bits 7:6
+    pub rsvdp: u8,
 }
 impl From<ExpansionSlotProto> for ExpansionSlot {
     fn from(proto: ExpansionSlotProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             expansion_slots_provided: proto.expansion_slots_provided(),
             first_in_chassis: proto.first_in_chassis(),
+            rsvdp: proto.rsvdp(),
         }
     }
 }
 impl From<u8> for ExpansionSlot {
     fn from(byte: u8) -> Self { ExpansionSlotProto::from(byte).into() }
 }
+impl From<ExpansionSlot> for ExpansionSlotProto {
+    fn from(data: ExpansionSlot) -> Self {
+        Self::new()
+            .with_expansion_slots_provided(data.expansion_slots_provided)
+            .with_first_in_chassis(data.first_in_chassis)
+            .with_rsvdp(data.rsvdp)
+    }
+}
+impl From<ExpansionSlot> for u8 {
+    fn from(data: ExpansionSlot) -> Self { ExpansionSlotProto::from(data).into() }
+}
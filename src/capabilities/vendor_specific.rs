@@ -11,20 +11,23 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
+use crate::RawBytes;
+
 
 /// Only vendor-specific data length. Without Cap ID, Next Ptr and length itself
-#[derive(Debug, PartialEq, Eq)]
-pub struct VendorSpecific<'a>(pub &'a [u8]);
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VendorSpecific<'a>(pub RawBytes<'a>);
 impl<'a> VendorSpecific<'a> {
     pub fn new(data: &'a [u8]) -> Self {
-        Self(data)
+        Self(RawBytes::new(data))
     }
-    pub fn vendor_capability(&self, vendor_id: u16, device_id: u16) -> VendorCapabilty<'a> {
-        let data = self.0;
+    pub fn vendor_capability(&self, vendor_id: u16, device_id: u16) -> VendorCapabilty<'_> {
+        let data = &*self.0;
         let offset = &mut 0;
         match (vendor_id, device_id) {
             (0x1af4, 0x1000..=0x107f) => {
@@ -41,16 +44,31 @@ impl<'a> VendorSpecific<'a> {
 impl<'a> TryRead<'a, Endian> for VendorSpecific<'a> {
     fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
         let offset = &mut 0;
-        let length = bytes.read_with::<u8>(offset, endian)? as usize - 1;
-        let vs = VendorSpecific(bytes.read_with(offset, Bytes::Len(length))?);
+        let length = usize::from(bytes.read_with::<u8>(offset, endian)?).checked_sub(1)
+            .ok_or(byte::Error::BadInput { err: "vendor specific length is zero" })?;
+        let vs = VendorSpecific::new(bytes.read_with(offset, Bytes::Len(length))?);
         Ok((vs, *offset))
     }
 }
+impl<'a> TryWrite<Endian> for VendorSpecific<'a> {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        let length = u8::try_from(self.0.len() + 1)
+            .map_err(|_| byte::Error::BadInput { err: "vendor specific data too long" })?;
+        bytes.write_with::<u8>(offset, length, endian)?;
+        bytes.write::<&[u8]>(offset, &self.0)?;
+        Ok(*offset)
+    }
+}
 
 /// Known vendor-specific capabilities
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum VendorCapabilty<'a> {
-    Unspecified(&'a [u8]),
+    Unspecified(
+        #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serde_hex::serialize"))]
+        &'a [u8],
+    ),
     Virtio(Virtio),
 }
 impl<'a> VendorCapabilty<'a> {
@@ -71,6 +89,7 @@ impl<'a> VendorCapabilty<'a> {
 }
 
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Virtio {
     CommonCfg { bar: u8, offset: u32, size: u32 },
     Notify {
@@ -7,13 +7,14 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
 
 /// Vital Product Data
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VitalProductData {
     /// DWORD-aligned byte address of the VPD to be accessed
     pub vpd_address: u16,
@@ -35,3 +36,12 @@ impl<'a> TryRead<'a, Endian> for VitalProductData {
         Ok((vpd, *offset))
     }
 }
+impl TryWrite<Endian> for VitalProductData {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        let word = (self.vpd_address & !0x8000) | ((self.transfer_completed as u16) << 15);
+        bytes.write_with::<u16>(offset, word, endian)?;
+        bytes.write_with::<u32>(offset, self.vpd_data, endian)?;
+        Ok(*offset)
+    }
+}
@@ -0,0 +1,55 @@
+/*!
+Configuration space parsing errors
+
+Every error carries the configuration space offset of the offending register or structure, so
+broken lists can be diagnosed instead of being silently truncated.
+*/
+
+use displaydoc::Display as DisplayDoc;
+
+
+/// Configuration space parsing error
+#[derive(DisplayDoc, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Error {
+    /// pointer {pointer:#x} at {offset:#x} is out of bounds
+    PointerOutOfBounds { offset: u16, pointer: u16 },
+    /// pointer at {offset:#x} refers to already visited structure at {pointer:#x}
+    PointerLoop { offset: u16, pointer: u16 },
+    /// structure at {offset:#x} overlaps already visited structure
+    Overlap { offset: u16 },
+    /// too many structures in list, iteration stopped at {offset:#x}
+    IterationLimit { offset: u16 },
+    /// structure at {offset:#x} has invalid length
+    InvalidLength { offset: u16 },
+    /// unknown header type {header_type:#04x} at {offset:#x}
+    UnknownHeaderType { offset: u16, header_type: u8 },
+    /// structure at {offset:#x} is truncated
+    Truncated { offset: u16 },
+}
+impl Error {
+    /// Configuration space offset the error refers to
+    pub fn offset(&self) -> u16 {
+        match *self {
+            Self::PointerOutOfBounds { offset, .. }
+            | Self::PointerLoop { offset, .. }
+            | Self::Overlap { offset }
+            | Self::IterationLimit { offset }
+            | Self::InvalidLength { offset }
+            | Self::UnknownHeaderType { offset, .. }
+            | Self::Truncated { offset } => offset,
+        }
+    }
+    /// Error of reading structure at `offset` with [byte] context
+    pub(crate) fn from_byte(offset: u16, error: byte::Error) -> Self {
+        match error {
+            byte::Error::BadInput { .. } => Self::InvalidLength { offset },
+            byte::Error::Incomplete | byte::Error::BadOffset(_) => Self::Truncated { offset },
+        }
+    }
+}
+impl From<Error> for byte::Error {
+    fn from(error: Error) -> Self {
+        byte::Error::BadOffset(error.offset().into())
+    }
+}
@@ -12,11 +12,11 @@ Extended Capabilities list:
 - [x] [Virtual Channel](virtual_channel) (VC) – used if an MFVC Extended Cap structure is not present in the device (0002h)
 - [x] [Device Serial Number](device_serial_number) (0003h)
 - [x] [Power Budgeting](power_budgeting) (0004h)
-- [ ] Root Complex Link Declaration (0005h)
-- [ ] Root Complex Internal Link Control (0006h)
-- [ ] Root Complex Event Collector Endpoint Association (0007h)
-- [ ] Multi-Function Virtual Channel (MFVC) (0008h)
-- [ ] Virtual Channel (VC) – used if an MFVC Extended Cap structure is present in the device (0009h)
+- [x] [Root Complex Link Declaration](root_complex_link_declaration) (0005h)
+- [x] [Root Complex Internal Link Control](root_complex_internal_link_control) (0006h)
+- [x] [Root Complex Event Collector Endpoint Association](root_complex_event_collector_endpoint_association) (0007h)
+- [x] [Multi-Function Virtual Channel](multi_function_virtual_channel) (MFVC) (0008h)
+- [x] [Virtual Channel](virtual_channel) (VC) – used if an MFVC Extended Cap structure is present in the device (0009h)
 - [ ] Root Complex Register Block (RCRB) Header (000Ah)
 - [x] [Vendor-Specific Extended Capability](vendor_specific_extended_capability) (VSEC) (000Bh)
 - [ ] Configuration Access Correlation (CAC) (000Ch)
@@ -28,7 +28,7 @@ Extended Capabilities list:
 - [ ] Multicast (0012h)
 - [x] [Page Request Interface](page_request_interface) (PRI) (0013h)
 - [ ] Reserved for AMD (0014h)
-- [ ] Resizable BAR (0015h)
+- [x] [Resizable BAR](resizable_bar) (0015h)
 - [ ] Dynamic Power Allocation (DPA) (0016h)
 - [x] [TPH Requester](tph_requester) (0017h)
 - [x] [Latency Tolerance Reporting (LTR)](latency_tolerance_reporting) (0018h)
@@ -42,8 +42,8 @@ Extended Capabilities list:
 - [ ] PCI Express over M-PHY (M-PCIe) (0020h)
 - [ ] FRS Queueing (0021h)
 - [ ] Readiness Time Reporting (0022h)
-- [ ] Designated Vendor-Specific Extended Capability (0023h)
-- [ ] VF Resizable BAR (0024h)
+- [x] [Designated Vendor-Specific Extended Capability](designated_vendor_specific_extended_capability) (DVSEC) (0023h)
+- [x] [VF Resizable BAR](vf_resizable_bar) (0024h)
 - [ ] Data Link Feature (0025h)
 - [ ] Physical Layer 16.0 GT/s (0026h)
 - [ ] Lane Margining at the Receiver (0027h)
@@ -92,14 +92,19 @@ let sample = vec![
 use modular_bitfield::prelude::*;
 use byte::{
     ctx::*,
+    self,
+    TryWrite,
     BytesExt,
 };
 
-use super::ECS_OFFSET;
+use super::{ECS_OFFSET, Error, visited::Visited};
 
 /// Extended Capability Header length in bytes
 pub const ECH_BYTES: usize = 4;
 
+/// Upper bound of list length, the same as Linux uses for extended capabilities lookup
+const MAX_EXTENDED_CAPABILITIES: u16 = 480;
+
 
 
 #[bitfield(bits = 32)]
@@ -116,66 +121,100 @@ pub struct ExtendedCapabilities<'a> {
     /// Extended Configuration Space
     ecs: &'a [u8],
     offset: u16,
+    /// Offset of capability header holding the offset
+    pointer_offset: u16,
+    /// Dwords occupied by already parsed capabilities
+    visited: Visited<16, 2>,
 }
 impl<'a> ExtendedCapabilities<'a> {
     pub fn new(ecs: &'a [u8]) -> Self {
-        Self { ecs, offset: 0x100 }
+        Self::with_offset(ecs, 0x100)
     }
-}
-impl<'a> Iterator for ExtendedCapabilities<'a> {
-    type Item = ExtendedCapability<'a>;
-
-    fn next(&mut self) -> Option<Self::Item> {
+    /// Iterator starting at arbitrary capability `offset`
+    pub(crate) fn with_offset(ecs: &'a [u8], offset: u16) -> Self {
+        Self { ecs, offset, pointer_offset: 0, visited: Visited::new(MAX_EXTENDED_CAPABILITIES) }
+    }
+    /// Iterator yielding parsing errors instead of silently ending the list
+    pub fn checked(self) -> CheckedExtendedCapabilities<'a> {
+        CheckedExtendedCapabilities(self)
+    }
+    /// Extended Configuration Space the list resides in
+    pub(crate) fn data(&self) -> &'a [u8] {
+        self.ecs
+    }
+    fn try_next(&mut self) -> Option<Result<ExtendedCapability<'a>, Error>> {
         if self.offset == 0 {
             return None;
         }
         let offset = self.offset;
+        let result = self.read(offset);
+        // Nothing can be read after a broken capability
+        self.offset = match result {
+            Ok(Some((_, next))) => next,
+            _ => 0,
+        };
+        self.pointer_offset = offset;
+        result.map(|ecap| ecap.map(|(ecap, _)| ecap)).transpose()
+    }
+    fn read(&mut self, offset: u16) -> Result<Option<(ExtendedCapability<'a>, u16)>, Error> {
         let bytes = &self.ecs;
-        let ecs_offset = &mut usize::from(self.offset).checked_sub(ECS_OFFSET)?;
-        let header = bytes.read_with::<u32>(ecs_offset, LE).ok()?;
+        let ecs_offset = &mut usize::from(offset).checked_sub(ECS_OFFSET)
+            .ok_or(Error::PointerOutOfBounds { offset: self.pointer_offset, pointer: offset })?;
+        // Extended configuration space may be not accessible at all
+        if bytes.is_empty() && offset == ECS_OFFSET as u16 {
+            return Ok(None);
+        }
+        let start = *ecs_offset;
+        self.visited.enter(self.pointer_offset, offset)?;
+        let header = bytes.read_with::<u32>(ecs_offset, LE)
+            .map_err(|_| Error::Truncated { offset })?;
         if header == 0 {
-            return None;
+            return Ok(None);
         }
+        let invalid = |e| Error::from_byte(offset, e);
         let header: ExtendedCapabilityHeaderProto = header.into();
         use ExtendedCapabilityKind::*;
         let kind = match header.id() {
             0x0000 => Null,
-            0x0001 => AdvancedErrorReporting(bytes.read_with(ecs_offset, LE).ok()?),
-            0x0002 => VirtualChannel(bytes.read_with(ecs_offset, LE).ok()?),
-            0x0003 => DeviceSerialNumber(bytes.read_with(ecs_offset, LE).ok()?),
-            0x0004 => PowerBudgeting(bytes.read_with(ecs_offset, LE).ok()?),
-            0x0005 => RootComplexLinkDeclaration,
-            0x0006 => RootComplexInternalLinkControl,
-            0x0007 => RootComplexEventCollectorEndpointAssociation,
-            0x0008 => MultiFunctionVirtualChannel,
-            0x0009 => VirtualChannelMfvcPresent,
+            0x0001 => bytes.read_with(ecs_offset, LE).map(AdvancedErrorReporting).map_err(invalid)?,
+            0x0002 => bytes.read_with(ecs_offset, LE).map(VirtualChannel).map_err(invalid)?,
+            0x0003 => bytes.read_with(ecs_offset, LE).map(DeviceSerialNumber).map_err(invalid)?,
+            0x0004 => bytes.read_with(ecs_offset, LE).map(PowerBudgeting).map_err(invalid)?,
+            0x0005 => bytes.read_with(ecs_offset, LE).map(RootComplexLinkDeclaration).map_err(invalid)?,
+            0x0006 => bytes.read_with(ecs_offset, LE).map(RootComplexInternalLinkControl).map_err(invalid)?,
+            0x0007 => {
+                let ctx = RootComplexEventCollectorEndpointAssociationCtx { endian: LE, version: header.version() };
+                bytes.read_with(ecs_offset, ctx).map(RootComplexEventCollectorEndpointAssociation).map_err(invalid)?
+            },
+            0x0008 => bytes.read_with(ecs_offset, LE).map(MultiFunctionVirtualChannel).map_err(invalid)?,
+            0x0009 => bytes.read_with(ecs_offset, LE).map(VirtualChannelMfvcPresent).map_err(invalid)?,
             0x000A => RootComplexRegisterBlock,
-            0x000B => VendorSpecificExtendedCapability(bytes.read_with(ecs_offset, LE).ok()?),
+            0x000B => bytes.read_with(ecs_offset, LE).map(VendorSpecificExtendedCapability).map_err(invalid)?,
             0x000C => ConfigurationAccessCorrelation,
-            0x000D => AccessControlServices(bytes.read_with(ecs_offset, LE).ok()?),
-            0x000E => AlternativeRoutingIdInterpretation(bytes.read_with(ecs_offset, LE).ok()?),
-            0x000F => AddressTranslationServices(bytes.read_with(ecs_offset, LE).ok()?),
-            0x0010 => SingleRootIoVirtualization(bytes.read_with(ecs_offset, LE).ok()?),
+            0x000D => bytes.read_with(ecs_offset, LE).map(AccessControlServices).map_err(invalid)?,
+            0x000E => bytes.read_with(ecs_offset, LE).map(AlternativeRoutingIdInterpretation).map_err(invalid)?,
+            0x000F => bytes.read_with(ecs_offset, LE).map(AddressTranslationServices).map_err(invalid)?,
+            0x0010 => bytes.read_with(ecs_offset, LE).map(SingleRootIoVirtualization).map_err(invalid)?,
             0x0011 => MultiRootIoVirtualization,
             0x0012 => Multicast,
-            0x0013 => PageRequestInterface(bytes.read_with(ecs_offset, LE).ok()?),
+            0x0013 => bytes.read_with(ecs_offset, LE).map(PageRequestInterface).map_err(invalid)?,
             0x0014 => AmdReserved,
-            0x0015 => ResizableBar,
+            0x0015 => bytes.read_with(ecs_offset, LE).map(ResizableBar).map_err(invalid)?,
             0x0016 => DynamicPowerAllocation,
-            0x0017 => TphRequester(bytes.read_with(ecs_offset, LE).ok()?),
-            0x0018 => LatencyToleranceReporting(bytes.read_with(ecs_offset, LE).ok()?),
-            0x0019 => SecondaryPciExpress(bytes.read_with(ecs_offset, LE).ok()?),
+            0x0017 => bytes.read_with(ecs_offset, LE).map(TphRequester).map_err(invalid)?,
+            0x0018 => bytes.read_with(ecs_offset, LE).map(LatencyToleranceReporting).map_err(invalid)?,
+            0x0019 => bytes.read_with(ecs_offset, LE).map(SecondaryPciExpress).map_err(invalid)?,
             0x001A => ProtocolMultiplexing,
-            0x001B => ProcessAddressSpaceId(bytes.read_with(ecs_offset, LE).ok()?),
+            0x001B => bytes.read_with(ecs_offset, LE).map(ProcessAddressSpaceId).map_err(invalid)?,
             0x001C => LnRequester,
-            0x001D => DownstreamPortContainment(bytes.read_with(ecs_offset, LE).ok()?),
-            0x001E => L1PmSubstates(bytes.read_with(ecs_offset, LE).ok()?),
-            0x001F => PrecisionTimeMeasurement(bytes.read_with(ecs_offset, LE).ok()?),
+            0x001D => bytes.read_with(ecs_offset, LE).map(DownstreamPortContainment).map_err(invalid)?,
+            0x001E => bytes.read_with(ecs_offset, LE).map(L1PmSubstates).map_err(invalid)?,
+            0x001F => bytes.read_with(ecs_offset, LE).map(PrecisionTimeMeasurement).map_err(invalid)?,
             0x0020 => PciExpressOverMphy,
             0x0021 => FrsQueueing,
             0x0022 => ReadinessTimeReporting,
-            0x0023 => DesignatedVendorSpecificExtendedCapability,
-            0x0024 => VFResizableBar,
+            0x0023 => bytes.read_with(ecs_offset, LE).map(DesignatedVendorSpecificExtendedCapability).map_err(invalid)?,
+            0x0024 => bytes.read_with(ecs_offset, LE).map(VFResizableBar).map_err(invalid)?,
             0x0025 => DataLinkFeature,
             0x0026 => PhysicalLayer16GTps,
             0x0027 => ReceiverLaneMargining,
@@ -186,14 +225,35 @@ impl<'a> Iterator for ExtendedCapabilities<'a> {
             0x002C => SystemFirmwareIntermediary,
                  v => Reserved(v),
         };
+        self.visited.claim(offset, *ecs_offset - start)?;
         let ecap = ExtendedCapability { kind, version: header.version(), offset, };
-        self.offset = header.offset();
-        Some(ecap)
+        Ok(Some((ecap, header.offset())))
+    }
+}
+impl<'a> Iterator for ExtendedCapabilities<'a> {
+    type Item = ExtendedCapability<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.try_next()?.ok()
     }
 }
 
+/// An iterator through *Extended Capabilities List* yielding [Error] on the first broken
+/// capability
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckedExtendedCapabilities<'a>(ExtendedCapabilities<'a>);
+impl<'a> Iterator for CheckedExtendedCapabilities<'a> {
+    type Item = Result<ExtendedCapability<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.try_next()
+    }
+}
+
+
 /// Extended Capability
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtendedCapability<'a> {
     pub kind: ExtendedCapabilityKind<'a>,
     pub version: u8,
@@ -201,58 +261,45 @@ pub struct ExtendedCapability<'a> {
 }
 impl<'a> ExtendedCapability<'a> {
     pub fn id(&self) -> u16 {
-        match self.kind {
-            ExtendedCapabilityKind::Null => 0x0000,
-            ExtendedCapabilityKind::AdvancedErrorReporting(_) => 0x0001,
-            ExtendedCapabilityKind::VirtualChannel(_) => 0x0002,
-            ExtendedCapabilityKind::DeviceSerialNumber(_) => 0x0003,
-            ExtendedCapabilityKind::PowerBudgeting(_) => 0x0004,
-            ExtendedCapabilityKind::RootComplexLinkDeclaration => 0x0005,
-            ExtendedCapabilityKind::RootComplexInternalLinkControl => 0x0006,
-            ExtendedCapabilityKind::RootComplexEventCollectorEndpointAssociation => 0x0007,
-            ExtendedCapabilityKind::MultiFunctionVirtualChannel => 0x0008,
-            ExtendedCapabilityKind::VirtualChannelMfvcPresent => 0x0009,
-            ExtendedCapabilityKind::RootComplexRegisterBlock => 0x000A,
-            ExtendedCapabilityKind::VendorSpecificExtendedCapability(_) => 0x000B,
-            ExtendedCapabilityKind::ConfigurationAccessCorrelation => 0x000C,
-            ExtendedCapabilityKind::AccessControlServices(_) => 0x000D,
-            ExtendedCapabilityKind::AlternativeRoutingIdInterpretation(_) => 0x000E,
-            ExtendedCapabilityKind::AddressTranslationServices(_) => 0x000F,
-            ExtendedCapabilityKind::SingleRootIoVirtualization(_) => 0x0010,
-            ExtendedCapabilityKind::MultiRootIoVirtualization => 0x0011,
-            ExtendedCapabilityKind::Multicast => 0x0012,
-            ExtendedCapabilityKind::PageRequestInterface(_) => 0x0013,
-            ExtendedCapabilityKind::AmdReserved => 0x0014,
-            ExtendedCapabilityKind::ResizableBar => 0x0015,
-            ExtendedCapabilityKind::DynamicPowerAllocation => 0x0016,
-            ExtendedCapabilityKind::TphRequester(_) => 0x0017,
-            ExtendedCapabilityKind::LatencyToleranceReporting(_) => 0x0018,
-            ExtendedCapabilityKind::SecondaryPciExpress(_) => 0x0019,
-            ExtendedCapabilityKind::ProtocolMultiplexing => 0x001A,
-            ExtendedCapabilityKind::ProcessAddressSpaceId(_) => 0x001B,
-            ExtendedCapabilityKind::LnRequester => 0x001C,
-            ExtendedCapabilityKind::DownstreamPortContainment(_) => 0x001D,
-            ExtendedCapabilityKind::L1PmSubstates(_) => 0x001E,
-            ExtendedCapabilityKind::PrecisionTimeMeasurement(_) => 0x001F,
-            ExtendedCapabilityKind::PciExpressOverMphy => 0x0020,
-            ExtendedCapabilityKind::FrsQueueing => 0x0021,
-            ExtendedCapabilityKind::ReadinessTimeReporting => 0x0022,
-            ExtendedCapabilityKind::DesignatedVendorSpecificExtendedCapability => 0x0023,
-            ExtendedCapabilityKind::VFResizableBar => 0x0024,
-            ExtendedCapabilityKind::DataLinkFeature => 0x0025,
-            ExtendedCapabilityKind::PhysicalLayer16GTps => 0x0026,
-            ExtendedCapabilityKind::ReceiverLaneMargining => 0x0027,
-            ExtendedCapabilityKind::HierarchyId => 0x0028,
-            ExtendedCapabilityKind::NativePcieEnclosureManagement => 0x0029,
-            ExtendedCapabilityKind::PhysicalLayer32GTps => 0x002A,
-            ExtendedCapabilityKind::AlternateProtocol => 0x002B,
-            ExtendedCapabilityKind::SystemFirmwareIntermediary => 0x002C,
-            ExtendedCapabilityKind::Reserved(v) => v,
+        self.kind.id()
+    }
+}
+/// Writes Extended Capability Header with the given Next Capability Offset followed by the
+/// capability registers
+impl<'a> TryWrite<ExtendedCapabilityWriteCtx> for ExtendedCapability<'a> {
+    fn try_write(self, bytes: &mut [u8], ctx: ExtendedCapabilityWriteCtx) -> byte::Result<usize> {
+        let offset = &mut 0;
+        if self.version > 0xf {
+            return Err(byte::Error::BadInput { err: "Capability Version does not fit in 4 bits" });
+        }
+        if ctx.next > 0xfff {
+            return Err(byte::Error::BadInput { err: "Next Capability Offset does not fit in 12 bits" });
         }
+        let header = ExtendedCapabilityHeaderProto::new()
+            .with_id(self.id())
+            .with_version(self.version)
+            .with_offset(ctx.next);
+        bytes.write_with::<u32>(offset, header.into(), ctx.endian)?;
+        bytes.write_with(offset, self.kind, ctx.endian)?;
+        Ok(*offset)
     }
 }
 
+/// Context for writing [ExtendedCapability]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtendedCapabilityWriteCtx {
+    pub endian: Endian,
+    /// Next Capability Offset
+    pub next: u16,
+}
+
+/// Extended Capability ID assigned by the PCI-SIG
+///
+/// With the `serde` feature, variants borrowing configuration space (the ones with a lifetime
+/// parameter) are serialized with hex strings or decoded tables and deserialized into owned
+/// [RawBytes](crate::RawBytes), which needs no allocator
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExtendedCapabilityKind<'a> {
     /// Null Capability – This capability contains no registers other than those in the Extended
     /// Capability Header
@@ -266,15 +313,15 @@ pub enum ExtendedCapabilityKind<'a> {
     /// Power Budgeting
     PowerBudgeting(PowerBudgeting),
     /// Root Complex Link Declaration
-    RootComplexLinkDeclaration,
+    RootComplexLinkDeclaration(RootComplexLinkDeclaration<'a>),
     /// Root Complex Internal Link Control
-    RootComplexInternalLinkControl,
+    RootComplexInternalLinkControl(RootComplexInternalLinkControl),
     /// Root Complex Event Collector Endpoint Association
-    RootComplexEventCollectorEndpointAssociation,
+    RootComplexEventCollectorEndpointAssociation(RootComplexEventCollectorEndpointAssociation),
     /// Multi-Function Virtual Channel (MFVC)
-    MultiFunctionVirtualChannel,
+    MultiFunctionVirtualChannel(MultiFunctionVirtualChannel<'a>),
     /// Virtual Channel (VC) – used if an MFVC Extended Cap structure is present in the device
-    VirtualChannelMfvcPresent,
+    VirtualChannelMfvcPresent(VirtualChannel<'a>),
     /// Root Complex Register Block (RCRB) Header
     RootComplexRegisterBlock,
     /// Vendor-Specific Extended Capability (VSEC)
@@ -300,7 +347,7 @@ pub enum ExtendedCapabilityKind<'a> {
     /// Reserved for AMD
     AmdReserved,
     /// Resizable BAR
-    ResizableBar,
+    ResizableBar(ResizableBar<'a>),
     /// Dynamic Power Allocation (DPA)
     DynamicPowerAllocation,
     /// TPH Requester
@@ -327,10 +374,13 @@ pub enum ExtendedCapabilityKind<'a> {
     FrsQueueing,
     /// Readiness Time Reporting
     ReadinessTimeReporting,
-    /// Designated Vendor-Specific Extended Capability
-    DesignatedVendorSpecificExtendedCapability,
+    /// Designated Vendor-Specific Extended Capability (DVSEC)
+    ///
+    /// Registers are kept raw, so the capability can be written back regardless of the DVSEC
+    /// vendor, decoded ones are available with [ExtendedCapabilityKind::dvsec_capability]
+    DesignatedVendorSpecificExtendedCapability(DesignatedVendorSpecificExtendedCapability<'a>),
     /// VF Resizable BAR
-    VFResizableBar,
+    VFResizableBar(VFResizableBar<'a>),
     /// Data Link Feature
     DataLinkFeature,
     /// Physical Layer 16.0 GT/s
@@ -349,6 +399,106 @@ pub enum ExtendedCapabilityKind<'a> {
     SystemFirmwareIntermediary,
     Reserved(u16),
 }
+impl<'a> ExtendedCapabilityKind<'a> {
+    pub fn id(&self) -> u16 {
+        match self {
+            Self::Null => 0x0000,
+            Self::AdvancedErrorReporting(_) => 0x0001,
+            Self::VirtualChannel(_) => 0x0002,
+            Self::DeviceSerialNumber(_) => 0x0003,
+            Self::PowerBudgeting(_) => 0x0004,
+            Self::RootComplexLinkDeclaration(_) => 0x0005,
+            Self::RootComplexInternalLinkControl(_) => 0x0006,
+            Self::RootComplexEventCollectorEndpointAssociation(_) => 0x0007,
+            Self::MultiFunctionVirtualChannel(_) => 0x0008,
+            Self::VirtualChannelMfvcPresent(_) => 0x0009,
+            Self::RootComplexRegisterBlock => 0x000A,
+            Self::VendorSpecificExtendedCapability(_) => 0x000B,
+            Self::ConfigurationAccessCorrelation => 0x000C,
+            Self::AccessControlServices(_) => 0x000D,
+            Self::AlternativeRoutingIdInterpretation(_) => 0x000E,
+            Self::AddressTranslationServices(_) => 0x000F,
+            Self::SingleRootIoVirtualization(_) => 0x0010,
+            Self::MultiRootIoVirtualization => 0x0011,
+            Self::Multicast => 0x0012,
+            Self::PageRequestInterface(_) => 0x0013,
+            Self::AmdReserved => 0x0014,
+            Self::ResizableBar(_) => 0x0015,
+            Self::DynamicPowerAllocation => 0x0016,
+            Self::TphRequester(_) => 0x0017,
+            Self::LatencyToleranceReporting(_) => 0x0018,
+            Self::SecondaryPciExpress(_) => 0x0019,
+            Self::ProtocolMultiplexing => 0x001A,
+            Self::ProcessAddressSpaceId(_) => 0x001B,
+            Self::LnRequester => 0x001C,
+            Self::DownstreamPortContainment(_) => 0x001D,
+            Self::L1PmSubstates(_) => 0x001E,
+            Self::PrecisionTimeMeasurement(_) => 0x001F,
+            Self::PciExpressOverMphy => 0x0020,
+            Self::FrsQueueing => 0x0021,
+            Self::ReadinessTimeReporting => 0x0022,
+            Self::DesignatedVendorSpecificExtendedCapability(_) => 0x0023,
+            Self::VFResizableBar(_) => 0x0024,
+            Self::DataLinkFeature => 0x0025,
+            Self::PhysicalLayer16GTps => 0x0026,
+            Self::ReceiverLaneMargining => 0x0027,
+            Self::HierarchyId => 0x0028,
+            Self::NativePcieEnclosureManagement => 0x0029,
+            Self::PhysicalLayer32GTps => 0x002A,
+            Self::AlternateProtocol => 0x002B,
+            Self::SystemFirmwareIntermediary => 0x002C,
+            Self::Reserved(v) => *v,
+        }
+    }
+    /// Registers of [DVSEC](Self::DesignatedVendorSpecificExtendedCapability) decoded with known
+    /// decoders, `None` for other capabilities
+    pub fn dvsec_capability(
+        &self,
+    ) -> Option<byte::Result<designated_vendor_specific_extended_capability::DvsecCapability<'_>>> {
+        match self {
+            Self::DesignatedVendorSpecificExtendedCapability(dvsec) => Some(dvsec.dvsec_capability()),
+            _ => None,
+        }
+    }
+}
+/// Writes capability registers following the Extended Capability Header, fails on capabilities
+/// which registers are not decoded
+impl<'a> TryWrite<Endian> for ExtendedCapabilityKind<'a> {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        match self {
+            Self::AdvancedErrorReporting(data) => bytes.write_with(offset, data, endian)?,
+            Self::VirtualChannel(data) => bytes.write_with(offset, data, endian)?,
+            Self::DeviceSerialNumber(data) => bytes.write_with(offset, data, endian)?,
+            Self::PowerBudgeting(data) => bytes.write_with(offset, data, endian)?,
+            Self::RootComplexLinkDeclaration(data) => bytes.write_with(offset, data, endian)?,
+            Self::RootComplexInternalLinkControl(data) => bytes.write_with(offset, data, endian)?,
+            Self::RootComplexEventCollectorEndpointAssociation(data) => bytes.write_with(offset, data, endian)?,
+            Self::MultiFunctionVirtualChannel(data) => bytes.write_with(offset, data, endian)?,
+            Self::VirtualChannelMfvcPresent(data) => bytes.write_with(offset, data, endian)?,
+            Self::VendorSpecificExtendedCapability(data) => bytes.write_with(offset, data, endian)?,
+            Self::AccessControlServices(data) => bytes.write_with(offset, data, endian)?,
+            Self::AlternativeRoutingIdInterpretation(data) => bytes.write_with(offset, data, endian)?,
+            Self::AddressTranslationServices(data) => bytes.write_with(offset, data, endian)?,
+            Self::SingleRootIoVirtualization(data) => bytes.write_with(offset, data, endian)?,
+            Self::PageRequestInterface(data) => bytes.write_with(offset, data, endian)?,
+            Self::ResizableBar(data) => bytes.write_with(offset, data, endian)?,
+            Self::TphRequester(data) => bytes.write_with(offset, data, endian)?,
+            Self::LatencyToleranceReporting(data) => bytes.write_with(offset, data, endian)?,
+            Self::SecondaryPciExpress(data) => bytes.write_with(offset, data, endian)?,
+            Self::ProcessAddressSpaceId(data) => bytes.write_with(offset, data, endian)?,
+            Self::DownstreamPortContainment(data) => bytes.write_with(offset, data, endian)?,
+            Self::L1PmSubstates(data) => bytes.write_with(offset, data, endian)?,
+            Self::PrecisionTimeMeasurement(data) => bytes.write_with(offset, data, endian)?,
+            Self::DesignatedVendorSpecificExtendedCapability(data) => bytes.write_with(offset, data, endian)?,
+            Self::VFResizableBar(data) => bytes.write_with(offset, data, endian)?,
+            Self::Null => (),
+            // Registers of undecoded capabilities are unknown, even their length
+            _ => return Err(byte::Error::BadInput { err: "Extended Capability is not decoded" }),
+        }
+        Ok(*offset)
+    }
+}
 
 // 0001h Advanced Error Reporting (AER)
 pub mod advanced_error_reporting;
@@ -366,6 +516,23 @@ pub use device_serial_number::DeviceSerialNumber;
 pub mod power_budgeting;
 pub use power_budgeting::PowerBudgeting;
 
+// 0005h Root Complex Link Declaration
+pub mod root_complex_link_declaration;
+pub use root_complex_link_declaration::RootComplexLinkDeclaration;
+
+// 0006h Root Complex Internal Link Control
+pub mod root_complex_internal_link_control;
+pub use root_complex_internal_link_control::RootComplexInternalLinkControl;
+
+// 0007h Root Complex Event Collector Endpoint Association
+pub mod root_complex_event_collector_endpoint_association;
+pub use root_complex_event_collector_endpoint_association::RootComplexEventCollectorEndpointAssociation;
+use root_complex_event_collector_endpoint_association::RootComplexEventCollectorEndpointAssociationCtx;
+
+// 0008h Multi-Function Virtual Channel (MFVC)
+pub mod multi_function_virtual_channel;
+pub use multi_function_virtual_channel::MultiFunctionVirtualChannel;
+
 // 000Bh Vendor-Specific Extended Capability (VSEC)
 pub mod vendor_specific_extended_capability;
 pub use vendor_specific_extended_capability::VendorSpecificExtendedCapability;
@@ -391,6 +558,10 @@ pub use single_root_io_virtualization::SingleRootIoVirtualization;
 pub mod page_request_interface;
 pub use page_request_interface::PageRequestInterface;
 
+// 0015h Resizable BAR
+pub mod resizable_bar;
+pub use resizable_bar::ResizableBar;
+
 // 0017h TPH Requester
 pub mod tph_requester;
 pub use tph_requester::TphRequester;
@@ -419,12 +590,21 @@ pub use l1_pm_substates::L1PmSubstates;
 pub mod precision_time_measurement;
 pub use precision_time_measurement::PrecisionTimeMeasurement;
 
+// 0023h Designated Vendor-Specific Extended Capability (DVSEC)
+pub mod designated_vendor_specific_extended_capability;
+pub use designated_vendor_specific_extended_capability::DesignatedVendorSpecificExtendedCapability;
+
+// 0024h VF Resizable BAR
+pub mod vf_resizable_bar;
+pub use vf_resizable_bar::VFResizableBar;
+
 
 
 #[cfg(test)]
 mod tests {
     use std::prelude::v1::*;
     use pretty_assertions::assert_eq;
+    use crate::ECS_LENGTH;
     use super::*;
 
     #[test]
@@ -457,4 +637,167 @@ mod tests {
             .collect::<Vec<_>>();
         assert_eq!(sample, result);
     }
+
+    #[test]
+    fn parse_then_write() {
+        for (name, data) in crate::test_data::device_configs() {
+            let ecs = data.get(ECS_OFFSET..).unwrap_or_default();
+            let ecaps = ExtendedCapabilities::new(ecs).collect::<Vec<_>>();
+            let nexts = ecaps.iter().skip(1).map(|ecap| ecap.offset).chain([0]).collect::<Vec<_>>();
+            let mut result = [0u8; 4096 - ECS_OFFSET];
+            for (ecap, next) in ecaps.into_iter().zip(nexts) {
+                let start = usize::from(ecap.offset) - ECS_OFFSET;
+                let ctx = ExtendedCapabilityWriteCtx { endian: LE, next };
+                let offset = &mut start.clone();
+                result.write_with(offset, ecap.clone(), ctx).unwrap();
+                assert_eq!(ecs[start..*offset], result[start..*offset], "{}: {:x?}", name, ecap);
+            }
+            let headers = |ecaps: ExtendedCapabilities| {
+                ecaps.map(|ecap| (ecap.offset, ecap.id(), ecap.version)).collect::<Vec<_>>()
+            };
+            assert_eq!(
+                headers(ExtendedCapabilities::new(ecs)),
+                headers(ExtendedCapabilities::new(&result[..ecs.len()])),
+                "{}", name
+            );
+        }
+    }
+
+    #[test]
+    fn write_header_out_of_range() {
+        let ecap = ExtendedCapability { kind: ExtendedCapabilityKind::Null, version: 1, offset: 0x100 };
+        let mut bytes = [0u8; ECH_BYTES];
+        let ctx = ExtendedCapabilityWriteCtx { endian: LE, next: 0xffc };
+        assert_eq!(Ok(()), bytes.write_with(&mut 0, ecap.clone(), ctx));
+        assert_eq!([0x00, 0x00, 0xc1, 0xff], bytes);
+        let result = bytes.write_with(&mut 0, ExtendedCapability { version: 0x10, ..ecap.clone() }, ctx);
+        assert_eq!(Err(byte::Error::BadInput { err: "Capability Version does not fit in 4 bits" }), result);
+        let ctx = ExtendedCapabilityWriteCtx { endian: LE, next: 0x1000 };
+        let result = bytes.write_with(&mut 0, ecap, ctx);
+        assert_eq!(Err(byte::Error::BadInput { err: "Next Capability Offset does not fit in 12 bits" }), result);
+    }
+
+    #[test]
+    fn parse_then_write_random() {
+        let mut rng = crate::test_data::Random::new(0x5eed);
+        let mut ecs = [0u8; ECS_LENGTH];
+        // Write each capability over both blank patterns, bytes that differ were not written
+        let mut zeros = [0u8; ECS_LENGTH];
+        let mut ones = [0u8; ECS_LENGTH];
+        for i in 0..1_000u32 {
+            rng.fill(&mut ecs);
+            let id = (i % 0x2d) as u16;
+            let version = match i % 3 { 0 => 1, 1 => 2, _ => ecs[2] & 0xf };
+            let header = u32::from(id) | u32::from(version) << 16;
+            ecs[..4].copy_from_slice(&header.to_le_bytes());
+            let ecap = match ExtendedCapabilities::new(&ecs).next() {
+                Some(ecap) => ecap,
+                None => continue,
+            };
+            zeros.fill(0);
+            ones.fill(0xff);
+            let zeros_result = zeros.write_with(&mut ECH_BYTES.clone(), ecap.kind.clone(), LE);
+            let ones_result = ones.write_with(&mut ECH_BYTES.clone(), ecap.kind.clone(), LE);
+            assert_eq!(zeros_result, ones_result);
+            use ExtendedCapabilityKind::*;
+            let undecoded = matches!(ecap.kind,
+                RootComplexRegisterBlock | ConfigurationAccessCorrelation
+                | MultiRootIoVirtualization | Multicast | AmdReserved | DynamicPowerAllocation
+                | ProtocolMultiplexing | LnRequester | PciExpressOverMphy | FrsQueueing
+                | ReadinessTimeReporting | DataLinkFeature | PhysicalLayer16GTps
+                | ReceiverLaneMargining | HierarchyId | NativePcieEnclosureManagement
+                | PhysicalLayer32GTps | AlternateProtocol | SystemFirmwareIntermediary
+                | Reserved(_)
+            );
+            assert_eq!(undecoded, zeros_result.is_err(), "{:#x?}: {:?}", ecap, zeros_result);
+            if undecoded {
+                continue;
+            }
+            let lost = (ECH_BYTES..ECS_LENGTH)
+                .filter(|&n| zeros[n] == ones[n] && zeros[n] != ecs[n])
+                .collect::<Vec<_>>();
+            assert!(lost.is_empty(), "{:#x?}: bytes {:x?} differ", ecap, lost);
+        }
+    }
+
+    #[test]
+    fn parse_random() {
+        let mut rng = crate::test_data::Random::new(0x3c5a);
+        let mut ecs = [0u8; ECS_LENGTH];
+        for i in 0..1_000u32 {
+            rng.fill(&mut ecs);
+            // Known capability IDs with version 1, 2 or random and the end of list
+            let id = (i % 0x2d) as u16;
+            let version = match i % 3 { 0 => 1, 1 => 2, _ => ecs[2] & 0xf };
+            let header = u32::from(id) | u32::from(version) << 16;
+            ecs[..4].copy_from_slice(&header.to_le_bytes());
+            for ecap in ExtendedCapabilities::new(&ecs).checked() {
+                let _ = format!("{:?}", ecap);
+            }
+        }
+    }
+
+    #[test]
+    fn checked() {
+        let offsets = |ecaps: ExtendedCapabilities| {
+            ecaps.checked().map(|ecap| ecap.map(|ecap| ecap.offset)).collect::<Vec<_>>()
+        };
+        assert_eq!(Vec::<Result<u16, Error>>::new(), offsets(ExtendedCapabilities::new(&[])));
+
+        // Device Serial Number pointing below extended configuration space
+        let mut ecs = [0u8; 0x10];
+        ecs[..4].copy_from_slice(&0x08010003u32.to_le_bytes());
+        assert_eq!(
+            vec![Ok(0x100), Err(Error::PointerOutOfBounds { offset: 0x100, pointer: 0x80 })],
+            offsets(ExtendedCapabilities::new(&ecs))
+        );
+        assert_eq!(1, ExtendedCapabilities::new(&ecs).count(), "Unchecked iterator silently stops");
+
+        // Next capability header does not fit
+        ecs[..4].copy_from_slice(&0x20010003u32.to_le_bytes());
+        assert_eq!(
+            vec![Ok(0x100), Err(Error::Truncated { offset: 0x200 })],
+            offsets(ExtendedCapabilities::new(&ecs))
+        );
+
+        // Device Serial Number body does not fit
+        assert_eq!(
+            vec![Err(Error::Truncated { offset: 0x100 })],
+            offsets(ExtendedCapabilities::new(&ecs[..8]))
+        );
+    }
+
+    #[test]
+    fn loops_and_overlaps() {
+        let offsets = |ecs: &[u8]| {
+            ExtendedCapabilities::new(ecs).checked()
+                .map(|ecap| ecap.map(|ecap| ecap.offset))
+                .collect::<Vec<_>>()
+        };
+        let mut ecs = [0u8; ECS_LENGTH];
+        // Null capabilities pointing to each other
+        ecs[0x00..0x04].copy_from_slice(&0x10400000u32.to_le_bytes());
+        ecs[0x04..0x08].copy_from_slice(&0x10000000u32.to_le_bytes());
+        assert_eq!(
+            vec![Ok(0x100), Ok(0x104), Err(Error::PointerLoop { offset: 0x104, pointer: 0x100 })],
+            offsets(&ecs)
+        );
+        assert_eq!(2, ExtendedCapabilities::new(&ecs).count(), "Unchecked iterator terminates");
+
+        // Pointer into Device Serial Number registers
+        ecs[0x00..0x04].copy_from_slice(&0x10810003u32.to_le_bytes());
+        assert_eq!(
+            vec![Ok(0x100), Err(Error::Overlap { offset: 0x108 })],
+            offsets(&ecs)
+        );
+
+        // Chain of Null capabilities
+        let mut ecs = [0u8; ECS_LENGTH];
+        for (header, next) in ecs.chunks_exact_mut(4).zip((0x104u32..0x1000).step_by(4)) {
+            header.copy_from_slice(&(next << 20).to_le_bytes());
+        }
+        let result = offsets(&ecs);
+        assert_eq!(481, result.len());
+        assert_eq!(Some(&Err(Error::IterationLimit { offset: 0x880 })), result.last());
+    }
 }
@@ -11,21 +11,24 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
+use crate::RawBytes;
+
 use super::ECH_BYTES;
 
 
 /// Egress Control Vector is DWORD
 const ECV_BYTES: usize = 4;
 
-#[derive(Derivative, Clone, PartialEq, Eq)]
+/// Egress Control Vector is compared only if ACS P2P Egress Control is implemented
+#[derive(Derivative, Clone)]
 #[derivative(Debug)]
 pub struct AccessControlServices<'a> {
     #[derivative(Debug="ignore")]
-    data: &'a [u8],
+    data: RawBytes<'a>,
     /// ACS Capability
     pub acs_capability: AcsCapability,
     /// ACS Control
@@ -33,11 +36,69 @@ pub struct AccessControlServices<'a> {
 }
 
 impl<'a> AccessControlServices<'a> {
-    pub fn egress_control_vectors(&self) -> EgressControlVectors<'a> {
+    pub fn egress_control_vectors(&self) -> EgressControlVectors<'_> {
         let size = self.acs_capability.egress_control_vector_size as usize;
         let start = 0x08 - ECH_BYTES;
-        let end = start + size / (u32::BITS as usize) * ECV_BYTES;
-        EgressControlVectors::new(&self.data[start..end], size)
+        let len = size.div_ceil(u32::BITS as usize) * ECV_BYTES;
+        let data = self.data.get(start..).unwrap_or_default();
+        EgressControlVectors::new(&data[..len.min(data.len())], size)
+    }
+    fn implemented_egress_control_vectors(&self) -> Option<EgressControlVectors<'_>> {
+        self.acs_capability.acs_p2p_egress_control.then(|| self.egress_control_vectors())
+    }
+}
+impl<'a> PartialEq for AccessControlServices<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.acs_capability == other.acs_capability
+        && self.acs_control == other.acs_control
+        && match (self.implemented_egress_control_vectors(), other.implemented_egress_control_vectors()) {
+            (Some(a), Some(b)) => a.eq(b),
+            (a, b) => a.is_none() && b.is_none(),
+        }
+    }
+}
+impl<'a> Eq for AccessControlServices<'a> {}
+/// Egress Control Vector is serialized as decoded bits if ACS P2P Egress Control is implemented
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for AccessControlServices<'a> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let egress_control_vectors = self.implemented_egress_control_vectors();
+        let mut state = serializer.serialize_struct("AccessControlServices", 3)?;
+        state.serialize_field("acs_capability", &self.acs_capability)?;
+        state.serialize_field("acs_control", &self.acs_control)?;
+        state.serialize_field("egress_control_vectors", &egress_control_vectors)?;
+        state.end()
+    }
+}
+/// Registers are rebuilt from decoded Egress Control Vector bits
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for AccessControlServices<'a> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "AccessControlServices")]
+        struct Decoded {
+            acs_capability: AcsCapability,
+            acs_control: AcsControl,
+            egress_control_vectors: Option<crate::serde_hex::Seq<bool, 256>>,
+        }
+        let Decoded { acs_capability, acs_control, egress_control_vectors } =
+            Decoded::deserialize(deserializer)?;
+        let start = 0x08 - ECH_BYTES;
+        let mut data = [0u8; 0x08 - ECH_BYTES + 256 / 8];
+        data[..2].copy_from_slice(&u16::from(acs_capability.clone()).to_le_bytes());
+        data[2..start].copy_from_slice(&u16::from(acs_control.clone()).to_le_bytes());
+        let mut len = start;
+        if let Some(vectors) = egress_control_vectors {
+            let size = acs_capability.egress_control_vector_size as usize;
+            len += size.div_ceil(u32::BITS as usize) * ECV_BYTES;
+            for (n, _) in vectors.iter().enumerate().filter(|(_, &bit)| bit) {
+                data[start + n / 8] |= 1 << (n % 8);
+            }
+        }
+        let data = RawBytes::owned(&data[..len])
+            .ok_or_else(|| serde::de::Error::custom("Egress Control Vector is too long"))?;
+        Ok(AccessControlServices { data, acs_capability, acs_control })
     }
 }
 
@@ -45,13 +106,30 @@ impl<'a> TryRead<'a, Endian> for AccessControlServices<'a> {
     fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
         let offset = &mut 0;
         let acs = AccessControlServices {
-            data: bytes,
+            data: RawBytes::new(bytes),
             acs_capability: bytes.read_with::<u16>(offset, endian)?.into(),
             acs_control: bytes.read_with::<u16>(offset, endian)?.into(),
         };
         Ok((acs, *offset))
     }
 }
+impl<'a> TryWrite<Endian> for AccessControlServices<'a> {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        let egress_control_vector = if self.acs_capability.acs_p2p_egress_control {
+            let size = self.acs_capability.egress_control_vector_size as usize;
+            let start = 0x08 - ECH_BYTES;
+            let end = start + size.div_ceil(u32::BITS as usize) * ECV_BYTES;
+            self.data.get(start..end).ok_or(byte::Error::Incomplete)?
+        } else {
+            &[]
+        };
+        bytes.write_with::<u16>(offset, self.acs_capability.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.acs_control.into(), endian)?;
+        bytes.write::<&[u8]>(offset, egress_control_vector)?;
+        Ok(*offset)
+    }
+}
 
 #[bitfield(bits = 16)]
 #[repr(u16)]
@@ -69,6 +147,7 @@ pub struct AcsCapabilityProto {
 
 /// ACS Capability
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AcsCapability {
     /// ACS Source Validation (V)
     pub acs_source_validation: bool,
@@ -84,12 +163,13 @@ pub struct AcsCapability {
     pub acs_p2p_egress_control: bool,
     /// ACS Direct Translated P2P (T)
     pub acs_direct_translated_p2p: bool,
+    /// Reserved bit 7
+    pub rsvdp: u8,
     /// Egress Control Vector Size
     pub egress_control_vector_size: u8,
 }
 impl From<AcsCapabilityProto> for AcsCapability {
     fn from(proto: AcsCapabilityProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             acs_source_validation: proto.acs_source_validation(),
             acs_translation_blocking: proto.acs_translation_blocking(),
@@ -98,6 +178,7 @@ impl From<AcsCapabilityProto> for AcsCapability {
             acs_upstream_forwarding: proto.acs_upstream_forwarding(),
             acs_p2p_egress_control: proto.acs_p2p_egress_control(),
             acs_direct_translated_p2p: proto.acs_direct_translated_p2p(),
+            rsvdp: proto.rsvdp(),
             egress_control_vector_size: proto.egress_control_vector_size(),
         }
     }
@@ -105,6 +186,23 @@ impl From<AcsCapabilityProto> for AcsCapability {
 impl From<u16> for AcsCapability {
     fn from(word: u16) -> Self { AcsCapabilityProto::from(word).into() }
 }
+impl From<AcsCapability> for AcsCapabilityProto {
+    fn from(data: AcsCapability) -> Self {
+        Self::new()
+            .with_acs_source_validation(data.acs_source_validation)
+            .with_acs_translation_blocking(data.acs_translation_blocking)
+            .with_acs_p2p_request_redirect(data.acs_p2p_request_redirect)
+            .with_acs_p2p_completion_redirect(data.acs_p2p_completion_redirect)
+            .with_acs_upstream_forwarding(data.acs_upstream_forwarding)
+            .with_acs_p2p_egress_control(data.acs_p2p_egress_control)
+            .with_acs_direct_translated_p2p(data.acs_direct_translated_p2p)
+            .with_rsvdp(data.rsvdp)
+            .with_egress_control_vector_size(data.egress_control_vector_size)
+    }
+}
+impl From<AcsCapability> for u16 {
+    fn from(data: AcsCapability) -> Self { AcsCapabilityProto::from(data).into() }
+}
 
 #[bitfield(bits = 16)]
 #[repr(u16)]
@@ -121,6 +219,7 @@ pub struct AcsControlProto {
 
 /// ACS Control
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AcsControl {
     /// ACS Source Validation Enable (V)
     pub acs_source_validation_enable: bool,
@@ -136,10 +235,11 @@ pub struct AcsControl {
     pub acs_p2p_egress_control_enable: bool,
     /// ACS Direct Translated P2P Enable (T)
     pub acs_direct_translated_p2p_enable: bool,
+    /// Reserved bits 15:7
+    pub rsvdp: u16,
 }
 impl From<AcsControlProto> for AcsControl {
     fn from(proto: AcsControlProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             acs_source_validation_enable: proto.acs_source_validation_enable(),
             acs_translation_blocking_enable: proto.acs_translation_blocking_enable(),
@@ -148,12 +248,29 @@ impl From<AcsControlProto> for AcsControl {
             acs_upstream_forwarding_enable: proto.acs_upstream_forwarding_enable(),
             acs_p2p_egress_control_enable: proto.acs_p2p_egress_control_enable(),
             acs_direct_translated_p2p_enable: proto.acs_direct_translated_p2p_enable(),
+            rsvdp: proto.rsvdp(),
         }
     }
 }
 impl From<u16> for AcsControl {
     fn from(word: u16) -> Self { AcsControlProto::from(word).into() }
 }
+impl From<AcsControl> for AcsControlProto {
+    fn from(data: AcsControl) -> Self {
+        Self::new()
+            .with_acs_source_validation_enable(data.acs_source_validation_enable)
+            .with_acs_translation_blocking_enable(data.acs_translation_blocking_enable)
+            .with_acs_p2p_request_redirect_enable(data.acs_p2p_request_redirect_enable)
+            .with_acs_p2p_completion_redirect_enable(data.acs_p2p_completion_redirect_enable)
+            .with_acs_upstream_forwarding_enable(data.acs_upstream_forwarding_enable)
+            .with_acs_p2p_egress_control_enable(data.acs_p2p_egress_control_enable)
+            .with_acs_direct_translated_p2p_enable(data.acs_direct_translated_p2p_enable)
+            .with_rsvdp(data.rsvdp)
+    }
+}
+impl From<AcsControl> for u16 {
+    fn from(data: AcsControl) -> Self { AcsControlProto::from(data).into() }
+}
 
 
 /// An iterator through bits controlled the blocking or redirecting of  peer-to-peer Requests
@@ -179,6 +296,12 @@ impl<'a> PartialEq for EgressControlVectors<'a> {
     }
 }
 impl<'a> Eq for EgressControlVectors<'a> {}
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for EgressControlVectors<'a> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.clone())
+    }
+}
 impl<'a> Iterator for EgressControlVectors<'a> {
     type Item = bool;
 
@@ -224,4 +347,23 @@ mod tests {
         println!("{:?}", &result);
         assert_eq!(sample, result);
     }
+
+    #[test]
+    fn parse_then_write_with_egress_control_vectors() {
+        let data = [
+            // Header
+            0x0d,0x00,0x01,0x00,
+            // ACSCap: SrcValid+ TransBlk+ ReqRedir+ CmpltRedir+ UpstreamFwd+ EgressCtrl+
+            // DirectTrans- EgressVectorSize=35, ACSCtl: SrcValid+
+            0x3f,0x23,0x01,0x00,
+            // Egress Control Vector
+            0x00,0x0F,0xAA,0xFF,0x55,0x00,0x00,0x00,
+        ];
+        let acs: AccessControlServices = data[ECH_BYTES..].read_with(&mut 0, LE).unwrap();
+        let mut result = [0u8; 12];
+        let offset = &mut 0;
+        result.write_with(offset, acs, LE).unwrap();
+        assert_eq!(12, *offset);
+        assert_eq!(data[ECH_BYTES..], result);
+    }
 }
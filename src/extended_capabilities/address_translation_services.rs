@@ -5,12 +5,13 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AddressTranslationServices {
     /// ATS Capability
     pub ats_capability: AtsCapability,
@@ -27,6 +28,14 @@ impl<'a> TryRead<'a, Endian> for AddressTranslationServices {
         Ok((ats, *offset))
     }
 }
+impl TryWrite<Endian> for AddressTranslationServices {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u16>(offset, self.ats_capability.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.ats_control.into(), endian)?;
+        Ok(*offset)
+    }
+}
 
 #[bitfield(bits = 16)]
 #[repr(u16)]
@@ -39,6 +48,7 @@ pub struct AtsCapabilityProto {
 
 /// ATS Capability
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AtsCapability {
     /// Invalidate Queue Depth
     pub invalidate_queue_depth: u8,
@@ -46,20 +56,34 @@ pub struct AtsCapability {
     pub page_aligned_request: bool,
     /// Global Invalidate Supported
     pub global_invalidate_supported: bool,
+    /// Reserved bits 15:7
+    pub rsvdp: u16,
 }
 impl From<AtsCapabilityProto> for AtsCapability {
     fn from(proto: AtsCapabilityProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             invalidate_queue_depth: proto.invalidate_queue_depth(),
             page_aligned_request: proto.page_aligned_request(),
             global_invalidate_supported: proto.global_invalidate_supported(),
+            rsvdp: proto.rsvdp(),
         }
     }
 }
 impl From<u16> for AtsCapability {
     fn from(word: u16) -> Self { AtsCapabilityProto::from(word).into() }
 }
+impl From<AtsCapability> for AtsCapabilityProto {
+    fn from(data: AtsCapability) -> Self {
+        Self::new()
+            .with_invalidate_queue_depth(data.invalidate_queue_depth)
+            .with_page_aligned_request(data.page_aligned_request)
+            .with_global_invalidate_supported(data.global_invalidate_supported)
+            .with_rsvdp(data.rsvdp)
+    }
+}
+impl From<AtsCapability> for u16 {
+    fn from(data: AtsCapability) -> Self { AtsCapabilityProto::from(data).into() }
+}
 
 
 #[bitfield(bits = 16)]
@@ -71,17 +95,20 @@ pub struct AtsControlProto {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AtsControl {
     /// Smallest Translation Unit (STU)
     pub smallest_translation_unit: u8,
+    /// Reserved bits 14:5
+    pub rsvdp: u16,
     /// Enable (E)
     pub enable: bool,
 }
 impl From<AtsControlProto> for AtsControl {
     fn from(proto: AtsControlProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             smallest_translation_unit: proto.smallest_translation_unit(),
+            rsvdp: proto.rsvdp(),
             enable: proto.enable(),
         }
     }
@@ -89,4 +116,15 @@ impl From<AtsControlProto> for AtsControl {
 impl From<u16> for AtsControl {
     fn from(word: u16) -> Self { AtsControlProto::from(word).into() }
 }
+impl From<AtsControl> for AtsControlProto {
+    fn from(data: AtsControl) -> Self {
+        Self::new()
+            .with_smallest_translation_unit(data.smallest_translation_unit)
+            .with_rsvdp(data.rsvdp)
+            .with_enable(data.enable)
+    }
+}
+impl From<AtsControl> for u16 {
+    fn from(data: AtsControl) -> Self { AtsControlProto::from(data).into() }
+}
 
@@ -8,12 +8,13 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AdvancedErrorReporting {
     /// The Uncorrectable Error Status register indicates error detection status of individual errors
     /// on a PCI Express device Function.
@@ -69,6 +70,29 @@ impl<'a> TryRead<'a, Endian> for AdvancedErrorReporting {
         Ok((aer, *offset))
     }
 }
+impl TryWrite<Endian> for AdvancedErrorReporting {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u32>(offset, self.uncorrectable_error_status.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.uncorrectable_error_mask.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.uncorrectable_error_severity.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.correctable_error_status.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.correctable_error_mask.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.advanced_error_capabilities_and_control.into(), endian)?;
+        for dword in self.header_log.0 {
+            bytes.write_with::<u32>(offset, dword, endian)?;
+        }
+        bytes.write_with::<u32>(offset, self.root_error_command.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.root_error_status.into(), endian)?;
+        let esi = self.error_source_identification;
+        bytes.write_with::<u16>(offset, esi.err_cor_source_identification, endian)?;
+        bytes.write_with::<u16>(offset, esi.err_fatal_or_nonfatal_source_identification, endian)?;
+        for dword in self.tlp_prefix_log.0 {
+            bytes.write_with::<u32>(offset, dword, endian)?;
+        }
+        Ok(*offset)
+    }
+}
 
 
 
@@ -101,13 +125,18 @@ pub struct UncorrectableErrorProto {
 /// Uncorrectable Error Status, Uncorrectable Error Mask and Uncorrectable Error Severity has same
 /// fields
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UncorrectableError {
     /// Indicate a Link Training Error (legacy)
     pub link_training_error: bool,
+    /// Reserved bits 3:1
+    pub rsvdz: u8,
     /// Data Link Protocol Error Status
     pub data_link_protocol_error_status: bool,
     /// Surprise Down Error Status (Optional)
     pub surprise_down_error_status: bool,
+    /// Reserved bits 11:6
+    pub rsvdz_2: u8,
     /// Poisoned TLP Received Status
     pub poisoned_tlp_received_status: bool,
     /// Flow Control Protocol Error Status (Optional)
@@ -138,16 +167,17 @@ pub struct UncorrectableError {
     pub tlp_prefix_blocked_error_status: bool,
     /// Poisoned TLP Egress Blocked Status (Optional)
     pub poisoned_tlp_egress_blocked_status: bool,
+    /// Reserved bits 31:27
+    pub rsvdz_3: u8,
 }
 impl From<UncorrectableErrorProto> for UncorrectableError {
     fn from(proto: UncorrectableErrorProto) -> Self {
-        let _ = proto.rsvdz();
-        let _ = proto.rsvdz_2();
-        let _ = proto.rsvdz_3();
         Self {
             link_training_error: proto.link_training_error(),
+            rsvdz: proto.rsvdz(),
             data_link_protocol_error_status: proto.data_link_protocol_error_status(),
             surprise_down_error_status: proto.surprise_down_error_status(),
+            rsvdz_2: proto.rsvdz_2(),
             poisoned_tlp_received_status: proto.poisoned_tlp_received_status(),
             flow_control_protocol_error_status: proto.flow_control_protocol_error_status(),
             completion_timeout_status: proto.completion_timeout_status(),
@@ -163,12 +193,42 @@ impl From<UncorrectableErrorProto> for UncorrectableError {
             atomicop_egress_blocked_status: proto.atomicop_egress_blocked_status(),
             tlp_prefix_blocked_error_status: proto.tlp_prefix_blocked_error_status(),
             poisoned_tlp_egress_blocked_status: proto.poisoned_tlp_egress_blocked_status(),
+            rsvdz_3: proto.rsvdz_3(),
         }
     }
 }
 impl From<u32> for UncorrectableError {
     fn from(dword: u32) -> Self { UncorrectableErrorProto::from(dword).into() }
 }
+impl From<UncorrectableError> for UncorrectableErrorProto {
+    fn from(data: UncorrectableError) -> Self {
+        Self::new()
+            .with_link_training_error(data.link_training_error)
+            .with_rsvdz(data.rsvdz)
+            .with_data_link_protocol_error_status(data.data_link_protocol_error_status)
+            .with_surprise_down_error_status(data.surprise_down_error_status)
+            .with_rsvdz_2(data.rsvdz_2)
+            .with_poisoned_tlp_received_status(data.poisoned_tlp_received_status)
+            .with_flow_control_protocol_error_status(data.flow_control_protocol_error_status)
+            .with_completion_timeout_status(data.completion_timeout_status)
+            .with_completer_abort_status(data.completer_abort_status)
+            .with_unexpected_completion_status(data.unexpected_completion_status)
+            .with_receiver_overflow_status(data.receiver_overflow_status)
+            .with_malformed_tlp_status(data.malformed_tlp_status)
+            .with_ecrc_error_status(data.ecrc_error_status)
+            .with_unsupported_request_error_status(data.unsupported_request_error_status)
+            .with_acs_violation_status(data.acs_violation_status)
+            .with_uncorrectable_internal_error_status(data.uncorrectable_internal_error_status)
+            .with_mc_blocked_tlp_status(data.mc_blocked_tlp_status)
+            .with_atomicop_egress_blocked_status(data.atomicop_egress_blocked_status)
+            .with_tlp_prefix_blocked_error_status(data.tlp_prefix_blocked_error_status)
+            .with_poisoned_tlp_egress_blocked_status(data.poisoned_tlp_egress_blocked_status)
+            .with_rsvdz_3(data.rsvdz_3)
+    }
+}
+impl From<UncorrectableError> for u32 {
+    fn from(data: UncorrectableError) -> Self { UncorrectableErrorProto::from(data).into() }
+}
 
 #[bitfield(bits = 32)]
 #[repr(u32)]
@@ -188,15 +248,20 @@ pub struct CorrectableErrorProto {
 
 /// Correctable Error Status and Correctable Error Mask has same fields
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CorrectableError {
     /// Receiver Error Status
     pub receiver_error_status: bool,
+    /// Reserved bits 5:1
+    pub rsvdz: u8,
     /// Bad TLP Status
     pub bad_tlp_status: bool,
     /// Bad DLLP Status
     pub bad_dllp_status: bool,
     /// REPLAY_NUM Rollover Status
     pub replay_num_rollover_status: bool,
+    /// Reserved bits 11:9
+    pub rsvdz_2: u8,
     /// Replay Timer Timeout Status
     pub replay_timer_timeout_status: bool,
     /// Advisory Non-Fatal Error Status
@@ -205,27 +270,48 @@ pub struct CorrectableError {
     pub corrected_internal_error_status: bool,
     /// Header Log Overflow Status 
     pub header_log_overflow_status: bool,
+    /// Reserved bits 31:16
+    pub rsvdz_3: u16,
 }
 impl From<CorrectableErrorProto> for CorrectableError {
     fn from(proto: CorrectableErrorProto) -> Self {
-        let _ = proto.rsvdz();
-        let _ = proto.rsvdz_2();
-        let _ = proto.rsvdz_3();
         Self {
             receiver_error_status: proto.receiver_error_status(),
+            rsvdz: proto.rsvdz(),
             bad_tlp_status: proto.bad_tlp_status(),
             bad_dllp_status: proto.bad_dllp_status(),
             replay_num_rollover_status: proto.replay_num_rollover_status(),
+            rsvdz_2: proto.rsvdz_2(),
             replay_timer_timeout_status: proto.replay_timer_timeout_status(),
             advisory_non_fatal_error_status: proto.advisory_non_fatal_error_status(),
             corrected_internal_error_status: proto.corrected_internal_error_status(),
             header_log_overflow_status: proto.header_log_overflow_status(),
+            rsvdz_3: proto.rsvdz_3(),
         }
     }
 }
 impl From<u32> for CorrectableError {
     fn from(dword: u32) -> Self { CorrectableErrorProto::from(dword).into() }
 }
+impl From<CorrectableError> for CorrectableErrorProto {
+    fn from(data: CorrectableError) -> Self {
+        Self::new()
+            .with_receiver_error_status(data.receiver_error_status)
+            .with_rsvdz(data.rsvdz)
+            .with_bad_tlp_status(data.bad_tlp_status)
+            .with_bad_dllp_status(data.bad_dllp_status)
+            .with_replay_num_rollover_status(data.replay_num_rollover_status)
+            .with_rsvdz_2(data.rsvdz_2)
+            .with_replay_timer_timeout_status(data.replay_timer_timeout_status)
+            .with_advisory_non_fatal_error_status(data.advisory_non_fatal_error_status)
+            .with_corrected_internal_error_status(data.corrected_internal_error_status)
+            .with_header_log_overflow_status(data.header_log_overflow_status)
+            .with_rsvdz_3(data.rsvdz_3)
+    }
+}
+impl From<CorrectableError> for u32 {
+    fn from(data: CorrectableError) -> Self { CorrectableErrorProto::from(data).into() }
+}
 
 
 #[bitfield(bits = 32)]
@@ -245,6 +331,7 @@ pub struct AdvancedErrorCapabilitiesAndControlProto {
 
 /// Advanced Error Capabilities and Control Register
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AdvancedErrorCapabilitiesAndControl {
     /// First Error Pointer
     pub first_error_pointer: u8,
@@ -264,10 +351,11 @@ pub struct AdvancedErrorCapabilitiesAndControl {
     pub tlp_prefix_log_present: bool,
     /// Completion Timeout Prefix/Header Log Capable
     pub completion_timeout_prefix_or_header_log_capable: bool,
+    /// Reserved bits 31:13
+    pub rsvdp: u32,
 }
 impl From<AdvancedErrorCapabilitiesAndControlProto> for AdvancedErrorCapabilitiesAndControl {
     fn from(proto: AdvancedErrorCapabilitiesAndControlProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             first_error_pointer: proto.first_error_pointer(),
             ecrc_generation_capable: proto.ecrc_generation_capable(),
@@ -278,16 +366,36 @@ impl From<AdvancedErrorCapabilitiesAndControlProto> for AdvancedErrorCapabilitie
             multiple_header_recording_enable: proto.multiple_header_recording_enable(),
             tlp_prefix_log_present: proto.tlp_prefix_log_present(),
             completion_timeout_prefix_or_header_log_capable: proto.completion_timeout_prefix_or_header_log_capable(),
+            rsvdp: proto.rsvdp(),
         }
     }
 }
 impl From<u32> for AdvancedErrorCapabilitiesAndControl {
     fn from(dword: u32) -> Self { AdvancedErrorCapabilitiesAndControlProto::from(dword).into() }
 }
+impl From<AdvancedErrorCapabilitiesAndControl> for AdvancedErrorCapabilitiesAndControlProto {
+    fn from(data: AdvancedErrorCapabilitiesAndControl) -> Self {
+        Self::new()
+            .with_first_error_pointer(data.first_error_pointer)
+            .with_ecrc_generation_capable(data.ecrc_generation_capable)
+            .with_ecrc_generation_enable(data.ecrc_generation_enable)
+            .with_ecrc_check_capable(data.ecrc_check_capable)
+            .with_ecrc_check_enable(data.ecrc_check_enable)
+            .with_multiple_header_recording_capable(data.multiple_header_recording_capable)
+            .with_multiple_header_recording_enable(data.multiple_header_recording_enable)
+            .with_tlp_prefix_log_present(data.tlp_prefix_log_present)
+            .with_completion_timeout_prefix_or_header_log_capable(data.completion_timeout_prefix_or_header_log_capable)
+            .with_rsvdp(data.rsvdp)
+    }
+}
+impl From<AdvancedErrorCapabilitiesAndControl> for u32 {
+    fn from(data: AdvancedErrorCapabilitiesAndControl) -> Self { AdvancedErrorCapabilitiesAndControlProto::from(data).into() }
+}
 
 
 /// The Header Log register contains the header for the TLP corresponding to a detected error
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HeaderLog(pub [u32; 4]);
 
 
@@ -303,6 +411,7 @@ pub struct RootErrorCommandProto {
 /// Non-Fatal, and Fatal error Messages than the basic Root Complex capability to generate system
 /// errors in response to error Messages (either received or internally generated).
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RootErrorCommand {
     /// Correctable Error Reporting Enable
     pub correctable_error_reporting_enable: bool,
@@ -310,20 +419,34 @@ pub struct RootErrorCommand {
     pub non_fatal_error_reporting_enable: bool,
     /// Fatal Error Reporting Enable
     pub fatal_error_reporting_enable: bool,
+    /// Reserved bits 31:3
+    pub rsvdp: u32,
 }
 impl From<RootErrorCommandProto> for RootErrorCommand {
     fn from(proto: RootErrorCommandProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             correctable_error_reporting_enable: proto.correctable_error_reporting_enable(),
             non_fatal_error_reporting_enable: proto.non_fatal_error_reporting_enable(),
             fatal_error_reporting_enable: proto.fatal_error_reporting_enable(),
+            rsvdp: proto.rsvdp(),
         }
     }
 }
 impl From<u32> for RootErrorCommand {
     fn from(dword: u32) -> Self { RootErrorCommandProto::from(dword).into() }
 }
+impl From<RootErrorCommand> for RootErrorCommandProto {
+    fn from(data: RootErrorCommand) -> Self {
+        Self::new()
+            .with_correctable_error_reporting_enable(data.correctable_error_reporting_enable)
+            .with_non_fatal_error_reporting_enable(data.non_fatal_error_reporting_enable)
+            .with_fatal_error_reporting_enable(data.fatal_error_reporting_enable)
+            .with_rsvdp(data.rsvdp)
+    }
+}
+impl From<RootErrorCommand> for u32 {
+    fn from(data: RootErrorCommand) -> Self { RootErrorCommandProto::from(data).into() }
+}
 
 
 #[bitfield(bits = 32)]
@@ -343,6 +466,7 @@ pub struct RootErrorStatusProto {
 /// ERR_FATAL) received by the Root Port, and of errors detected by the Root Port itself (which are
 /// treated conceptually as if the Root Port had sent an error Message to itself).
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RootErrorStatus {
     /// ERR_COR Received
     pub err_cor_received: bool,
@@ -358,12 +482,13 @@ pub struct RootErrorStatus {
     pub non_fatal_error_messages_received: bool,
     /// Fatal Error Messages Received
     pub fatal_error_messages_received: bool,
+    /// Reserved bits 26:7
+    pub rsvdz: u32,
     /// Advanced Error Interrupt Message Number
     pub advanced_error_interrupt_message_number: u8,
 }
 impl From<RootErrorStatusProto> for RootErrorStatus {
     fn from(proto: RootErrorStatusProto) -> Self {
-        let _ = proto.rsvdz();
         Self {
             err_cor_received: proto.err_cor_received(),
             multiple_err_cor_received: proto.multiple_err_cor_received(),
@@ -372,6 +497,7 @@ impl From<RootErrorStatusProto> for RootErrorStatus {
             first_uncorrectable_fatal: proto.first_uncorrectable_fatal(),
             non_fatal_error_messages_received: proto.non_fatal_error_messages_received(),
             fatal_error_messages_received: proto.fatal_error_messages_received(),
+            rsvdz: proto.rsvdz(),
             advanced_error_interrupt_message_number: proto.advanced_error_interrupt_message_number(),
         }
     }
@@ -379,12 +505,30 @@ impl From<RootErrorStatusProto> for RootErrorStatus {
 impl From<u32> for RootErrorStatus {
     fn from(dword: u32) -> Self { RootErrorStatusProto::from(dword).into() }
 }
+impl From<RootErrorStatus> for RootErrorStatusProto {
+    fn from(data: RootErrorStatus) -> Self {
+        Self::new()
+            .with_err_cor_received(data.err_cor_received)
+            .with_multiple_err_cor_received(data.multiple_err_cor_received)
+            .with_err_fatal_or_nonfatal_received(data.err_fatal_or_nonfatal_received)
+            .with_multiple_err_fatal_or_nonfatal_received(data.multiple_err_fatal_or_nonfatal_received)
+            .with_first_uncorrectable_fatal(data.first_uncorrectable_fatal)
+            .with_non_fatal_error_messages_received(data.non_fatal_error_messages_received)
+            .with_fatal_error_messages_received(data.fatal_error_messages_received)
+            .with_rsvdz(data.rsvdz)
+            .with_advanced_error_interrupt_message_number(data.advanced_error_interrupt_message_number)
+    }
+}
+impl From<RootErrorStatus> for u32 {
+    fn from(data: RootErrorStatus) -> Self { RootErrorStatusProto::from(data).into() }
+}
 
 
 /// The Error Source Identification register identifies the source (Requester ID) of first
 /// correctable and uncorrectable (Non-fatal/Fatal) errors reported in the Root Error Status
 /// register. 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ErrorSourceIdentification {
     /// ERR_COR Source Identification
     pub err_cor_source_identification: u16,
@@ -396,6 +540,7 @@ pub struct ErrorSourceIdentification {
 /// The TLP Prefix Log register captures the End-End TLP Prefix(s) for the TLP corresponding to the
 /// detected error
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TlpPrefixLog(pub [u32; 4]);
 
 
@@ -427,6 +572,9 @@ mod tests {
             atomicop_egress_blocked_status: false,
             tlp_prefix_blocked_error_status: false,
             poisoned_tlp_egress_blocked_status: false,
+            rsvdz: 0,
+            rsvdz_2: 0,
+            rsvdz_3: 0,
         };
         assert_eq!(sample, u32::from_le_bytes(data).into());
     }
@@ -444,6 +592,9 @@ mod tests {
             advisory_non_fatal_error_status: true,
             corrected_internal_error_status: false,
             header_log_overflow_status: false,
+            rsvdz: 0,
+            rsvdz_2: 0,
+            rsvdz_3: 0,
         };
         assert_eq!(sample, u32::from_le_bytes(data).into());
     }
@@ -463,6 +614,7 @@ mod tests {
             multiple_header_recording_enable: false,
             tlp_prefix_log_present: false,
             completion_timeout_prefix_or_header_log_capable: false,
+            rsvdp: 0,
         };
         assert_eq!(sample, u32::from_le_bytes(data).into());
     }
@@ -475,6 +627,7 @@ mod tests {
             correctable_error_reporting_enable: true,
             non_fatal_error_reporting_enable: true,
             fatal_error_reporting_enable: true,
+            rsvdp: 0,
         };
         assert_eq!(sample, u32::from_le_bytes(data).into());
     }
@@ -493,6 +646,7 @@ mod tests {
             non_fatal_error_messages_received: false,
             fatal_error_messages_received: false,
             advanced_error_interrupt_message_number: 0,
+            rsvdz: 0,
         };
         assert_eq!(sample, u32::from_le_bytes(data).into());
     }
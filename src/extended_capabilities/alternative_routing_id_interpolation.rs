@@ -12,11 +12,12 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AlternativeRoutingIdInterpretation {
     pub ari_capability: AriCapability,
     pub ari_control: AriControl,
@@ -31,6 +32,14 @@ impl<'a> TryRead<'a, Endian> for AlternativeRoutingIdInterpretation {
         Ok((ari, *offset))
     }
 }
+impl TryWrite<Endian> for AlternativeRoutingIdInterpretation {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u16>(offset, self.ari_capability.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.ari_control.into(), endian)?;
+        Ok(*offset)
+    }
+}
 
 
 #[bitfield(bits = 16)]
@@ -44,20 +53,23 @@ pub struct AriCapabilityProto {
 
 /// ARI Capability
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AriCapability {
     /// MFVC Function Groups Capability (M)
     pub mfvc_function_groups_capability: bool,
     /// ACS Function Groups Capability (A)
     pub acs_function_groups_capability: bool,
+    /// Reserved bits 7:2
+    pub rsvdp: u8,
     /// Next Function Number
     pub next_function_number: u8,
 }
 impl From<AriCapabilityProto> for AriCapability {
     fn from(proto: AriCapabilityProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             mfvc_function_groups_capability: proto.mfvc_function_groups_capability(),
             acs_function_groups_capability: proto.acs_function_groups_capability(),
+            rsvdp: proto.rsvdp(),
             next_function_number: proto.next_function_number(),
         }
     }
@@ -65,6 +77,18 @@ impl From<AriCapabilityProto> for AriCapability {
 impl From<u16> for AriCapability {
     fn from(word: u16) -> Self { AriCapabilityProto::from(word).into() }
 }
+impl From<AriCapability> for AriCapabilityProto {
+    fn from(data: AriCapability) -> Self {
+        Self::new()
+            .with_mfvc_function_groups_capability(data.mfvc_function_groups_capability)
+            .with_acs_function_groups_capability(data.acs_function_groups_capability)
+            .with_rsvdp(data.rsvdp)
+            .with_next_function_number(data.next_function_number)
+    }
+}
+impl From<AriCapability> for u16 {
+    fn from(data: AriCapability) -> Self { AriCapabilityProto::from(data).into() }
+}
 
 
 #[bitfield(bits = 16)]
@@ -78,6 +102,7 @@ pub struct AriControlProto {
 
 /// ARI Control
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AriControl {
     /// MFVC Function Groups Enable (M)
     pub mfvc_function_groups_enable: bool,
@@ -85,17 +110,31 @@ pub struct AriControl {
     pub acs_function_groups_enable: bool,
     /// Function Group
     pub function_group: u8,
+    /// Reserved bits 15:8
+    pub rsvdp: u8,
 }
 impl From<AriControlProto> for AriControl {
     fn from(proto: AriControlProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             mfvc_function_groups_enable: proto.mfvc_function_groups_enable(),
             acs_function_groups_enable: proto.acs_function_groups_enable(),
             function_group: proto.function_group(),
+            rsvdp: proto.rsvdp(),
         }
     }
 }
 impl From<u16> for AriControl {
     fn from(word: u16) -> Self { AriControlProto::from(word).into() }
 }
+impl From<AriControl> for AriControlProto {
+    fn from(data: AriControl) -> Self {
+        Self::new()
+            .with_mfvc_function_groups_enable(data.mfvc_function_groups_enable)
+            .with_acs_function_groups_enable(data.acs_function_groups_enable)
+            .with_function_group(data.function_group)
+            .with_rsvdp(data.rsvdp)
+    }
+}
+impl From<AriControl> for u16 {
+    fn from(data: AriControl) -> Self { AriControlProto::from(data).into() }
+}
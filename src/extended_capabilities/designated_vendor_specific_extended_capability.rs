@@ -0,0 +1,275 @@
+//! Designated Vendor-Specific Extended Capability
+//!
+//! The Designated Vendor-Specific Extended Capability (DVSEC) is an optional Extended Capability
+//! that is permitted to be implemented by any PCI Express Function or RCRB. Unlike
+//! [VSEC](super::vendor_specific_extended_capability) the format of DVSEC registers is defined
+//! by the vendor designated by DVSEC Vendor ID regardless of the Function's Vendor ID.
+//!
+//! Registers are decoded by a [DvsecRegistry] of [DvsecDecoder]s keyed by DVSEC Vendor ID and
+//! DVSEC ID. DVSECs defined by [Compute Express Link](compute_express_link) are decoded by
+//! [DesignatedVendorSpecificExtendedCapability::dvsec_capability].
+
+use modular_bitfield::prelude::*;
+use byte::{
+    ctx::*,
+    self,
+    TryRead,
+    TryWrite,
+    BytesExt,
+};
+
+use crate::RawBytes;
+
+use super::ECH_BYTES;
+
+pub mod compute_express_link;
+pub use compute_express_link::ComputeExpressLink;
+
+
+/// DVSEC Header 1 and DVSEC Header 2 length in bytes
+const DVSEC_HEADER_BYTES: usize = 6;
+
+/// Designated Vendor-Specific Extended Capability
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DesignatedVendorSpecificExtendedCapability<'a> {
+    pub header: DvsecHeader,
+    /// DVSEC vendor-specific registers following DVSEC Header 2
+    pub registers: RawBytes<'a>,
+}
+impl<'a> DesignatedVendorSpecificExtendedCapability<'a> {
+    /// Decode registers with the decoder registered for this DVSEC, `None` if there is no one
+    pub fn decode_with<'b, T>(
+        &'b self,
+        registry: &DvsecRegistry<'_, 'b, T>,
+    ) -> Option<byte::Result<T>> {
+        registry.decode(self)
+    }
+    /// DVSEC decoded with known decoders, [DvsecCapability::Unspecified] if there is no one
+    pub fn dvsec_capability(&self) -> byte::Result<DvsecCapability<'_>> {
+        let decoders = compute_express_link::decoders();
+        match self.decode_with(&DvsecRegistry::new(&decoders)) {
+            Some(result) => result.map(DvsecCapability::ComputeExpressLink),
+            None => Ok(DvsecCapability::Unspecified(&self.registers)),
+        }
+    }
+}
+impl<'a> TryRead<'a, Endian> for DesignatedVendorSpecificExtendedCapability<'a> {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let header_1: DvsecHeader1Proto = bytes.read_with::<u32>(offset, endian)?.into();
+        let dvsec_id = bytes.read_with::<u16>(offset, endian)?;
+        let len = usize::from(header_1.dvsec_length())
+            .checked_sub(ECH_BYTES + DVSEC_HEADER_BYTES)
+            .ok_or(byte::Error::BadInput { err: "DVSEC Length is too small" })?;
+        let dvsec = DesignatedVendorSpecificExtendedCapability {
+            header: DvsecHeader {
+                dvsec_vendor_id: header_1.dvsec_vendor_id(),
+                dvsec_revision: header_1.dvsec_revision(),
+                dvsec_length: header_1.dvsec_length(),
+                dvsec_id,
+            },
+            registers: RawBytes::new(bytes.read_with::<&[u8]>(offset, Bytes::Len(len))?),
+        };
+        Ok((dvsec, *offset))
+    }
+}
+impl<'a> TryWrite<Endian> for DesignatedVendorSpecificExtendedCapability<'a> {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let len = self.registers.len() + ECH_BYTES + DVSEC_HEADER_BYTES;
+        if usize::from(self.header.dvsec_length) != len {
+            return Err(byte::Error::BadInput { err: "DVSEC Length does not match registers length" });
+        }
+        if len > 0xfff {
+            return Err(byte::Error::BadInput { err: "DVSEC Length does not fit in 12 bits" });
+        }
+        if self.header.dvsec_revision > 0xf {
+            return Err(byte::Error::BadInput { err: "DVSEC Revision does not fit in 4 bits" });
+        }
+        let offset = &mut 0;
+        let header_1 = DvsecHeader1Proto::new()
+            .with_dvsec_vendor_id(self.header.dvsec_vendor_id)
+            .with_dvsec_revision(self.header.dvsec_revision)
+            .with_dvsec_length(self.header.dvsec_length);
+        bytes.write_with::<u32>(offset, header_1.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.header.dvsec_id, endian)?;
+        bytes.write::<&[u8]>(offset, &self.registers)?;
+        Ok(*offset)
+    }
+}
+
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct DvsecHeader1Proto {
+    dvsec_vendor_id: u16,
+    dvsec_revision: B4,
+    dvsec_length: B12,
+}
+
+/// DVSEC Header 1 and DVSEC Header 2
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DvsecHeader {
+    /// Vendor ID associated with the vendor that defined the contents of this capability
+    pub dvsec_vendor_id: u16,
+    /// Vendor-defined version number that indicates the version of the DVSEC structure
+    pub dvsec_revision: u8,
+    /// Number of bytes in the entire DVSEC structure, including the PCI Express Extended
+    /// Capability header, the DVSEC Header 1, DVSEC Header 2, and DVSEC vendor-specific registers
+    pub dvsec_length: u16,
+    /// Vendor-defined ID that indicates the nature and format of the DVSEC structure
+    pub dvsec_id: u16,
+}
+
+/// DVSEC registers decoded by known DVSEC Vendor ID and DVSEC ID
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum DvsecCapability<'a> {
+    Unspecified(
+        #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serde_hex::serialize"))]
+        &'a [u8],
+    ),
+    ComputeExpressLink(ComputeExpressLink<'a>),
+}
+
+
+/// Decoder of DVSEC registers with particular DVSEC Vendor ID and DVSEC ID
+///
+/// Decoded value may borrow registers of the DVSEC for `'a`
+#[derive(Debug)]
+pub struct DvsecDecoder<'a, T> {
+    pub dvsec_vendor_id: u16,
+    pub dvsec_id: u16,
+    pub decode: fn(&'a DesignatedVendorSpecificExtendedCapability<'a>) -> byte::Result<T>,
+}
+impl<'a, T> Clone for DvsecDecoder<'a, T> {
+    fn clone(&self) -> Self { *self }
+}
+impl<'a, T> Copy for DvsecDecoder<'a, T> {}
+
+/// A set of [DvsecDecoder]s dispatched by DVSEC Vendor ID and DVSEC ID
+///
+/// Decoders are looked up in order, so a decoder may be overridden by one placed before it.
+/// ```
+/// # use pcics::extended_capabilities::designated_vendor_specific_extended_capability::*;
+/// # use byte::{BytesExt, ctx::LE};
+/// #[derive(Debug, PartialEq, Eq)]
+/// struct Acme { flags: u16 }
+///
+/// let registry = DvsecRegistry::new(&[
+///     DvsecDecoder {
+///         dvsec_vendor_id: 0x1234,
+///         dvsec_id: 0x0001,
+///         decode: |dvsec| Ok(Acme { flags: dvsec.registers.read_with(&mut 0, LE)? }),
+///     },
+/// ]);
+/// let data = [0x34, 0x12, 0xc1, 0x00, 0x01, 0x00, 0xaa, 0x55];
+/// let dvsec: DesignatedVendorSpecificExtendedCapability = data.read_with(&mut 0, LE).unwrap();
+/// assert_eq!(Some(Ok(Acme { flags: 0x55aa })), registry.decode(&dvsec));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct DvsecRegistry<'r, 'a, T> {
+    decoders: &'r [DvsecDecoder<'a, T>],
+}
+impl<'r, 'a, T> DvsecRegistry<'r, 'a, T> {
+    pub const fn new(decoders: &'r [DvsecDecoder<'a, T>]) -> Self {
+        Self { decoders }
+    }
+    /// Decoder registered for DVSEC Vendor ID and DVSEC ID
+    pub fn get(&self, dvsec_vendor_id: u16, dvsec_id: u16) -> Option<&'r DvsecDecoder<'a, T>> {
+        self.decoders.iter()
+            .find(|d| d.dvsec_vendor_id == dvsec_vendor_id && d.dvsec_id == dvsec_id)
+    }
+    /// Decode `dvsec` registers, `None` if there is no decoder for this DVSEC
+    pub fn decode(
+        &self,
+        dvsec: &'a DesignatedVendorSpecificExtendedCapability<'a>,
+    ) -> Option<byte::Result<T>> {
+        let decoder = self.get(dvsec.header.dvsec_vendor_id, dvsec.header.dvsec_id)?;
+        Some((decoder.decode)(dvsec))
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use super::*;
+
+    const DATA: [u8; 12] = [
+        0x98, 0x1e, 0x01, 0x01, 0x08, 0x00, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn parse_full_struct() {
+        let result: DesignatedVendorSpecificExtendedCapability = DATA.read_with(&mut 0, LE).unwrap();
+        let sample = DesignatedVendorSpecificExtendedCapability {
+            header: DvsecHeader {
+                dvsec_vendor_id: 0x1e98,
+                dvsec_revision: 1,
+                dvsec_length: 0x10,
+                dvsec_id: 0x0008,
+            },
+            registers: RawBytes::new(&DATA[6..]),
+        };
+        assert_eq!(sample, result);
+
+        let mut buf = [0u8; 12];
+        buf.write_with(&mut 0, result, LE).unwrap();
+        assert_eq!(DATA, buf);
+    }
+
+    #[test]
+    fn length_too_small() {
+        let mut data = DATA;
+        data[3] = 0x00;
+        let result = data.read_with::<DesignatedVendorSpecificExtendedCapability>(&mut 0, LE);
+        assert_eq!(Err(byte::Error::BadInput { err: "DVSEC Length is too small" }), result);
+    }
+
+    #[test]
+    fn write_out_of_range() {
+        let dvsec: DesignatedVendorSpecificExtendedCapability = DATA.read_with(&mut 0, LE).unwrap();
+        let mut buf = [0u8; 0x1000];
+        let mut header = DvsecHeader { dvsec_revision: 0x10, ..dvsec.header.clone() };
+        let result = buf.write_with(&mut 0,
+            DesignatedVendorSpecificExtendedCapability { header: header.clone(), ..dvsec }, LE);
+        assert_eq!(Err(byte::Error::BadInput { err: "DVSEC Revision does not fit in 4 bits" }), result);
+        let registers = [0u8; 0xff6];
+        header.dvsec_length = 0x1000;
+        let result = buf.write_with(&mut 0,
+            DesignatedVendorSpecificExtendedCapability { header, registers: RawBytes::new(&registers) }, LE);
+        assert_eq!(Err(byte::Error::BadInput { err: "DVSEC Length does not fit in 12 bits" }), result);
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Decoded<'a> {
+        Locator(u32),
+        Raw(&'a [u8]),
+    }
+
+    #[test]
+    fn registry() {
+        let dvsec: DesignatedVendorSpecificExtendedCapability = DATA.read_with(&mut 0, LE).unwrap();
+        let raw = DvsecDecoder {
+            dvsec_vendor_id: 0x1e98,
+            dvsec_id: 0x0008,
+            decode: |dvsec| Ok(Decoded::Raw(&dvsec.registers)),
+        };
+        let locator = DvsecDecoder {
+            decode: |dvsec| dvsec.registers.read_with(&mut 2, LE).map(Decoded::Locator),
+            ..raw
+        };
+        let other = DvsecDecoder { dvsec_id: 0x0007, ..raw };
+
+        assert_eq!(None, DvsecRegistry::new(&[other]).decode(&dvsec));
+        assert_eq!(Some(Ok(Decoded::Raw(&DATA[6..]))), dvsec.decode_with(&DvsecRegistry::new(&[other, raw])));
+        assert_eq!(Some(Ok(Decoded::Locator(2))), DvsecRegistry::new(&[locator, raw]).decode(&dvsec));
+
+        let truncated = DesignatedVendorSpecificExtendedCapability { registers: RawBytes::new(&DATA[6..8]), ..dvsec.clone() };
+        let result = DvsecRegistry::new(&[locator]).decode(&truncated);
+        assert_eq!(Some(Err(byte::Error::Incomplete)), result);
+    }
+}
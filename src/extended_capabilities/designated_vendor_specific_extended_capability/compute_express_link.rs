@@ -0,0 +1,811 @@
+//! Compute Express Link
+//!
+//! CXL devices and ports are enumerated as PCI Express functions and describe CXL specific
+//! features with DVSECs of DVSEC Vendor ID [CXL_VENDOR_ID]. Register offsets of the CXL
+//! specification are relative to the beginning of the DVSEC, here all structures are read from
+//! [registers](super::DesignatedVendorSpecificExtendedCapability::registers) starting at offset
+//! 0Ah.
+
+use modular_bitfield::prelude::*;
+use byte::{
+    ctx::*,
+    self,
+    TryRead,
+    BytesExt,
+};
+
+use super::{DesignatedVendorSpecificExtendedCapability, DvsecDecoder};
+
+
+/// DVSEC Vendor ID assigned to CXL Consortium
+pub const CXL_VENDOR_ID: u16 = 0x1e98;
+
+/// Decoders of all known CXL DVSECs
+pub fn decoders<'a>() -> [DvsecDecoder<'a, ComputeExpressLink<'a>>; 6] {
+    fn decoder<'a>(
+        dvsec_id: u16,
+        decode: fn(&'a DesignatedVendorSpecificExtendedCapability<'a>) -> byte::Result<ComputeExpressLink<'a>>,
+    ) -> DvsecDecoder<'a, ComputeExpressLink<'a>> {
+        DvsecDecoder { dvsec_vendor_id: CXL_VENDOR_ID, dvsec_id, decode }
+    }
+    [
+        decoder(0x0000, |dvsec| dvsec.registers.read_with(&mut 0, LE).map(ComputeExpressLink::Device)),
+        decoder(0x0003, |dvsec| dvsec.registers.read_with(&mut 0, LE)
+            .map(ComputeExpressLink::ExtensionsForPorts)),
+        decoder(0x0004, |dvsec| dvsec.registers.read_with(&mut 0, LE)
+            .map(ComputeExpressLink::GpfForPorts)),
+        decoder(0x0005, |dvsec| dvsec.registers.read_with(&mut 0, LE)
+            .map(ComputeExpressLink::GpfForDevices)),
+        decoder(0x0007, |dvsec| dvsec.registers.read_with(&mut 0, LE)
+            .map(ComputeExpressLink::FlexBusPort)),
+        decoder(0x0008, |dvsec| dvsec.registers.read_with(&mut 0, LE)
+            .map(ComputeExpressLink::RegisterLocator)),
+    ]
+}
+
+/// Known CXL DVSECs
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ComputeExpressLink<'a> {
+    /// PCIe DVSEC for CXL Devices (0000h)
+    Device(Device),
+    /// CXL Extensions DVSEC for Ports (0003h)
+    ExtensionsForPorts(ExtensionsForPorts),
+    /// GPF DVSEC for CXL Ports (0004h)
+    GpfForPorts(GpfForPorts),
+    /// GPF DVSEC for CXL Devices (0005h)
+    GpfForDevices(GpfForDevices),
+    /// PCIe DVSEC for Flex Bus Port (0007h)
+    FlexBusPort(FlexBusPort),
+    /// Register Locator DVSEC (0008h)
+    RegisterLocator(RegisterLocator<'a>),
+}
+
+
+/// PCIe DVSEC for CXL Devices
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Device {
+    pub capability: DeviceCapability,
+    pub control: DeviceControl,
+    /// DVSEC CXL Status bit 14: Viral has been detected
+    pub viral_status: bool,
+    /// DVSEC CXL Control2
+    pub control_2: u16,
+    /// DVSEC CXL Status2
+    pub status_2: u16,
+    /// DVSEC CXL Lock bit 0: registers marked RWL are locked
+    pub config_lock: bool,
+    /// DVSEC CXL Capability2
+    pub capability_2: u16,
+    /// DVSEC CXL Range 1 and Range 2 registers
+    pub ranges: [Range; 2],
+}
+impl Device {
+    /// Device type derived from Cache_Capable and Mem_Capable bits
+    pub fn device_type(&self) -> Option<DeviceType> {
+        match (self.capability.cache_capable, self.capability.mem_capable) {
+            (true, false) => Some(DeviceType::Type1),
+            (true, true) => Some(DeviceType::Type2),
+            (false, true) => Some(DeviceType::Type3),
+            (false, false) => None,
+        }
+    }
+    /// Ranges of Host-managed Device Memory (HDM) implemented by the device
+    pub fn hdm_ranges(&self) -> impl Iterator<Item = &Range> {
+        self.ranges.iter().take(self.capability.hdm_count.min(2).into())
+    }
+}
+impl<'a> TryRead<'a, Endian> for Device {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let capability = bytes.read_with::<u16>(offset, endian)?.into();
+        let control = bytes.read_with::<u16>(offset, endian)?.into();
+        let status = bytes.read_with::<u16>(offset, endian)?;
+        let control_2 = bytes.read_with::<u16>(offset, endian)?;
+        let status_2 = bytes.read_with::<u16>(offset, endian)?;
+        let lock = bytes.read_with::<u16>(offset, endian)?;
+        let capability_2 = bytes.read_with::<u16>(offset, endian)?;
+        let range_1 = bytes.read_with(offset, endian)?;
+        let range_2 = bytes.read_with(offset, endian)?;
+        let device = Device {
+            capability,
+            control,
+            viral_status: status & (1 << 14) != 0,
+            control_2,
+            status_2,
+            config_lock: lock & 1 != 0,
+            capability_2,
+            ranges: [range_1, range_2],
+        };
+        Ok((device, *offset))
+    }
+}
+
+/// CXL device types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DeviceType {
+    /// Caching device without Host-managed Device Memory, e.g. accelerator or SmartNIC
+    Type1,
+    /// Caching device with Host-managed Device Memory, e.g. accelerator with attached memory
+    Type2,
+    /// Memory expander without coherent cache
+    Type3,
+}
+
+#[bitfield(bits = 16)]
+#[repr(u16)]
+pub struct DeviceCapabilityProto {
+    cache_capable: bool,
+    io_capable: bool,
+    mem_capable: bool,
+    mem_hwinit_mode: bool,
+    hdm_count: B2,
+    cache_writeback_and_invalidate_capable: bool,
+    cxl_reset_capable: bool,
+    cxl_reset_timeout: B3,
+    cxl_reset_mem_clr_capable: bool,
+    rsvdp: B1,
+    multiple_logical_device: bool,
+    viral_capable: bool,
+    pm_init_completion_reporting_capable: bool,
+}
+
+/// DVSEC CXL Capability
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceCapability {
+    /// Cache_Capable, device supports CXL.cache protocol
+    pub cache_capable: bool,
+    /// IO_Capable, device supports CXL.io protocol, must be set
+    pub io_capable: bool,
+    /// Mem_Capable, device supports CXL.mem protocol
+    pub mem_capable: bool,
+    /// Mem_HwInit_Mode, device initializes memory without software assistance
+    pub mem_hwinit_mode: bool,
+    /// HDM_Count, number of HDM ranges: 00b – zero, 01b – one, 10b – two
+    pub hdm_count: u8,
+    /// Cache Writeback and Invalidate Capable
+    pub cache_writeback_and_invalidate_capable: bool,
+    /// CXL Reset Capable
+    pub cxl_reset_capable: bool,
+    /// CXL Reset Timeout, encoded time the device takes to complete CXL Reset
+    pub cxl_reset_timeout: u8,
+    /// CXL Reset Mem Clr Capable
+    pub cxl_reset_mem_clr_capable: bool,
+    /// Multiple Logical Device
+    pub multiple_logical_device: bool,
+    /// Viral_Capable
+    pub viral_capable: bool,
+    /// PM Init Completion Reporting Capable
+    pub pm_init_completion_reporting_capable: bool,
+}
+impl From<DeviceCapabilityProto> for DeviceCapability {
+    fn from(proto: DeviceCapabilityProto) -> Self {
+        let _ = proto.rsvdp();
+        Self {
+            cache_capable: proto.cache_capable(),
+            io_capable: proto.io_capable(),
+            mem_capable: proto.mem_capable(),
+            mem_hwinit_mode: proto.mem_hwinit_mode(),
+            hdm_count: proto.hdm_count(),
+            cache_writeback_and_invalidate_capable: proto.cache_writeback_and_invalidate_capable(),
+            cxl_reset_capable: proto.cxl_reset_capable(),
+            cxl_reset_timeout: proto.cxl_reset_timeout(),
+            cxl_reset_mem_clr_capable: proto.cxl_reset_mem_clr_capable(),
+            multiple_logical_device: proto.multiple_logical_device(),
+            viral_capable: proto.viral_capable(),
+            pm_init_completion_reporting_capable: proto.pm_init_completion_reporting_capable(),
+        }
+    }
+}
+impl From<u16> for DeviceCapability {
+    fn from(word: u16) -> Self { DeviceCapabilityProto::from(word).into() }
+}
+
+#[bitfield(bits = 16)]
+#[repr(u16)]
+pub struct DeviceControlProto {
+    cache_enable: bool,
+    io_enable: bool,
+    mem_enable: bool,
+    cache_sf_coverage: B5,
+    cache_sf_granularity: B3,
+    cache_clean_eviction: bool,
+    rsvdp: B2,
+    viral_enable: bool,
+    rsvdp_1: B1,
+}
+
+/// DVSEC CXL Control
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceControl {
+    /// Cache_Enable
+    pub cache_enable: bool,
+    /// IO_Enable
+    pub io_enable: bool,
+    /// Mem_Enable
+    pub mem_enable: bool,
+    /// Cache_SF_Coverage, snoop filter coverage is 2 ^ (Cache_SF_Coverage + 15) bytes
+    pub cache_sf_coverage: u8,
+    /// Cache_SF_Granularity, snoop filter tracking granularity is 2 ^ (Cache_SF_Granularity + 6)
+    /// bytes
+    pub cache_sf_granularity: u8,
+    /// Cache_Clean_Eviction, host does not require clean evictions
+    pub cache_clean_eviction: bool,
+    /// Viral_Enable
+    pub viral_enable: bool,
+}
+impl From<DeviceControlProto> for DeviceControl {
+    fn from(proto: DeviceControlProto) -> Self {
+        let _ = (proto.rsvdp(), proto.rsvdp_1());
+        Self {
+            cache_enable: proto.cache_enable(),
+            io_enable: proto.io_enable(),
+            mem_enable: proto.mem_enable(),
+            cache_sf_coverage: proto.cache_sf_coverage(),
+            cache_sf_granularity: proto.cache_sf_granularity(),
+            cache_clean_eviction: proto.cache_clean_eviction(),
+            viral_enable: proto.viral_enable(),
+        }
+    }
+}
+impl From<u16> for DeviceControl {
+    fn from(word: u16) -> Self { DeviceControlProto::from(word).into() }
+}
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct RangeSizeLowProto {
+    memory_info_valid: bool,
+    memory_active: bool,
+    media_type: B3,
+    memory_class: B3,
+    desired_interleave: B5,
+    memory_active_timeout: B3,
+    rsvdp: B12,
+    memory_size_low: B4,
+}
+
+/// DVSEC CXL Range Size and Base registers
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Range {
+    /// Memory_Info_Valid, Memory Size and other fields are valid
+    pub memory_info_valid: bool,
+    /// Memory_Active, memory is initialized and ready for software use
+    pub memory_active: bool,
+    /// Media_Type, 000b – volatile memory, 001b – non-volatile memory, 010b – CDAT
+    pub media_type: u8,
+    /// Memory_Class, 000b – memory, 001b – storage class memory, 010b – CDAT
+    pub memory_class: u8,
+    /// Desired_Interleave
+    pub desired_interleave: u8,
+    /// Memory_Active_Timeout, 000b – 1 s, 001b – 4 s, 010b – 16 s, 011b – 64 s, 100b – 256 s
+    pub memory_active_timeout: u8,
+    /// Memory Size in bytes, multiple of 256 MB
+    pub size: u64,
+    /// Memory Base, 256 MB aligned Host Physical Address of the range
+    pub base: u64,
+}
+impl<'a> TryRead<'a, Endian> for Range {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let size_high = bytes.read_with::<u32>(offset, endian)?;
+        let size_low: RangeSizeLowProto = bytes.read_with::<u32>(offset, endian)?.into();
+        let base_high = bytes.read_with::<u32>(offset, endian)?;
+        let base_low = bytes.read_with::<u32>(offset, endian)?;
+        let _ = size_low.rsvdp();
+        let range = Range {
+            memory_info_valid: size_low.memory_info_valid(),
+            memory_active: size_low.memory_active(),
+            media_type: size_low.media_type(),
+            memory_class: size_low.memory_class(),
+            desired_interleave: size_low.desired_interleave(),
+            memory_active_timeout: size_low.memory_active_timeout(),
+            size: (u64::from(size_high) << 32) | (u64::from(size_low.memory_size_low()) << 28),
+            base: (u64::from(base_high) << 32) | u64::from(base_low & 0xf000_0000),
+        };
+        Ok((range, *offset))
+    }
+}
+
+
+/// CXL Extensions DVSEC for Ports
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExtensionsForPorts {
+    /// CXL Port Extension Status bit 0: Port Power Management Initialization Complete
+    pub port_pm_initialization_complete: bool,
+    /// CXL Port Extension Status bit 14: Viral_Status
+    pub viral_status: bool,
+    pub port_control_extensions: PortControlExtensions,
+    /// Alternate Bus Base
+    pub alt_bus_base: u8,
+    /// Alternate Bus Limit
+    pub alt_bus_limit: u8,
+    /// Alternate Memory Base, bits 31:20 of the address in bits 15:4
+    pub alt_memory_base: u16,
+    /// Alternate Memory Limit, bits 31:20 of the address in bits 15:4
+    pub alt_memory_limit: u16,
+    /// Alternate Prefetchable Memory Base with Alternate Prefetchable Base High
+    pub alt_prefetchable_memory_base: u64,
+    /// Alternate Prefetchable Memory Limit with Alternate Prefetchable Limit High
+    pub alt_prefetchable_memory_limit: u64,
+    /// CXL RCRB Base bit 0: CXL RCRB Enable
+    pub cxl_rcrb_enable: bool,
+    /// CXL RCRB Base Address, 8 KB aligned
+    pub cxl_rcrb_base_address: u64,
+}
+impl<'a> TryRead<'a, Endian> for ExtensionsForPorts {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let status = bytes.read_with::<u16>(offset, endian)?;
+        let port_control_extensions = bytes.read_with::<u16>(offset, endian)?.into();
+        let alt_bus_base = bytes.read_with::<u8>(offset, endian)?;
+        let alt_bus_limit = bytes.read_with::<u8>(offset, endian)?;
+        let alt_memory_base = bytes.read_with::<u16>(offset, endian)?;
+        let alt_memory_limit = bytes.read_with::<u16>(offset, endian)?;
+        let alt_prefetchable_memory_base = bytes.read_with::<u16>(offset, endian)?;
+        let alt_prefetchable_memory_limit = bytes.read_with::<u16>(offset, endian)?;
+        let alt_prefetchable_base_high = bytes.read_with::<u32>(offset, endian)?;
+        let alt_prefetchable_limit_high = bytes.read_with::<u32>(offset, endian)?;
+        let cxl_rcrb_base = bytes.read_with::<u32>(offset, endian)?;
+        let cxl_rcrb_base_high = bytes.read_with::<u32>(offset, endian)?;
+        let prefetchable = |low: u16, high: u32| {
+            (u64::from(high) << 32) | (u64::from(low & 0xfff0) << 16)
+        };
+        let ext = ExtensionsForPorts {
+            port_pm_initialization_complete: status & 1 != 0,
+            viral_status: status & (1 << 14) != 0,
+            port_control_extensions,
+            alt_bus_base,
+            alt_bus_limit,
+            alt_memory_base,
+            alt_memory_limit,
+            alt_prefetchable_memory_base:
+                prefetchable(alt_prefetchable_memory_base, alt_prefetchable_base_high),
+            alt_prefetchable_memory_limit:
+                prefetchable(alt_prefetchable_memory_limit, alt_prefetchable_limit_high),
+            cxl_rcrb_enable: cxl_rcrb_base & 1 != 0,
+            cxl_rcrb_base_address: (u64::from(cxl_rcrb_base_high) << 32)
+                | u64::from(cxl_rcrb_base & !0x1fff),
+        };
+        Ok((ext, *offset))
+    }
+}
+
+#[bitfield(bits = 16)]
+#[repr(u16)]
+pub struct PortControlExtensionsProto {
+    unmask_sbr: bool,
+    unmask_link_disable: bool,
+    alt_memory_and_id_space_enable: bool,
+    alt_bme: bool,
+    rsvdp: B10,
+    viral_enable: bool,
+    rsvdp_1: B1,
+}
+
+/// Port Control Extensions
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PortControlExtensions {
+    /// Unmask SBR, Secondary Bus Reset is propagated to the link
+    pub unmask_sbr: bool,
+    /// Unmask Link Disable, Link Disable is propagated to the link
+    pub unmask_link_disable: bool,
+    /// Alt Memory and ID Space Enable, alternate ranges are used for decoding
+    pub alt_memory_and_id_space_enable: bool,
+    /// Alt BME, Bus Master Enable for the alternate ranges
+    pub alt_bme: bool,
+    /// Viral Enable
+    pub viral_enable: bool,
+}
+impl From<PortControlExtensionsProto> for PortControlExtensions {
+    fn from(proto: PortControlExtensionsProto) -> Self {
+        let _ = (proto.rsvdp(), proto.rsvdp_1());
+        Self {
+            unmask_sbr: proto.unmask_sbr(),
+            unmask_link_disable: proto.unmask_link_disable(),
+            alt_memory_and_id_space_enable: proto.alt_memory_and_id_space_enable(),
+            alt_bme: proto.alt_bme(),
+            viral_enable: proto.viral_enable(),
+        }
+    }
+}
+impl From<u16> for PortControlExtensions {
+    fn from(word: u16) -> Self { PortControlExtensionsProto::from(word).into() }
+}
+
+
+/// GPF DVSEC for CXL Ports
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GpfForPorts {
+    /// GPF Phase 1 Control, Port GPF Phase 1 Timeout
+    pub phase_1_timeout: GpfTime,
+    /// GPF Phase 2 Control, Port GPF Phase 2 Timeout
+    pub phase_2_timeout: GpfTime,
+}
+impl<'a> TryRead<'a, Endian> for GpfForPorts {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let _reserved = bytes.read_with::<u16>(offset, endian)?;
+        let gpf = GpfForPorts {
+            phase_1_timeout: bytes.read_with::<u16>(offset, endian)?.into(),
+            phase_2_timeout: bytes.read_with::<u16>(offset, endian)?.into(),
+        };
+        Ok((gpf, *offset))
+    }
+}
+
+/// GPF DVSEC for CXL Devices
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GpfForDevices {
+    /// GPF Phase 2 Duration, time the device takes to complete GPF Phase 2
+    pub phase_2_duration: GpfTime,
+    /// GPF Phase 2 Power, active power consumed by the device during GPF Phase 2 in mW
+    pub phase_2_power: u32,
+}
+impl<'a> TryRead<'a, Endian> for GpfForDevices {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let gpf = GpfForDevices {
+            phase_2_duration: bytes.read_with::<u16>(offset, endian)?.into(),
+            phase_2_power: bytes.read_with::<u32>(offset, endian)?,
+        };
+        Ok((gpf, *offset))
+    }
+}
+
+/// GPF time encoded as Base multiplied by Scale
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GpfTime {
+    pub base: u8,
+    /// 0h – 1 us, 1h – 10 us, 2h – 100 us, … 7h – 10 s, other encodings are reserved
+    pub scale: u8,
+}
+impl GpfTime {
+    /// Time in microseconds, `None` if Scale is reserved
+    pub fn microseconds(&self) -> Option<u64> {
+        (self.scale <= 7).then(|| u64::from(self.base) * 10u64.pow(self.scale.into()))
+    }
+}
+impl From<u16> for GpfTime {
+    fn from(word: u16) -> Self {
+        Self {
+            base: (word & 0x0f) as u8,
+            scale: ((word >> 8) & 0x0f) as u8,
+        }
+    }
+}
+
+
+/// PCIe DVSEC for Flex Bus Port
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlexBusPort {
+    /// Flex Bus Port Capability
+    pub capability: FlexBusPortFlags,
+    /// Flex Bus Port Control
+    pub control: FlexBusPortFlags,
+    /// Flex Bus Port Status
+    pub status: FlexBusPortStatus,
+    /// Flex Bus Port Received Modified TS Data Phase1
+    pub received_modified_ts_data_phase_1: u32,
+}
+impl<'a> TryRead<'a, Endian> for FlexBusPort {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let fbp = FlexBusPort {
+            capability: bytes.read_with::<u16>(offset, endian)?.into(),
+            control: bytes.read_with::<u16>(offset, endian)?.into(),
+            status: bytes.read_with::<u16>(offset, endian)?.into(),
+            received_modified_ts_data_phase_1: bytes.read_with::<u32>(offset, endian)? & 0xff_ffff,
+        };
+        Ok((fbp, *offset))
+    }
+}
+
+#[bitfield(bits = 16)]
+#[repr(u16)]
+pub struct FlexBusPortFlagsProto {
+    cache: bool,
+    io: bool,
+    mem: bool,
+    cxl_sync_hdr_bypass: bool,
+    drift_buffer: bool,
+    cxl_68b_flit_and_vh: bool,
+    cxl_multi_logical_device: bool,
+    rsvdp: B9,
+}
+
+/// Protocols and features of Flex Bus Port Capability and Control registers
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlexBusPortFlags {
+    /// CXL.cache
+    pub cache: bool,
+    /// CXL.io
+    pub io: bool,
+    /// CXL.mem
+    pub mem: bool,
+    /// CXL Sync Header Bypass
+    pub cxl_sync_hdr_bypass: bool,
+    /// Drift Buffer
+    pub drift_buffer: bool,
+    /// CXL 68B Flit and VH, CXL 2.0 mode
+    pub cxl_68b_flit_and_vh: bool,
+    /// CXL Multi-Logical Device
+    pub cxl_multi_logical_device: bool,
+}
+impl From<FlexBusPortFlagsProto> for FlexBusPortFlags {
+    fn from(proto: FlexBusPortFlagsProto) -> Self {
+        let _ = proto.rsvdp();
+        Self {
+            cache: proto.cache(),
+            io: proto.io(),
+            mem: proto.mem(),
+            cxl_sync_hdr_bypass: proto.cxl_sync_hdr_bypass(),
+            drift_buffer: proto.drift_buffer(),
+            cxl_68b_flit_and_vh: proto.cxl_68b_flit_and_vh(),
+            cxl_multi_logical_device: proto.cxl_multi_logical_device(),
+        }
+    }
+}
+impl From<u16> for FlexBusPortFlags {
+    fn from(word: u16) -> Self { FlexBusPortFlagsProto::from(word).into() }
+}
+
+/// Flex Bus Port Status
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlexBusPortStatus {
+    /// Protocols and features negotiated on the link
+    pub enabled: FlexBusPortFlags,
+    /// Even Half Failed
+    pub even_half_failed: bool,
+    /// CXL Correctable Protocol ID Framing Error
+    pub correctable_protocol_id_framing_error: bool,
+    /// CXL Uncorrectable Protocol ID Framing Error
+    pub uncorrectable_protocol_id_framing_error: bool,
+    /// CXL Unexpected Protocol ID Dropped
+    pub unexpected_protocol_id_dropped: bool,
+    /// Retimers Present Mismatched
+    pub retimers_present_mismatched: bool,
+    /// FlexBusEnableBits Phase2 Mismatch
+    pub flex_bus_enable_bits_phase_2_mismatch: bool,
+}
+impl From<u16> for FlexBusPortStatus {
+    fn from(word: u16) -> Self {
+        let bit = |n: u16| word & (1 << n) != 0;
+        Self {
+            enabled: (word & 0x7f).into(),
+            even_half_failed: bit(8),
+            correctable_protocol_id_framing_error: bit(9),
+            uncorrectable_protocol_id_framing_error: bit(10),
+            unexpected_protocol_id_dropped: bit(11),
+            retimers_present_mismatched: bit(12),
+            flex_bus_enable_bits_phase_2_mismatch: bit(13),
+        }
+    }
+}
+
+
+/// Register Locator DVSEC
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RegisterLocator<'a> {
+    /// Raw Register Block entries, see [RegisterLocator::register_blocks]
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serde_hex::serialize"))]
+    pub entries_data: &'a [u8],
+}
+impl<'a> RegisterLocator<'a> {
+    pub fn register_blocks(&self) -> RegisterBlocks<'a> {
+        RegisterBlocks { data: self.entries_data, offset: 0 }
+    }
+}
+impl<'a> TryRead<'a, Endian> for RegisterLocator<'a> {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let _reserved = bytes.read_with::<u16>(offset, endian)?;
+        let len = (bytes.len() - *offset) / 8 * 8;
+        let rl = RegisterLocator {
+            entries_data: bytes.read_with::<&[u8]>(offset, Bytes::Len(len))?,
+        };
+        Ok((rl, *offset))
+    }
+}
+
+/// An iterator through Register Locator DVSEC entries
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisterBlocks<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+impl<'a> Iterator for RegisterBlocks<'a> {
+    type Item = RegisterBlock;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.data.read_with(&mut self.offset, LE).ok()
+    }
+}
+
+/// Register Block location
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RegisterBlock {
+    /// Register BIR, BAR containing the Register Block: 0 – BAR at offset 10h, … 5 – BAR at
+    /// offset 24h
+    pub register_bir: u8,
+    pub register_block_identifier: RegisterBlockIdentifier,
+    /// Register Block Offset, 64 KB aligned offset from the BAR address
+    pub register_block_offset: u64,
+}
+impl<'a> TryRead<'a, Endian> for RegisterBlock {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let low = bytes.read_with::<u32>(offset, endian)?;
+        let high = bytes.read_with::<u32>(offset, endian)?;
+        let rb = RegisterBlock {
+            register_bir: (low & 0b111) as u8,
+            register_block_identifier: ((low >> 8) as u8).into(),
+            register_block_offset: (u64::from(high) << 32) | u64::from(low & 0xffff_0000),
+        };
+        Ok((rb, *offset))
+    }
+}
+
+/// Type of registers in the Register Block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RegisterBlockIdentifier {
+    /// Register Block entry is empty
+    Empty,
+    /// Component Registers
+    ComponentRegisters,
+    /// BAR Virtualization ACL Registers
+    BarVirtualizationAclRegisters,
+    /// CXL Memory Device Registers
+    MemoryDeviceRegisters,
+    /// Designated Vendor Specific Registers
+    VendorSpecificRegisters,
+    Reserved(u8),
+}
+impl From<u8> for RegisterBlockIdentifier {
+    fn from(byte: u8) -> Self {
+        match byte {
+            0x00 => Self::Empty,
+            0x01 => Self::ComponentRegisters,
+            0x02 => Self::BarVirtualizationAclRegisters,
+            0x03 => Self::MemoryDeviceRegisters,
+            0xff => Self::VendorSpecificRegisters,
+            v => Self::Reserved(v),
+        }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use pretty_assertions::assert_eq;
+    use crate::extended_capabilities::ExtendedCapabilityKind;
+    use super::super::{DvsecHeader, DvsecRegistry};
+    use super::*;
+
+    fn dvsec(dvsec_id: u16, registers: &[u8]) -> DesignatedVendorSpecificExtendedCapability<'_> {
+        DesignatedVendorSpecificExtendedCapability {
+            header: DvsecHeader {
+                dvsec_vendor_id: CXL_VENDOR_ID,
+                dvsec_revision: 1,
+                dvsec_length: registers.len() as u16 + 10,
+                dvsec_id,
+            },
+            registers: registers.into(),
+        }
+    }
+
+    #[test]
+    fn type_3_device() {
+        let registers = [
+            // Capability: Mem_Capable, IO_Capable, HDM_Count 1, Mem_HwInit_Mode
+            0x1e, 0x00,
+            // Control: IO_Enable, Mem_Enable
+            0x06, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            // Range 1 Size: 16 GB, valid, active
+            0x04, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00,
+            // Range 1 Base
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            // Range 2
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let dvsec = dvsec(0x0000, &registers);
+        let result = dvsec.dvsec_capability().unwrap();
+        let device = match result {
+            super::super::DvsecCapability::ComputeExpressLink(ComputeExpressLink::Device(device)) =>
+                device,
+            other => std::panic!("{:?}", other),
+        };
+        assert!(device.capability.mem_capable && device.capability.mem_hwinit_mode);
+        assert!(device.control.mem_enable);
+        assert_eq!(Some(DeviceType::Type3), device.device_type());
+        let ranges = device.hdm_ranges().collect::<Vec<_>>();
+        let sample = Range {
+            memory_info_valid: true,
+            memory_active: true,
+            media_type: 0,
+            memory_class: 0,
+            desired_interleave: 0,
+            memory_active_timeout: 0,
+            size: 16 << 30,
+            base: 0,
+        };
+        assert_eq!(vec![&sample], ranges);
+
+        // Truncated registers are reported instead of being left undecoded
+        let truncated = DesignatedVendorSpecificExtendedCapability { registers: registers[..4].into(), ..dvsec };
+        let kind = ExtendedCapabilityKind::DesignatedVendorSpecificExtendedCapability(truncated);
+        assert!(matches!(kind.dvsec_capability(), Some(Err(_))));
+    }
+
+    #[test]
+    fn register_locator() {
+        let registers = [
+            0x00, 0x00,
+            0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x02, 0x03, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+        ];
+        let dvsec = dvsec(0x0008, &registers);
+        let decoders = decoders();
+        let registry = DvsecRegistry::new(&decoders);
+        let rl = match dvsec.decode_with(&registry) {
+            Some(Ok(ComputeExpressLink::RegisterLocator(rl))) => rl,
+            other => std::panic!("{:?}", other),
+        };
+        let result = rl.register_blocks().collect::<Vec<_>>();
+        let sample = vec![
+            RegisterBlock {
+                register_bir: 0,
+                register_block_identifier: RegisterBlockIdentifier::ComponentRegisters,
+                register_block_offset: 0x1_0000,
+            },
+            RegisterBlock {
+                register_bir: 2,
+                register_block_identifier: RegisterBlockIdentifier::MemoryDeviceRegisters,
+                register_block_offset: 0x1_0000_0000,
+            },
+        ];
+        assert_eq!(sample, result);
+    }
+
+    #[test]
+    fn gpf_and_flex_bus() {
+        let gpf = dvsec(0x0004, &[0x00, 0x00, 0x02, 0x03, 0x05, 0x02]);
+        let sample = ComputeExpressLink::GpfForPorts(GpfForPorts {
+            phase_1_timeout: GpfTime { base: 2, scale: 3 },
+            phase_2_timeout: GpfTime { base: 5, scale: 2 },
+        });
+        let result = DvsecRegistry::new(&decoders()).decode(&gpf);
+        assert_eq!(Some(Ok(sample)), result);
+        assert_eq!(Some(2000), GpfTime { base: 2, scale: 3 }.microseconds());
+        assert_eq!(None, GpfTime { base: 2, scale: 8 }.microseconds());
+
+        let fbp = dvsec(0x0007, &[0x27, 0x00, 0x27, 0x00, 0x26, 0x01, 0x00, 0x00, 0x00, 0x00]);
+        let result = match DvsecRegistry::new(&decoders()).decode(&fbp) {
+            Some(Ok(ComputeExpressLink::FlexBusPort(fbp))) => fbp,
+            other => std::panic!("{:?}", other),
+        };
+        assert!(result.capability.cache && result.capability.cxl_68b_flit_and_vh);
+        assert!(!result.status.enabled.cache && result.status.enabled.mem);
+        assert!(result.status.even_half_failed);
+    }
+}
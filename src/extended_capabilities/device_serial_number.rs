@@ -8,13 +8,14 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
 /// The Serial Number register is a 64-bit field that contains the IEEE defined 64-bit extended
 /// unique identifier (EUI-64™).
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DeviceSerialNumber {
     /// PCI Express Device Serial Number (1st DW)
     pub lower_dword: u32,
@@ -31,3 +32,11 @@ impl<'a> TryRead<'a, Endian> for DeviceSerialNumber {
         Ok((dsn, *offset))
     }
 }
+impl TryWrite<Endian> for DeviceSerialNumber {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u32>(offset, self.lower_dword, endian)?;
+        bytes.write_with::<u32>(offset, self.upper_dword, endian)?;
+        Ok(*offset)
+    }
+}
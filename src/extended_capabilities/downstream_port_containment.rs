@@ -8,12 +8,13 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DownstreamPortContainment {
     /// DPC Capability
     pub dpc_capability: DpcCapability,
@@ -62,6 +63,24 @@ impl<'a> TryRead<'a, Endian> for DownstreamPortContainment {
         Ok((dpc, *offset))
     }
 }
+impl TryWrite<Endian> for DownstreamPortContainment {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u16>(offset, (&self.dpc_capability).into(), endian)?;
+        bytes.write_with::<u16>(offset, (&self.dpc_control).into(), endian)?;
+        bytes.write_with::<u16>(offset, (&self.dpc_status).into(), endian)?;
+        bytes.write_with::<u16>(offset, self.dpc_error_source_id, endian)?;
+        bytes.write_with::<u32>(offset, (&self.rp_pio_status).into(), endian)?;
+        bytes.write_with::<u32>(offset, (&self.rp_pio_mask).into(), endian)?;
+        bytes.write_with::<u32>(offset, (&self.rp_pio_severity).into(), endian)?;
+        bytes.write_with::<u32>(offset, (&self.rp_pio_syserr).into(), endian)?;
+        bytes.write_with::<u32>(offset, (&self.rp_pio_exception).into(), endian)?;
+        bytes.write::<&[u8]>(offset, &self.rp_pio_header_log)?;
+        bytes.write_with::<u32>(offset, self.rp_pio_impspec_log, endian)?;
+        bytes.write::<&[u8]>(offset, &self.rp_pio_tlp_prefix_log)?;
+        Ok(*offset)
+    }
+}
 
 #[bitfield(bits = 16)]
 #[repr(u16)]
@@ -83,11 +102,12 @@ impl<'a> From<&'a DpcCapability> for DpcCapabilityProto {
             .with_dpc_software_triggering_supported(data.dpc_software_triggering_supported)
             .with_rp_pio_log_size(data.rp_pio_log_size)
             .with_dl_active_err_cor_signaling_supported(data.dl_active_err_cor_signaling_supported)
-            .with_rsvdp(0)
+            .with_rsvdp(data.rsvdp)
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DpcCapability {
     /// DPC Interrupt Message Number
     pub dpc_interrupt_message_number: u8,
@@ -101,10 +121,11 @@ pub struct DpcCapability {
     pub rp_pio_log_size: u8,
     /// DL_Active ERR_COR Signaling Supported
     pub dl_active_err_cor_signaling_supported: bool,
+    /// Reserved bits 15:13
+    pub rsvdp: u8,
 }
 impl From<DpcCapabilityProto> for DpcCapability {
     fn from(proto: DpcCapabilityProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             dpc_interrupt_message_number: proto.dpc_interrupt_message_number(),
             rp_extensions_for_dpc: proto.rp_extensions_for_dpc(),
@@ -112,6 +133,7 @@ impl From<DpcCapabilityProto> for DpcCapability {
             dpc_software_triggering_supported: proto.dpc_software_triggering_supported(),
             rp_pio_log_size: proto.rp_pio_log_size(),
             dl_active_err_cor_signaling_supported: proto.dl_active_err_cor_signaling_supported(),
+            rsvdp: proto.rsvdp(),
         }
     }
 }
@@ -144,11 +166,12 @@ impl<'a> From<&'a DpcControl> for DpcControlProto {
             .with_poisoned_tlp_egress_blocking_enable(data.poisoned_tlp_egress_blocking_enable)
             .with_dpc_software_trigger(data.dpc_software_trigger)
             .with_dl_active_err_cor_enable(data.dl_active_err_cor_enable)
-            .with_rsvdp(0)
+            .with_rsvdp(data.rsvdp)
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DpcControl {
     /// DPC Trigger Enable
     pub dpc_trigger_enable: DpcTrigger,
@@ -164,10 +187,11 @@ pub struct DpcControl {
     pub dpc_software_trigger: bool,
     /// DL_Active ERR_COR Enable
     pub dl_active_err_cor_enable: bool,
+    /// Reserved bits 15:8
+    pub rsvdp: u8,
 }
 impl From<DpcControlProto> for DpcControl {
     fn from(proto: DpcControlProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             dpc_trigger_enable: proto.dpc_trigger_enable().into(),
             dpc_completion_control: proto.dpc_completion_control(),
@@ -176,6 +200,7 @@ impl From<DpcControlProto> for DpcControl {
             poisoned_tlp_egress_blocking_enable: proto.poisoned_tlp_egress_blocking_enable(),
             dpc_software_trigger: proto.dpc_software_trigger(),
             dl_active_err_cor_enable: proto.dl_active_err_cor_enable(),
+            rsvdp: proto.rsvdp(),
         }
     }
 }
@@ -188,6 +213,7 @@ impl<'a> From<&'a DpcControl> for u16 {
 
 /// Enables DPC and controls the conditions that cause DPC to be triggered
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DpcTrigger {
     Disabled,
     /// Enabled and is triggered when the Downstream Port detects an unmasked uncorrectable error
@@ -223,19 +249,26 @@ pub struct DpcStatusProto {
 }
 impl<'a> From<&'a DpcStatus> for DpcStatusProto {
     fn from(data: &'a DpcStatus) -> Self {
+        let reason = data.dpc_trigger_reason.value();
+        let reason_extension = if reason == 0b11 {
+            data.dpc_trigger_reason.extension_value()
+        } else {
+            data.dpc_trigger_reason_extension_rsvdz
+        };
         Self::new()
             .with_dpc_trigger_status(data.dpc_trigger_status)
-            .with_dpc_trigger_reason(data.dpc_trigger_reason.value())
+            .with_dpc_trigger_reason(reason)
             .with_dpc_interrupt_status(data.dpc_interrupt_status)
             .with_dpc_rp_busy(data.dpc_rp_busy)
-            .with_dpc_trigger_reason_extension(data.dpc_trigger_reason.extension_value())
-            .with_rsvdz(0)
+            .with_dpc_trigger_reason_extension(reason_extension)
+            .with_rsvdz(data.rsvdz)
             .with_rp_pio_first_error_pointer(data.rp_pio_first_error_pointer)
-            .with_rsvdz_2(0)
+            .with_rsvdz_2(data.rsvdz_2)
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DpcStatus {
     /// DPC Trigger Status
     pub dpc_trigger_status: bool,
@@ -245,13 +278,17 @@ pub struct DpcStatus {
     pub dpc_interrupt_status: bool,
     /// DPC RP Busy
     pub dpc_rp_busy: bool,
+    /// DPC Trigger Reason Extension, reserved unless DPC Trigger Reason is 11b
+    pub dpc_trigger_reason_extension_rsvdz: u8,
+    /// Reserved bit 7
+    pub rsvdz: u8,
     /// RP PIO First Error Pointer
     pub rp_pio_first_error_pointer: u8,
+    /// Reserved bits 15:13
+    pub rsvdz_2: u8,
 }
 impl From<DpcStatusProto> for DpcStatus {
     fn from(proto: DpcStatusProto) -> Self {
-        let _ = proto.rsvdz();
-        let _ = proto.rsvdz_2();
         Self {
             dpc_trigger_status: proto.dpc_trigger_status(),
             dpc_trigger_reason: DpcTriggerReason::new(
@@ -260,7 +297,14 @@ impl From<DpcStatusProto> for DpcStatus {
             ),
             dpc_interrupt_status: proto.dpc_interrupt_status(),
             dpc_rp_busy: proto.dpc_rp_busy(),
+            dpc_trigger_reason_extension_rsvdz: if proto.dpc_trigger_reason() == 0b11 {
+                0
+            } else {
+                proto.dpc_trigger_reason_extension()
+            },
+            rsvdz: proto.rsvdz(),
             rp_pio_first_error_pointer: proto.rp_pio_first_error_pointer(),
+            rsvdz_2: proto.rsvdz_2(),
         }
     }
 }
@@ -272,6 +316,7 @@ impl<'a> From<&'a DpcStatus> for u16 {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DpcTriggerReason {
     /// unmasked uncorrectable error
     UnmaskedUncorrectableError,
@@ -333,6 +378,7 @@ pub struct RpPioProto {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RpPio {
     /// Configuration Request received UR Completion
     pub cfg_ur_cpl: bool,
@@ -340,37 +386,63 @@ pub struct RpPio {
     pub cfg_ca_cpl: bool,
     /// Configuration Request Completion Timeout
     pub cfg_cto: bool,
+    /// Reserved bits 7:3
+    pub rsvdp: u8,
     /// I/O Request received UR Completion
     pub io_ur_cpl: bool,
     /// I/O Request received CA Completion
     pub io_ca_cpl: bool,
     /// I/O Request Completion Timeout
     pub io_cto: bool,
+    /// Reserved bits 15:11
+    pub rsvdp_2: u8,
     /// Memory Request received UR Completion
     pub mem_ur_cpl: bool,
     /// Memory Request received CA Completion
     pub mem_ca_cpl: bool,
     /// Memory Request Completion Timeout
     pub mem_cto: bool,
+    /// Reserved bits 31:19
+    pub rsvdp_3: u16,
 }
 impl From<RpPioProto> for RpPio {
     fn from(proto: RpPioProto) -> Self {
-        let _ = proto.rsvdp();
-        let _ = proto.rsvdp_2();
-        let _ = proto.rsvdp_3();
         Self {
             cfg_ur_cpl: proto.cfg_ur_cpl(),
             cfg_ca_cpl: proto.cfg_ca_cpl(),
             cfg_cto: proto.cfg_cto(),
+            rsvdp: proto.rsvdp(),
             io_ur_cpl: proto.io_ur_cpl(),
             io_ca_cpl: proto.io_ca_cpl(),
             io_cto: proto.io_cto(),
+            rsvdp_2: proto.rsvdp_2(),
             mem_ur_cpl: proto.mem_ur_cpl(),
             mem_ca_cpl: proto.mem_ca_cpl(),
             mem_cto: proto.mem_cto(),
+            rsvdp_3: proto.rsvdp_3(),
         }
     }
 }
 impl From<u32> for RpPio {
     fn from(dword: u32) -> Self { RpPioProto::from(dword).into() }
 }
+impl<'a> From<&'a RpPio> for RpPioProto {
+    fn from(data: &'a RpPio) -> Self {
+        Self::new()
+            .with_cfg_ur_cpl(data.cfg_ur_cpl)
+            .with_cfg_ca_cpl(data.cfg_ca_cpl)
+            .with_cfg_cto(data.cfg_cto)
+            .with_rsvdp(data.rsvdp)
+            .with_io_ur_cpl(data.io_ur_cpl)
+            .with_io_ca_cpl(data.io_ca_cpl)
+            .with_io_cto(data.io_cto)
+            .with_rsvdp_2(data.rsvdp_2)
+            .with_mem_ur_cpl(data.mem_ur_cpl)
+            .with_mem_ca_cpl(data.mem_ca_cpl)
+            .with_mem_cto(data.mem_cto)
+            .with_rsvdp_3(data.rsvdp_3)
+    }
+}
+impl<'a> From<&'a RpPio> for u32 {
+    fn from(data: &'a RpPio) -> Self { RpPioProto::from(data).into() }
+}
@@ -13,7 +13,7 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
@@ -22,6 +22,7 @@ use super::latency_tolerance_reporting::MaxLatency;
 
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct L1PmSubstates {
     /// L1 PM Substates Capabilities
     pub l1_pm_substates_capabilities: L1PmSubstatesCapabilities,
@@ -41,6 +42,15 @@ impl<'a> TryRead<'a, Endian> for L1PmSubstates {
         Ok((l1pms, *offset))
     }
 }
+impl TryWrite<Endian> for L1PmSubstates {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u32>(offset, self.l1_pm_substates_capabilities.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.l1_pm_substates_control_1.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.l1_pm_substates_control_2.into(), endian)?;
+        Ok(*offset)
+    }
+}
 
 
 #[bitfield(bits = 32)]
@@ -66,17 +76,18 @@ impl From<L1PmSubstatesCapabilities> for L1PmSubstatesCapabilitiesProto {
             .with_aspm_l1_2_supported(data.aspm_l1_2_supported)
             .with_aspm_l1_1_supported(data.aspm_l1_1_supported)
             .with_l1_pm_substates_supported(data.l1_pm_substates_supported)
-            .with_rsvdp(0)
+            .with_rsvdp(data.rsvdp)
             .with_port_common_mode_restore_time(data.port_common_mode_restore_time)
             .with_port_t_power_on_scale(data.port_t_power_on.scale.into())
-            .with_rsvdp_2(0)
+            .with_rsvdp_2(data.rsvdp_2)
             .with_port_t_power_on_value(data.port_t_power_on.value)
-            .with_rsvdp_3(0)
+            .with_rsvdp_3(data.rsvdp_3)
     }
 }
 
 /// L1 PM Substates Capabilities
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct L1PmSubstatesCapabilities {
     /// PCI-PM L1.2 Supported
     pub pci_pm_l1_2_supported: bool,
@@ -88,22 +99,28 @@ pub struct L1PmSubstatesCapabilities {
     pub aspm_l1_1_supported: bool,
     /// L1 PM Substates Supported
     pub l1_pm_substates_supported: bool,
+    /// Reserved bits 7:5
+    pub rsvdp: u8,
     /// Port Common_Mode_Restore_Time
     pub port_common_mode_restore_time: u8,
+    /// Reserved bit 18
+    pub rsvdp_2: u8,
+    /// Reserved bits 31:24
+    pub rsvdp_3: u8,
     pub port_t_power_on: PortTPowerOn,
 }
 impl From<L1PmSubstatesCapabilitiesProto> for L1PmSubstatesCapabilities {
     fn from(proto: L1PmSubstatesCapabilitiesProto) -> Self {
-        let _ = proto.rsvdp();
-        let _ = proto.rsvdp_2();
-        let _ = proto.rsvdp_3();
         Self {
             pci_pm_l1_2_supported: proto.pci_pm_l1_2_supported(),
             pci_pm_l1_1_supported: proto.pci_pm_l1_1_supported(),
             aspm_l1_2_supported: proto.aspm_l1_2_supported(),
             aspm_l1_1_supported: proto.aspm_l1_1_supported(),
             l1_pm_substates_supported: proto.l1_pm_substates_supported(),
+            rsvdp: proto.rsvdp(),
             port_common_mode_restore_time: proto.port_common_mode_restore_time(),
+            rsvdp_2: proto.rsvdp_2(),
+            rsvdp_3: proto.rsvdp_3(),
             port_t_power_on: PortTPowerOn {
                 scale: proto.port_t_power_on_scale().into(),
                 value: proto.port_t_power_on_value(),
@@ -124,6 +141,7 @@ impl From<L1PmSubstatesCapabilities> for u32 {
 /// Sets the time (in μs) that this Port requires the port on the opposite side of Link to wait in
 /// L1.2.Exit after sampling CLKREQ# asserted before actively driving the interface
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PortTPowerOn {
     pub value: u8,
     pub scale: PortTPowerOnScale,
@@ -139,6 +157,7 @@ impl PortTPowerOn {
 
 /// Specifies the scale used for the Port T_POWER_ON Value
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PortTPowerOnScale {
     /// 2 µs
     Time2us = 2,
@@ -191,16 +210,17 @@ impl From<L1PmSubstatesControl1> for L1PmSubstatesControl1Proto {
             .with_pci_pm_l1_1_enable(data.pci_pm_l1_1_enable)
             .with_aspm_l1_2_enable(data.aspm_l1_2_enable)
             .with_aspm_l1_1_enable(data.aspm_l1_1_enable)
-            .with_rsvdp(0)
+            .with_rsvdp(data.rsvdp)
             .with_common_mode_restore_time(data.common_mode_restore_time)
             .with_ltr_l1_2_threshold_value(data.ltr_l1_2_threshold.value)
-            .with_rsvdp_2(0)
+            .with_rsvdp_2(data.rsvdp_2)
             .with_ltr_l1_2_threshold_scale(data.ltr_l1_2_threshold.scale)
     }
 }
 
 /// L1 PM Substates Control 1
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct L1PmSubstatesControl1 {
     /// PCI-PM L1.2 Enable
     pub pci_pm_l1_2_enable: bool,
@@ -210,23 +230,27 @@ pub struct L1PmSubstatesControl1 {
     pub aspm_l1_2_enable: bool,
     /// ASPM L1.1 Enable
     pub aspm_l1_1_enable: bool,
+    /// Reserved bits 7:4
+    pub rsvdp: u8,
     /// Value of T(COMMONMODE) (in µs), which must be used by the Downstream Port for timing the
     /// re-establishment of common mode
     pub common_mode_restore_time: u8,
+    /// Reserved bits 28:26
+    pub rsvdp_2: u8,
     /// Indicates the LTR threshold used to determine if entry into L1 results in L1.1 (if enabled)
     /// or L1.2 (if enabled).
     pub ltr_l1_2_threshold: MaxLatency,
 }
 impl From<L1PmSubstatesControl1Proto> for L1PmSubstatesControl1 {
     fn from(proto: L1PmSubstatesControl1Proto) -> Self {
-        let _ = proto.rsvdp();
-        let _ = proto.rsvdp_2();
         Self {
             pci_pm_l1_2_enable: proto.pci_pm_l1_2_enable(),
             pci_pm_l1_1_enable: proto.pci_pm_l1_1_enable(),
             aspm_l1_2_enable: proto.aspm_l1_2_enable(),
             aspm_l1_1_enable: proto.aspm_l1_1_enable(),
+            rsvdp: proto.rsvdp(),
             common_mode_restore_time: proto.common_mode_restore_time(),
+            rsvdp_2: proto.rsvdp_2(),
             ltr_l1_2_threshold: MaxLatency {
                 value: proto.ltr_l1_2_threshold_value(),
                 scale: proto.ltr_l1_2_threshold_scale(),
@@ -257,27 +281,32 @@ impl From<L1PmSubstatesControl2> for L1PmSubstatesControl2Proto {
     fn from(data: L1PmSubstatesControl2) -> Self {
         Self::new()
             .with_t_power_on_scale(data.t_power_on.scale.into())
-            .with_rsvdp(0)
+            .with_rsvdp(data.rsvdp)
             .with_t_power_on_value(data.t_power_on.value)
-            .with_rsvdp_2(0)
+            .with_rsvdp_2(data.rsvdp_2)
     }
 }
 
 /// L1 PM Substates Control 2
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct L1PmSubstatesControl2 {
     /// T_POWER_ON
-    pub t_power_on: PortTPowerOn
+    pub t_power_on: PortTPowerOn,
+    /// Reserved bit 2
+    pub rsvdp: u8,
+    /// Reserved bits 31:8
+    pub rsvdp_2: u32,
 }
 impl From<L1PmSubstatesControl2Proto> for L1PmSubstatesControl2 {
     fn from(proto: L1PmSubstatesControl2Proto) -> Self {
-        let _ = proto.rsvdp();
-        let _ = proto.rsvdp_2();
         Self {
             t_power_on: PortTPowerOn {
                 scale: proto.t_power_on_scale().into(),
                 value: proto.t_power_on_value(),
-            }
+            },
+            rsvdp: proto.rsvdp(),
+            rsvdp_2: proto.rsvdp_2(),
         }
     }
 }
@@ -317,6 +346,9 @@ mod tests {
             l1_pm_substates_supported: true,
             port_common_mode_restore_time: 255,
             port_t_power_on: PortTPowerOn { value: 5, scale: PortTPowerOnScale::Time2us },
+            rsvdp: 0,
+            rsvdp_2: 0,
+            rsvdp_3: 0,
         },
         l1_pm_substates_control_1: L1PmSubstatesControl1 {
             pci_pm_l1_2_enable: true,
@@ -325,9 +357,13 @@ mod tests {
             aspm_l1_1_enable: false,
             common_mode_restore_time: 0,
             ltr_l1_2_threshold: MaxLatency { value: 50, scale: 2 },
+            rsvdp: 0,
+            rsvdp_2: 0,
         },
         l1_pm_substates_control_2: L1PmSubstatesControl2 {
             t_power_on: PortTPowerOn { value: 22, scale: PortTPowerOnScale::Time2us },
+            rsvdp: 0,
+            rsvdp_2: 0,
         },
     };
 
@@ -337,11 +373,20 @@ mod tests {
         assert_eq!(SAMPLE, result);
     }
 
+    #[test]
+    fn from_struct_into_bytes() {
+        let mut result = [0u8; 12];
+        let offset = &mut 0;
+        result.write_with(offset, SAMPLE, LE).unwrap();
+        assert_eq!(12, *offset);
+        assert_eq!(DATA[ECH_BYTES..], result);
+    }
+
     #[test]
     fn from_capabilities_into_dword() {
         assert_eq!(
             u32::from_le_bytes([0x1f,0xff,0x28,0x00]),
-            SAMPLE.l1_pm_substates_capabilities.into(),
+            u32::from(SAMPLE.l1_pm_substates_capabilities),
             "Capabilities"
         );
     }
@@ -350,7 +395,7 @@ mod tests {
     fn from_control_1_into_dword() {
         assert_eq!(
             u32::from_le_bytes([0x03,0x00,0x32,0x40]),
-            SAMPLE.l1_pm_substates_control_1.into(),
+            u32::from(SAMPLE.l1_pm_substates_control_1),
             "Control 1"
         );
     }
@@ -359,7 +404,7 @@ mod tests {
     fn from_control_2_into_dword() {
         assert_eq!(
             u32::from_le_bytes([0xb0,0x00,0x00,0x00]),
-            SAMPLE.l1_pm_substates_control_2.into(),
+            u32::from(SAMPLE.l1_pm_substates_control_2),
             "Control 2"
         );
     }
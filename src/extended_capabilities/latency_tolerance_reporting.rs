@@ -11,28 +11,49 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LatencyToleranceReporting {
     /// Max Snoop Latency
     pub max_snoop_latency: MaxLatency,
+    /// Reserved bits 15:13 of Max Snoop Latency register
+    pub max_snoop_latency_rsvdp: u8,
     /// Max No-Snoop Latency
     pub max_no_snoop_latency: MaxLatency,
+    /// Reserved bits 15:13 of Max No-Snoop Latency register
+    pub max_no_snoop_latency_rsvdp: u8,
 }
 impl<'a> TryRead<'a, Endian> for LatencyToleranceReporting {
     fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
         let offset = &mut 0;
+        let max_snoop_latency = bytes.read_with::<u16>(offset, endian)?;
+        let max_no_snoop_latency = bytes.read_with::<u16>(offset, endian)?;
         let ltr = LatencyToleranceReporting {
-            max_snoop_latency: bytes.read_with::<u16>(offset, endian)?.into(),
-            max_no_snoop_latency: bytes.read_with::<u16>(offset, endian)?.into(),
+            max_snoop_latency: max_snoop_latency.into(),
+            max_snoop_latency_rsvdp: (max_snoop_latency >> 13) as u8,
+            max_no_snoop_latency: max_no_snoop_latency.into(),
+            max_no_snoop_latency_rsvdp: (max_no_snoop_latency >> 13) as u8,
         };
         Ok((ltr, *offset))
     }
 }
+impl TryWrite<Endian> for LatencyToleranceReporting {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        let max_snoop_latency = u16::from(self.max_snoop_latency)
+            | u16::from(self.max_snoop_latency_rsvdp & 0b111) << 13;
+        let max_no_snoop_latency = u16::from(self.max_no_snoop_latency)
+            | u16::from(self.max_no_snoop_latency_rsvdp & 0b111) << 13;
+        bytes.write_with::<u16>(offset, max_snoop_latency, endian)?;
+        bytes.write_with::<u16>(offset, max_no_snoop_latency, endian)?;
+        Ok(*offset)
+    }
+}
 
 
 #[bitfield(bits = 16)]
@@ -44,6 +65,7 @@ pub struct MaxLatencyProto {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MaxLatency {
     /// Specifies the maximum latency that a device is permitted to request
     pub value: u16,
@@ -71,4 +93,15 @@ impl From<MaxLatencyProto> for MaxLatency {
 impl From<u16> for MaxLatency {
     fn from(word: u16) -> Self { MaxLatencyProto::from(word).into() }
 }
+impl From<MaxLatency> for MaxLatencyProto {
+    fn from(data: MaxLatency) -> Self {
+        Self::new()
+            .with_value(data.value)
+            .with_scale(data.scale)
+            .with_rsvdp(0)
+    }
+}
+impl From<MaxLatency> for u16 {
+    fn from(data: MaxLatency) -> Self { MaxLatencyProto::from(data).into() }
+}
 
@@ -0,0 +1,117 @@
+//! Multi-Function Virtual Channel Capability
+//!
+//! The Multi-Function Virtual Channel (MFVC) Capability is an optional Extended Capability that
+//! permits enhanced QoS management in a Multi-Function Device. Its registers have the same layout
+//! as [Virtual Channel](super::virtual_channel) registers, but the Port Arbitration fields of the
+//! VC Capability structure control Function Arbitration: arbitration between the Functions of the
+//! device within each VC.
+
+use byte::{
+    ctx::*,
+    self,
+    TryRead,
+    TryWrite,
+};
+
+use super::virtual_channel::{
+    ExtendedVirtualChannel,
+    ExtendedVirtualChannels,
+    PortArbitrationTable,
+    VcArbitrationTable,
+    VirtualChannel,
+};
+
+
+/// Multi-Function Virtual Channel Extended Capability structure
+///
+/// Fields of the underlying [VirtualChannel] named after Port Arbitration mean Function
+/// Arbitration, e.g. Port Arbitration Table Entry Size is Function Arbitration Table Entry Size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultiFunctionVirtualChannel<'a>(pub VirtualChannel<'a>);
+impl<'a> MultiFunctionVirtualChannel<'a> {
+    pub fn extended_virtual_channels(&self) -> ExtendedVirtualChannels<'_> {
+        self.0.extended_virtual_channels()
+    }
+    pub fn vc_arbitration_table(&self) -> VcArbitrationTable<'_> {
+        self.0.vc_arbitration_table()
+    }
+    pub fn function_arbitration_table(
+        &self,
+        evc: &ExtendedVirtualChannel,
+    ) -> FunctionArbitrationTable<'_> {
+        FunctionArbitrationTable(self.0.port_arbitration_table(evc))
+    }
+}
+impl<'a> TryRead<'a, Endian> for MultiFunctionVirtualChannel<'a> {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let (vc, size) = VirtualChannel::try_read(bytes, endian)?;
+        Ok((Self(vc), size))
+    }
+}
+impl<'a> TryWrite<Endian> for MultiFunctionVirtualChannel<'a> {
+    /// Writes MFVC registers, all Extended VC Resources, VC Arbitration Table and Function
+    /// Arbitration Tables at the offsets pointed by the registers
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        self.0.try_write(bytes, endian)
+    }
+}
+
+
+/// An iterator through Function Arbitration Table entries of a VC resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionArbitrationTable<'a>(PortArbitrationTable<'a>);
+impl<'a> Iterator for FunctionArbitrationTable<'a> {
+    type Item = FunctionArbitrationTableEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|entry| FunctionArbitrationTableEntry { function_number: entry.into() })
+    }
+}
+
+/// Function Arbitration Table entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FunctionArbitrationTableEntry {
+    /// Function Number (or Function Group Number with ARI) assigned to the arbitration phase
+    pub function_number: u8,
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use pretty_assertions::assert_eq;
+    use byte::BytesExt;
+    use super::*;
+
+    #[test]
+    fn function_arbitration_table() {
+        let data = [
+            // MFVC Port VC Capability 1: Function Arbitration Table Entry Size 4 bits
+            0x00,0x08,0x00,0x00,
+            // Port VC Capability 2, Port VC Control, Port VC Status
+            0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,
+            // VC0: FATOffset=02 WRR32+ Enable+ ArbSelect=WRR32 TC/VC=ff
+            0x02,0x00,0x00,0x02,0xff,0x00,0x02,0x80,0x00,0x00,0x00,0x00,
+            // RsvdP
+            0x00,0x00,0x00,0x00,
+            // Function Arbitration Table
+            0x10,0x32,0x10,0x32,0x10,0x32,0x10,0x32,0x10,0x32,0x10,0x32,0x10,0x32,0x10,0x32,
+        ];
+        let mfvc: MultiFunctionVirtualChannel = data.read_with(&mut 0, LE).unwrap();
+        let evc = mfvc.extended_virtual_channels().next().unwrap();
+        let result = mfvc.function_arbitration_table(&evc)
+            .map(|entry| entry.function_number)
+            .collect::<Vec<_>>();
+        let sample = [0, 1, 2, 3].repeat(8);
+        assert_eq!(sample, result);
+
+        let mut buf = [0u8; 44];
+        let offset = &mut 0;
+        buf.write_with(offset, mfvc.clone(), LE).unwrap();
+        assert_eq!(buf.len(), *offset);
+        assert_eq!(data, buf);
+    }
+}
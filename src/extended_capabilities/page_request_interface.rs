@@ -9,12 +9,13 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PageRequestInterface {
     /// Page Request Control
     pub page_request_control: PageRequestControl,
@@ -37,6 +38,16 @@ impl<'a> TryRead<'a, Endian> for PageRequestInterface {
         Ok((pri, *offset))
     }
 }
+impl TryWrite<Endian> for PageRequestInterface {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u16>(offset, self.page_request_control.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.page_request_status.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.outstanding_page_request_capacity, endian)?;
+        bytes.write_with::<u32>(offset, self.outstanding_page_request_allocation, endian)?;
+        Ok(*offset)
+    }
+}
 
 #[bitfield(bits = 16)]
 #[repr(u16)]
@@ -48,24 +59,38 @@ pub struct PageRequestControlProto {
 
 /// Page Request Control
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PageRequestControl {
     /// Enable (E)
     pub enable: bool,
     /// Reset (R)
     pub reset: bool,
+    /// Reserved bits 15:2
+    pub rsvdp: u16,
 }
 impl From<PageRequestControlProto> for PageRequestControl {
     fn from(proto: PageRequestControlProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             enable: proto.enable(),
             reset: proto.reset(),
+            rsvdp: proto.rsvdp(),
         }
     }
 }
 impl From<u16> for PageRequestControl {
     fn from(word: u16) -> Self { PageRequestControlProto::from(word).into() }
 }
+impl From<PageRequestControl> for PageRequestControlProto {
+    fn from(data: PageRequestControl) -> Self {
+        Self::new()
+            .with_enable(data.enable)
+            .with_reset(data.reset)
+            .with_rsvdp(data.rsvdp)
+    }
+}
+impl From<PageRequestControl> for u16 {
+    fn from(data: PageRequestControl) -> Self { PageRequestControlProto::from(data).into() }
+}
 
 #[bitfield(bits = 16)]
 #[repr(u16)]
@@ -80,24 +105,29 @@ pub struct PageRequestStatusProto {
 
 /// Page Request Status
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PageRequestStatus {
     /// Response Failure (RF)
     pub response_failure: bool,
     /// Unexpected Page Request Group Index (UPRGI)
     pub unexpected_page_request_group_index: bool,
+    /// Reserved bits 7:2
+    pub rsvdz: u8,
     /// Stopped (S)
     pub stopped: bool,
+    /// Reserved bits 14:9
+    pub rsvdz_2: u8,
     /// PRG Response PASID Required
     pub prg_response_pasid_required: bool,
 }
 impl From<PageRequestStatusProto> for PageRequestStatus {
     fn from(proto: PageRequestStatusProto) -> Self {
-        let _ = proto.rsvdz();
-        let _ = proto.rsvdz_2();
         Self {
             response_failure: proto.response_failure(),
             unexpected_page_request_group_index: proto.unexpected_page_request_group_index(),
+            rsvdz: proto.rsvdz(),
             stopped: proto.stopped(),
+            rsvdz_2: proto.rsvdz_2(),
             prg_response_pasid_required: proto.prg_response_pasid_required(),
         }
     }
@@ -105,3 +135,17 @@ impl From<PageRequestStatusProto> for PageRequestStatus {
 impl From<u16> for PageRequestStatus {
     fn from(word: u16) -> Self { PageRequestStatusProto::from(word).into() }
 }
+impl From<PageRequestStatus> for PageRequestStatusProto {
+    fn from(data: PageRequestStatus) -> Self {
+        Self::new()
+            .with_response_failure(data.response_failure)
+            .with_unexpected_page_request_group_index(data.unexpected_page_request_group_index)
+            .with_rsvdz(data.rsvdz)
+            .with_stopped(data.stopped)
+            .with_rsvdz_2(data.rsvdz_2)
+            .with_prg_response_pasid_required(data.prg_response_pasid_required)
+    }
+}
+impl From<PageRequestStatus> for u16 {
+    fn from(data: PageRequestStatus) -> Self { PageRequestStatusProto::from(data).into() }
+}
@@ -8,15 +8,18 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PowerBudgeting {
     /// Data Select
     pub data_select: u8,
+    /// Reserved bits 31:8 of Data Select register
+    pub data_select_rsvdp: [u8; 3],
     /// Data
     pub data: Data,
     /// Power Budget Capability
@@ -27,15 +30,24 @@ impl<'a> TryRead<'a, Endian> for PowerBudgeting {
         let offset = &mut 0;
         let pb = PowerBudgeting {
             data_select: bytes.read_with::<u8>(offset, endian)?,
-            data: {
-                let _rsvdp = bytes.read_with::<&[u8]>(offset, Bytes::Len(3))?;
-                bytes.read_with::<u32>(offset, endian)?.into()
-            },
+            data_select_rsvdp: bytes.read_with::<&[u8]>(offset, Bytes::Len(3))?
+                .try_into().unwrap(),
+            data: bytes.read_with::<u32>(offset, endian)?.into(),
             power_budget_capability: bytes.read_with::<u8>(offset, endian)?.into(),
         };
         Ok((pb, *offset))
     }
 }
+impl TryWrite<Endian> for PowerBudgeting {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u8>(offset, self.data_select, endian)?;
+        bytes.write::<&[u8]>(offset, &self.data_select_rsvdp)?;
+        bytes.write_with::<u32>(offset, self.data.into(), endian)?;
+        bytes.write_with::<u8>(offset, self.power_budget_capability.into(), endian)?;
+        Ok(*offset)
+    }
+}
 
 #[bitfield(bits = 32)]
 #[repr(u32)]
@@ -50,6 +62,7 @@ pub struct DataProto {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Data {
     /// Base Power
     pub base_power: BasePower,
@@ -63,10 +76,11 @@ pub struct Data {
     pub operation_condition_type: OperationConditionType,
     /// Power Rail
     pub power_rail: PowerRail,
+    /// Reserved bits 31:21
+    pub rsvdp: u16,
 }
 impl From<DataProto> for Data {
     fn from(proto: DataProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             base_power: proto.base_power().into(),
             data_scale: proto.data_scale().into(),
@@ -74,21 +88,39 @@ impl From<DataProto> for Data {
             pm_state: proto.pm_state().into(),
             operation_condition_type: proto.operation_condition_type().into(),
             power_rail: proto.power_rail().into(),
+            rsvdp: proto.rsvdp(),
         }
     }
 }
 impl From<u32> for Data {
     fn from(dword: u32) -> Self { DataProto::from(dword).into() }
 }
+impl From<Data> for DataProto {
+    fn from(data: Data) -> Self {
+        Self::new()
+            .with_base_power(data.base_power.into())
+            .with_data_scale(data.data_scale.into())
+            .with_pm_sub_state(data.pm_sub_state.into())
+            .with_pm_state(data.pm_state.into())
+            .with_operation_condition_type(data.operation_condition_type.into())
+            .with_power_rail(data.power_rail.into())
+            .with_rsvdp(data.rsvdp)
+    }
+}
+impl From<Data> for u32 {
+    fn from(data: Data) -> Self { DataProto::from(data).into() }
+}
 
 /// Specifies in watts the base power value in the given operating condition
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BasePower {
     Value(u8),
     Gt239Le250,
     Gt250Le275,
     Gt275Le300,
     Gt300,
+    Reserved(u8),
 }
 impl From<u8> for BasePower {
     fn from(byte: u8) -> Self {
@@ -97,13 +129,27 @@ impl From<u8> for BasePower {
             0xF0 => Self::Gt239Le250,
             0xF1 => Self::Gt250Le275,
             0xF2 => Self::Gt275Le300,
-            _ => Self::Gt300,
+            0xF3 => Self::Gt300,
+            v => Self::Reserved(v),
+        }
+    }
+}
+impl From<BasePower> for u8 {
+    fn from(data: BasePower) -> Self {
+        match data {
+            BasePower::Value(v)    => v,
+            BasePower::Gt239Le250  => 0xF0,
+            BasePower::Gt250Le275  => 0xF1,
+            BasePower::Gt275Le300  => 0xF2,
+            BasePower::Gt300       => 0xF3,
+            BasePower::Reserved(v) => v,
         }
     }
 }
 
 /// Specifies the scale to apply to the Base Power value
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DataScale {
     /// 1.0x
     One,
@@ -113,6 +159,8 @@ pub enum DataScale {
     Centi,
     /// 0.001x
     Milli,
+    /// Reserved
+    Reserved(u8),
 }
 impl DataScale {
     pub fn multiplier(&self) -> f64 {
@@ -121,6 +169,7 @@ impl DataScale {
             Self::Deci => 0.1,
             Self::Centi => 0.01,
             Self::Milli => 0.001,
+            Self::Reserved(_) => 0.0,
         }
     }
 }
@@ -131,13 +180,25 @@ impl From<u8> for DataScale {
             0b01 => Self::Deci,
             0b10 => Self::Centi,
             0b11 => Self::Milli,
-            _ => unreachable!(),
+                v => Self::Reserved(v),
+        }
+    }
+}
+impl From<DataScale> for u8 {
+    fn from(data: DataScale) -> Self {
+        match data {
+            DataScale::One         => 0b00,
+            DataScale::Deci        => 0b01,
+            DataScale::Centi       => 0b10,
+            DataScale::Milli       => 0b11,
+            DataScale::Reserved(v) => v,
         }
     }
 }
 
 /// Specifies the power management sub state of the operating condition being described
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PmSubState {
     /// Default Sub State
     Default,
@@ -152,14 +213,25 @@ impl From<u8> for PmSubState {
         }
     }
 }
+impl From<PmSubState> for u8 {
+    fn from(data: PmSubState) -> Self {
+        match data {
+            PmSubState::Default     => 0b00,
+            PmSubState::Specific(v) => v,
+        }
+    }
+}
 
 /// Specifies the power management state of the operating condition being described
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PmState {
     D0,
     D1,
     D2,
     D3,
+    /// Reserved
+    Reserved(u8),
 }
 impl From<u8> for PmState {
     fn from(byte: u8) -> Self {
@@ -168,13 +240,25 @@ impl From<u8> for PmState {
             0b01 => Self::D1,
             0b10 => Self::D2,
             0b11 => Self::D3,
-            _ => unreachable!(),
+                v => Self::Reserved(v),
+        }
+    }
+}
+impl From<PmState> for u8 {
+    fn from(data: PmState) -> Self {
+        match data {
+            PmState::D0          => 0b00,
+            PmState::D1          => 0b01,
+            PmState::D2          => 0b10,
+            PmState::D3          => 0b11,
+            PmState::Reserved(v) => v,
         }
     }
 }
 
 /// Specifies the type of the operating condition being described
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OperationConditionType {
     /// PME Aux
     PmeAux,
@@ -207,8 +291,23 @@ impl From<u8> for OperationConditionType {
         }
     }
 }
+impl From<OperationConditionType> for u8 {
+    fn from(data: OperationConditionType) -> Self {
+        match data {
+            OperationConditionType::PmeAux                                => 0b000,
+            OperationConditionType::Auxiliary                             => 0b001,
+            OperationConditionType::Idle                                  => 0b010,
+            OperationConditionType::Sustained                             => 0b011,
+            OperationConditionType::SustainedEmergencyPowerReductionState => 0b100,
+            OperationConditionType::MaximumEmergencyPowerReductionState   => 0b101,
+            OperationConditionType::Maximum                               => 0b111,
+            OperationConditionType::Reserved(v)                           => v,
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PowerRail {
     /// Power (12V)
     Power12v,
@@ -218,6 +317,8 @@ pub enum PowerRail {
     Power1_5vOr1_8v,
     /// Thermal
     Thermal,
+    /// Reserved
+    Reserved(u8),
 }
 impl From<u8> for PowerRail {
     fn from(byte: u8) -> Self {
@@ -226,7 +327,18 @@ impl From<u8> for PowerRail {
             0b01 => Self::Power3_3v,
             0b10 => Self::Power1_5vOr1_8v,
             0b11 => Self::Thermal,
-            _ => unreachable!(),
+                v => Self::Reserved(v),
+        }
+    }
+}
+impl From<PowerRail> for u8 {
+    fn from(data: PowerRail) -> Self {
+        match data {
+            PowerRail::Power12v        => 0b000,
+            PowerRail::Power3_3v       => 0b001,
+            PowerRail::Power1_5vOr1_8v => 0b010,
+            PowerRail::Thermal         => 0b011,
+            PowerRail::Reserved(v)     => v,
         }
     }
 }
@@ -240,18 +352,31 @@ pub struct PowerBudgetCapabilityProto {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PowerBudgetCapability {
     /// System Allocated
     pub system_allocated: bool,
+    /// Reserved bits 7:1
+    pub rsvdp: u8,
 }
 impl From<PowerBudgetCapabilityProto> for PowerBudgetCapability {
     fn from(proto: PowerBudgetCapabilityProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             system_allocated: proto.system_allocated(),
+            rsvdp: proto.rsvdp(),
         }
     }
 }
 impl From<u8> for PowerBudgetCapability {
     fn from(byte: u8) -> Self { PowerBudgetCapabilityProto::from(byte).into() }
 }
+impl From<PowerBudgetCapability> for PowerBudgetCapabilityProto {
+    fn from(data: PowerBudgetCapability) -> Self {
+        Self::new()
+            .with_system_allocated(data.system_allocated)
+            .with_rsvdp(data.rsvdp)
+    }
+}
+impl From<PowerBudgetCapability> for u8 {
+    fn from(data: PowerBudgetCapability) -> Self { PowerBudgetCapabilityProto::from(data).into() }
+}
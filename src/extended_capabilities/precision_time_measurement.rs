@@ -8,12 +8,13 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PrecisionTimeMeasurement {
     /// PTM Capability
     pub ptm_capability: PtmCapability,
@@ -30,6 +31,14 @@ impl<'a> TryRead<'a, Endian> for PrecisionTimeMeasurement {
         Ok((ptm, *offset))
     }
 }
+impl TryWrite<Endian> for PrecisionTimeMeasurement {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u32>(offset, self.ptm_capability.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.ptm_control.into(), endian)?;
+        Ok(*offset)
+    }
+}
 
 #[bitfield(bits = 32)]
 #[repr(u32)]
@@ -47,14 +56,15 @@ impl From<PtmCapability> for PtmCapabilityProto {
             .with_ptm_requester_capable(data.ptm_requester_capable)
             .with_ptm_responder_capable(data.ptm_responder_capable)
             .with_ptm_root_capable(data.ptm_root_capable)
-            .with_rsvdp(0)
+            .with_rsvdp(data.rsvdp)
             .with_local_clock_granularity(data.local_clock_granularity)
-            .with_rsvdp_2(0)
+            .with_rsvdp_2(data.rsvdp_2)
     }
 }
 
 /// Describes a Function’s support for Precision Time Measurement
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PtmCapability {
     /// PTM Requester Capable
     pub ptm_requester_capable: bool,
@@ -62,18 +72,22 @@ pub struct PtmCapability {
     pub ptm_responder_capable: bool,
     /// PTM Root Capable
     pub ptm_root_capable: bool,
+    /// Reserved bits 7:3
+    pub rsvdp: u8,
     /// Local Clock Granularity
     pub local_clock_granularity: u8,
+    /// Reserved bits 31:16
+    pub rsvdp_2: u16,
 }
 impl From<PtmCapabilityProto> for PtmCapability {
     fn from(proto: PtmCapabilityProto) -> Self {
-        let _ = proto.rsvdp();
-        let _ = proto.rsvdp_2();
         Self {
             ptm_requester_capable: proto.ptm_requester_capable(),
             ptm_responder_capable: proto.ptm_responder_capable(),
             ptm_root_capable: proto.ptm_root_capable(),
+            rsvdp: proto.rsvdp(),
             local_clock_granularity: proto.local_clock_granularity(),
+            rsvdp_2: proto.rsvdp_2(),
         }
     }
 }
@@ -98,29 +112,34 @@ impl From<PtmControl> for PtmControlProto {
         Self::new()
             .with_ptm_enable(data.ptm_enable)
             .with_root_select(data.root_select)
-            .with_rsvdp(0)
+            .with_rsvdp(data.rsvdp)
             .with_effective_granularity(data.effective_granularity)
-            .with_rsvdp_2(0)
+            .with_rsvdp_2(data.rsvdp_2)
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PtmControl {
     /// PTM Enable
     pub ptm_enable: bool,
     /// Root Select
     pub root_select: bool,
+    /// Reserved bits 7:2
+    pub rsvdp: u8,
     /// Effective Granularity
     pub effective_granularity: u8,
+    /// Reserved bits 31:16
+    pub rsvdp_2: u16,
 }
 impl From<PtmControlProto> for PtmControl {
     fn from(proto: PtmControlProto) -> Self {
-        let _ = proto.rsvdp();
-        let _ = proto.rsvdp_2();
         Self {
             ptm_enable: proto.ptm_enable(),
             root_select: proto.root_select(),
+            rsvdp: proto.rsvdp(),
             effective_granularity: proto.effective_granularity(),
+            rsvdp_2: proto.rsvdp_2(),
         }
     }
 }
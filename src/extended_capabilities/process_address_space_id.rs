@@ -9,11 +9,12 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProcessAddressSpaceId {
     pub pacid_capability: PacidCapability,
     pub pacid_control: PacidControl,
@@ -28,6 +29,14 @@ impl<'a> TryRead<'a, Endian> for ProcessAddressSpaceId {
         Ok((pacid, *offset))
     }
 }
+impl TryWrite<Endian> for ProcessAddressSpaceId {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u16>(offset, self.pacid_capability.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.pacid_control.into(), endian)?;
+        Ok(*offset)
+    }
+}
 
 
 #[bitfield(bits = 16)]
@@ -43,29 +52,50 @@ pub struct PacidCapabilityProto {
 
 /// PASID Capability
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PacidCapability {
+    /// Reserved bit 0
+    pub rsvdp: u8,
     /// Execute Permission Supported
     pub execute_permission_supported: bool,
     /// Privileged Mode Supported
     pub privileged_mode_supported: bool,
+    /// Reserved bits 7:3
+    pub rsvdp_2: u8,
     /// Max PASID Width
     pub max_pasid_width: u8,
+    /// Reserved bits 15:13
+    pub rsvdp_3: u8,
 }
 impl From<PacidCapabilityProto> for PacidCapability {
     fn from(proto: PacidCapabilityProto) -> Self {
-        let _ = proto.rsvdp();
-        let _ = proto.rsvdp_2();
-        let _ = proto.rsvdp_3();
         Self {
+            rsvdp: proto.rsvdp(),
             execute_permission_supported: proto.execute_permission_supported(),
             privileged_mode_supported: proto.privileged_mode_supported(),
+            rsvdp_2: proto.rsvdp_2(),
             max_pasid_width: proto.max_pasid_width(),
+            rsvdp_3: proto.rsvdp_3(),
         }
     }
 }
 impl From<u16> for PacidCapability {
     fn from(word: u16) -> Self { PacidCapabilityProto::from(word).into() }
 }
+impl From<PacidCapability> for PacidCapabilityProto {
+    fn from(data: PacidCapability) -> Self {
+        Self::new()
+            .with_rsvdp(data.rsvdp)
+            .with_execute_permission_supported(data.execute_permission_supported)
+            .with_privileged_mode_supported(data.privileged_mode_supported)
+            .with_rsvdp_2(data.rsvdp_2)
+            .with_max_pasid_width(data.max_pasid_width)
+            .with_rsvdp_3(data.rsvdp_3)
+    }
+}
+impl From<PacidCapability> for u16 {
+    fn from(data: PacidCapability) -> Self { PacidCapabilityProto::from(data).into() }
+}
 
 
 #[bitfield(bits = 16)]
@@ -79,6 +109,7 @@ pub struct PacidControlProto {
 
 /// PASID Control
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PacidControl {
     /// PASID Enable
     pub pasid_enable: bool,
@@ -86,18 +117,32 @@ pub struct PacidControl {
     pub execute_permission_enable: bool,
     /// Privileged Mode Enable
     pub privileged_mode_enable: bool,
+    /// Reserved bits 15:3
+    pub rsvdp: u16,
 }
 impl From<PacidControlProto> for PacidControl {
     fn from(proto: PacidControlProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             pasid_enable: proto.pasid_enable(),
             execute_permission_enable: proto.execute_permission_enable(),
             privileged_mode_enable: proto.privileged_mode_enable(),
+            rsvdp: proto.rsvdp(),
         }
     }
 }
 impl From<u16> for PacidControl {
     fn from(word: u16) -> Self { PacidControlProto::from(word).into() }
 }
+impl From<PacidControl> for PacidControlProto {
+    fn from(data: PacidControl) -> Self {
+        Self::new()
+            .with_pasid_enable(data.pasid_enable)
+            .with_execute_permission_enable(data.execute_permission_enable)
+            .with_privileged_mode_enable(data.privileged_mode_enable)
+            .with_rsvdp(data.rsvdp)
+    }
+}
+impl From<PacidControl> for u16 {
+    fn from(data: PacidControl) -> Self { PacidControlProto::from(data).into() }
+}
 
@@ -0,0 +1,348 @@
+//! Resizable BAR
+//!
+//! The Resizable BAR Capability is an optional capability that allows hardware to communicate
+//! resource sizes, and system software, after determining the optimal size, to communicate this
+//! optimal size back to the hardware. Capability contains from one to six entries, each consists
+//! of Resizable BAR Capability and Resizable BAR Control registers.
+
+use modular_bitfield::prelude::*;
+use byte::{
+    ctx::*,
+    self,
+    TryRead,
+    TryWrite,
+    BytesExt,
+};
+
+use crate::RawBytes;
+
+
+/// Size of Resizable BAR Capability and Resizable BAR Control registers pair
+const ENTRY_BYTES: usize = 8;
+
+/// Resizable BAR Extended Capability structure
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResizableBar<'a> {
+    /// Raw entries, see [ResizableBar::entries]
+    pub entries_data: RawBytes<'a>,
+}
+impl<'a> ResizableBar<'a> {
+    pub fn entries(&self) -> Entries<'_> {
+        Entries::new(&self.entries_data)
+    }
+}
+impl<'a> TryRead<'a, Endian> for ResizableBar<'a> {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let first: Entry = bytes.read_with(&mut 0, endian)?;
+        // Number of Resizable BARs is valid in the first Resizable BAR Control register only
+        let count = first.control.number_of_resizable_bars.max(1);
+        for _ in 0..count {
+            let _: Entry = bytes.read_with(offset, endian)?;
+        }
+        let rebar = ResizableBar { entries_data: RawBytes::new(&bytes[..*offset]) };
+        Ok((rebar, *offset))
+    }
+}
+impl<'a> TryWrite<Endian> for ResizableBar<'a> {
+    fn try_write(self, bytes: &mut [u8], _: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write::<&[u8]>(offset, &self.entries_data)?;
+        Ok(*offset)
+    }
+}
+
+
+/// An iterator through Resizable BAR entries
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entries<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+impl<'a> Entries<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+}
+impl<'a> Iterator for Entries<'a> {
+    type Item = Entry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.data.read_with(&mut self.offset, LE).ok()
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.data.len() - self.offset) / ENTRY_BYTES;
+        (len, Some(len))
+    }
+}
+impl<'a> ExactSizeIterator for Entries<'a> {}
+
+
+/// Resizable BAR Capability and Control registers of a single BAR
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Entry {
+    pub capability: Capability,
+    pub control: Control,
+}
+impl Entry {
+    /// Bitmap of all supported sizes, bit 0 – 1 MB, bit 1 – 2 MB, … bit 43 – 8 EB
+    pub fn supported_sizes_bitmap(&self) -> u64 {
+        (u64::from(self.control.upper_sizes) << 28) | u64::from(self.capability.sizes)
+    }
+    /// Supported BAR sizes in bytes, in ascending order
+    pub fn supported_sizes(&self) -> impl Iterator<Item = u64> {
+        let bitmap = self.supported_sizes_bitmap();
+        (0..44).filter(move |n| bitmap & (1 << n) != 0).map(|n| BarSize(n).bytes())
+    }
+}
+impl<'a> TryRead<'a, Endian> for Entry {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let entry = Entry {
+            capability: bytes.read_with::<u32>(offset, endian)?.into(),
+            control: bytes.read_with::<u32>(offset, endian)?.into(),
+        };
+        Ok((entry, *offset))
+    }
+}
+impl TryWrite<Endian> for Entry {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        let bad_input = |err| move |_| byte::Error::BadInput { err };
+        let capability = CapabilityProto::new()
+            .with_rsvdp_checked(self.capability.rsvdp)
+            .map_err(bad_input("Resizable BAR Capability reserved bits do not fit in 4 bits"))?
+            .with_sizes_checked(self.capability.sizes)
+            .map_err(bad_input("Resizable BAR Capability sizes do not fit in 28 bits"))?;
+        let Control { bar_index, rsvdp, number_of_resizable_bars, bar_size, rsvdp_1, upper_sizes } =
+            self.control;
+        let control = ControlProto::new()
+            .with_bar_index_checked(bar_index)
+            .map_err(bad_input("BAR Index does not fit in 3 bits"))?
+            .with_rsvdp_checked(rsvdp)
+            .map_err(bad_input("Resizable BAR Control reserved bits 4:3 do not fit in 2 bits"))?
+            .with_number_of_resizable_bars_checked(number_of_resizable_bars)
+            .map_err(bad_input("Number of Resizable BARs does not fit in 3 bits"))?
+            .with_bar_size_checked(bar_size.0)
+            .map_err(bad_input("BAR Size does not fit in 6 bits"))?
+            .with_rsvdp_1_checked(rsvdp_1)
+            .map_err(bad_input("Resizable BAR Control reserved bits 15:14 do not fit in 2 bits"))?
+            .with_upper_sizes(upper_sizes);
+        bytes.write_with::<u32>(offset, capability.into(), endian)?;
+        bytes.write_with::<u32>(offset, control.into(), endian)?;
+        Ok(*offset)
+    }
+}
+
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct CapabilityProto {
+    rsvdp: B4,
+    sizes: B28,
+}
+
+/// Resizable BAR Capability
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Capability {
+    /// Reserved bits 3:0
+    pub rsvdp: u8,
+    /// Bitmap of supported sizes from 1 MB (bit 0) to 128 TB (bit 27)
+    pub sizes: u32,
+}
+impl From<CapabilityProto> for Capability {
+    fn from(proto: CapabilityProto) -> Self {
+        Self {
+            rsvdp: proto.rsvdp(),
+            sizes: proto.sizes(),
+        }
+    }
+}
+impl From<u32> for Capability {
+    fn from(dword: u32) -> Self { CapabilityProto::from(dword).into() }
+}
+impl From<Capability> for CapabilityProto {
+    fn from(data: Capability) -> Self {
+        Self::new()
+            .with_rsvdp(data.rsvdp)
+            .with_sizes(data.sizes)
+    }
+}
+impl From<Capability> for u32 {
+    fn from(data: Capability) -> Self { CapabilityProto::from(data).into() }
+}
+
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct ControlProto {
+    bar_index: B3,
+    rsvdp: B2,
+    number_of_resizable_bars: B3,
+    bar_size: B6,
+    rsvdp_1: B2,
+    upper_sizes: u16,
+}
+
+/// Resizable BAR Control
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Control {
+    /// BAR Index, 0 – BAR located at offset 10h, 1 – BAR located at offset 14h, etc.
+    pub bar_index: u8,
+    /// Reserved bits 4:3
+    pub rsvdp: u8,
+    /// Number of Resizable BARs in the capability structure, valid in the first entry only
+    pub number_of_resizable_bars: u8,
+    /// Current size of the BAR
+    pub bar_size: BarSize,
+    /// Reserved bits 15:14
+    pub rsvdp_1: u8,
+    /// Bitmap of supported sizes from 256 TB (bit 0) to 8 EB (bit 15)
+    pub upper_sizes: u16,
+}
+impl From<ControlProto> for Control {
+    fn from(proto: ControlProto) -> Self {
+        Self {
+            bar_index: proto.bar_index(),
+            rsvdp: proto.rsvdp(),
+            number_of_resizable_bars: proto.number_of_resizable_bars(),
+            bar_size: BarSize(proto.bar_size()),
+            rsvdp_1: proto.rsvdp_1(),
+            upper_sizes: proto.upper_sizes(),
+        }
+    }
+}
+impl From<u32> for Control {
+    fn from(dword: u32) -> Self { ControlProto::from(dword).into() }
+}
+impl From<Control> for ControlProto {
+    fn from(data: Control) -> Self {
+        Self::new()
+            .with_bar_index(data.bar_index)
+            .with_rsvdp(data.rsvdp)
+            .with_number_of_resizable_bars(data.number_of_resizable_bars)
+            .with_bar_size(data.bar_size.0)
+            .with_rsvdp_1(data.rsvdp_1)
+            .with_upper_sizes(data.upper_sizes)
+    }
+}
+impl From<Control> for u32 {
+    fn from(data: Control) -> Self { ControlProto::from(data).into() }
+}
+
+/// Encoded BAR size: 0 – 1 MB, 1 – 2 MB, … 43 – 8 EB
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BarSize(pub u8);
+impl BarSize {
+    /// Size in bytes, encodings above 43 are reserved and saturate to 8 EB
+    pub fn bytes(&self) -> u64 {
+        1 << (self.0.min(43) + 20)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use pretty_assertions::assert_eq;
+    use super::*;
+
+    // BAR 0: current size: 256MB, supported: 256MB 512MB 1GB 2GB 4GB 8GB 16GB
+    // BAR 2: current size: 32MB, supported: 32MB 512TB
+    const DATA: [u8; 16] = [
+        0x00, 0xf0, 0x07, 0x00, 0x40, 0x08, 0x00, 0x00,
+        0x00, 0x02, 0x00, 0x00, 0x02, 0x05, 0x02, 0x00,
+    ];
+
+    #[test]
+    fn parse_full_struct() {
+        let (result, len) = ResizableBar::try_read(&DATA, LE).unwrap();
+        assert_eq!(16, len);
+        let entries = result.entries().collect::<Vec<_>>();
+        let sample = vec![
+            Entry {
+                capability: Capability { rsvdp: 0, sizes: 0x7f00 },
+                control: Control {
+                    bar_index: 0,
+                    number_of_resizable_bars: 2,
+                    bar_size: BarSize(8),
+                    upper_sizes: 0,
+                    rsvdp: 0,
+                    rsvdp_1: 0,
+                },
+            },
+            Entry {
+                capability: Capability { rsvdp: 0, sizes: 0x20 },
+                control: Control {
+                    bar_index: 2,
+                    number_of_resizable_bars: 0,
+                    bar_size: BarSize(5),
+                    upper_sizes: 0x02,
+                    rsvdp: 0,
+                    rsvdp_1: 0,
+                },
+            },
+        ];
+        assert_eq!(sample, entries);
+
+        let mut buf = [0u8; 16];
+        buf.write_with(&mut 0, result, LE).unwrap();
+        assert_eq!(DATA, buf);
+    }
+
+    #[test]
+    fn supported_sizes() {
+        let result: ResizableBar = DATA.read_with(&mut 0, LE).unwrap();
+        let sizes = result.entries()
+            .map(|entry| (entry.control.bar_size.bytes(), entry.supported_sizes().collect()))
+            .collect::<Vec<(u64, Vec<u64>)>>();
+        let sample = vec![
+            (256 << 20, (28..=34).map(|n| 1 << n).collect()),
+            (32 << 20, vec![32 << 20, 512 << 40]),
+        ];
+        assert_eq!(sample, sizes);
+        assert_eq!(8 << 60, BarSize(43).bytes());
+    }
+
+    #[test]
+    fn reserved_bits() {
+        let mut data = DATA;
+        data[0] = 0x0a;
+        let result: ResizableBar = data.read_with(&mut 0, LE).unwrap();
+        let entry = result.entries().next().unwrap();
+        assert_eq!(Capability { rsvdp: 0xa, sizes: 0x7f00 }, entry.capability);
+        let mut buf = [0u8; 8];
+        buf.write_with(&mut 0, entry, LE).unwrap();
+        assert_eq!(data[..8], buf);
+    }
+
+    #[test]
+    fn write_out_of_range() {
+        let entry: Entry = DATA.read_with(&mut 0, LE).unwrap();
+        let mut buf = [0u8; 8];
+        let mut control = Control { number_of_resizable_bars: 8, ..entry.control.clone() };
+        let result = buf.write_with(&mut 0, Entry { control: control.clone(), ..entry.clone() }, LE);
+        let err = "Number of Resizable BARs does not fit in 3 bits";
+        assert_eq!(Err(byte::Error::BadInput { err }), result);
+        control.number_of_resizable_bars = 7;
+        control.bar_size = BarSize(64);
+        let result = buf.write_with(&mut 0, Entry { control, ..entry.clone() }, LE);
+        assert_eq!(Err(byte::Error::BadInput { err: "BAR Size does not fit in 6 bits" }), result);
+        let capability = Capability { rsvdp: 0, sizes: 1 << 28 };
+        let result = buf.write_with(&mut 0, Entry { capability, ..entry }, LE);
+        let err = "Resizable BAR Capability sizes do not fit in 28 bits";
+        assert_eq!(Err(byte::Error::BadInput { err }), result);
+    }
+
+    #[test]
+    fn truncated() {
+        let result = ResizableBar::try_read(&DATA[..12], LE);
+        assert_eq!(Err(byte::Error::Incomplete), result);
+    }
+}
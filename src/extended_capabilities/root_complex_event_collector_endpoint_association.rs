@@ -0,0 +1,200 @@
+//! Root Complex Event Collector Endpoint Association
+//!
+//! The Root Complex Event Collector Endpoint Association Capability is implemented by Root Complex
+//! Event Collectors (RCECs). It declares the RCiEPs supported by the RCEC on the same Logical Bus
+//! on which the RCEC is located and, since version 2, additional Logical Busses on which RCiEPs
+//! are associated with the RCEC.
+
+use byte::{
+    ctx::*,
+    self,
+    TryRead,
+    TryWrite,
+    BytesExt,
+};
+
+use crate::{
+    address::Address,
+    capabilities::pci_express::DeviceType,
+};
+
+
+/// Context for reading [RootComplexEventCollectorEndpointAssociation]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RootComplexEventCollectorEndpointAssociationCtx {
+    pub endian: Endian,
+    /// Capability Version of the Extended Capability Header
+    pub version: u8,
+}
+
+/// Root Complex Event Collector Endpoint Association Extended Capability structure
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RootComplexEventCollectorEndpointAssociation {
+    /// Association Bitmap for RCiEPs, bit N is set for the RCiEP with Device Number N on the same
+    /// bus as the RCEC
+    pub association_bitmap_for_rcieps: u32,
+    /// RCEC Associated Bus Numbers, present in Capability Version 2h and later
+    pub associated_bus_numbers: Option<AssociatedBusNumbers>,
+}
+impl RootComplexEventCollectorEndpointAssociation {
+    /// Whether errors of `rciep` are collected by the RCEC located at `rcec`
+    pub fn is_associated(&self, rcec: &Address, rciep: &Address) -> bool {
+        if rcec.domain != rciep.domain || rcec == rciep {
+            false
+        } else if rcec.bus == rciep.bus {
+            rciep.device <= Address::DEVICE_MAX
+                && self.association_bitmap_for_rcieps & (1 << rciep.device) != 0
+        } else {
+            self.associated_bus_numbers.as_ref()
+                .map(|abn| abn.contains(rciep.bus))
+                .unwrap_or(false)
+        }
+    }
+    /// RCiEPs among `devices` whose errors are collected by the RCEC located at `rcec`
+    pub fn associated_rcieps<'s, I>(
+        &'s self,
+        rcec: Address,
+        devices: I,
+    ) -> impl Iterator<Item = Address> + 's
+    where
+        I: IntoIterator<Item = (Address, DeviceType)>,
+        I::IntoIter: 's,
+    {
+        devices.into_iter()
+            .filter(|(_, device_type)| *device_type == DeviceType::RootComplexIntegratedEndpoint)
+            .map(|(address, _)| address)
+            .filter(move |address| self.is_associated(&rcec, address))
+    }
+}
+impl<'a> TryRead<'a, RootComplexEventCollectorEndpointAssociationCtx>
+    for RootComplexEventCollectorEndpointAssociation
+{
+    fn try_read(
+        bytes: &'a [u8],
+        ctx: RootComplexEventCollectorEndpointAssociationCtx,
+    ) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let association_bitmap_for_rcieps = bytes.read_with::<u32>(offset, ctx.endian)?;
+        let associated_bus_numbers = if ctx.version >= 2 {
+            Some(bytes.read_with::<u32>(offset, ctx.endian)?.into())
+        } else {
+            None
+        };
+        let rcec_ea = RootComplexEventCollectorEndpointAssociation {
+            association_bitmap_for_rcieps,
+            associated_bus_numbers,
+        };
+        Ok((rcec_ea, *offset))
+    }
+}
+impl TryWrite<Endian> for RootComplexEventCollectorEndpointAssociation {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u32>(offset, self.association_bitmap_for_rcieps, endian)?;
+        if let Some(abn) = self.associated_bus_numbers {
+            bytes.write_with::<u32>(offset, abn.into(), endian)?;
+        }
+        Ok(*offset)
+    }
+}
+
+/// RCEC Associated Bus Numbers
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AssociatedBusNumbers {
+    /// Reserved bits 7:0
+    pub rsvdp: u8,
+    /// RCEC Next Bus, first additional Logical Bus with associated RCiEPs
+    pub next_bus: u8,
+    /// RCEC Last Bus, last additional Logical Bus with associated RCiEPs
+    pub last_bus: u8,
+    /// Reserved bits 31:24
+    pub rsvdp_2: u8,
+}
+impl AssociatedBusNumbers {
+    /// RCiEPs on `bus` are associated, there are no additional busses if Next Bus is greater
+    /// than Last Bus
+    pub fn contains(&self, bus: u8) -> bool {
+        (self.next_bus..=self.last_bus).contains(&bus)
+    }
+}
+impl From<u32> for AssociatedBusNumbers {
+    fn from(dword: u32) -> Self {
+        Self {
+            rsvdp: dword as u8,
+            next_bus: (dword >> 8) as u8,
+            last_bus: (dword >> 16) as u8,
+            rsvdp_2: (dword >> 24) as u8,
+        }
+    }
+}
+impl From<AssociatedBusNumbers> for u32 {
+    fn from(data: AssociatedBusNumbers) -> Self {
+        (u32::from(data.rsvdp_2) << 24)
+            | (u32::from(data.last_bus) << 16)
+            | (u32::from(data.next_bus) << 8)
+            | u32::from(data.rsvdp)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use pretty_assertions::assert_eq;
+    use super::*;
+
+    // RCEC at 00:0f.0 associated with devices 02h, 04h and busses 80h..=81h
+    const DATA: [u8; 8] = [0x14, 0x00, 0x00, 0x00, 0x00, 0x80, 0x81, 0x00];
+
+    #[test]
+    fn parse_full_struct() {
+        let ctx = RootComplexEventCollectorEndpointAssociationCtx { endian: LE, version: 2 };
+        let (result, len) =
+            RootComplexEventCollectorEndpointAssociation::try_read(&DATA, ctx).unwrap();
+        let sample = RootComplexEventCollectorEndpointAssociation {
+            association_bitmap_for_rcieps: 0x14,
+            associated_bus_numbers: Some(AssociatedBusNumbers {
+                rsvdp: 0,
+                next_bus: 0x80,
+                last_bus: 0x81,
+                rsvdp_2: 0,
+            }),
+        };
+        assert_eq!((sample, 8), (result.clone(), len));
+
+        let mut buf = [0u8; 8];
+        buf.write_with(&mut 0, result, LE).unwrap();
+        assert_eq!(DATA, buf);
+
+        let ctx = RootComplexEventCollectorEndpointAssociationCtx { version: 1, ..ctx };
+        let (result, len) =
+            RootComplexEventCollectorEndpointAssociation::try_read(&DATA, ctx).unwrap();
+        assert_eq!((None, 4), (result.associated_bus_numbers, len));
+    }
+
+    #[test]
+    fn associated_rcieps() {
+        let ctx = RootComplexEventCollectorEndpointAssociationCtx { endian: LE, version: 2 };
+        let rcec_ea: RootComplexEventCollectorEndpointAssociation =
+            DATA.read_with(&mut 0, ctx).unwrap();
+        let rcec: Address = "00:0f.0".parse().unwrap();
+        let devices = [
+            ("00:00.0", DeviceType::RootComplexIntegratedEndpoint),
+            ("00:02.0", DeviceType::RootComplexIntegratedEndpoint),
+            ("00:04.3", DeviceType::RootComplexIntegratedEndpoint),
+            ("00:04.4", DeviceType::RootPort),
+            ("00:0f.0", DeviceType::RootComplexEventCollector),
+            ("0001:00:02.0", DeviceType::RootComplexIntegratedEndpoint),
+            ("7f:00.0", DeviceType::RootComplexIntegratedEndpoint),
+            ("81:1f.7", DeviceType::RootComplexIntegratedEndpoint),
+            ("82:00.0", DeviceType::RootComplexIntegratedEndpoint),
+        ].map(|(address, device_type)| (address.parse().unwrap(), device_type));
+        let result = rcec_ea.associated_rcieps(rcec, devices)
+            .map(|address| address.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["0000:00:02.0", "0000:00:04.3", "0000:81:1f.7"], result);
+    }
+}
@@ -0,0 +1,81 @@
+//! Root Complex Internal Link Control
+//!
+//! The Root Complex Internal Link Control Capability is an optional Capability that controls an
+//! internal Root Complex link between two distinct Root Complex Components. Its registers mirror
+//! Link Capabilities, Link Control and Link Status registers of the
+//! [PCI Express Capability](crate::capabilities::pci_express).
+
+use byte::{
+    ctx::*,
+    self,
+    TryRead,
+    TryWrite,
+    BytesExt,
+};
+
+use crate::capabilities::pci_express::{LinkCapabilities, LinkControl, LinkStatus};
+
+
+/// Root Complex Internal Link Control Extended Capability structure
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RootComplexInternalLinkControl {
+    /// Root Complex Link Capabilities, Port Number is reserved
+    pub link_capabilities: LinkCapabilities,
+    /// Root Complex Link Control
+    pub link_control: LinkControl,
+    /// Root Complex Link Status
+    pub link_status: LinkStatus,
+}
+impl<'a> TryRead<'a, Endian> for RootComplexInternalLinkControl {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let rcilc = RootComplexInternalLinkControl {
+            link_capabilities: bytes.read_with::<u32>(offset, endian)?.into(),
+            link_control: bytes.read_with::<u16>(offset, endian)?.into(),
+            link_status: bytes.read_with::<u16>(offset, endian)?.into(),
+        };
+        Ok((rcilc, *offset))
+    }
+}
+impl TryWrite<Endian> for RootComplexInternalLinkControl {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u32>(offset, self.link_capabilities.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.link_control.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.link_status.into(), endian)?;
+        Ok(*offset)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use crate::capabilities::pci_express::{
+        ActiveStatePowerManagement,
+        L0sExitLatency,
+        L1ExitLatency,
+    };
+    use super::*;
+
+    #[test]
+    fn parse_full_struct() {
+        // ASPM L0s L1, L0s < 1us, L1 < 16us; ASPM L1 Enabled; Speed 8GT/s, Width x4
+        let data = [0x43, 0x4c, 0x02, 0x00, 0x02, 0x00, 0x43, 0x00];
+        let result: RootComplexInternalLinkControl = data.read_with(&mut 0, LE).unwrap();
+        let caps = &result.link_capabilities;
+        assert_eq!(
+            ActiveStatePowerManagement::L0sAndL1,
+            caps.active_state_power_management_support
+        );
+        assert_eq!(L0sExitLatency::Ge512nsAndLt1us, caps.l0s_exit_latency);
+        assert_eq!(L1ExitLatency::Ge8usAndLt16us, caps.l1_exit_latency);
+        assert_eq!(0x0002, u16::from(result.link_control.clone()));
+
+        let mut buf = [0u8; 8];
+        buf.write_with(&mut 0, result, LE).unwrap();
+        assert_eq!(data, buf);
+    }
+}
@@ -0,0 +1,418 @@
+//! Root Complex Link Declaration
+//!
+//! The PCI Express Root Complex Link Declaration Capability is an optional Capability that is
+//! permitted to be implemented by Root Ports, RCiEPs, or RCRBs to declare a Root Complex’s internal
+//! topology. Each element (Root Port, RCiEP or RCRB) describes itself and declares links to other
+//! elements with Link Entries.
+
+use modular_bitfield::prelude::*;
+use byte::{
+    ctx::*,
+    self,
+    TryRead,
+    TryWrite,
+    BytesExt,
+};
+
+use crate::RawBytes;
+
+
+/// Link Description, reserved DWORD and Link Address
+const LINK_ENTRY_BYTES: usize = 16;
+
+/// Root Complex Link Declaration Extended Capability structure
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RootComplexLinkDeclaration<'a> {
+    pub element_self_description: ElementSelfDescription,
+    /// Reserved registers at 08h – 0Fh, Link Entries start at 10h
+    pub reserved: u64,
+    /// Raw Link Entries, see [RootComplexLinkDeclaration::link_entries]
+    pub link_entries_data: RawBytes<'a>,
+}
+impl<'a> RootComplexLinkDeclaration<'a> {
+    pub fn link_entries(&self) -> LinkEntries<'_> {
+        LinkEntries::new(&self.link_entries_data)
+    }
+}
+impl<'a> TryRead<'a, Endian> for RootComplexLinkDeclaration<'a> {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let element_self_description: ElementSelfDescription =
+            bytes.read_with::<u32>(offset, endian)?.into();
+        let reserved = bytes.read_with::<u64>(offset, endian)?;
+        let len = usize::from(element_self_description.number_of_link_entries) * LINK_ENTRY_BYTES;
+        let rcld = RootComplexLinkDeclaration {
+            element_self_description,
+            reserved,
+            link_entries_data: RawBytes::new(bytes.read_with::<&[u8]>(offset, Bytes::Len(len))?),
+        };
+        Ok((rcld, *offset))
+    }
+}
+impl<'a> TryWrite<Endian> for RootComplexLinkDeclaration<'a> {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let len = usize::from(self.element_self_description.number_of_link_entries)
+            * LINK_ENTRY_BYTES;
+        if self.link_entries_data.len() != len {
+            return Err(byte::Error::BadInput { err: "Number of Link Entries does not match data" });
+        }
+        let offset = &mut 0;
+        bytes.write_with::<u32>(offset, self.element_self_description.into(), endian)?;
+        bytes.write_with::<u64>(offset, self.reserved, endian)?;
+        bytes.write::<&[u8]>(offset, &self.link_entries_data)?;
+        Ok(*offset)
+    }
+}
+
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct ElementSelfDescriptionProto {
+    element_type: B4,
+    rsvdp: B4,
+    number_of_link_entries: u8,
+    component_id: u8,
+    port_number: u8,
+}
+
+/// Element Self Description
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ElementSelfDescription {
+    pub element_type: ElementType,
+    /// Reserved bits 7:4
+    pub rsvdp: u8,
+    /// Number of Link Entries following the Element Self Description
+    pub number_of_link_entries: u8,
+    /// Component ID of the Root Complex Component containing this element
+    pub component_id: u8,
+    /// Port Number associated with this element with respect to the component that contains it
+    pub port_number: u8,
+}
+impl From<ElementSelfDescriptionProto> for ElementSelfDescription {
+    fn from(proto: ElementSelfDescriptionProto) -> Self {
+        Self {
+            element_type: proto.element_type().into(),
+            rsvdp: proto.rsvdp(),
+            number_of_link_entries: proto.number_of_link_entries(),
+            component_id: proto.component_id(),
+            port_number: proto.port_number(),
+        }
+    }
+}
+impl From<u32> for ElementSelfDescription {
+    fn from(dword: u32) -> Self { ElementSelfDescriptionProto::from(dword).into() }
+}
+impl From<ElementSelfDescription> for ElementSelfDescriptionProto {
+    fn from(data: ElementSelfDescription) -> Self {
+        Self::new()
+            .with_element_type(data.element_type.into())
+            .with_rsvdp(data.rsvdp)
+            .with_number_of_link_entries(data.number_of_link_entries)
+            .with_component_id(data.component_id)
+            .with_port_number(data.port_number)
+    }
+}
+impl From<ElementSelfDescription> for u32 {
+    fn from(data: ElementSelfDescription) -> Self { ElementSelfDescriptionProto::from(data).into() }
+}
+
+/// Type of the element
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ElementType {
+    /// Configuration Space Element
+    ConfigurationSpaceElement,
+    /// System Egress Port or internal sink (memory)
+    SystemEgressPortOrInternalSink,
+    /// Internal Root Complex Link
+    InternalRootComplexLink,
+    Reserved(u8),
+}
+impl From<u8> for ElementType {
+    fn from(byte: u8) -> Self {
+        match byte {
+            0x0 => Self::ConfigurationSpaceElement,
+            0x1 => Self::SystemEgressPortOrInternalSink,
+            0x2 => Self::InternalRootComplexLink,
+            v => Self::Reserved(v),
+        }
+    }
+}
+impl From<ElementType> for u8 {
+    fn from(data: ElementType) -> Self {
+        match data {
+            ElementType::ConfigurationSpaceElement => 0x0,
+            ElementType::SystemEgressPortOrInternalSink => 0x1,
+            ElementType::InternalRootComplexLink => 0x2,
+            ElementType::Reserved(v) => v,
+        }
+    }
+}
+
+
+/// An iterator through Link Entries
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkEntries<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+impl<'a> LinkEntries<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+}
+impl<'a> Iterator for LinkEntries<'a> {
+    type Item = LinkEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.data.read_with(&mut self.offset, LE).ok()
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.data.len() - self.offset) / LINK_ENTRY_BYTES;
+        (len, Some(len))
+    }
+}
+impl<'a> ExactSizeIterator for LinkEntries<'a> {}
+
+/// Link Entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LinkEntry {
+    pub link_description: LinkDescription,
+    /// Reserved register following the Link Description
+    pub reserved: u32,
+    /// Link Address, its format depends on Link Type
+    pub link_address: LinkAddress,
+}
+impl<'a> TryRead<'a, Endian> for LinkEntry {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let link_description: LinkDescriptionProto = bytes.read_with::<u32>(offset, endian)?.into();
+        let reserved = bytes.read_with::<u32>(offset, endian)?;
+        let address = bytes.read_with::<u64>(offset, endian)?;
+        let link_address = if link_description.link_type() {
+            LinkAddress::ConfigurationSpace(address.into())
+        } else {
+            LinkAddress::MemoryMappedSpace(address)
+        };
+        let entry = LinkEntry {
+            link_description: link_description.into(),
+            reserved,
+            link_address,
+        };
+        Ok((entry, *offset))
+    }
+}
+impl TryWrite<Endian> for LinkEntry {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let (link_type, address) = match self.link_address {
+            LinkAddress::MemoryMappedSpace(address) => (false, address),
+            LinkAddress::ConfigurationSpace(address) => (true, address.into()),
+        };
+        let link_description = LinkDescriptionProto::from(self.link_description)
+            .with_link_type(link_type);
+        let offset = &mut 0;
+        bytes.write_with::<u32>(offset, link_description.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.reserved, endian)?;
+        bytes.write_with::<u64>(offset, address, endian)?;
+        Ok(*offset)
+    }
+}
+
+
+#[bitfield(bits = 32)]
+#[repr(u32)]
+pub struct LinkDescriptionProto {
+    link_valid: bool,
+    link_type: bool,
+    associate_rcrb_header: bool,
+    rsvdp: B13,
+    target_component_id: u8,
+    target_port_number: u8,
+}
+
+/// Link Description
+///
+/// Link Type is represented by the [LinkAddress] variant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LinkDescription {
+    /// Link Entry specifies a valid link
+    pub link_valid: bool,
+    /// Link Entry specifies a link to an RCRB with Link Type set to configuration space
+    pub associate_rcrb_header: bool,
+    /// Reserved bits 15:3
+    pub rsvdp: u16,
+    /// Component ID of the component on the other side of the link
+    pub target_component_id: u8,
+    /// Port Number associated with the element targeted by this link entry
+    pub target_port_number: u8,
+}
+impl From<LinkDescriptionProto> for LinkDescription {
+    fn from(proto: LinkDescriptionProto) -> Self {
+        Self {
+            link_valid: proto.link_valid(),
+            associate_rcrb_header: proto.associate_rcrb_header(),
+            rsvdp: proto.rsvdp(),
+            target_component_id: proto.target_component_id(),
+            target_port_number: proto.target_port_number(),
+        }
+    }
+}
+impl From<LinkDescription> for LinkDescriptionProto {
+    fn from(data: LinkDescription) -> Self {
+        Self::new()
+            .with_link_valid(data.link_valid)
+            .with_link_type(false)
+            .with_associate_rcrb_header(data.associate_rcrb_header)
+            .with_rsvdp(data.rsvdp)
+            .with_target_component_id(data.target_component_id)
+            .with_target_port_number(data.target_port_number)
+    }
+}
+
+/// Link Address
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LinkAddress {
+    /// Memory-mapped base address of the RCRB, 4 KB aligned
+    MemoryMappedSpace(u64),
+    /// Configuration space of a Root Port or RCiEP accessed through the Enhanced Configuration
+    /// Access Mechanism
+    ConfigurationSpace(ConfigurationSpaceAddress),
+}
+
+/// Link Address in configuration space
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConfigurationSpaceAddress {
+    /// N, number of bits in the Bus Number field, from 1 to 8
+    pub bus_number_bits: u8,
+    /// Reserved bits 11:3
+    pub rsvdp: u16,
+    pub bus: u8,
+    pub device: u8,
+    pub function: u8,
+    /// Enhanced Configuration Space base address, aligned to 2 ^ (N + 20) bytes
+    pub base_address: u64,
+}
+impl From<u64> for ConfigurationSpaceAddress {
+    fn from(qword: u64) -> Self {
+        // 000b encodes 8 bits
+        let n = match (qword & 0b111) as u8 {
+            0 => 8,
+            n => n,
+        };
+        let base_shift = 20 + u32::from(n);
+        Self {
+            bus_number_bits: n,
+            rsvdp: ((qword >> 3) & 0x1ff) as u16,
+            bus: ((qword >> 20) & ((1 << n) - 1)) as u8,
+            device: ((qword >> 15) & 0x1f) as u8,
+            function: ((qword >> 12) & 0x07) as u8,
+            base_address: (qword >> base_shift) << base_shift,
+        }
+    }
+}
+impl From<ConfigurationSpaceAddress> for u64 {
+    fn from(data: ConfigurationSpaceAddress) -> Self {
+        data.base_address
+            | (u64::from(data.bus) << 20)
+            | (u64::from(data.device & 0x1f) << 15)
+            | (u64::from(data.function & 0x07) << 12)
+            | (u64::from(data.rsvdp & 0x1ff) << 3)
+            | u64::from(data.bus_number_bits & 0b111)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use pretty_assertions::assert_eq;
+    use super::*;
+
+    // Root Port of Intel platform: element links to the RCRB of the Egress Port and to the
+    // Root Complex Event Collector at 00:1f.7 with 256 bus ECAM at 0xe0000000
+    const DATA: [u8; 44] = [
+        0x00, 0x02, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x90, 0xd1, 0xfe, 0x00, 0x00, 0x00, 0x00,
+        0x03, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0xf0, 0x0f, 0xe0, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn parse_full_struct() {
+        let result: RootComplexLinkDeclaration = DATA.read_with(&mut 0, LE).unwrap();
+        let sample = ElementSelfDescription {
+            element_type: ElementType::ConfigurationSpaceElement,
+            number_of_link_entries: 2,
+            component_id: 1,
+            port_number: 2,
+            rsvdp: 0,
+        };
+        assert_eq!(sample, result.element_self_description);
+        let entries = result.link_entries().collect::<Vec<_>>();
+        let sample = vec![
+            LinkEntry {
+                link_description: LinkDescription {
+                    link_valid: true,
+                    associate_rcrb_header: false,
+                    target_component_id: 1,
+                    target_port_number: 0,
+                    rsvdp: 0,
+                },
+                link_address: LinkAddress::MemoryMappedSpace(0xfed19000),
+                reserved: 0,
+            },
+            LinkEntry {
+                link_description: LinkDescription {
+                    link_valid: true,
+                    associate_rcrb_header: false,
+                    target_component_id: 2,
+                    target_port_number: 0,
+                    rsvdp: 0,
+                },
+                link_address: LinkAddress::ConfigurationSpace(ConfigurationSpaceAddress {
+                    bus_number_bits: 8,
+                    bus: 0,
+                    device: 0x1f,
+                    function: 7,
+                    base_address: 0xe0000000,
+                    rsvdp: 0,
+                }),
+                reserved: 0,
+            },
+        ];
+        assert_eq!(sample, entries);
+
+        assert_eq!(0, result.reserved);
+        let mut buf = [0u8; 44];
+        buf.write_with(&mut 0, result, LE).unwrap();
+        assert_eq!(DATA, buf);
+
+        let mut buf = [0u8; 16];
+        buf.write_with(&mut 0, entries[1].clone(), LE).unwrap();
+        assert_eq!(DATA[28..], buf);
+    }
+
+    #[test]
+    fn configuration_space_address() {
+        // N = 2: 4 buses, base address aligned to 4 MB
+        let result: ConfigurationSpaceAddress = 0x1_2070_a002.into();
+        let sample = ConfigurationSpaceAddress {
+            bus_number_bits: 2,
+            bus: 3,
+            device: 1,
+            function: 2,
+            base_address: 0x1_2040_0000,
+            rsvdp: 0,
+        };
+        assert_eq!(sample, result);
+        assert_eq!(0x1_2070_a002, u64::from(result));
+    }
+}
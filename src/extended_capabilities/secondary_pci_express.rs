@@ -9,48 +9,68 @@
 
 use core::slice::Chunks;
 
+use derivative::Derivative;
 use modular_bitfield::prelude::*;
 use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
 use crate::capabilities::pci_express::{SupportedLinkSpeedsVector, TransmitterPreset, ReceiverPresetHint, LinkWidth};
 
+use crate::RawBytes;
+
 use super::ECH_BYTES;
 
 /// Lane Equalization Control offset
 pub const ECL_OFFSET: usize = 0x0C;
 
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Lane Equalization Control registers count depends on Maximum Link Width from PCI Express
+/// Capability, so they are neither serialized nor compared, deserialized capability has none
+#[derive(Derivative, Debug, Clone)]
+#[derivative(PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SecondaryPciExpress<'a> {
-    data: &'a [u8],
+    #[derivative(PartialEq="ignore")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    data: RawBytes<'a>,
     pub link_control_3: LinkControl3,
     pub lane_error_status: LaneErrorStatus,
 }
 impl<'a> SecondaryPciExpress<'a> {
-    pub fn equalization_control_lanes(&self, link_width: LinkWidth) -> EqualizationControlLanes<'a> {
+    pub fn equalization_control_lanes(&self, link_width: LinkWidth) -> EqualizationControlLanes<'_> {
         let start = ECL_OFFSET - ECH_BYTES;
         // One Lane Equalization Control 2 bytes width
-        let end = start + link_width.value() * 2;
-        EqualizationControlLanes::new(&self.data[start..end])
+        let len = link_width.value() * 2;
+        let data = self.data.get(start..).unwrap_or_default();
+        EqualizationControlLanes::new(&data[..len.min(data.len())])
     }
 }
 impl<'a> TryRead<'a, Endian> for SecondaryPciExpress<'a> {
     fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
         let offset = &mut 0;
         let spe = SecondaryPciExpress {
-            data: bytes,
+            data: RawBytes::new(bytes),
             link_control_3: bytes.read_with::<u32>(offset, endian)?.into(),
             lane_error_status: LaneErrorStatus(bytes.read_with::<u32>(offset, endian)?),
         };
         Ok((spe, *offset))
     }
 }
+/// Lane Equalization Control registers count depends on Maximum Link Width from PCI Express
+/// Capability and is not written
+impl<'a> TryWrite<Endian> for SecondaryPciExpress<'a> {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u32>(offset, self.link_control_3.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.lane_error_status.0, endian)?;
+        Ok(*offset)
+    }
+}
 
 
 #[bitfield(bits = 32)]
@@ -73,24 +93,27 @@ pub struct LinkControl3Proto {
 
 /// Link Control 3
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LinkControl3 {
     /// Perform Equalization
     pub perform_equalization: bool,
     /// Link Equalization Request Interrupt Enable
     pub link_equalization_request_interrupt_enable: bool,
+    /// Reserved bits 8:2
+    pub rsvdp: u8,
     /// Corresponding to the current Link speed is Set, SKP Ordered Sets are scheduled at the rate
     /// defined for SRNS, overriding the rate required based on the clock tolerance architecture.
     pub lower_skp_os_generation_vector: SupportedLinkSpeedsVector,
+    /// Reserved bits 31:16
+    pub rsvdp_3: u16,
 }
 impl From<LinkControl3Proto> for LinkControl3 {
     fn from(proto: LinkControl3Proto) -> Self {
-        let _ = proto.rsvdp();
-        let _ = proto.rsvdp_2();
-        let _ = proto.rsvdp_3();
         Self {
             perform_equalization: proto.perform_equalization(),
             link_equalization_request_interrupt_enable:
                 proto.link_equalization_request_interrupt_enable(),
+            rsvdp: proto.rsvdp(),
             lower_skp_os_generation_vector: SupportedLinkSpeedsVector {
                 speed_2_5_gtps: proto.speed_2_5_gtps(),
                 speed_5_0_gtps: proto.speed_5_0_gtps(),
@@ -98,17 +121,40 @@ impl From<LinkControl3Proto> for LinkControl3 {
                 speed_16_0_gtps: proto.speed_16_0_gtps(),
                 speed_32_0_gtps: proto.speed_32_0_gtps(),
                 speed_64_0_gtps: proto.speed_64_0_gtps(),
+                rsvdp: proto.rsvdp_2(),
             },
+            rsvdp_3: proto.rsvdp_3(),
         }
     }
 }
 impl From<u32> for LinkControl3 {
     fn from(dword: u32) -> Self { LinkControl3Proto::from(dword).into() }
 }
+impl From<LinkControl3> for LinkControl3Proto {
+    fn from(data: LinkControl3) -> Self {
+        let vector = data.lower_skp_os_generation_vector;
+        Self::new()
+            .with_perform_equalization(data.perform_equalization)
+            .with_link_equalization_request_interrupt_enable(data.link_equalization_request_interrupt_enable)
+            .with_rsvdp(data.rsvdp)
+            .with_speed_2_5_gtps(vector.speed_2_5_gtps)
+            .with_speed_5_0_gtps(vector.speed_5_0_gtps)
+            .with_speed_8_0_gtps(vector.speed_8_0_gtps)
+            .with_speed_16_0_gtps(vector.speed_16_0_gtps)
+            .with_speed_32_0_gtps(vector.speed_32_0_gtps)
+            .with_speed_64_0_gtps(vector.speed_64_0_gtps)
+            .with_rsvdp_2(vector.rsvdp)
+            .with_rsvdp_3(data.rsvdp_3)
+    }
+}
+impl From<LinkControl3> for u32 {
+    fn from(data: LinkControl3) -> Self { LinkControl3Proto::from(data).into() }
+}
 
 /// The Lane Error Status register consists of a 32-bit vector, where each bit indicates if the
 /// Lane with the corresponding Lane number detected an error.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LaneErrorStatus(pub u32);
 
 /// An iterator through Lane Equalization Controls
@@ -144,6 +190,7 @@ pub struct LaneEqualizationControlProto {
 /// The Lane Equalization Control register consists of control fields required for per-Lane 8.0
 /// GT/s equalization and the number of entries in this register are sized by Maximum Link Width
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LaneEqualizationControl {
     /// Downstream Port 8.0 GT/s Transmitter Preset
     downstream_port_transmitter_preset: TransmitterPreset,
@@ -153,22 +200,40 @@ pub struct LaneEqualizationControl {
     upstream_port_transmitter_preset: TransmitterPreset,
     /// Upstream Port 8.0 GT/s Receiver Preset Hint
     upstream_port_receiver_preset_hint: ReceiverPresetHint,
+    /// Reserved bit 7
+    rsvdz: u8,
+    /// Reserved bit 15
+    rsvdz_2: u8,
 }
 impl From<LaneEqualizationControlProto> for LaneEqualizationControl {
     fn from(proto: LaneEqualizationControlProto) -> Self {
-        let _ = proto.rsvdz();
-        let _ = proto.rsvdz_2();
         Self {
             downstream_port_transmitter_preset: proto.downstream_port_transmitter_preset().into(),
             downstream_port_receiver_preset_hint: proto.downstream_port_receiver_preset_hint().into(),
             upstream_port_transmitter_preset: proto.upstream_port_transmitter_preset().into(),
             upstream_port_receiver_preset_hint: proto.upstream_port_receiver_preset_hint().into(),
+            rsvdz: proto.rsvdz(),
+            rsvdz_2: proto.rsvdz_2(),
         }
     }
 }
 impl From<u16> for LaneEqualizationControl {
     fn from(word: u16) -> Self { LaneEqualizationControlProto::from(word).into() }
 }
+impl From<LaneEqualizationControl> for LaneEqualizationControlProto {
+    fn from(data: LaneEqualizationControl) -> Self {
+        Self::new()
+            .with_downstream_port_transmitter_preset(data.downstream_port_transmitter_preset.into())
+            .with_downstream_port_receiver_preset_hint(data.downstream_port_receiver_preset_hint.into())
+            .with_rsvdz(data.rsvdz)
+            .with_upstream_port_transmitter_preset(data.upstream_port_transmitter_preset.into())
+            .with_upstream_port_receiver_preset_hint(data.upstream_port_receiver_preset_hint.into())
+            .with_rsvdz_2(data.rsvdz_2)
+    }
+}
+impl From<LaneEqualizationControl> for u16 {
+    fn from(data: LaneEqualizationControl) -> Self { LaneEqualizationControlProto::from(data).into() }
+}
 
 #[cfg(test)]
 mod tests {
@@ -189,7 +254,7 @@ mod tests {
     fn lane_error_status() {
         let result = DATA[ECH_BYTES..].read_with::<SecondaryPciExpress>(&mut 0, LE).unwrap();
         let sample = SecondaryPciExpress {
-            data: &DATA[ECH_BYTES..],
+            data: RawBytes::new(&DATA[ECH_BYTES..]),
             link_control_3: LinkControl3 {
                 perform_equalization: false,
                 link_equalization_request_interrupt_enable: false,
@@ -200,7 +265,10 @@ mod tests {
                     speed_16_0_gtps: false,
                     speed_32_0_gtps: false,
                     speed_64_0_gtps: false,
+                    rsvdp: 0,
                 },
+                rsvdp: 0,
+                rsvdp_3: 0,
             },
             lane_error_status: LaneErrorStatus(0b1100_1111_1011),
         };
@@ -217,6 +285,8 @@ mod tests {
             downstream_port_receiver_preset_hint: ReceiverPresetHint::Reserved,
             upstream_port_transmitter_preset: TransmitterPreset::P7,
             upstream_port_receiver_preset_hint: ReceiverPresetHint::Minus8dB,
+            rsvdz: 0,
+            rsvdz_2: 0,
         }).take(8).collect::<Vec<_>>();
         assert_eq!(sample, result);
     }
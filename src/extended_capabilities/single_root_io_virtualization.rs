@@ -9,13 +9,14 @@ use crate::header::BaseAddressesNormal;
 use byte::{
     self,
     ctx::*,
-    // TryWrite,
+    TryWrite,
     BytesExt,
     TryRead,
 };
 use modular_bitfield::prelude::*;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SingleRootIoVirtualization {
     /// SR-IOV Capabilities
     pub sriov_capability: SrIovCapability,
@@ -42,6 +43,9 @@ pub struct SingleRootIoVirtualization {
     /// System Page Size (RW)
     pub sriov_system_page_size: u32,
     /// VF BAR0 ~ BAR 5
+    ///
+    /// Sizes of resizable VF BARs are reported by
+    /// [VF Resizable BAR](super::vf_resizable_bar::VFResizableBar::vf_bar_apertures) capability
     pub sriov_vf_bar: BaseAddressesNormal,
     /// VF Migration State Array Offset (RO)
     pub sriov_vf_migration_state_array_offset: Table,
@@ -68,6 +72,26 @@ impl<'a> TryRead<'a, Endian> for SingleRootIoVirtualization {
         Ok((ptm, *offset))
     }
 }
+impl TryWrite<Endian> for SingleRootIoVirtualization {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u32>(offset, self.sriov_capability.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.sriov_control.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.sriov_status.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.sriov_initial_vfs, endian)?;
+        bytes.write_with::<u16>(offset, self.sriov_total_vfs, endian)?;
+        bytes.write_with::<u16>(offset, self.sriov_num_vfs, endian)?;
+        bytes.write_with::<u16>(offset, self.sriov_function_denpendency_link.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.sriov_first_vf_offset, endian)?;
+        bytes.write_with::<u16>(offset, self.sriov_vf_stride, endian)?;
+        bytes.write_with::<u32>(offset, self.sriov_vf_device_id.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.sriov_supported_page_sizes, endian)?;
+        bytes.write_with::<u32>(offset, self.sriov_system_page_size, endian)?;
+        bytes.write_with::<BaseAddressesNormal>(offset, self.sriov_vf_bar, endian)?;
+        bytes.write_with::<u32>(offset, self.sriov_vf_migration_state_array_offset.into(), endian)?;
+        Ok(*offset)
+    }
+}
 
 #[bitfield(bits = 32)]
 #[repr(u32)]
@@ -76,8 +100,7 @@ pub struct SrIovCapabilityProto {
     pub vf_migration: bool,
     /// ARI Capable Hierarchy Preserved
     pub ari_preserved: bool,
-    #[skip]
-    __: B19,
+    pub rsvdp: B19,
     /// VF Migration Interrupt Message Number
     pub vf_mig_int: B11,
 }
@@ -86,17 +109,21 @@ impl From<SrIovCapability> for SrIovCapabilityProto {
         Self::new()
             .with_vf_migration(data.vf_migration)
             .with_ari_preserved(data.ari_preserved)
+            .with_rsvdp(data.rsvdp)
             .with_vf_mig_int(data.vf_mig_int)
     }
 }
 
 /// Describes a Function’s support for Precision Time Measurement
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SrIovCapability {
     /// VF Migration Capable
     pub vf_migration: bool,
     /// ARI Capable Hierarchy Preserved
     pub ari_preserved: bool,
+    /// Reserved bits 20:2
+    pub rsvdp: u32,
     /// VF Migration Interrupt Message Number
     pub vf_mig_int: u16,
 }
@@ -105,6 +132,7 @@ impl From<SrIovCapabilityProto> for SrIovCapability {
         Self {
             vf_migration: proto.vf_migration(),
             ari_preserved: proto.ari_preserved(),
+            rsvdp: proto.rsvdp(),
             vf_mig_int: proto.vf_mig_int(),
         }
     }
@@ -128,8 +156,7 @@ pub struct SrIovControlProto {
     pub vf_mig_int_enable: bool,
     pub vf_mse: bool,
     pub ari_capable: bool,
-    #[skip]
-    __: B11,
+    pub rsvdp: B11,
 }
 impl From<SrIovControl> for SrIovControlProto {
     fn from(data: SrIovControl) -> Self {
@@ -139,11 +166,13 @@ impl From<SrIovControl> for SrIovControlProto {
             .with_vf_mig_int_enable(data.vf_mig_int_enable)
             .with_vf_mse(data.vf_mse)
             .with_ari_capable(data.ari_capable)
+            .with_rsvdp(data.rsvdp)
     }
 }
 
 /// SR-IOV control
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SrIovControl {
     /// VF Enable
     pub vf_enable: bool,
@@ -155,6 +184,8 @@ pub struct SrIovControl {
     pub vf_mse: bool,
     /// ARI Capable Hierarchy
     pub ari_capable: bool,
+    /// Reserved bits 15:5
+    pub rsvdp: u16,
 }
 impl From<SrIovControlProto> for SrIovControl {
     fn from(proto: SrIovControlProto) -> Self {
@@ -164,6 +195,7 @@ impl From<SrIovControlProto> for SrIovControl {
             vf_mig_int_enable: proto.vf_mig_int_enable(),
             vf_mse: proto.vf_mse(),
             ari_capable: proto.ari_capable(),
+            rsvdp: proto.rsvdp(),
         }
     }
 }
@@ -182,25 +214,30 @@ impl From<SrIovControl> for u16 {
 #[repr(u16)]
 pub struct SrIovStatusProto {
     pub vf_migration: bool,
-    #[skip]
-    __: B15,
+    pub rsvdz: B15,
 }
 impl From<SrIovStatus> for SrIovStatusProto {
     fn from(data: SrIovStatus) -> Self {
-        Self::new().with_vf_migration(data.vf_migration)
+        Self::new()
+            .with_vf_migration(data.vf_migration)
+            .with_rsvdz(data.rsvdz)
     }
 }
 
 /// SR-IOV status
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SrIovStatus {
     /// VF Migration Status
     pub vf_migration: bool,
+    /// Reserved bits 15:1
+    pub rsvdz: u16,
 }
 impl From<SrIovStatusProto> for SrIovStatus {
     fn from(proto: SrIovStatusProto) -> Self {
         Self {
             vf_migration: proto.vf_migration(),
+            rsvdz: proto.rsvdz(),
         }
     }
 }
@@ -226,21 +263,24 @@ impl From<SrIovFunctionDepLink> for SrIovFunctionDepLinkProto {
     fn from(data: SrIovFunctionDepLink) -> Self {
         Self::new()
             .with_function_dependency_link(data.function_dependency_link)
-            .with_rsvdp(0)
+            .with_rsvdp(data.rsvdp)
     }
 }
 
 /// Function Dependency Link
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SrIovFunctionDepLink {
     /// Function Dependency Link
     pub function_dependency_link: u8,
+    /// Reserved bits 15:8
+    pub rsvdp: u8,
 }
 impl From<SrIovFunctionDepLinkProto> for SrIovFunctionDepLink {
     fn from(proto: SrIovFunctionDepLinkProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             function_dependency_link: proto.function_dependency_link(),
+            rsvdp: proto.rsvdp(),
         }
     }
 }
@@ -265,21 +305,24 @@ pub struct SrIovVfDeviceIdProto {
 impl From<SrIovVfDeviceId> for SrIovVfDeviceIdProto {
     fn from(data: SrIovVfDeviceId) -> Self {
         Self::new()
-            .with_rsvdp(0)
+            .with_rsvdp(data.rsvdp)
             .with_vf_device_id(data.vf_device_id)
     }
 }
 
 /// vf device id
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SrIovVfDeviceId {
+    /// Reserved bits 15:0
+    pub rsvdp: u16,
     /// vf device id
     pub vf_device_id: u16,
 }
 impl From<SrIovVfDeviceIdProto> for SrIovVfDeviceId {
     fn from(proto: SrIovVfDeviceIdProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
+            rsvdp: proto.rsvdp(),
             vf_device_id: proto.vf_device_id(),
         }
     }
@@ -9,13 +9,21 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+use crate::RawBytes;
+
+use super::ECH_BYTES;
+
+/// ST Table offset in TPH Requester Capability structure
+const ST_TABLE_OFFSET: usize = 0x0C;
+
+/// ST Table is compared only if it is located in the capability
+#[derive(Debug, Clone)]
 pub struct TphRequester<'a> {
-    data: &'a [u8],
+    data: RawBytes<'a>,
     /// TPH Requester Capability
     pub tph_requester_capability: TphRequesterCapability,
     /// TPH Requester Control
@@ -24,20 +32,94 @@ pub struct TphRequester<'a> {
 
 impl<'a> TphRequester<'a> {
     pub fn tph_st_table(&self) -> TphStTable {
-        TphStTable::new(self.data)
+        let st_table = self.st_table_bytes();
+        let len = (usize::from(self.tph_requester_capability.st_table_size) + 1) * 2;
+        TphStTable::new(&st_table[..len.min(st_table.len())])
+    }
+    /// ST Table bytes located in the TPH Requester Capability structure
+    fn st_table_bytes(&self) -> &[u8] {
+        let capability = &self.tph_requester_capability;
+        if capability.st_table_location != StTableLocation::TphRequesterCapability {
+            return &[];
+        }
+        let start = ST_TABLE_OFFSET - ECH_BYTES;
+        // ST Table entries are 2 bytes wide, the table is padded to a DWORD boundary
+        let len = (usize::from(capability.st_table_size) + 1) * 2;
+        let end = (start + len.next_multiple_of(4)).min(self.data.len());
+        self.data.get(start..end).unwrap_or(&[])
+    }
+}
+impl<'a> PartialEq for TphRequester<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.tph_requester_capability == other.tph_requester_capability
+        && self.tph_requester_control == other.tph_requester_control
+        && self.tph_st_table() == other.tph_st_table()
+    }
+}
+impl<'a> Eq for TphRequester<'a> {}
+/// ST Table is serialized as decoded entries, empty if it is not located in the capability
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for TphRequester<'a> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("TphRequester", 3)?;
+        state.serialize_field("tph_requester_capability", &self.tph_requester_capability)?;
+        state.serialize_field("tph_requester_control", &self.tph_requester_control)?;
+        state.serialize_field("tph_st_table", &self.tph_st_table())?;
+        state.end()
+    }
+}
+/// ST Table located in the capability is rebuilt from decoded entries
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for TphRequester<'a> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "TphRequester")]
+        struct Decoded {
+            tph_requester_capability: TphRequesterCapability,
+            tph_requester_control: TphRequesterControl,
+            tph_st_table: crate::serde_hex::Seq<TphStTableEntry, 0x800>,
+        }
+        let Decoded { tph_requester_capability, tph_requester_control, tph_st_table } =
+            Decoded::deserialize(deserializer)?;
+        let start = ST_TABLE_OFFSET - ECH_BYTES;
+        let mut data = [0u8; crate::ECS_LENGTH];
+        data[..4].copy_from_slice(&u32::from(tph_requester_capability.clone()).to_le_bytes());
+        data[4..start].copy_from_slice(&u32::from(tph_requester_control.clone()).to_le_bytes());
+        let mut len = start;
+        for entry in tph_st_table.iter() {
+            let st_entry = data.get_mut(len..len + 2)
+                .ok_or_else(|| serde::de::Error::custom("ST Table does not fit in ECS"))?;
+            st_entry.copy_from_slice(&[entry.st_lower, entry.st_upper]);
+            len += 2;
+        }
+        // ST Table is padded to a DWORD boundary
+        let data = RawBytes::owned(&data[..len.next_multiple_of(4).min(data.len())])
+            .ok_or_else(|| serde::de::Error::custom("ST Table does not fit in ECS"))?;
+        Ok(TphRequester { data, tph_requester_capability, tph_requester_control })
     }
 }
 impl<'a> TryRead<'a, Endian> for TphRequester<'a> {
     fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
         let offset = &mut 0;
         let tphr = TphRequester {
-            data: bytes,
+            data: RawBytes::new(bytes),
             tph_requester_capability: bytes.read_with::<u32>(offset, endian)?.into(),
             tph_requester_control: bytes.read_with::<u32>(offset, endian)?.into(),
         };
         Ok((tphr, *offset))
     }
 }
+impl<'a> TryWrite<Endian> for TphRequester<'a> {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        let st_table = self.st_table_bytes();
+        bytes.write_with::<u32>(offset, self.tph_requester_capability.clone().into(), endian)?;
+        bytes.write_with::<u32>(offset, self.tph_requester_control.clone().into(), endian)?;
+        bytes.write::<&[u8]>(offset, st_table)?;
+        Ok(*offset)
+    }
+}
 
 
 #[bitfield(bits = 32)]
@@ -56,6 +138,7 @@ pub struct TphRequesterCapabilityProto {
 
 /// TPH Requester Capability
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TphRequesterCapability {
     /// No ST Mode Supported
     pub no_st_mode_supported: bool,
@@ -63,34 +146,58 @@ pub struct TphRequesterCapability {
     pub interrupt_vector_mode_supported: bool,
     /// Device Specific Mode Supported
     pub device_specific_mode_supported: bool,
+    /// Reserved bits 7:3
+    pub rsvdp: u8,
     /// Extended TPH Requester Supported
     pub extended_tph_requester_supported: bool,
     /// ST Table Location
     pub st_table_location: StTableLocation,
+    /// Reserved bits 15:11
+    pub rsvdp_2: u8,
     /// ST Table Size
     pub st_table_size: u16,
+    /// Reserved bits 31:27
+    pub rsvdp_3: u8,
 }
 impl From<TphRequesterCapabilityProto> for TphRequesterCapability {
     fn from(proto: TphRequesterCapabilityProto) -> Self {
-        let _ = proto.rsvdp();
-        let _ = proto.rsvdp_2();
-        let _ = proto.rsvdp_3();
         Self {
             no_st_mode_supported: proto.no_st_mode_supported(),
             interrupt_vector_mode_supported: proto.interrupt_vector_mode_supported(),
             device_specific_mode_supported: proto.device_specific_mode_supported(),
+            rsvdp: proto.rsvdp(),
             extended_tph_requester_supported: proto.extended_tph_requester_supported(),
             st_table_location: proto.st_table_location().into(),
+            rsvdp_2: proto.rsvdp_2(),
             st_table_size: proto.st_table_size(),
+            rsvdp_3: proto.rsvdp_3(),
         }
     }
 }
 impl From<u32> for TphRequesterCapability {
     fn from(dword: u32) -> Self { TphRequesterCapabilityProto::from(dword).into() }
 }
+impl From<TphRequesterCapability> for TphRequesterCapabilityProto {
+    fn from(data: TphRequesterCapability) -> Self {
+        Self::new()
+            .with_no_st_mode_supported(data.no_st_mode_supported)
+            .with_interrupt_vector_mode_supported(data.interrupt_vector_mode_supported)
+            .with_device_specific_mode_supported(data.device_specific_mode_supported)
+            .with_rsvdp(data.rsvdp)
+            .with_extended_tph_requester_supported(data.extended_tph_requester_supported)
+            .with_st_table_location(data.st_table_location as u8)
+            .with_rsvdp_2(data.rsvdp_2)
+            .with_st_table_size(data.st_table_size)
+            .with_rsvdp_3(data.rsvdp_3)
+    }
+}
+impl From<TphRequesterCapability> for u32 {
+    fn from(data: TphRequesterCapability) -> Self { TphRequesterCapabilityProto::from(data).into() }
+}
 
 /// Indicates if and where the ST Table is located
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StTableLocation {
     /// ST Table is not present
     NotPresent,
@@ -125,28 +232,46 @@ pub struct TphRequesterControlProto {
 
 /// TPH Requester Control
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TphRequesterControl {
     /// ST Mode Select
     pub st_mode_select: StModeSelect,
+    /// Reserved bits 7:3
+    pub rsvdp: u8,
     /// TPH Requester Enable
     pub tph_requester_enable: TphRequesterEnable,
+    /// Reserved bits 31:10
+    pub rsvdp_2: u32,
 }
 impl From<TphRequesterControlProto> for TphRequesterControl {
     fn from(proto: TphRequesterControlProto) -> Self {
-        let _ = proto.rsvdp();
-        let _ = proto.rsvdp_2();
         Self {
             st_mode_select: proto.st_mode_select().into(),
+            rsvdp: proto.rsvdp(),
             tph_requester_enable: proto.tph_requester_enable().into(),
+            rsvdp_2: proto.rsvdp_2(),
         }
     }
 }
 impl From<u32> for TphRequesterControl {
     fn from(dword: u32) -> Self { TphRequesterControlProto::from(dword).into() }
 }
+impl From<TphRequesterControl> for TphRequesterControlProto {
+    fn from(data: TphRequesterControl) -> Self {
+        Self::new()
+            .with_st_mode_select(data.st_mode_select.into())
+            .with_rsvdp(data.rsvdp)
+            .with_tph_requester_enable(data.tph_requester_enable as u8)
+            .with_rsvdp_2(data.rsvdp_2)
+    }
+}
+impl From<TphRequesterControl> for u32 {
+    fn from(data: TphRequesterControl) -> Self { TphRequesterControlProto::from(data).into() }
+}
 
 /// Selects the ST Mode of operation
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StModeSelect {
     /// No ST Mode
     NoStMode,
@@ -167,9 +292,20 @@ impl From<u8> for StModeSelect {
         }
     }
 }
+impl From<StModeSelect> for u8 {
+    fn from(data: StModeSelect) -> Self {
+        match data {
+            StModeSelect::NoStMode            => 0b00,
+            StModeSelect::InterruptVectorMode => 0b01,
+            StModeSelect::DeviceSpecificMode  => 0b10,
+            StModeSelect::Reserved(v)         => v,
+        }
+    }
+}
 
 /// Controls the ability to issue Request TLPs using either TPH or Extended TPH
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TphRequesterEnable {
     /// Function operating as a Requester is not permitted to issue Requests with TPH or Extended
     /// TPH
@@ -206,12 +342,21 @@ impl<'a> Iterator for TphStTable<'a> {
     type Item = TphStTableEntry;
 
     fn next(&mut self) -> Option<Self::Item> {
-        todo!()
+        let (&[st_lower, st_upper], rest) = self.0.split_first_chunk::<2>()?;
+        self.0 = rest;
+        Some(TphStTableEntry { st_lower, st_upper })
+    }
+}
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for TphStTable<'a> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.clone())
     }
 }
 
 /// Each implemented ST Entry is 16 bits 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TphStTableEntry {
     pub st_lower: u8,
     pub st_upper: u8,
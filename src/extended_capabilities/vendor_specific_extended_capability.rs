@@ -8,30 +8,45 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
+use crate::RawBytes;
+
 
 /// Vendor-Specific Extended Capability
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VendorSpecificExtendedCapability<'a> {
     pub header: VsecHeader,
     /// Vendor-Specific Registers
-    pub registers: &'a [u8],
+    pub registers: RawBytes<'a>,
 }
 impl<'a> TryRead<'a, Endian> for VendorSpecificExtendedCapability<'a> {
     fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
         let offset = &mut 0;
         let header: VsecHeader = bytes.read_with::<u32>(offset, endian)?.into();
-        let len = (header.vsec_length - 8).into();
+        let len = usize::from(header.vsec_length).checked_sub(8)
+            .ok_or(byte::Error::BadInput { err: "VSEC Length is too small" })?;
         let vsec = VendorSpecificExtendedCapability {
             header,
-            registers: bytes.read_with::<&[u8]>(offset, Bytes::Len(len))?,
+            registers: RawBytes::new(bytes.read_with::<&[u8]>(offset, Bytes::Len(len))?),
         };
         Ok((vsec, *offset))
     }
 }
+impl<'a> TryWrite<Endian> for VendorSpecificExtendedCapability<'a> {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        if usize::from(self.header.vsec_length) != self.registers.len() + 8 {
+            return Err(byte::Error::BadInput { err: "VSEC Length does not match registers length" });
+        }
+        let offset = &mut 0;
+        bytes.write_with::<u32>(offset, self.header.into(), endian)?;
+        bytes.write::<&[u8]>(offset, &self.registers)?;
+        Ok(*offset)
+    }
+}
 
 
 #[bitfield(bits = 32)]
@@ -44,6 +59,7 @@ pub struct VsecHeaderProto {
 
 /// Vendor-Specific Header
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VsecHeader {
     /// Vendor-defined ID number that indicates the nature and format of the VSEC structure.
     pub vsec_id: u16,
@@ -65,3 +81,14 @@ impl From<VsecHeaderProto> for VsecHeader {
 impl From<u32> for VsecHeader {
     fn from(dword: u32) -> Self { VsecHeaderProto::from(dword).into() }
 }
+impl From<VsecHeader> for VsecHeaderProto {
+    fn from(data: VsecHeader) -> Self {
+        Self::new()
+            .with_vsec_id(data.vsec_id)
+            .with_vsec_rev(data.vsec_rev)
+            .with_vsec_length(data.vsec_length)
+    }
+}
+impl From<VsecHeader> for u32 {
+    fn from(data: VsecHeader) -> Self { VsecHeaderProto::from(data).into() }
+}
@@ -0,0 +1,122 @@
+//! VF Resizable BAR
+//!
+//! The VF Resizable BAR Capability has the same layout as [Resizable BAR](super::resizable_bar)
+//! but its entries control the sizes of the VF BARs located in the
+//! [SR-IOV](super::single_root_io_virtualization) capability of the same PF. Size of each VF BAR
+//! applies to every VF, so the PF has to map aperture of the size multiplied by number of VFs.
+
+use byte::{
+    ctx::*,
+    self,
+    TryRead,
+    TryWrite,
+};
+
+use crate::header::bar::{BaseAddress, BaseAddresses};
+use super::{
+    SingleRootIoVirtualization,
+    resizable_bar::{Entries, ResizableBar},
+};
+
+
+/// VF Resizable BAR Extended Capability structure
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VFResizableBar<'a>(pub ResizableBar<'a>);
+impl<'a> VFResizableBar<'a> {
+    pub fn entries(&self) -> Entries<'_> {
+        self.0.entries()
+    }
+    /// Apertures of resizable VF BARs required by TotalVFs of the `sriov` capability
+    pub fn vf_bar_apertures(
+        &self,
+        sriov: &SingleRootIoVirtualization,
+    ) -> impl Iterator<Item = VfBarAperture> + '_ {
+        let vf_bars: BaseAddresses = sriov.sriov_vf_bar.clone().into();
+        let total_vfs = sriov.sriov_total_vfs;
+        self.entries().map(move |entry| {
+            let bar_index = entry.control.bar_index;
+            let vf_bar_size = entry.control.bar_size.bytes();
+            VfBarAperture {
+                bar_index,
+                base_address: vf_bars.clone()
+                    .find(|bar| bar.region == usize::from(bar_index)),
+                vf_bar_size,
+                total_size: vf_bar_size.saturating_mul(total_vfs.into()),
+            }
+        })
+    }
+}
+impl<'a> TryRead<'a, Endian> for VFResizableBar<'a> {
+    fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
+        let (rebar, size) = ResizableBar::try_read(bytes, endian)?;
+        Ok((Self(rebar), size))
+    }
+}
+impl<'a> TryWrite<Endian> for VFResizableBar<'a> {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        self.0.try_write(bytes, endian)
+    }
+}
+
+/// Memory space required by a resizable VF BAR
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VfBarAperture {
+    /// VF BAR Index, 0 – VF BAR0, 1 – VF BAR1, etc.
+    pub bar_index: u8,
+    /// VF BAR of the SR-IOV capability, `None` if the BAR is not assigned
+    pub base_address: Option<BaseAddress>,
+    /// Current size of the BAR of a single VF
+    pub vf_bar_size: u64,
+    /// VF BAR size multiplied by TotalVFs, saturates at `u64::MAX`
+    pub total_size: u64,
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use pretty_assertions::assert_eq;
+    use byte::BytesExt;
+    use crate::header::BaseAddressType;
+    use super::*;
+
+    #[test]
+    fn vf_bar_apertures() {
+        let mut sriov = [0u8; 60];
+        // TotalVFs
+        sriov[10] = 64;
+        // VF BAR0 64-bit prefetchable, VF BAR2 is not assigned
+        sriov[32..40].copy_from_slice(&[0x0c, 0x00, 0x00, 0x00, 0x38, 0x00, 0x00, 0x00]);
+        let sriov: SingleRootIoVirtualization = sriov.read_with(&mut 0, LE).unwrap();
+        let data = [
+            0xf0, 0x01, 0x00, 0x00, 0x40, 0x02, 0x00, 0x00,
+            0x10, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+        ];
+        let vf_rebar: VFResizableBar = data.read_with(&mut 0, LE).unwrap();
+        let result = vf_rebar.vf_bar_apertures(&sriov).collect::<Vec<_>>();
+        let sample = vec![
+            VfBarAperture {
+                bar_index: 0,
+                base_address: Some(BaseAddress {
+                    region: 0,
+                    base_address_type: BaseAddressType::MemorySpace64 {
+                        prefetchable: true,
+                        base_address: 0x38_0000_0000,
+                    },
+                }),
+                vf_bar_size: 4 << 20,
+                total_size: 256 << 20,
+            },
+            VfBarAperture {
+                bar_index: 2,
+                base_address: None,
+                vf_bar_size: 1 << 20,
+                total_size: 64 << 20,
+            },
+        ];
+        assert_eq!(sample, result);
+    }
+}
@@ -11,10 +11,12 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
+use crate::RawBytes;
+
 use super::ECH_BYTES;
 
 
@@ -22,9 +24,10 @@ use super::ECH_BYTES;
 const DQWORD: usize = 16;
 
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Arbitration tables are compared by decoded entries
+#[derive(Debug, Clone)]
 pub struct VirtualChannel<'a> {
-    data: &'a [u8],
+    data: RawBytes<'a>,
     /// Port VC Capability Register 1
     pub port_vc_capability_1: PortVcCapability1,
     /// Port VC Capability Register 2
@@ -35,38 +38,132 @@ pub struct VirtualChannel<'a> {
     pub port_vc_status: PortVcStatus,
 }
 impl<'a> VirtualChannel<'a> {
-    pub fn extended_virtual_channels(&self) -> ExtendedVirtualChannels<'a> {
+    pub fn extended_virtual_channels(&self) -> ExtendedVirtualChannels<'_> {
         let count = self.port_vc_capability_1.extended_vc_count;
         let start = 0x10 - ECH_BYTES;
         let data = &self.data[start..];
         ExtendedVirtualChannels::new(data, count)
     }
-    pub fn vc_arbitration_table(&self) -> VcArbitrationTable<'a> {
+    pub fn vc_arbitration_table(&self) -> VcArbitrationTable<'_> {
         let offset = self.port_vc_capability_2.vc_arbitration_table_offset;
         let entries_number = self.port_vc_control.vc_arbitration_select
             .vc_arbitration_table_length();
-        let start = offset as usize * DQWORD - ECH_BYTES;
         // VC Arbitration Table entry length is 4 bits, so there are 2 entries in one byte
-        let end = start + entries_number / 2;
-        let data = &self.data[start..end];
-        VcArbitrationTable::new(data)
+        VcArbitrationTable::new(table(&self.data, offset, entries_number / 2))
     }
-    pub fn port_arbitration_table(&'a self, evc: &'a ExtendedVirtualChannel) -> PortArbitrationTable<'a> {
+    pub fn port_arbitration_table(&self, evc: &ExtendedVirtualChannel) -> PortArbitrationTable<'_> {
         let offset = evc.vc_resource_capability.port_arbitration_table_offset;
         let entry_size_bits = self.port_vc_capability_1.port_arbitration_table_entry_size.bits();
         let entries_number = evc.vc_resource_control.port_arbitration_select
             .port_arbitration_table_length();
-        let start = offset as usize * DQWORD - ECH_BYTES;
-        let end = start + entry_size_bits * entries_number / 8;
-        let data = &self.data[start..end];
+        let data = table(&self.data, offset, entry_size_bits * entries_number / 8);
         PortArbitrationTable::new(data, entry_size_bits)
     }
+    /// Port Arbitration Tables in the order of Extended VC Resources
+    fn port_arbitration_tables(&self) -> impl Iterator<Item = PortArbitrationTable<'_>> {
+        self.extended_virtual_channels().map(|evc| self.port_arbitration_table(&evc))
+    }
+}
+impl<'a> PartialEq for VirtualChannel<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.port_vc_capability_1 == other.port_vc_capability_1
+        && self.port_vc_capability_2 == other.port_vc_capability_2
+        && self.port_vc_control == other.port_vc_control
+        && self.port_vc_status == other.port_vc_status
+        && self.extended_virtual_channels().eq(other.extended_virtual_channels())
+        && self.vc_arbitration_table().eq(other.vc_arbitration_table())
+        && self.port_arbitration_tables().zip(other.port_arbitration_tables()).all(|(a, b)| a.eq(b))
+    }
+}
+impl<'a> Eq for VirtualChannel<'a> {}
+/// Extended VC Resources and arbitration tables are serialized decoded, Port Arbitration Tables
+/// are listed in the order of Extended VC Resources
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for VirtualChannel<'a> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("VirtualChannel", 7)?;
+        state.serialize_field("port_vc_capability_1", &self.port_vc_capability_1)?;
+        state.serialize_field("port_vc_capability_2", &self.port_vc_capability_2)?;
+        state.serialize_field("port_vc_control", &self.port_vc_control)?;
+        state.serialize_field("port_vc_status", &self.port_vc_status)?;
+        state.serialize_field("extended_virtual_channels", &self.extended_virtual_channels())?;
+        state.serialize_field("vc_arbitration_table", &self.vc_arbitration_table())?;
+        state.serialize_field("port_arbitration_tables", &PortArbitrationTables(self))?;
+        state.end()
+    }
+}
+#[cfg(feature = "serde")]
+struct PortArbitrationTables<'a, 'b>(&'b VirtualChannel<'a>);
+#[cfg(feature = "serde")]
+impl<'a, 'b> serde::Serialize for PortArbitrationTables<'a, 'b> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.port_arbitration_tables())
+    }
+}
+/// Registers are rebuilt from decoded Extended VC Resources and arbitration tables
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for VirtualChannel<'a> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use crate::serde_hex::Seq;
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "VirtualChannel")]
+        struct Decoded {
+            port_vc_capability_1: PortVcCapability1,
+            port_vc_capability_2: PortVcCapability2,
+            port_vc_control: PortVcControl,
+            port_vc_status: PortVcStatus,
+            extended_virtual_channels: Seq<ExtendedVirtualChannel, 8>,
+            vc_arbitration_table: Seq<VcArbitrationTableEntry, 0x100>,
+            port_arbitration_tables: Seq<Seq<PortArbitrationTableEntry, 0x100>, 8>,
+        }
+        let decoded = Decoded::deserialize(deserializer)?;
+        let too_long = |_| serde::de::Error::custom("VC registers do not fit in ECS");
+        let mut data = [0u8; crate::ECS_LENGTH];
+        let offset = &mut 0;
+        data.write_with::<u32>(offset, decoded.port_vc_capability_1.clone().into(), LE).map_err(too_long)?;
+        data.write_with::<u32>(offset, decoded.port_vc_capability_2.clone().into(), LE).map_err(too_long)?;
+        data.write_with::<u16>(offset, decoded.port_vc_control.clone().into(), LE).map_err(too_long)?;
+        data.write_with::<u16>(offset, decoded.port_vc_status.clone().into(), LE).map_err(too_long)?;
+        for evc in decoded.extended_virtual_channels.iter() {
+            data.write_with(offset, evc.clone(), LE).map_err(too_long)?;
+        }
+        let mut end = *offset;
+        let table_start = |offset: u8| (offset != 0).then(|| offset as usize * DQWORD - ECH_BYTES);
+        if let Some(start) = table_start(decoded.port_vc_capability_2.vc_arbitration_table_offset) {
+            for (n, entry) in decoded.vc_arbitration_table.iter().enumerate() {
+                let byte = data.get_mut(start + n / 2).ok_or_else(|| too_long(byte::Error::Incomplete))?;
+                *byte |= entry.vc_id << (n % 2 * 4);
+                end = end.max(start + n / 2 + 1);
+            }
+        }
+        let entry_size_bits = decoded.port_vc_capability_1.port_arbitration_table_entry_size.bits();
+        let evcs = decoded.extended_virtual_channels.iter();
+        for (evc, table) in evcs.zip(decoded.port_arbitration_tables.iter()) {
+            let Some(start) = table_start(evc.vc_resource_capability.port_arbitration_table_offset) else {
+                continue;
+            };
+            for (n, entry) in table.iter().enumerate() {
+                let bit = n * entry_size_bits;
+                let byte = data.get_mut(start + bit / 8).ok_or_else(|| too_long(byte::Error::Incomplete))?;
+                *byte |= entry.0 << (bit % 8);
+                end = end.max(start + bit / 8 + 1);
+            }
+        }
+        Ok(VirtualChannel {
+            data: RawBytes::owned(&data[..end]).ok_or_else(|| too_long(byte::Error::Incomplete))?,
+            port_vc_capability_1: decoded.port_vc_capability_1,
+            port_vc_capability_2: decoded.port_vc_capability_2,
+            port_vc_control: decoded.port_vc_control,
+            port_vc_status: decoded.port_vc_status,
+        })
+    }
 }
 impl<'a> TryRead<'a, Endian> for VirtualChannel<'a> {
     fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
         let offset = &mut 0;
         let vc = VirtualChannel {
-            data: bytes,
+            data: RawBytes::new(bytes),
             port_vc_capability_1: bytes.read_with::<u32>(offset, endian)?.into(),
             port_vc_capability_2: bytes.read_with::<u32>(offset, endian)?.into(),
             port_vc_control: bytes.read_with::<u16>(offset, endian)?.into(),
@@ -75,6 +172,54 @@ impl<'a> TryRead<'a, Endian> for VirtualChannel<'a> {
         Ok((vc, *offset))
     }
 }
+impl<'a> TryWrite<Endian> for VirtualChannel<'a> {
+    /// Writes Port VC registers, all Extended VC Resources, VC Arbitration Table and Port
+    /// Arbitration Tables at the offsets pointed by the registers
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        let evcs = self.extended_virtual_channels();
+        bytes.write_with::<u32>(offset, self.port_vc_capability_1.clone().into(), endian)?;
+        bytes.write_with::<u32>(offset, self.port_vc_capability_2.clone().into(), endian)?;
+        bytes.write_with::<u16>(offset, self.port_vc_control.clone().into(), endian)?;
+        bytes.write_with::<u16>(offset, self.port_vc_status.clone().into(), endian)?;
+        let mut tables = [(0, 0); 9];
+        tables[0] = (
+            self.port_vc_capability_2.vc_arbitration_table_offset,
+            self.port_vc_control.vc_arbitration_select.vc_arbitration_table_length() / 2,
+        );
+        let entry_size_bits = self.port_vc_capability_1.port_arbitration_table_entry_size.bits();
+        for (evc, table) in evcs.zip(tables[1..].iter_mut()) {
+            *table = (
+                evc.vc_resource_capability.port_arbitration_table_offset,
+                entry_size_bits * evc.vc_resource_control.port_arbitration_select
+                    .port_arbitration_table_length() / 8,
+            );
+            bytes.write_with(offset, evc, endian)?;
+        }
+        let mut end = *offset;
+        for (table_offset, len) in tables {
+            let table = table(&self.data, table_offset, len);
+            if table.is_empty() {
+                continue;
+            }
+            let start = table_offset as usize * DQWORD - ECH_BYTES;
+            bytes.write::<&[u8]>(&mut start.clone(), table)?;
+            end = end.max(start + table.len());
+        }
+        Ok(end)
+    }
+}
+
+/// Arbitration table of `len` bytes at `offset` DQWORDs from the capability base address,
+/// truncated to the available data
+fn table(data: &[u8], offset: u8, len: usize) -> &[u8] {
+    if offset == 0 {
+        return &[];
+    }
+    let start = offset as usize * DQWORD - ECH_BYTES;
+    let data = data.get(start..).unwrap_or_default();
+    &data[..len.min(data.len())]
+}
 
 
 
@@ -92,39 +237,62 @@ pub struct PortVcCapability1Proto {
 /// The Port VC Capability register 1 describes the configuration of the Virtual Channels
 /// associated with a PCI Express Port.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PortVcCapability1 {
     /// Indicates the number of (extended) Virtual Channels in addition to the default VC supported
     /// by the device.
     pub extended_vc_count: u8,
+    /// Reserved bit 3
+    pub rsvdp: u8,
     /// Indicates the number of (extended) Virtual Channels in addition to the default VC belonging
     /// to the low-priority VC (LPVC) group that has the lowest priority with respect to other VC
     /// resources in a strictpriority VC Arbitration.
     pub low_priority_extended_vc_count: u8,
+    /// Reserved bit 7
+    pub rsvdp_2: u8,
     /// Reference Clock
     pub reference_clock: ReferenceClock,
     /// Indicates the size (in bits) of Port Arbitration table entry in the Function.
     pub port_arbitration_table_entry_size: PortArbitrationTableEntrySize,
+    /// Reserved bits 31:12
+    pub rsvdp_3: u32,
 }
 impl From<PortVcCapability1Proto> for PortVcCapability1 {
     fn from(proto: PortVcCapability1Proto) -> Self {
-        let _ = proto.rsvdp();
-        let _ = proto.rsvdp_2();
-        let _ = proto.rsvdp_3();
         Self {
             extended_vc_count: proto.extended_vc_count(),
+            rsvdp: proto.rsvdp(),
             low_priority_extended_vc_count: proto.low_priority_extended_vc_count(),
+            rsvdp_2: proto.rsvdp_2(),
             reference_clock: proto.reference_clock().into(),
             port_arbitration_table_entry_size: proto.port_arbitration_table_entry_size().into(),
+            rsvdp_3: proto.rsvdp_3(),
         }
     }
 }
 impl From<u32> for PortVcCapability1 {
     fn from(dword: u32) -> Self { PortVcCapability1Proto::from(dword).into() }
 }
+impl From<PortVcCapability1> for PortVcCapability1Proto {
+    fn from(data: PortVcCapability1) -> Self {
+        Self::new()
+            .with_extended_vc_count(data.extended_vc_count)
+            .with_rsvdp(data.rsvdp)
+            .with_low_priority_extended_vc_count(data.low_priority_extended_vc_count)
+            .with_rsvdp_2(data.rsvdp_2)
+            .with_reference_clock(data.reference_clock.into())
+            .with_port_arbitration_table_entry_size(data.port_arbitration_table_entry_size.into())
+            .with_rsvdp_3(data.rsvdp_3)
+    }
+}
+impl From<PortVcCapability1> for u32 {
+    fn from(data: PortVcCapability1) -> Self { PortVcCapability1Proto::from(data).into() }
+}
 
 /// Indicates the reference clock for Virtual Channels that support time-based WRR Port
 /// Arbitration.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ReferenceClock {
     /// 100 ns reference clock
     Rc100ns,
@@ -139,8 +307,17 @@ impl From<u8> for ReferenceClock {
         }
     }
 }
+impl From<ReferenceClock> for u8 {
+    fn from(data: ReferenceClock) -> Self {
+        match data {
+            ReferenceClock::Rc100ns     => 0b000,
+            ReferenceClock::Reserved(v) => v,
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PortArbitrationTableEntrySize(u8);
 impl PortArbitrationTableEntrySize {
     pub fn bits(&self) -> usize { 1 << self.0 }
@@ -148,6 +325,9 @@ impl PortArbitrationTableEntrySize {
 impl From<u8> for PortArbitrationTableEntrySize {
     fn from(byte: u8) -> Self { Self(byte) }
 }
+impl From<PortArbitrationTableEntrySize> for u8 {
+    fn from(data: PortArbitrationTableEntrySize) -> Self { data.0 }
+}
 
 /// An iterator through 0 - 7 (Extended Virtual Channels)[ExtendedVirtualChannel]
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -183,14 +363,23 @@ impl<'a> Iterator for ExtendedVirtualChannels<'a> {
         }
     }
 }
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for ExtendedVirtualChannels<'a> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.clone())
+    }
+}
 
 /// Virtual Channel resources
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtendedVirtualChannel {
     /// VC Resource Capability Register
     pub vc_resource_capability: VcResourceCapability,
     /// VC Resource Control Register
     pub vc_resource_control: VcResourceControl,
+    /// Reserved bits 15:0 of the dword holding VC Resource Status Register
+    pub rsvdp: u16,
     /// VC Resource Status Register
     pub vc_resource_status: VcResourceStatus,
 }
@@ -208,15 +397,22 @@ impl<'a> TryRead<'a, Endian> for ExtendedVirtualChannel {
         let evc = ExtendedVirtualChannel {
             vc_resource_capability: bytes.read_with::<u32>(offset, endian)?.into(),
             vc_resource_control: bytes.read_with::<u32>(offset, endian)?.into(),
-            vc_resource_status: {
-                // Skip RsvdP part 18h - 19h Extended Virtual Channel structure
-                let _ = bytes.read_with::<u16>(offset, endian)?;
-                bytes.read_with::<u16>(offset, endian)?.into()
-            },
+            rsvdp: bytes.read_with::<u16>(offset, endian)?,
+            vc_resource_status: bytes.read_with::<u16>(offset, endian)?.into(),
         };
         Ok((evc, *offset))
     }
 }
+impl TryWrite<Endian> for ExtendedVirtualChannel {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u32>(offset, self.vc_resource_capability.into(), endian)?;
+        bytes.write_with::<u32>(offset, self.vc_resource_control.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.rsvdp, endian)?;
+        bytes.write_with::<u16>(offset, self.vc_resource_status.into(), endian)?;
+        Ok(*offset)
+    }
+}
 
 #[bitfield(bits = 32)]
 #[repr(u32)]
@@ -244,11 +440,11 @@ impl From<VcResourceCapability> for VcResourceCapabilityProto {
             .with_wrr_128_phases(pac.wrr_128_phases)
             .with_time_based_wrr_128_phases(pac.time_based_wrr_128_phases)
             .with_wrr_256_phases(pac.wrr_256_phases)
-            .with_rsvdp(0)
+            .with_rsvdp(data.rsvdp)
             .with_advanced_packet_switching(data.advanced_packet_switching)
             .with_reject_snoop_transactions(data.reject_snoop_transactions)
             .with_maximum_time_slots(data.maximum_time_slots)
-            .with_rsvdp_2(0)
+            .with_rsvdp_2(data.rsvdp_2)
             .with_port_arbitration_table_offset(data.port_arbitration_table_offset)
     }
 }
@@ -256,9 +452,12 @@ impl From<VcResourceCapability> for VcResourceCapabilityProto {
 /// The VC Resource Capability register describes the capabilities and configuration of a
 /// particular Virtual Channel resource
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VcResourceCapability {
     /// Port Arbitration Capability
     pub port_arbitration_capability: PortArbitrationCapability,
+    /// Reserved bits 13:6
+    pub rsvdp: u8,
     /// Advanced Packet Switching
     pub advanced_packet_switching: bool,
     /// When Set, any transaction for which the No Snoop attribute is applicable but is not Set
@@ -267,13 +466,13 @@ pub struct VcResourceCapability {
     /// Indicates the maximum number of time slots (minus one) that the VC resource is capable of
     /// supporting when it is configured for time-based WRR Port Arbitration.
     pub maximum_time_slots: u8,
+    /// Reserved bit 23
+    pub rsvdp_2: u8,
     /// Indicates the location of the Port Arbitration Table associated with the VC resource.
     pub port_arbitration_table_offset: u8,
 }
 impl From<VcResourceCapabilityProto> for VcResourceCapability {
     fn from(proto: VcResourceCapabilityProto) -> Self {
-        let _ = proto.rsvdp();
-        let _ = proto.rsvdp_2();
         Self {
             port_arbitration_capability: PortArbitrationCapability {
                 hardware_fixed_arbitration: proto.hardware_fixed_arbitration(),
@@ -283,9 +482,11 @@ impl From<VcResourceCapabilityProto> for VcResourceCapability {
                 time_based_wrr_128_phases: proto.time_based_wrr_128_phases(),
                 wrr_256_phases: proto.wrr_256_phases(),
             },
+            rsvdp: proto.rsvdp(),
             advanced_packet_switching: proto.advanced_packet_switching(),
             reject_snoop_transactions: proto.reject_snoop_transactions(),
             maximum_time_slots: proto.maximum_time_slots(),
+            rsvdp_2: proto.rsvdp_2(),
             port_arbitration_table_offset: proto.port_arbitration_table_offset(),
         }
     }
@@ -293,10 +494,14 @@ impl From<VcResourceCapabilityProto> for VcResourceCapability {
 impl From<u32> for VcResourceCapability {
     fn from(dword: u32) -> Self { VcResourceCapabilityProto::from(dword).into() }
 }
+impl From<VcResourceCapability> for u32 {
+    fn from(data: VcResourceCapability) -> Self { VcResourceCapabilityProto::from(data).into() }
+}
 
 
 /// Indicates types of Port Arbitration supported by the VC resource
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PortArbitrationCapability {
     /// Non-configurable hardware-fixed arbitration scheme, e.g., Round Robin (RR)
     pub hardware_fixed_arbitration: bool,
@@ -351,9 +556,19 @@ impl<'a> PartialEq for PortArbitrationTable<'a> {
     }
 }
 impl<'a> Eq for PortArbitrationTable<'a> {}
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for PortArbitrationTable<'a> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.clone())
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PortArbitrationTableEntry(u8);
+impl From<PortArbitrationTableEntry> for u8 {
+    fn from(data: PortArbitrationTableEntry) -> Self { data.0 }
+}
 
 
 #[bitfield(bits = 32)]
@@ -372,40 +587,47 @@ impl From<VcResourceControl> for VcResourceControlProto {
     fn from(data: VcResourceControl) -> Self {
         Self::new()
             .with_tc_or_vc_map(data.tc_or_vc_map)
-            .with_rsvdp(0)
+            .with_rsvdp(data.rsvdp)
             .with_load_port_arbitration_table(data.load_port_arbitration_table)
             .with_port_arbitration_select(data.port_arbitration_select.into())
-            .with_rsvdp_2(0)
+            .with_rsvdp_2(data.rsvdp_2)
             .with_vc_id(data.vc_id)
-            .with_rsvdp_3(0)
+            .with_rsvdp_3(data.rsvdp_3)
             .with_vc_enable(data.vc_enable)
     }
 }
 
 /// VC Resource Control
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VcResourceControl {
     /// TC/VC Map
     pub tc_or_vc_map: u8,
+    /// Reserved bits 15:8
+    pub rsvdp: u8,
     /// Load Port Arbitration Table
     pub load_port_arbitration_table: bool,
     /// Port Arbitration Select
     pub port_arbitration_select: PortArbitrationSelect,
+    /// Reserved bits 23:20
+    pub rsvdp_2: u8,
     /// VC ID
     pub vc_id: u8,
+    /// Reserved bits 30:27
+    pub rsvdp_3: u8,
     /// VC Enable
     pub vc_enable: bool,
 }
 impl From<VcResourceControlProto> for VcResourceControl {
     fn from(proto: VcResourceControlProto) -> Self {
-        let _ = proto.rsvdp();
-        let _ = proto.rsvdp_2();
-        let _ = proto.rsvdp_3();
         Self {
             tc_or_vc_map: proto.tc_or_vc_map(),
+            rsvdp: proto.rsvdp(),
             load_port_arbitration_table: proto.load_port_arbitration_table(),
             port_arbitration_select: proto.port_arbitration_select().into(),
+            rsvdp_2: proto.rsvdp_2(),
             vc_id: proto.vc_id(),
+            rsvdp_3: proto.rsvdp_3(),
             vc_enable: proto.vc_enable(),
         }
     }
@@ -413,10 +635,14 @@ impl From<VcResourceControlProto> for VcResourceControl {
 impl From<u32> for VcResourceControl {
     fn from(dword: u32) -> Self { VcResourceControlProto::from(dword).into() }
 }
+impl From<VcResourceControl> for u32 {
+    fn from(data: VcResourceControl) -> Self { VcResourceControlProto::from(data).into() }
+}
 
 /// Corresponding to one of the filed in the (Port Arbitration
 /// Capability)[PortArbitrationCapability]
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PortArbitrationSelect {
     /// Non-configurable hardware-fixed arbitration scheme, e.g., Round Robin (RR)
     HardwareFixedArbitration,
@@ -486,29 +712,35 @@ impl From<VcResourceStatus> for VcResourceStatusProto {
         Self::new()
             .with_port_arbitration_table_status(data.port_arbitration_table_status)
             .with_vc_negotiation_pending(data.vc_negotiation_pending)
-            .with_rsvdz(0)
+            .with_rsvdz(data.rsvdz)
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VcResourceStatus {
     /// Port Arbitration Table Status
     pub port_arbitration_table_status: bool,
     /// VC Negotiation Pending
     pub vc_negotiation_pending: bool,
+    /// Reserved bits 15:2
+    pub rsvdz: u16,
 }
 impl From<VcResourceStatusProto> for VcResourceStatus {
     fn from(proto: VcResourceStatusProto) -> Self {
-        let _ = proto.rsvdz();
         Self {
             port_arbitration_table_status: proto.port_arbitration_table_status(),
             vc_negotiation_pending: proto.vc_negotiation_pending(),
+            rsvdz: proto.rsvdz(),
         }
     }
 }
 impl From<u16> for VcResourceStatus {
     fn from(word: u16) -> Self { VcResourceStatusProto::from(word).into() }
 }
+impl From<VcResourceStatus> for u16 {
+    fn from(data: VcResourceStatus) -> Self { VcResourceStatusProto::from(data).into() }
+}
 
 
 #[bitfield(bits = 32)]
@@ -528,17 +760,20 @@ pub struct PortVcCapability2Proto {
 /// The Port VC Capability register 2 provides further information about the configuration of the
 /// Virtual Channels associated with a PCI Express Port.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PortVcCapability2 {
     /// VC Arbitration Capability
     pub vc_arbitration_capability: VcArbitrationCapability,
+    /// Reserved bits 7:4
+    pub rsvdp: u8,
+    /// Reserved bits 23:8
+    pub rsvdp_2: u16,
     /// This field contains the zero-based offset of the table in DQWORDS (16 bytes) from the base
     /// address of the Virtual Channel Capability structure.
     pub vc_arbitration_table_offset: u8,
 }
 impl From<PortVcCapability2Proto> for PortVcCapability2 {
     fn from(proto: PortVcCapability2Proto) -> Self {
-        let _ = proto.rsvdp();
-        let _ = proto.rsvdp_2();
         Self {
             vc_arbitration_capability: VcArbitrationCapability {
                 hardware_fixed_arbitration: proto.hardware_fixed_arbitration(),
@@ -546,6 +781,8 @@ impl From<PortVcCapability2Proto> for PortVcCapability2 {
                 wrr_64_phases: proto.wrr_64_phases(),
                 wrr_128_phases: proto.wrr_128_phases(),
             },
+            rsvdp: proto.rsvdp(),
+            rsvdp_2: proto.rsvdp_2(),
             vc_arbitration_table_offset: proto.vc_arbitration_table_offset(),
         }
     }
@@ -553,10 +790,27 @@ impl From<PortVcCapability2Proto> for PortVcCapability2 {
 impl From<u32> for PortVcCapability2 {
     fn from(dword: u32) -> Self { PortVcCapability2Proto::from(dword).into() }
 }
+impl From<PortVcCapability2> for PortVcCapability2Proto {
+    fn from(data: PortVcCapability2) -> Self {
+        let vac = data.vc_arbitration_capability;
+        Self::new()
+            .with_hardware_fixed_arbitration(vac.hardware_fixed_arbitration)
+            .with_wrr_32_phases(vac.wrr_32_phases)
+            .with_wrr_64_phases(vac.wrr_64_phases)
+            .with_wrr_128_phases(vac.wrr_128_phases)
+            .with_rsvdp(data.rsvdp)
+            .with_rsvdp_2(data.rsvdp_2)
+            .with_vc_arbitration_table_offset(data.vc_arbitration_table_offset)
+    }
+}
+impl From<PortVcCapability2> for u32 {
+    fn from(data: PortVcCapability2) -> Self { PortVcCapability2Proto::from(data).into() }
+}
 
 
 /// Indicates the types of VC Arbitration supported by the Function for the LPVC group.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VcArbitrationCapability {
     /// Hardware fixed arbitration scheme, e.g., Round Robin
     pub hardware_fixed_arbitration: bool,
@@ -599,10 +853,17 @@ impl<'a> PartialEq for VcArbitrationTable<'a> {
     }
 }
 impl<'a> Eq for VcArbitrationTable<'a> {}
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for VcArbitrationTable<'a> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.clone())
+    }
+}
 
 /// The VC Arbitration Table is a register array with fixed-size entries of 4 bits.
 /// Each 4-bit table entry corresponds to a phase within a WRR arbitration period.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VcArbitrationTableEntry {
     /// Indicating that the corresponding phase within the WRR arbitration period is assigned to
     /// the Virtual Channel indicated by the VC ID
@@ -629,28 +890,43 @@ pub struct PortVcControlProto {
     rsvdp: B12,
 }
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PortVcControl {
     /// Load VC Arbitration Table
     pub load_vc_arbitration_table: bool,
     /// VC Arbitration Select
     pub vc_arbitration_select: VcArbitrationSelect,
+    /// Reserved bits 15:4
+    pub rsvdp: u16,
 }
 impl From<PortVcControlProto> for PortVcControl {
     fn from(proto: PortVcControlProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             load_vc_arbitration_table: proto.load_vc_arbitration_table(),
             vc_arbitration_select: proto.vc_arbitration_select().into(),
+            rsvdp: proto.rsvdp(),
         }
     }
 }
 impl From<u16> for PortVcControl {
     fn from(word: u16) -> Self { PortVcControlProto::from(word).into() }
 }
+impl From<PortVcControl> for PortVcControlProto {
+    fn from(data: PortVcControl) -> Self {
+        Self::new()
+            .with_load_vc_arbitration_table(data.load_vc_arbitration_table)
+            .with_vc_arbitration_select(data.vc_arbitration_select.into())
+            .with_rsvdp(data.rsvdp)
+    }
+}
+impl From<PortVcControl> for u16 {
+    fn from(data: PortVcControl) -> Self { PortVcControlProto::from(data).into() }
+}
 
 /// The values of this field are corresponding to one of the field in the
 /// [VcArbitrationCapability].
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VcArbitrationSelect {
     /// Hardware fixed arbitration scheme, e.g., Round Robin
     HardwareFixedArbitration,
@@ -686,6 +962,17 @@ impl From<u8> for VcArbitrationSelect {
         }
     }
 }
+impl From<VcArbitrationSelect> for u8 {
+    fn from(data: VcArbitrationSelect) -> Self {
+        match data {
+            VcArbitrationSelect::HardwareFixedArbitration => 0b000,
+            VcArbitrationSelect::Wrr32phases              => 0b001,
+            VcArbitrationSelect::Wrr64phases              => 0b010,
+            VcArbitrationSelect::Wrr128phases             => 0b011,
+            VcArbitrationSelect::Reserved(v)              => v,
+        }
+    }
+}
 
 
 
@@ -698,21 +985,34 @@ pub struct PortVcStatusProto {
 /// The Port VC Status register provides status of the configuration of Virtual Channels associated
 /// with a Port.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PortVcStatus {
     /// VC Arbitration Table Status
     pub vc_arbitration_table_status: bool,
+    /// Reserved bits 15:1
+    pub rsvdp: u16,
 }
 impl From<PortVcStatusProto> for PortVcStatus {
     fn from(proto: PortVcStatusProto) -> Self {
-        let _ = proto.rsvdp();
         Self {
             vc_arbitration_table_status: proto.vc_arbitration_table_status(),
+            rsvdp: proto.rsvdp(),
         }
     }
 }
 impl From<u16> for PortVcStatus {
     fn from(word: u16) -> Self { PortVcStatusProto::from(word).into() }
 }
+impl From<PortVcStatus> for PortVcStatusProto {
+    fn from(data: PortVcStatus) -> Self {
+        Self::new()
+            .with_vc_arbitration_table_status(data.vc_arbitration_table_status)
+            .with_rsvdp(data.rsvdp)
+    }
+}
+impl From<PortVcStatus> for u16 {
+    fn from(data: PortVcStatus) -> Self { PortVcStatusProto::from(data).into() }
+}
 
 
 
@@ -731,6 +1031,9 @@ mod tests {
             low_priority_extended_vc_count: 2,
             reference_clock: ReferenceClock::Reserved(2),
             port_arbitration_table_entry_size: PortArbitrationTableEntrySize(2),
+            rsvdp: 1,
+            rsvdp_2: 1,
+            rsvdp_3: 0,
         };
         assert_eq!(sample, result);
     }
@@ -747,6 +1050,8 @@ mod tests {
                 wrr_128_phases: true,
             },
             vc_arbitration_table_offset: 0xf,
+            rsvdp: 0b1010,
+            rsvdp_2: 0,
         };
         assert_eq!(sample, result);
     }
@@ -758,6 +1063,7 @@ mod tests {
         let sample = PortVcControl {
             load_vc_arbitration_table: true,
             vc_arbitration_select: VcArbitrationSelect::Wrr64phases,
+            rsvdp: 0,
         };
         assert_eq!(sample, result);
     }
@@ -768,6 +1074,7 @@ mod tests {
         let result = PortVcStatus::from(data);
         let sample = PortVcStatus {
             vc_arbitration_table_status: true,
+            rsvdp: 0,
         };
         assert_eq!(sample, result);
     }
@@ -789,6 +1096,8 @@ mod tests {
             reject_snoop_transactions: true,
             maximum_time_slots: 85,
             port_arbitration_table_offset: 0xAA,
+            rsvdp: 0b10,
+            rsvdp_2: 0,
         };
         assert_eq!(sample, result);
     }
@@ -803,6 +1112,9 @@ mod tests {
             port_arbitration_select: PortArbitrationSelect::Wrr256phases,
             vc_id: 5,
             vc_enable: true,
+            rsvdp: 0,
+            rsvdp_2: 0,
+            rsvdp_3: 0,
         };
         assert_eq!(sample, result);
     }
@@ -814,6 +1126,7 @@ mod tests {
         let sample = VcResourceStatus {
             port_arbitration_table_status: true,
             vc_negotiation_pending: true,
+            rsvdz: 0,
         };
         assert_eq!(sample, result);
     }
@@ -911,6 +1224,8 @@ mod tests {
                     reject_snoop_transactions: false,
                     maximum_time_slots: 1 - 1,
                     port_arbitration_table_offset: 0x00,
+                    rsvdp: 0,
+                    rsvdp_2: 0,
                 },
                 vc_resource_control: VcResourceControl {
                     tc_or_vc_map: 0xff,
@@ -918,11 +1233,16 @@ mod tests {
                     port_arbitration_select: PortArbitrationSelect::HardwareFixedArbitration,
                     vc_id: 0,
                     vc_enable: true,
+                    rsvdp: 0,
+                    rsvdp_2: 0,
+                    rsvdp_3: 0,
                 },
                 vc_resource_status: VcResourceStatus {
                     port_arbitration_table_status: false,
                     vc_negotiation_pending: false,
+                    rsvdz: 0,
                 },
+                rsvdp: 0,
             },
             ExtendedVirtualChannel {
                 vc_resource_capability: VcResourceCapability {
@@ -938,6 +1258,8 @@ mod tests {
                     reject_snoop_transactions: false,
                     maximum_time_slots: 1 - 1,
                     port_arbitration_table_offset: 0x02,
+                    rsvdp: 0,
+                    rsvdp_2: 0,
                 },
                 vc_resource_control: VcResourceControl {
                     tc_or_vc_map: 0xff,
@@ -945,14 +1267,45 @@ mod tests {
                     port_arbitration_select: PortArbitrationSelect::HardwareFixedArbitration,
                     vc_id: 0,
                     vc_enable: true,
+                    rsvdp: 0,
+                    rsvdp_2: 0,
+                    rsvdp_3: 0,
                 },
                 vc_resource_status: VcResourceStatus {
                     port_arbitration_table_status: false,
                     vc_negotiation_pending: false,
+                    rsvdz: 0,
                 },
+                rsvdp: 0,
             },
         ];
         assert_eq!(sample, result);
     }
 
+    #[test]
+    fn parse_then_write_with_arbitration_tables() {
+        let data = [
+            // Header
+            0x02,0x00,0x01,0x00,
+            // Port VC Capability 1, Port VC Capability 2: VATOffset=02 WRR32+
+            0x00,0x00,0x00,0x00,0x02,0x00,0x00,0x02,
+            // Port VC Control: ArbSelect=WRR32, Port VC Status
+            0x02,0x00,0x00,0x00,
+            // VC0: PATOffset=03 WRR32+ Enable+ ArbSelect=WRR32 TC/VC=ff
+            0x02,0x00,0x00,0x03,0xff,0x00,0x02,0x80,0x00,0x00,0x00,0x00,
+            // RsvdP
+            0x00,0x00,0x00,0x00,
+            // VC Arbitration Table
+            0x10,0x10,0x10,0x10,0x10,0x10,0x10,0x10,0x10,0x10,0x10,0x10,0x10,0x10,0x10,0x10,
+            // Port Arbitration Table
+            0x55,0xaa,0x55,0xaa,
+        ];
+        let vc: VirtualChannel = data[ECH_BYTES..].read_with(&mut 0, LE).unwrap();
+        assert_eq!(32, vc.vc_arbitration_table().count());
+        let mut result = [0u8; 0x34 - ECH_BYTES];
+        let offset = &mut 0;
+        result.write_with(offset, vc, LE).unwrap();
+        assert_eq!(result.len(), *offset);
+        assert_eq!(data[ECH_BYTES..], result);
+    }
 }
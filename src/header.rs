@@ -52,8 +52,10 @@ let sample = Header {
         is_capable: false,
         is_running: false,
         completion_code: 0x00,
+        reserved: 0,
     },
     capabilities_pointer: 0x50,
+    capabilities_pointer_reserved: 0,
     is_multi_function: true,
     header_type: HeaderType::Normal(Normal {
         base_addresses: BaseAddressesNormal([
@@ -68,6 +70,7 @@ let sample = Header {
         sub_vendor_id: 0x1734,
         sub_device_id: 0x11da,
         expansion_rom: Default::default(),
+        reserved: [0; 7],
         min_grant: 0,
         max_latency: 0,
     }),
@@ -83,10 +86,17 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
+use crate::Error;
+
+/// Header Type register offset
+pub(crate) const HEADER_TYPE_OFFSET: usize = 0x0e;
+/// PCI-to-CardBus bridge header runs past the start of Device Dependent Region
+const CARDBUS_HEADER_LENGTH: usize = 0x48;
+
 pub mod command;
 pub use command::Command;
 
@@ -113,6 +123,7 @@ pub use cardbus_bridge_control::CardbusBridgeControl;
 
 /// Main structure
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Header {
     /// Identifies the manufacturer of the device. Where valid IDs are allocated by PCI-SIG to
     /// ensure uniqueness and 0xFFFF is an invalid value that will be returned on read accesses to
@@ -137,6 +148,9 @@ pub struct Header {
     pub bist: BuiltInSelfTest,
     /// Used to point to a linked list of new capabilities implemented by this device
     pub capabilities_pointer: u8,
+    /// Bottom two bits of Capabilities Pointer register, reserved and masked off from
+    /// [capabilities_pointer](Self::capabilities_pointer)
+    pub capabilities_pointer_reserved: u8,
     /// Specifies which input of the system interrupt controllers the device's interrupt pin is
     /// connected to and is implemented by any device that makes use of an interrupt pin. For
     /// the x86 architecture this register corresponds to the PIC IRQ numbers 0-15 (and not I/O
@@ -144,6 +158,17 @@ pub struct Header {
     pub interrupt_line: u8,
     pub interrupt_pin: InterruptPin,
 }
+impl Header {
+    /// Number of bytes occupied by header with `header_type` register value: 48h for
+    /// PCI-to-CardBus bridge (Type 02h), 40h for others
+    pub fn length(header_type: u8) -> usize {
+        if header_type & 0x7f == 0x02 {
+            CARDBUS_HEADER_LENGTH
+        } else {
+            crate::DDR_OFFSET
+        }
+    }
+}
 impl<'a> TryRead<'a, Endian> for Header {
     fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
         let offset = &mut 0;
@@ -157,7 +182,7 @@ impl<'a> TryRead<'a, Endian> for Header {
         let latency_timer = bytes.read_with::<u8>(offset, endian)?;
         let htype = bytes.read_with::<u8>(offset, endian)?;
         let bist = bytes.read_with::<BuiltInSelfTest>(offset, endian)?;
-        let (capabilities_pointer, interrupt_line, interrupt_pin);
+        let (capabilities_pointer, capabilities_pointer_reserved, interrupt_line, interrupt_pin);
         let is_multi_function = htype & 0x80 != 0;
         let header_type = match htype & !0x80 {
             0x00 => {
@@ -167,10 +192,14 @@ impl<'a> TryRead<'a, Endian> for Header {
                     sub_vendor_id: bytes.read_with::<u16>(offset, endian)?,
                     sub_device_id: bytes.read_with::<u16>(offset, endian)?,
                     expansion_rom: bytes.read_with::<ExpansionRom>(offset, endian)?,
+                    reserved: {
+                        let pointer = bytes.read_with::<u8>(offset, endian)?;
+                        capabilities_pointer = pointer & !0b11;
+                        capabilities_pointer_reserved = pointer & 0b11;
+                        bytes.read_with::<&[u8]>(offset, Bytes::Len(7))?
+                            .try_into().unwrap_or_default()
+                    },
                     min_grant: {
-                        capabilities_pointer = bytes.read_with::<u8>(offset, endian)? & !0b11;
-                        let _reserved: [u8; 7] = bytes.read_with::<&[u8]>(offset, Bytes::Len(7))?
-                            .try_into().unwrap_or_default();
                         interrupt_line = bytes.read_with::<u8>(offset, endian)?;
                         interrupt_pin = bytes.read_with::<InterruptPin>(offset, endian)?;
                         bytes.read_with::<u8>(offset, endian)?
@@ -204,12 +233,14 @@ impl<'a> TryRead<'a, Endian> for Header {
                         bytes.read_with::<u16>(offset, endian)?, // I/O Base Upper 16 Bits
                         bytes.read_with::<u16>(offset, endian)?, // I/O Limit Upper 16 Bits
                     ),
-                    expansion_rom: {
-                        capabilities_pointer = bytes.read_with::<u8>(offset, endian)? & !0b11;
-                        let _reserved: [u8; 3] = bytes.read_with::<&[u8]>(offset, Bytes::Len(3))?
-                            .try_into().unwrap_or_default();
-                        bytes.read_with::<ExpansionRom>(offset, endian)?
+                    reserved: {
+                        let pointer = bytes.read_with::<u8>(offset, endian)?;
+                        capabilities_pointer = pointer & !0b11;
+                        capabilities_pointer_reserved = pointer & 0b11;
+                        bytes.read_with::<&[u8]>(offset, Bytes::Len(3))?
+                            .try_into().unwrap_or_default()
                     },
+                    expansion_rom: bytes.read_with::<ExpansionRom>(offset, endian)?,
                     bridge_control: {
                         interrupt_line = bytes.read_with::<u8>(offset, endian)?;
                         interrupt_pin = bytes.read_with::<InterruptPin>(offset, endian)?;
@@ -220,11 +251,13 @@ impl<'a> TryRead<'a, Endian> for Header {
             0x02 => {
                 HeaderType::Cardbus(Cardbus {
                     base_addresses: bytes.read_with::<BaseAddressesCardbus>(offset, endian)?,
-                    secondary_status: {
-                        capabilities_pointer = bytes.read_with::<u8>(offset, endian)? & !0b11;
-                        let _reserved = bytes.read_with::<u8>(offset, endian)?;
-                        bytes.read_with::<u16>(offset, endian)?.into()
+                    reserved: {
+                        let pointer = bytes.read_with::<u8>(offset, endian)?;
+                        capabilities_pointer = pointer & !0b11;
+                        capabilities_pointer_reserved = pointer & 0b11;
+                        bytes.read_with::<u8>(offset, endian)?
                     },
+                    secondary_status: bytes.read_with::<u16>(offset, endian)?.into(),
                     pci_bus_number: bytes.read_with::<u8>(offset, endian)?,
                     cardbus_bus_number: bytes.read_with::<u8>(offset, endian)?,
                     subordinate_bus_number: bytes.read_with::<u8>(offset, endian)?,
@@ -260,17 +293,111 @@ impl<'a> TryRead<'a, Endian> for Header {
             header_type,
             bist,
             capabilities_pointer,
+            capabilities_pointer_reserved,
             interrupt_line,
             interrupt_pin,
         };
         Ok((header, *offset))
     }
 }
+impl TryWrite<Endian> for Header {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        let htype = match self.header_type {
+            HeaderType::Normal(_) => 0x00,
+            HeaderType::Bridge(_) => 0x01,
+            HeaderType::Cardbus(_) => 0x02,
+        } | if self.is_multi_function { 0x80 } else { 0x00 };
+        bytes.write_with::<u16>(offset, self.vendor_id, endian)?;
+        bytes.write_with::<u16>(offset, self.device_id, endian)?;
+        bytes.write_with::<u16>(offset, self.command.into(), endian)?;
+        bytes.write_with::<u16>(offset, self.status.into(), endian)?;
+        bytes.write_with::<u8>(offset, self.revision_id, endian)?;
+        bytes.write_with::<ClassCode>(offset, self.class_code, endian)?;
+        bytes.write_with::<u8>(offset, self.cache_line_size, endian)?;
+        bytes.write_with::<u8>(offset, self.latency_timer, endian)?;
+        bytes.write_with::<u8>(offset, htype, endian)?;
+        bytes.write_with::<BuiltInSelfTest>(offset, self.bist, endian)?;
+        let capabilities_pointer =
+            (self.capabilities_pointer & !0b11) | (self.capabilities_pointer_reserved & 0b11);
+        match self.header_type {
+            HeaderType::Normal(normal) => {
+                bytes.write_with::<BaseAddressesNormal>(offset, normal.base_addresses, endian)?;
+                bytes.write_with::<u32>(offset, normal.cardbus_cis_pointer, endian)?;
+                bytes.write_with::<u16>(offset, normal.sub_vendor_id, endian)?;
+                bytes.write_with::<u16>(offset, normal.sub_device_id, endian)?;
+                bytes.write_with::<ExpansionRom>(offset, normal.expansion_rom, endian)?;
+                bytes.write_with::<u8>(offset, capabilities_pointer, endian)?;
+                bytes.write::<&[u8]>(offset, &normal.reserved)?;
+                bytes.write_with::<u8>(offset, self.interrupt_line, endian)?;
+                bytes.write_with::<InterruptPin>(offset, self.interrupt_pin, endian)?;
+                bytes.write_with::<u8>(offset, normal.min_grant, endian)?;
+                bytes.write_with::<u8>(offset, normal.max_latency, endian)?;
+            },
+            HeaderType::Bridge(bridge) => {
+                let (io_base, io_limit, io_base_upper_16, io_limit_upper_16) =
+                    bridge.io_address_range.into();
+                let (pf_base, pf_limit, pf_base_upper_32, pf_limit_upper_32) =
+                    bridge.prefetchable_memory.into();
+                bytes.write_with::<BaseAddressesBridge>(offset, bridge.base_addresses, endian)?;
+                bytes.write_with::<u8>(offset, bridge.primary_bus_number, endian)?;
+                bytes.write_with::<u8>(offset, bridge.secondary_bus_number, endian)?;
+                bytes.write_with::<u8>(offset, bridge.subordinate_bus_number, endian)?;
+                bytes.write_with::<u8>(offset, bridge.secondary_latency_timer, endian)?;
+                bytes.write_with::<u8>(offset, io_base, endian)?;
+                bytes.write_with::<u8>(offset, io_limit, endian)?;
+                bytes.write_with::<u16>(offset, bridge.secondary_status.into(), endian)?;
+                bytes.write_with::<u16>(offset, bridge.memory_base, endian)?;
+                bytes.write_with::<u16>(offset, bridge.memory_limit, endian)?;
+                bytes.write_with::<u16>(offset, pf_base, endian)?;
+                bytes.write_with::<u16>(offset, pf_limit, endian)?;
+                bytes.write_with::<u32>(offset, pf_base_upper_32, endian)?;
+                bytes.write_with::<u32>(offset, pf_limit_upper_32, endian)?;
+                bytes.write_with::<u16>(offset, io_base_upper_16, endian)?;
+                bytes.write_with::<u16>(offset, io_limit_upper_16, endian)?;
+                bytes.write_with::<u8>(offset, capabilities_pointer, endian)?;
+                bytes.write::<&[u8]>(offset, &bridge.reserved)?;
+                bytes.write_with::<ExpansionRom>(offset, bridge.expansion_rom, endian)?;
+                bytes.write_with::<u8>(offset, self.interrupt_line, endian)?;
+                bytes.write_with::<InterruptPin>(offset, self.interrupt_pin, endian)?;
+                bytes.write_with::<u16>(offset, bridge.bridge_control.into(), endian)?;
+            },
+            HeaderType::Cardbus(cardbus) => {
+                bytes.write_with::<BaseAddressesCardbus>(offset, cardbus.base_addresses, endian)?;
+                bytes.write_with::<u8>(offset, capabilities_pointer, endian)?;
+                bytes.write_with::<u8>(offset, cardbus.reserved, endian)?;
+                bytes.write_with::<u16>(offset, cardbus.secondary_status.into(), endian)?;
+                bytes.write_with::<u8>(offset, cardbus.pci_bus_number, endian)?;
+                bytes.write_with::<u8>(offset, cardbus.cardbus_bus_number, endian)?;
+                bytes.write_with::<u8>(offset, cardbus.subordinate_bus_number, endian)?;
+                bytes.write_with::<u8>(offset, cardbus.cardbus_latency_timer, endian)?;
+                bytes.write_with::<u32>(offset, cardbus.memory_base_address_0, endian)?;
+                bytes.write_with::<u32>(offset, cardbus.memory_limit_address_0, endian)?;
+                bytes.write_with::<u32>(offset, cardbus.memory_base_address_1, endian)?;
+                bytes.write_with::<u32>(offset, cardbus.memory_limit_address_1, endian)?;
+                bytes.write_with::<IoAccessAddressRange>(offset, cardbus.io_access_address_range_0, endian)?;
+                bytes.write_with::<IoAccessAddressRange>(offset, cardbus.io_access_address_range_1, endian)?;
+                bytes.write_with::<u8>(offset, self.interrupt_line, endian)?;
+                bytes.write_with::<InterruptPin>(offset, self.interrupt_pin, endian)?;
+                bytes.write_with::<u16>(offset, cardbus.bridge_control.into(), endian)?;
+                bytes.write_with::<u16>(offset, cardbus.subsystem_vendor_id, endian)?;
+                bytes.write_with::<u16>(offset, cardbus.subsystem_device_id, endian)?;
+                bytes.write_with::<u32>(offset, cardbus.legacy_mode_base_address, endian)?;
+            },
+        }
+        Ok(*offset)
+    }
+}
 impl<'a> TryFrom<&'a [u8]> for Header {
-    type Error = byte::Error;
+    type Error = Error;
 
     fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
-        bytes.read_with(&mut 0, LE)
+        let header_type = *bytes.get(HEADER_TYPE_OFFSET)
+            .ok_or(Error::Truncated { offset: 0 })?;
+        if header_type & 0x7f > 0x02 {
+            return Err(Error::UnknownHeaderType { offset: HEADER_TYPE_OFFSET as u16, header_type });
+        }
+        bytes.read_with(&mut 0, LE).map_err(|e| Error::from_byte(0, e))
     }
 }
 
@@ -279,6 +406,7 @@ impl<'a> TryFrom<&'a [u8]> for Header {
 /// Identifies the layout of the second part of the predefined header (beginning at byte 10h in
 /// Configuration Space) and also whether or not the device contains multiple functions.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HeaderType {
     Normal(Normal),
     Bridge(Bridge),
@@ -304,6 +432,7 @@ impl HeaderType {
 
 /// General device (Type 00h)
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Normal {
     pub base_addresses: BaseAddressesNormal,
     /// Points to the Card Information Structure and is used by devices that share silicon between CardBus and PCI.
@@ -314,6 +443,8 @@ pub struct Normal {
     pub sub_device_id: u16,
     /// Expansion ROM
     pub expansion_rom: ExpansionRom,
+    /// Reserved bytes following Capabilities Pointer
+    pub reserved: [u8; 7],
     /// A read-only register that specifies the burst period length, in 1/4 microsecond units,
     /// that the device needs (assuming a 33 MHz clock rate).
     pub min_grant: u8,
@@ -324,6 +455,7 @@ pub struct Normal {
 
 /// PCI-to-PCI bridge (Type 01h)
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Bridge {
     /// Base Address Registers
     pub base_addresses: BaseAddressesBridge,
@@ -343,6 +475,8 @@ pub struct Bridge {
     /// Memory Limit
     pub memory_limit: u16,
     pub prefetchable_memory: BridgePrefetchableMemory,
+    /// Reserved bytes following Capabilities Pointer
+    pub reserved: [u8; 3],
     /// Expansion ROM
     pub expansion_rom: ExpansionRom,
     pub bridge_control: BridgeControl,
@@ -351,6 +485,7 @@ pub struct Bridge {
 /// The I/O Base and I/O Limit registers define an address range that is used by the bridge to
 /// determine when to forward I/O transactions from one interface to the other.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BridgeIoAddressRange {
     NotImplemented,
     IoAddr16 {
@@ -361,31 +496,58 @@ pub enum BridgeIoAddressRange {
         base: u32,
         limit: u32,
     },
+    /// Reserved addressing capability or registers not matching any other variant
     Reserved {
         base: u8,
         limit: u8,
+        base_upper_16: u16,
+        limit_upper_16: u16,
     },
 }
 impl BridgeIoAddressRange {
     pub fn new(io_base: u8, io_limit: u8, io_base_upper_16: u16, io_limit_upper_16: u16) -> Self {
+        let registers = (io_base, io_limit, io_base_upper_16, io_limit_upper_16);
         let base_capability = io_base & 0xf;
         let base_address = io_base & !0xf;
-        let _limit_capability = io_limit & 0xf;
         let limit_address = io_limit & !0xf;
-        match (base_capability, base_address) {
-            (0x00, 0x00) => Self::NotImplemented,
-            (0x00, _) => Self::IoAddr16 {
+        let result = match base_capability {
+            _ if registers == (0, 0, 0, 0) => Self::NotImplemented,
+            0x00 => Self::IoAddr16 {
                 base: (base_address as u16) << 8,
                 limit: (limit_address as u16) << 8,
             },
-            (0x01, _) => Self::IoAddr32 {
+            0x01 => Self::IoAddr32 {
                 base: ((base_address as u32) << 8) | ((io_base_upper_16 as u32) << 16),
                 limit: ((limit_address as u32) << 8) | ((io_limit_upper_16 as u32) << 16),
             },
-            _ => Self::Reserved {
-                base: io_base,
-                limit: io_limit,
-            },
+            _ => return Self::reserved(registers),
+        };
+        // Limit capability bits and unused upper registers have to be consistent with the base
+        if <(u8, u8, u16, u16)>::from(result.clone()) == registers {
+            result
+        } else {
+            Self::reserved(registers)
+        }
+    }
+    fn reserved((base, limit, base_upper_16, limit_upper_16): (u8, u8, u16, u16)) -> Self {
+        Self::Reserved { base, limit, base_upper_16, limit_upper_16 }
+    }
+}
+/// I/O Base, I/O Limit, I/O Base Upper 16 Bits and I/O Limit Upper 16 Bits registers
+impl From<BridgeIoAddressRange> for (u8, u8, u16, u16) {
+    fn from(data: BridgeIoAddressRange) -> Self {
+        match data {
+            BridgeIoAddressRange::NotImplemented => (0, 0, 0, 0),
+            BridgeIoAddressRange::IoAddr16 { base, limit } =>
+                ((base >> 8) as u8 & !0xf, (limit >> 8) as u8 & !0xf, 0, 0),
+            BridgeIoAddressRange::IoAddr32 { base, limit } => (
+                (base >> 8) as u8 & !0xf | 0x01,
+                (limit >> 8) as u8 & !0xf | 0x01,
+                (base >> 16) as u16,
+                (limit >> 16) as u16,
+            ),
+            BridgeIoAddressRange::Reserved { base, limit, base_upper_16, limit_upper_16 } =>
+                (base, limit, base_upper_16, limit_upper_16),
         }
     }
 }
@@ -394,6 +556,7 @@ impl BridgeIoAddressRange {
 /// memory address range which is used by the bridge to determine when to forward memory
 /// transactions from one interface to the other
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BridgePrefetchableMemory {
     NotImplemented,
     MemAddr32 {
@@ -404,39 +567,70 @@ pub enum BridgePrefetchableMemory {
         base: u64,
         limit: u64,
     },
+    /// Reserved addressing capability or registers not matching any other variant
     Reserved {
         base: u16,
         limit: u16,
+        base_upper_32: u32,
+        limit_upper_32: u32,
     },
 }
 impl BridgePrefetchableMemory {
     pub fn new(base: u16, limit: u16, base_upper_32: u32, limit_upper_32: u32) -> Self {
+        let registers = (base, limit, base_upper_32, limit_upper_32);
         let base_capability = base & 0xf;
         let base_address = base & !0xf;
-        let _limit_capability = limit & 0xf;
         let limit_address = limit & !0xf;
-        match (base_capability, base_address) {
-            (0x00, 0x00) => Self::NotImplemented,
-            (0x00, _) => Self::MemAddr32 {
+        let result = match base_capability {
+            _ if registers == (0, 0, 0, 0) => Self::NotImplemented,
+            0x00 => Self::MemAddr32 {
                 base: (base_address as u32) << 16,
                 limit: (limit_address as u32) << 16,
             },
-            (0x01, _) => Self::MemAddr64 {
+            0x01 => Self::MemAddr64 {
                 base: ((base_address as u64) << 16) | ((base_upper_32 as u64) << 32),
                 limit: ((limit_address as u64) << 16) | ((limit_upper_32 as u64) << 32),
             },
-            _ => Self::Reserved {
-                base,
-                limit,
-            },
+            _ => return Self::reserved(registers),
+        };
+        // Limit capability bits and unused upper registers have to be consistent with the base
+        if <(u16, u16, u32, u32)>::from(result.clone()) == registers {
+            result
+        } else {
+            Self::reserved(registers)
+        }
+    }
+    fn reserved((base, limit, base_upper_32, limit_upper_32): (u16, u16, u32, u32)) -> Self {
+        Self::Reserved { base, limit, base_upper_32, limit_upper_32 }
+    }
+}
+/// Prefetchable Memory Base, Prefetchable Memory Limit, Prefetchable Base Upper 32 Bits and
+/// Prefetchable Limit Upper 32 Bits registers
+impl From<BridgePrefetchableMemory> for (u16, u16, u32, u32) {
+    fn from(data: BridgePrefetchableMemory) -> Self {
+        match data {
+            BridgePrefetchableMemory::NotImplemented => (0, 0, 0, 0),
+            BridgePrefetchableMemory::MemAddr32 { base, limit } =>
+                ((base >> 16) as u16 & !0xf, (limit >> 16) as u16 & !0xf, 0, 0),
+            BridgePrefetchableMemory::MemAddr64 { base, limit } => (
+                (base >> 16) as u16 & !0xf | 0x01,
+                (limit >> 16) as u16 & !0xf | 0x01,
+                (base >> 32) as u32,
+                (limit >> 32) as u32,
+            ),
+            BridgePrefetchableMemory::Reserved { base, limit, base_upper_32, limit_upper_32 } =>
+                (base, limit, base_upper_32, limit_upper_32),
         }
     }
 }
 
 /// PCI-to-CardBus bridge (Type 02h)
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Cardbus {
     pub base_addresses: BaseAddressesCardbus,
+    /// Reserved byte following Capabilities Pointer
+    pub reserved: u8,
     /// Secondary status
     pub secondary_status: Status<'C'>,
     /// PCI Bus Number
@@ -469,6 +663,7 @@ pub struct Cardbus {
 
 /// Represents that status and allows control of a devices BIST (built-in self test).
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BuiltInSelfTest {
     /// Device supports BIST
     pub is_capable: bool,
@@ -477,6 +672,8 @@ pub struct BuiltInSelfTest {
     pub is_running: bool,
     /// Will return 0, after BIST execution, if the test completed successfully.
     pub completion_code: u8,
+    /// Reserved bits 4 and 5
+    pub reserved: u8,
 }
 impl From<u8> for BuiltInSelfTest {
     fn from(data: u8) -> Self {
@@ -484,12 +681,13 @@ impl From<u8> for BuiltInSelfTest {
             is_capable: data & 0b1000_0000 != 0,
             is_running: data & 0b0100_0000 != 0,
             completion_code: data & 0b1111,
+            reserved: (data >> 4) & 0b11,
         }
     }
 }
 impl From<BuiltInSelfTest> for u8 {
     fn from(bist: BuiltInSelfTest) -> Self {
-        let mut result = bist.completion_code & 0b1111;
+        let mut result = (bist.completion_code & 0b1111) | ((bist.reserved & 0b11) << 4);
         if bist.is_capable {
             result |= 0b1000_0000;
         }
@@ -502,19 +700,22 @@ impl From<BuiltInSelfTest> for u8 {
 impl<'a> TryRead<'a, Endian> for BuiltInSelfTest {
     fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
         let offset = &mut 0;
-        let byte = bytes.read_with::<u8>(offset, endian)?;
-        let bist = BuiltInSelfTest {
-            is_capable: byte & 0b1000_0000 != 0,
-            is_running: byte & 0b0100_0000 != 0,
-            completion_code: byte & 0b1111,
-        };
+        let bist = bytes.read_with::<u8>(offset, endian)?.into();
         Ok((bist, *offset))
     }
 }
+impl TryWrite<Endian> for BuiltInSelfTest {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u8>(offset, self.into(), endian)?;
+        Ok(*offset)
+    }
+}
 
 
 /// Specifies which interrupt pin the device uses.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InterruptPin {
     Unused,
     IntA,
@@ -541,6 +742,13 @@ impl<'a> TryRead<'a, Endian> for InterruptPin {
         Ok((interrupt_pin, *offset))
     }
 }
+impl TryWrite<Endian> for InterruptPin {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u8>(offset, self.into(), endian)?;
+        Ok(*offset)
+    }
+}
 impl From<u8> for InterruptPin {
     fn from(data: u8) -> Self {
         match data {
@@ -569,7 +777,8 @@ impl From<InterruptPin> for u8 {
 
 /// The IO Base Register and I/O Limit Register defines the address range that is used by the
 /// bridge to determine when to forward an I/O transaction to the CardBus.
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IoAccessAddressRange {
     Addr16Bit {
         base: u16,
@@ -579,8 +788,12 @@ pub enum IoAccessAddressRange {
         base: u32,
         limit: u32
     },
-    #[default]
-    Unknown,
+    /// Unknown address decoding type or 16-bit decoding with non-zero upper registers, holds
+    /// raw register contents
+    Unknown([[u16; 2]; 2]),
+}
+impl Default for IoAccessAddressRange {
+    fn default() -> Self { Self::Addr16Bit { base: 0, limit: 0 } }
 }
 
 impl<'a> TryRead<'a, Endian> for IoAccessAddressRange {
@@ -590,34 +803,26 @@ impl<'a> TryRead<'a, Endian> for IoAccessAddressRange {
         let base_upper = bytes.read_with::<u16>(offset, endian)?;
         let limit_lower = bytes.read_with::<u16>(offset, endian)?;
         let limit_upper = bytes.read_with::<u16>(offset, endian)?;
-        let io_access_address_range = match base_lower & 0b11 {
-            0x00 => Self::Addr16Bit {
-                base: base_lower & !0b11,
-                limit: limit_lower
-            },
-            0x01 => {
-                let base = u32::from_le_bytes({
-                    let lower = (base_lower & !0b11).to_le_bytes();
-                    let upper = base_upper.to_le_bytes();
-                    [lower[0], lower[1], upper[0], upper[1]]
-                });
-                let limit = u32::from_le_bytes({
-                    let lower = limit_lower.to_le_bytes();
-                    let upper = limit_upper.to_le_bytes();
-                    [lower[0], lower[1], upper[0], upper[1]]
-                });
-                Self::Addr32Bit { base, limit }
-            },
-            _ => Self::Unknown,
-        };
+        let io_access_address_range = [[base_lower, base_upper], [limit_lower, limit_upper]].into();
         Ok((io_access_address_range, *offset))
     }
 }
+impl TryWrite<Endian> for IoAccessAddressRange {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        let [[base_lower, base_upper], [limit_lower, limit_upper]]: [[u16; 2]; 2] = self.into();
+        bytes.write_with::<u16>(offset, base_lower, endian)?;
+        bytes.write_with::<u16>(offset, base_upper, endian)?;
+        bytes.write_with::<u16>(offset, limit_lower, endian)?;
+        bytes.write_with::<u16>(offset, limit_upper, endian)?;
+        Ok(*offset)
+    }
+}
 impl From<[[u16;2]; 2]> for IoAccessAddressRange {
     fn from(data: [[u16;2]; 2]) -> Self {
         let [[base_lower, base_upper], [limit_lower, limit_upper]] = data;
         match base_lower & 0b11 {
-            0x00 => Self::Addr16Bit {
+            0x00 if base_upper == 0 && limit_upper == 0 => Self::Addr16Bit {
                 base: base_lower & !0b11,
                 limit: limit_lower
             },
@@ -634,7 +839,7 @@ impl From<[[u16;2]; 2]> for IoAccessAddressRange {
                 });
                 Self::Addr32Bit { base, limit }
             },
-            _ => Self::Unknown,
+            _ => Self::Unknown(data),
         }
     }
 }
@@ -646,32 +851,31 @@ impl From<IoAccessAddressRange > for [[u16;2]; 2] {
             },
             IoAccessAddressRange::Addr32Bit { base, limit } => {
                 let base = base.to_le_bytes();
-                let base_upper = u16::from_le_bytes([base[0] & !0b11, base[1]]);
-                let base_lower = u16::from_le_bytes([base[2], base[3]]);
+                let base_lower = u16::from_le_bytes([(base[0] & !0b11) | 0b01, base[1]]);
+                let base_upper = u16::from_le_bytes([base[2], base[3]]);
                 let limit = limit.to_le_bytes();
-                let limit_upper = u16::from_le_bytes([limit[0], limit[1]]);
-                let limit_lower = u16::from_le_bytes([limit[2], limit[3]]);
-                [[base_upper, base_lower], [limit_upper, limit_lower]]
+                let limit_lower = u16::from_le_bytes([limit[0], limit[1]]);
+                let limit_upper = u16::from_le_bytes([limit[2], limit[3]]);
+                [[base_lower, base_upper], [limit_lower, limit_upper]]
             },
-            _ => unreachable!(),
+            IoAccessAddressRange::Unknown(data) => data,
         }
     }
 }
 
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExpansionRom {
     pub address: u32,
     pub is_enabled: bool,
+    /// Reserved bits 1 to 10
+    pub reserved: u16,
 }
 impl<'a> TryRead<'a, Endian> for ExpansionRom {
     fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
         let offset = &mut 0;
-        let dword = bytes.read_with::<u32>(offset, endian)?;
-        let expansion_rom = ExpansionRom {
-            address: dword & !0x7ff,
-            is_enabled: dword & 1 != 0,
-        };
+        let expansion_rom = bytes.read_with::<u32>(offset, endian)?.into();
         Ok((expansion_rom, *offset))
     }
 }
@@ -680,12 +884,20 @@ impl From<u32> for ExpansionRom {
         Self {
             address: dword & !0x7ff,
             is_enabled: dword & 1 != 0,
+            reserved: ((dword >> 1) & 0x3ff) as u16,
         }
     }
 }
 impl From<ExpansionRom> for u32 {
     fn from(rom: ExpansionRom) -> Self {
-        rom.address | (rom.is_enabled as u32)
+        rom.address | (u32::from(rom.reserved & 0x3ff) << 1) | (rom.is_enabled as u32)
+    }
+}
+impl TryWrite<Endian> for ExpansionRom {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u32>(offset, self.into(), endian)?;
+        Ok(*offset)
     }
 }
 
@@ -693,6 +905,7 @@ impl From<ExpansionRom> for u32 {
 
 #[cfg(test)]
 mod tests {
+    use std::prelude::v1::*;
     use byte::*;
     use pretty_assertions::assert_eq;
     use super::*;
@@ -709,7 +922,78 @@ mod tests {
         assert_eq!(IoAccessAddressRange::Addr32Bit { base: 0x500050, limit: 0x600060 }, a32.into(), "32 Bit");
 
         let unkn = [[ 0x52, 0x50 ], [ 0x60, 0x60 ]];
-        assert_eq!(IoAccessAddressRange::Unknown, unkn.into(), "Unknown");
+        assert_eq!(IoAccessAddressRange::Unknown(unkn), unkn.into(), "Unknown");
+
+        let a16_upper = [[ 0x50, 0x50 ], [ 0x60, 0x00 ]];
+        assert_eq!(IoAccessAddressRange::Unknown(a16_upper), a16_upper.into(), "16 Bit with upper");
+    }
+
+    #[test]
+    fn io_access_address_range_into_registers() {
+        let a16 = [[ 0x50, 0x00 ], [ 0x60, 0x00 ]];
+        assert_eq!(a16, <[[u16; 2]; 2]>::from(IoAccessAddressRange::from(a16)), "16 Bit");
+
+        let a32 = [[ 0x51, 0x50 ], [ 0x60, 0x60 ]];
+        assert_eq!(a32, <[[u16; 2]; 2]>::from(IoAccessAddressRange::from(a32)), "32 Bit");
+
+        let unkn = [[ 0x52, 0x50 ], [ 0x60, 0x60 ]];
+        assert_eq!(unkn, <[[u16; 2]; 2]>::from(IoAccessAddressRange::from(unkn)), "Unknown");
+    }
+
+    fn write_header(data: &[u8]) -> Vec<u8> {
+        let header: Header = data.read_with(&mut 0, LE).unwrap();
+        let mut result = vec![0u8; data.len()];
+        let len = result.write_with(&mut 0, header, LE).map(|_| data.len());
+        assert_eq!(Ok(data.len()), len);
+        result
+    }
+
+    #[test]
+    fn write_header_type_normal() {
+        let data = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/device/8086_9dc8/config"));
+        let data = &data[..0x40];
+        assert_eq!(data, &write_header(data)[..]);
+    }
+
+    #[test]
+    fn write_header_type_bridge() {
+        let data = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/device/8086_2030/config"));
+        let data = &data[..0x40];
+        assert_eq!(data, &write_header(data)[..]);
+    }
+
+    #[test]
+    fn write_header_type_cardbus() {
+        // Same as header_type_cardbus data
+        let data = [
+            0x8e, 0xdf, 0xee, 0x05, 0xb4, 0x00, 0x78, 0x4b, 0x37, 0x00, 0x07, 0x06, 0xf2, 0x29, 0x82, 0x00,
+            0x00, 0x80, 0xf8, 0x35, 0x80, 0x00, 0x00, 0x00, 0x6d, 0xba, 0xfe, 0xfc, 0x00, 0x40, 0xf5, 0x11,
+            0x00, 0x50, 0x47, 0x22, 0x00, 0x30, 0x85, 0x33, 0x00, 0xc0, 0xd0, 0x44, 0x60, 0x00, 0x00, 0x00,
+            0x70, 0x00, 0x00, 0x00, 0x61, 0x00, 0x06, 0x00, 0x70, 0x00, 0x07, 0x00, 0x06, 0x1a, 0x45, 0x05,
+            0x22, 0x33, 0x44, 0x55, 0x22, 0x33, 0x00, 0x00,
+        ];
+        assert_eq!(&data[..], &write_header(&data)[..]);
+    }
+
+    #[test]
+    fn write_header_of_every_device() {
+        for (name, data) in crate::test_data::device_configs() {
+            let data = &data[..0x40];
+            assert_eq!(data, &write_header(data)[..], "{}", name);
+        }
+    }
+
+    #[test]
+    fn write_random_headers() {
+        let mut random = crate::test_data::Random::new(0x0e);
+        for _ in 0..1_000 {
+            let mut data = [0u8; 0x48];
+            random.fill(&mut data);
+            let header_type = data[0] % 3;
+            data[HEADER_TYPE_OFFSET] = (data[HEADER_TYPE_OFFSET] & 0x80) | header_type;
+            let data = if header_type == 0x02 { &data[..] } else { &data[..0x40] };
+            assert_eq!(data, &write_header(data)[..], "{:02x?}", data);
+        }
     }
 
     #[test]
@@ -758,8 +1042,10 @@ mod tests {
                 is_capable: false,
                 is_running: false,
                 completion_code: 0x00,
+                reserved: 0,
             },
             capabilities_pointer: 0x80,
+            capabilities_pointer_reserved: 0,
             is_multi_function: false,
             header_type: HeaderType::Normal(Normal {
                 base_addresses: BaseAddressesNormal([
@@ -774,6 +1060,7 @@ mod tests {
                 sub_vendor_id: 0x1028,
                 sub_device_id: 0x06a5,
                 expansion_rom: Default::default(),
+                reserved: [0; 7],
                 min_grant: 0,
                 max_latency: 0,
             }),
@@ -852,10 +1139,12 @@ mod tests {
             cache_line_size: 0,
             latency_timer: 0,
             capabilities_pointer: 0x40,
+            capabilities_pointer_reserved: 0,
             bist: BuiltInSelfTest {
                 is_capable: true,
                 is_running: false,
                 completion_code: 0x00,
+                reserved: 0,
             },
             is_multi_function: false,
             header_type: HeaderType::Bridge(Bridge {
@@ -875,6 +1164,7 @@ mod tests {
                     base: 0x91000000,
                     limit: 0x91ffffff - 0xfffff,
                 },
+                reserved: [0; 3],
                 expansion_rom: Default::default(),
                 bridge_control: 0b0000_0000_0001_1011.into(),
             }),
@@ -925,9 +1215,11 @@ mod tests {
             cache_line_size: 0xf2,
             latency_timer: 41,
             capabilities_pointer: 0x80,
+            capabilities_pointer_reserved: 0,
             is_multi_function: true,
             header_type: HeaderType::Cardbus(Cardbus {
                 base_addresses: BaseAddressesCardbus([0x35f88000]),
+                reserved: 0x00,
                 secondary_status: 0x0000.into(),
                 pci_bus_number: 0x6d,
                 cardbus_bus_number: 0xba,
@@ -956,10 +1248,24 @@ mod tests {
                 is_capable: false,
                 is_running: false,
                 completion_code: 0x00,
+                reserved: 0,
             },
             interrupt_line: 0x06,
             interrupt_pin: InterruptPin::Reserved(0x1a),
         };
         assert_eq!(sample, result);
     }
+
+    #[test]
+    fn try_from_errors() {
+        let mut data = [0u8; 0x40];
+        data[0x0e] = 0x83;
+        assert_eq!(
+            Err(crate::Error::UnknownHeaderType { offset: 0x0e, header_type: 0x83 }),
+            Header::try_from(&data[..])
+        );
+        assert_eq!(Err(crate::Error::Truncated { offset: 0 }), Header::try_from(&data[..0x0e]));
+        data[0x0e] = 0x00;
+        assert_eq!(Err(crate::Error::Truncated { offset: 0 }), Header::try_from(&data[..0x20]));
+    }
 }
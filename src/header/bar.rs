@@ -9,12 +9,13 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BaseAddressesNormal(pub [u32; 6]);
 impl<'a> TryRead<'a, Endian> for BaseAddressesNormal {
     fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
@@ -30,8 +31,18 @@ impl<'a> TryRead<'a, Endian> for BaseAddressesNormal {
         Ok((Self(bar), *offset))
     }
 }
+impl TryWrite<Endian> for BaseAddressesNormal {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        for dword in self.0 {
+            bytes.write_with::<u32>(offset, dword, endian)?;
+        }
+        Ok(*offset)
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BaseAddressesBridge(pub [u32; 2]);
 impl<'a> TryRead<'a, Endian> for BaseAddressesBridge {
     fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
@@ -43,8 +54,18 @@ impl<'a> TryRead<'a, Endian> for BaseAddressesBridge {
         Ok((Self(bar), *offset))
     }
 }
+impl TryWrite<Endian> for BaseAddressesBridge {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        for dword in self.0 {
+            bytes.write_with::<u32>(offset, dword, endian)?;
+        }
+        Ok(*offset)
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BaseAddressesCardbus(pub [u32; 1]);
 impl<'a> TryRead<'a, Endian> for BaseAddressesCardbus {
     fn try_read(bytes: &'a [u8], endian: Endian) -> byte::Result<(Self, usize)> {
@@ -55,6 +76,15 @@ impl<'a> TryRead<'a, Endian> for BaseAddressesCardbus {
         Ok((Self(bar), *offset))
     }
 }
+impl TryWrite<Endian> for BaseAddressesCardbus {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        for dword in self.0 {
+            bytes.write_with::<u32>(offset, dword, endian)?;
+        }
+        Ok(*offset)
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum BaseAddressesType {
@@ -143,12 +173,14 @@ impl Iterator for BaseAddresses {
 
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BaseAddress {
     pub region: usize,
     pub base_address_type: BaseAddressType,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BaseAddressType {
     /// 32-bit Memory Space mapping
     MemorySpace32 {
@@ -10,6 +10,7 @@ use modular_bitfield::prelude::*;
 
 /// Bridge Control Register
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BridgeControl {
     /// Controls the bridge’s response to address and data parity errors on the secondary interface
     pub parity_error_response_enable: bool,
@@ -42,6 +43,7 @@ pub struct BridgeControl {
     /// either the Primary Discard Timer or Secondary Discard Timer expires and a Delayed
     /// Transaction is discarded from a queue in the bridge
     pub discard_timer_serr_enable: bool,
+    pub reserved: u8,
 }
 
 #[bitfield(bits = 16)]
@@ -79,12 +81,34 @@ impl From<BridgeControlProto> for BridgeControl {
             secondary_discard_timer: proto.secondary_discard_timer(),
             discard_timer_status: proto.discard_timer_status(),
             discard_timer_serr_enable: proto.discard_timer_serr_enable(),
+            reserved: proto.reserved(),
         }
     }
 }
 impl From<u16> for BridgeControl {
     fn from(word: u16) -> Self { BridgeControlProto::from(word).into() }
 }
+impl From<BridgeControl> for BridgeControlProto {
+    fn from(data: BridgeControl) -> Self {
+        Self::new()
+            .with_parity_error_response_enable(data.parity_error_response_enable)
+            .with_serr_enable(data.serr_enable)
+            .with_isa_enable(data.isa_enable)
+            .with_vga_enable(data.vga_enable)
+            .with_vga_16_enable(data.vga_16_enable)
+            .with_master_abort_mode(data.master_abort_mode)
+            .with_secondary_bus_reset(data.secondary_bus_reset)
+            .with_fast_back_to_back_enable(data.fast_back_to_back_enable)
+            .with_primary_discard_timer(data.primary_discard_timer)
+            .with_secondary_discard_timer(data.secondary_discard_timer)
+            .with_discard_timer_status(data.discard_timer_status)
+            .with_discard_timer_serr_enable(data.discard_timer_serr_enable)
+            .with_reserved(data.reserved)
+    }
+}
+impl From<BridgeControl> for u16 {
+    fn from(data: BridgeControl) -> Self { BridgeControlProto::from(data).into() }
+}
 
 
 
@@ -109,7 +133,14 @@ mod tests {
             secondary_discard_timer: true,
             discard_timer_status: false,
             discard_timer_serr_enable: true,
+            reserved: 0b1010,
         };
         assert_eq!(sample, result);
     }
+
+    #[test]
+    fn into_word() {
+        let sample: BridgeControl = 0xAAAA.into();
+        assert_eq!(0xAAAA, u16::from(sample));
+    }
 }
@@ -6,6 +6,7 @@ use modular_bitfield::prelude::*;
 
 /// Bridge Control Register (Offset = 3EH)
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CardbusBridgeControl {
     /// Controls the response to parity errors on the CardBus
     pub parity_error_response_enable: bool,
@@ -31,6 +32,8 @@ pub struct CardbusBridgeControl {
     pub memory_1_prefetch_enable: bool,
     /// Enables posting of Write data to and from the socket
     pub write_posting_enable: bool,
+    pub reserved0: u8,
+    pub reserved1: u8,
 }
 
 #[bitfield(bits = 16)]
@@ -65,12 +68,34 @@ impl From<CardbusBridgeControlProto> for CardbusBridgeControl {
             memory_0_prefetch_enable: proto.memory_0_prefetch_enable(),
             memory_1_prefetch_enable: proto.memory_1_prefetch_enable(),
             write_posting_enable: proto.write_posting_enable(),
+            reserved0: proto.reserved0(),
+            reserved1: proto.reserved1(),
         }
     }
 }
 impl From<u16> for CardbusBridgeControl {
     fn from(word: u16) -> Self { CardbusBridgeControlProto::from(word).into() }
 }
+impl From<CardbusBridgeControl> for CardbusBridgeControlProto {
+    fn from(data: CardbusBridgeControl) -> Self {
+        Self::new()
+            .with_parity_error_response_enable(data.parity_error_response_enable)
+            .with_serr_enable(data.serr_enable)
+            .with_isa_enable(data.isa_enable)
+            .with_vga_enable(data.vga_enable)
+            .with_reserved0(data.reserved0)
+            .with_master_abort_mode(data.master_abort_mode)
+            .with_cardbus_reset(data.cardbus_reset)
+            .with_ireq_int_enable(data.ireq_int_enable)
+            .with_memory_0_prefetch_enable(data.memory_0_prefetch_enable)
+            .with_memory_1_prefetch_enable(data.memory_1_prefetch_enable)
+            .with_write_posting_enable(data.write_posting_enable)
+            .with_reserved1(data.reserved1)
+    }
+}
+impl From<CardbusBridgeControl> for u16 {
+    fn from(data: CardbusBridgeControl) -> Self { CardbusBridgeControlProto::from(data).into() }
+}
 
 
 
@@ -93,7 +118,15 @@ mod tests {
             memory_0_prefetch_enable: false,
             memory_1_prefetch_enable: true,
             write_posting_enable: false,
+            reserved0: 0b0,
+            reserved1: 0b10101,
         };
         assert_eq!(sample, result);
     }
+
+    #[test]
+    fn into_word() {
+        let sample: CardbusBridgeControl = 0xAAAA.into();
+        assert_eq!(0xAAAA, u16::from(sample));
+    }
 }
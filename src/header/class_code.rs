@@ -2,12 +2,13 @@ use byte::{
     ctx::*,
     self,
     TryRead,
-    // TryWrite,
+    TryWrite,
     BytesExt,
 };
 
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClassCode {
     /// Specific register-level programming interface (if any) so that device independent software
     /// can interact with the device
@@ -30,6 +31,16 @@ impl<'a> TryRead<'a, Endian> for ClassCode {
     }
 }
 
+impl TryWrite<Endian> for ClassCode {
+    fn try_write(self, bytes: &mut [u8], endian: Endian) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with::<u8>(offset, self.interface, endian)?;
+        bytes.write_with::<u8>(offset, self.sub, endian)?;
+        bytes.write_with::<u8>(offset, self.base, endian)?;
+        Ok(*offset)
+    }
+}
+
 impl ClassCode {
     pub fn meaning(&self) -> (&str, Option<&str>, Option<&str>) {
         match (self.base, self.sub, self.interface) {
@@ -24,6 +24,7 @@ pub struct CommandProto {
 /// to this register, the device is disconnected from the PCI bus for all accesses except
 /// Configuration Space access.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Command {
     pub io_space: bool,
     pub memory_space: bool,
@@ -59,6 +60,26 @@ impl From<CommandProto> for Command {
 impl From<u16> for Command {
     fn from(word: u16) -> Self { CommandProto::from(word).into() }
 }
+impl From<Command> for CommandProto {
+    fn from(data: Command) -> Self {
+        Self::new()
+            .with_io_space(data.io_space)
+            .with_memory_space(data.memory_space)
+            .with_bus_master(data.bus_master)
+            .with_special_cycles(data.special_cycles)
+            .with_memory_write_and_invalidate_enable(data.memory_write_and_invalidate_enable)
+            .with_vga_palette_snoop(data.vga_palette_snoop)
+            .with_parity_error_response(data.parity_error_response)
+            .with_stepping(data.stepping)
+            .with_serr_enable(data.serr_enable)
+            .with_fast_back_to_back_enable(data.fast_back_to_back_enable)
+            .with_interrupt_disable(data.interrupt_disable)
+            .with_reserved(data.reserved)
+    }
+}
+impl From<Command> for u16 {
+    fn from(data: Command) -> Self { CommandProto::from(data).into() }
+}
 
 
 
@@ -86,4 +107,10 @@ mod tests {
         };
         assert_eq!(sample, result);
     }
+
+    #[test]
+    fn into_word() {
+        let sample: Command = 0xAAAA.into();
+        assert_eq!(0xAAAA, u16::from(sample));
+    }
 }
@@ -31,6 +31,7 @@ pub struct StatusProto {
 ///
 /// Status type selected by generic constant [char] 'P', 'B' or 'C'
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Status<const T: char> {
     pub reserved: u8,
     pub interrupt_status: bool,
@@ -71,8 +72,30 @@ impl<const T: char> From<StatusProto> for Status<T> {
 impl<const T: char> From<u16> for Status<T> {
     fn from(word: u16) -> Self { StatusProto::from(word).into() }
 }
+impl<const T: char> From<Status<T>> for StatusProto {
+    fn from(data: Status<T>) -> Self {
+        Self::new()
+            .with_reserved(data.reserved)
+            .with_interrupt_status(data.interrupt_status)
+            .with_capabilities_list(data.capabilities_list)
+            .with_is_66mhz_capable(data.is_66mhz_capable)
+            .with_user_definable_features(data.user_definable_features)
+            .with_fast_back_to_back_capable(data.fast_back_to_back_capable)
+            .with_master_data_parity_error(data.master_data_parity_error)
+            .with_devsel_timing(data.devsel_timing)
+            .with_signaled_target_abort(data.signaled_target_abort)
+            .with_received_target_abort(data.received_target_abort)
+            .with_received_master_abort(data.received_master_abort)
+            .with_system_error(data.system_error)
+            .with_detected_parity_error(data.detected_parity_error)
+    }
+}
+impl<const T: char> From<Status<T>> for u16 {
+    fn from(data: Status<T>) -> Self { StatusProto::from(data).into() }
+}
 
 #[derive(DisplayDoc, BitfieldSpecifier, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[bits = 2]
 pub enum DevselTiming {
     /// fast
@@ -112,4 +135,10 @@ mod tests {
         };
         assert_eq!(sample, result);
     }
+
+    #[test]
+    fn into_word() {
+        let sample: Status<'P'> = 0xAAAA.into();
+        assert_eq!(0xAAAA, u16::from(sample));
+    }
 }
@@ -2,11 +2,14 @@
 
 #![no_std]
 
-#[cfg(test)]
+#[cfg(any(test, feature = "std"))]
 #[macro_use]
 extern crate std;
 
 
+pub mod error;
+pub use error::Error;
+
 pub mod header;
 pub use header::Header;
 
@@ -16,6 +19,31 @@ pub use capabilities::Capabilities;
 pub mod extended_capabilities;
 pub use extended_capabilities::ExtendedCapabilities;
 
+pub mod builder;
+pub use builder::ConfigSpaceBuilder;
+
+pub mod access;
+pub use access::ConfigSpaceAccess;
+
+pub mod address;
+pub use address::Address;
+
+pub mod raw_bytes;
+pub use raw_bytes::RawBytes;
+
+pub mod lspci;
+
+#[cfg(feature = "std")]
+pub mod sysfs;
+
+mod visited;
+
+#[cfg(feature = "serde")]
+mod serde_hex;
+
+#[cfg(test)]
+mod test_data;
+
 
 /// Device dependent region starts at 0x40 offset
 pub const DDR_OFFSET: usize = 0x40;
@@ -0,0 +1,12 @@
+/*!
+lspci compatible text formats
+
+Conversions between configuration space and text produced by `lspci` utility from
+[pciutils](https://github.com/pciutils/pciutils).
+*/
+
+pub mod hex_dump;
+pub use hex_dump::{HexDump, HexDumps};
+
+pub mod verbose;
+pub use verbose::{Verbose, Verbosity};
@@ -0,0 +1,329 @@
+//! Hexadecimal dumps
+//!
+//! Textual configuration space dumps produced by `lspci -x` (64 bytes), `-xxx` (256 bytes) and
+//! `-xxxx` (4096 bytes). Each device block starts with a terse device line (`BB:DD.F` or
+//! `DDDD:BB:DD.F` address followed by description) followed by lines of 16 bytes prefixed with
+//! offset. Blocks are separated with an empty line. Indented lines of verbose output (`-vx`) are
+//! ignored.
+//!
+//! ```rust
+//! # use pcics::lspci::hex_dump::{HexDump, HexDumps};
+//! # use pcics::Header;
+//! let text = "\
+//! 00:1f.3 Audio device: Intel Corporation Sunrise Point-LP HD Audio (rev 21)
+//! 00: 86 80 c8 9d 06 04 10 00 30 80 03 04 10 20 00 00
+//! 10: 04 80 41 b4 00 00 00 00 00 00 00 00 00 00 00 00
+//! 20: 04 00 10 b4 00 00 00 00 00 00 00 00 43 10 a1 16
+//! 30: 00 00 00 00 50 00 00 00 00 00 00 00 ff 01 00 00
+//!
+//! ";
+//! let dump = HexDumps::new(text).next().unwrap().unwrap();
+//! assert_eq!(Some("00:1f.3".parse().unwrap()), dump.address);
+//! let header = Header::try_from(dump.data()).unwrap();
+//! assert_eq!((0x8086, 0x9dc8), (header.vendor_id, header.device_id));
+//! assert_eq!(text, format!("{}", dump));
+//! ```
+
+use core::{
+    fmt,
+    iter::{Enumerate, Peekable},
+    str::Lines,
+};
+
+use displaydoc::Display as DisplayDoc;
+
+use crate::{
+    address::Address,
+    builder::CONFIG_SPACE_LENGTH,
+};
+
+/// Bytes in one line of dump
+const LINE_BYTES: usize = 16;
+
+
+/// Configuration space dump of a single device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HexDump<'a> {
+    /// Device address, absent in dumps without device line
+    pub address: Option<Address>,
+    /// Rest of the device line (class, vendor and device names, revision)
+    pub description: &'a str,
+    data: [u8; CONFIG_SPACE_LENGTH],
+    len: usize,
+}
+impl<'a> HexDump<'a> {
+    /// Data longer than 4096 bytes is truncated
+    pub fn new(address: Option<Address>, description: &'a str, data: &[u8]) -> Self {
+        let len = data.len().min(CONFIG_SPACE_LENGTH);
+        let mut result = Self { address, description, data: [0; CONFIG_SPACE_LENGTH], len };
+        result.data[..len].copy_from_slice(&data[..len]);
+        result
+    }
+    /// Dumped configuration space part
+    pub fn data(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+}
+/// Formats dump the same way as lspci does, including an empty line after the device block.
+/// The domain is omitted if it is zero.
+impl<'a> fmt::Display for HexDump<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.address {
+            Some(Address { domain: 0, bus, device, function }) =>
+                write!(f, "{:02x}:{:02x}.{:x}", bus, device, function)?,
+            Some(address) => write!(f, "{}", address)?,
+            None => (),
+        }
+        if self.address.is_some() {
+            if !self.description.is_empty() {
+                write!(f, " {}", self.description)?;
+            }
+            writeln!(f)?;
+        }
+        for (n, line) in self.data().chunks(LINE_BYTES).enumerate() {
+            write!(f, "{:02x}:", n * LINE_BYTES)?;
+            for byte in line {
+                write!(f, " {:02x}", byte)?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f)
+    }
+}
+
+/// Iterator over device dumps in lspci output
+#[derive(Debug, Clone)]
+pub struct HexDumps<'a> {
+    lines: Peekable<Enumerate<Lines<'a>>>,
+}
+impl<'a> HexDumps<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self { lines: text.lines().enumerate().peekable() }
+    }
+    /// Skips the rest of a broken device block, so the next dump can be parsed
+    fn skip_block(&mut self, error: ParseHexDumpError) -> Option<Result<HexDump<'a>, ParseHexDumpError>> {
+        while let Some(&(_, line)) = self.lines.peek() {
+            if line.trim().is_empty() {
+                break;
+            }
+            if !line.starts_with(char::is_whitespace) {
+                match Line::parse(line) {
+                    Line::Device { address: Some(_), .. } => break,
+                    Line::Hex { offset, .. } if usize::from_str_radix(offset, 16) == Ok(0) => break,
+                    _ => (),
+                }
+            }
+            self.lines.next();
+        }
+        Some(Err(error))
+    }
+}
+impl<'a> Iterator for HexDumps<'a> {
+    type Item = Result<HexDump<'a>, ParseHexDumpError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut dump: Option<HexDump<'a>> = None;
+        while let Some(&(n, line)) = self.lines.peek() {
+            let line_number = n + 1;
+            if line.trim().is_empty() {
+                self.lines.next();
+                if dump.is_some() {
+                    break;
+                }
+                continue;
+            }
+            // Verbose output lines
+            if line.starts_with(char::is_whitespace) {
+                self.lines.next();
+                continue;
+            }
+            match Line::parse(line) {
+                Line::Device { address, description } => {
+                    if dump.is_some() {
+                        break;
+                    }
+                    self.lines.next();
+                    dump = Some(HexDump::new(address, description, &[]));
+                },
+                Line::Hex { offset, bytes } => {
+                    let Ok(offset) = usize::from_str_radix(offset, 16) else {
+                        self.lines.next();
+                        return self.skip_block(ParseHexDumpError::InvalidOffset { line: line_number });
+                    };
+                    let dump = dump.get_or_insert_with(|| HexDump::new(None, "", &[]));
+                    // Next dump without device line
+                    if offset == 0 && dump.len > 0 {
+                        break;
+                    }
+                    self.lines.next();
+                    if offset + bytes.split_whitespace().count() > dump.data.len() {
+                        return self.skip_block(ParseHexDumpError::InvalidOffset { line: line_number });
+                    }
+                    if offset != dump.len {
+                        return self.skip_block(ParseHexDumpError::Gap { line: line_number });
+                    }
+                    for (i, byte) in bytes.split_whitespace().enumerate() {
+                        let Some(byte) = parse_byte(byte) else {
+                            return self.skip_block(ParseHexDumpError::InvalidByte { line: line_number });
+                        };
+                        let Some(dst) = dump.data.get_mut(offset + i) else {
+                            return self.skip_block(ParseHexDumpError::InvalidOffset { line: line_number });
+                        };
+                        *dst = byte;
+                        dump.len = offset + i + 1;
+                    }
+                },
+            }
+        }
+        dump.map(Ok)
+    }
+}
+
+/// lspci hex dump parsing errors
+#[derive(DisplayDoc, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseHexDumpError {
+    /// line {line}: offset is not a hexadecimal number or is out of configuration space
+    InvalidOffset { line: usize },
+    /// line {line}: byte is not a two-digit hexadecimal number
+    InvalidByte { line: usize },
+    /// line {line}: offset does not continue the previous line
+    Gap { line: usize },
+}
+
+enum Line<'a> {
+    Device { address: Option<Address>, description: &'a str },
+    Hex { offset: &'a str, bytes: &'a str },
+}
+impl<'a> Line<'a> {
+    fn parse(line: &'a str) -> Self {
+        let (first, rest) = line.split_once(' ').unwrap_or((line, ""));
+        match first.strip_suffix(':') {
+            Some(offset) if (2..=3).contains(&offset.len()) =>
+                Self::Hex { offset, bytes: rest },
+            _ => match first.parse() {
+                Ok(address) => Self::Device { address: Some(address), description: rest.trim() },
+                Err(_) => Self::Device { address: None, description: line.trim() },
+            },
+        }
+    }
+}
+
+fn parse_byte(s: &str) -> Option<u8> {
+    if s.len() == 2 {
+        u8::from_str_radix(s, 16).ok()
+    } else {
+        None
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use pretty_assertions::assert_eq;
+    use crate::{DDR_OFFSET, ECS_OFFSET};
+    use super::*;
+
+    const DATA_2030: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/device/8086_2030/config"
+    ));
+    const DATA_9DC8: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/device/8086_9dc8/config"
+    ));
+
+    #[test]
+    fn multiple_devices() {
+        // lspci -xxxx -s 00:03.0; lspci -D -xxx -s 00:1f.3; lspci -x -s 00:1f.3
+        let sample = [
+            HexDump::new(
+                Some("00:03.0".parse().unwrap()),
+                "PCI bridge: Intel Corporation Sky Lake-E PCI Express Root Port A (rev 04)",
+                DATA_2030,
+            ),
+            HexDump::new(
+                Some("0001:00:1f.3".parse().unwrap()),
+                "Audio device: Intel Corporation Sunrise Point-LP HD Audio (rev 21)",
+                &DATA_9DC8[..ECS_OFFSET],
+            ),
+            HexDump::new(None, "", &DATA_9DC8[..DDR_OFFSET]),
+        ];
+        let text = sample.iter().map(|dump| dump.to_string()).collect::<String>();
+        assert_eq!(4096 / 16 + 256 / 16 + 64 / 16 + 2 + 3, text.lines().count());
+        assert!(text.contains("\n\n0001:00:1f.3 Audio device"));
+        assert!(text.contains("\nff0: "));
+        let result = HexDumps::new(&text).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(sample.to_vec(), result);
+    }
+
+    #[test]
+    fn verbose_and_numeric() {
+        let text = "\
+00:1f.3 0403: 8086:9dc8 (rev 30)
+\tSubsystem: 1043:16a1
+\tFlags: bus master, fast devsel, latency 32, IRQ 145
+00: 86 80 c8 9d 06 04 10 00 30 80 03 04 10 20 00 00
+10: 04 80 41 b4 00 00 00 00 00 00 00 00 00 00 00 00
+20: 04 00 10 b4 00 00 00 00 00 00 00 00 43 10 a1 16
+30: 00 00 00 00 50 00 00 00 00 00 00 00 ff 01 00 00
+";
+        let result = HexDumps::new(text).collect::<Vec<_>>();
+        let sample = vec![Ok(HexDump::new(
+            Some("00:1f.3".parse().unwrap()),
+            "0403: 8086:9dc8 (rev 30)",
+            &DATA_9DC8[..DDR_OFFSET],
+        ))];
+        assert_eq!(sample, result);
+    }
+
+    #[test]
+    fn headerless_dumps() {
+        let text = "00: 86 80 c8 9d\n00: 86 80 30 20\n";
+        let result = HexDumps::new(text)
+            .map(|dump| dump.unwrap().data().to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(vec![vec![0x86, 0x80, 0xc8, 0x9d], vec![0x86, 0x80, 0x30, 0x20]], result);
+    }
+
+    #[test]
+    fn errors() {
+        let result = HexDumps::new("00:1f.3 Audio device\n00: 86 8\n").next();
+        assert_eq!(Some(Err(ParseHexDumpError::InvalidByte { line: 2 })), result);
+        let result = HexDumps::new("00: 86\nfff: 00 00\n").next();
+        assert_eq!(Some(Err(ParseHexDumpError::InvalidOffset { line: 2 })), result);
+        let result = HexDumps::new("zz: 86\n").next();
+        assert_eq!(Some(Err(ParseHexDumpError::InvalidOffset { line: 1 })), result);
+        let result = HexDumps::new("00: 86 80 c8 9d 06 04 10 00 30 80 03 04 10 20 00 00\n20: 04\n").next();
+        assert_eq!(Some(Err(ParseHexDumpError::Gap { line: 2 })), result);
+    }
+
+    #[test]
+    fn errors_recovery() {
+        let text = "\
+00:1f.3 Audio device
+00: 86 8
+10: 04 80 41 b4
+\tFlags: bus master
+
+00:1f.4 SMBus
+00: 86 80 a3 9d
+00:1f.5 Serial bus controller
+10: 00
+00: 86 80 c8 9d
+00: 86 80 30 20
+";
+        let result = HexDumps::new(text)
+            .map(|dump| dump.map(|dump| (dump.address, dump.data().to_vec())))
+            .collect::<Vec<_>>();
+        let sample = vec![
+            Err(ParseHexDumpError::InvalidByte { line: 2 }),
+            Ok((Some("00:1f.4".parse().unwrap()), vec![0x86, 0x80, 0xa3, 0x9d])),
+            Err(ParseHexDumpError::Gap { line: 9 }),
+            Ok((None, vec![0x86, 0x80, 0xc8, 0x9d])),
+            Ok((None, vec![0x86, 0x80, 0x30, 0x20])),
+        ];
+        assert_eq!(sample, result);
+    }
+}
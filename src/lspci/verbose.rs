@@ -0,0 +1,2563 @@
+//! Verbose device description
+//!
+//! Text produced by `lspci -nvv` and `lspci -nvvv` (pciutils 3.6): the terse device line, decoded
+//! header registers, base address regions and all capabilities with their registers. Names of
+//! classes, vendors and devices are never resolved, numeric IDs are printed instead.
+//!
+//! Register decoding repeats lspci bit by bit, including its quirks, so the output can be
+//! compared with the original utility byte for byte.
+//!
+//! ```rust
+//! # use pcics::lspci::verbose::Verbose;
+//! # use pcics::Header;
+//! let data = [
+//!     0x02, 0x10, 0x15, 0x97, 0x07, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x03, 0x10, 0x00, 0x00, 0x00,
+//!     0x08, 0x00, 0x00, 0xfc, 0x01, 0xe0, 0x00, 0x00, 0x00, 0x00, 0x50, 0xff, 0x00, 0x00, 0x00, 0x00,
+//!     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0xff, 0x00, 0x00, 0x00, 0x00, 0x34, 0x17, 0xda, 0x11,
+//!     0x00, 0x00, 0x00, 0x00, 0x50, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0b, 0x01, 0x00, 0x00,
+//! ];
+//! let header = Header::try_from(&data[..]).unwrap();
+//! let verbose = Verbose::new("01:05.0".parse().unwrap(), &header);
+//! let sample = "\
+//! 01:05.0 0300: 1002:9715
+//! \tSubsystem: 1734:11da
+//! \tControl: I/O+ Mem+ BusMaster+ SpecCycle- MemWINV- VGASnoop- ParErr- Stepping- SERR- FastB2B- DisINTx-
+//! \tStatus: Cap+ 66MHz- UDF- FastB2B- ParErr- DEVSEL=fast >TAbort- <TAbort- <MAbort- >SERR- <PERR- INTx-
+//! \tLatency: 0, Cache Line Size: 64 bytes
+//! \tInterrupt: pin A routed to IRQ 11
+//! \tRegion 0: Memory at fc000000 (32-bit, prefetchable)
+//! \tRegion 1: I/O ports at e000
+//! \tRegion 2: Memory at ff500000 (32-bit, non-prefetchable)
+//! \tRegion 5: Memory at ff400000 (32-bit, non-prefetchable)
+//! \tCapabilities: <access denied>
+//!
+//! ";
+//! assert_eq!(sample, verbose.to_string());
+//! ```
+
+use core::fmt;
+
+use crate::{
+    address::Address,
+    header::{Header, HeaderType},
+    capabilities::{
+        Capabilities,
+        CapabilityKind,
+        PowerManagementInterface,
+        AcceleratedGraphicsPort,
+        MessageSignaledInterrups,
+        Hypertransport,
+        VendorSpecific,
+        PciExpress,
+        MsiX,
+        Sata,
+        PciX,
+        EnhancedAllocation,
+        accelerated_graphics_port::DataRate,
+        enhanced_allocation::{BarEquivalentIndicator, EntryProperties},
+        pci_x::DeviceComplexity,
+        message_signaled_interrups::MessageAddress,
+        hypertransport::{LinkControl, LinkConfiguration, LinkError},
+        pci_express::{Link, Slot, Root, Device2, Link2},
+        vendor_specific::{VendorCapabilty, Virtio},
+    },
+    extended_capabilities::{
+        ExtendedCapabilities,
+        ExtendedCapabilityKind,
+        AdvancedErrorReporting,
+        VirtualChannel,
+        SingleRootIoVirtualization,
+        L1PmSubstates,
+        ResizableBar,
+        RootComplexLinkDeclaration,
+        RootComplexEventCollectorEndpointAssociation,
+        DesignatedVendorSpecificExtendedCapability,
+        root_complex_link_declaration::LinkAddress,
+        designated_vendor_specific_extended_capability::{
+            DvsecCapability,
+            ComputeExpressLink,
+        },
+    },
+};
+
+
+/// Amount of details, corresponds to the number of `-v` options
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Verbosity {
+    /// `-vv`
+    #[default]
+    Vv,
+    /// `-vvv`, also shows disabled bridge and CardBus windows
+    Vvv,
+}
+
+/// Device description in `lspci -nvv` format
+#[derive(Debug, Clone, Copy)]
+pub struct Verbose<'a> {
+    pub address: Address,
+    pub header: &'a Header,
+    /// Capabilities list, `None` if configuration space is readable up to the header only
+    pub capabilities: Option<Capabilities<'a>>,
+    /// Extended capabilities, shown only after PCI Express or PCI-X capability
+    pub extended_capabilities: Option<ExtendedCapabilities<'a>>,
+    /// Bound kernel driver
+    pub driver: Option<&'a str>,
+    /// Region sizes: six base addresses followed by expansion ROM
+    pub sizes: &'a [u64],
+    pub verbosity: Verbosity,
+}
+impl<'a> Verbose<'a> {
+    /// Header only description, other fields can be set with struct update syntax
+    pub fn new(address: Address, header: &'a Header) -> Self {
+        Self {
+            address,
+            header,
+            capabilities: None,
+            extended_capabilities: None,
+            driver: None,
+            sizes: &[],
+            verbosity: Verbosity::default(),
+        }
+    }
+}
+/// Formats device the same way as `lspci -nvv` does, including an empty line after the device
+/// block. The domain is omitted if it is zero.
+impl<'a> fmt::Display for Verbose<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let h = self.header;
+        match self.address {
+            Address { domain: 0, bus, device, function } =>
+                write!(f, "{:02x}:{:02x}.{:x}", bus, device, function)?,
+            address => write!(f, "{}", address)?,
+        }
+        let class = u16::from_be_bytes([h.class_code.base, h.class_code.sub]);
+        write!(f, " {:04x}: {:04x}:{:04x}", class, h.vendor_id, h.device_id)?;
+        if h.revision_id != 0 {
+            write!(f, " (rev {:02x})", h.revision_id)?;
+        }
+        if h.class_code.interface != 0 {
+            write!(f, " (prog-if {:02x})", h.class_code.interface)?;
+        }
+        writeln!(f)?;
+        let subsystem = match &h.header_type {
+            HeaderType::Normal(normal) => Some((normal.sub_vendor_id, normal.sub_device_id)),
+            HeaderType::Cardbus(cardbus) if self.capabilities.is_some() =>
+                Some((cardbus.subsystem_vendor_id, cardbus.subsystem_device_id)),
+            _ => None,
+        };
+        if let Some((vendor_id, device_id)) = subsystem {
+            if vendor_id != 0 && vendor_id != 0xffff {
+                writeln!(f, "\tSubsystem: {:04x}:{:04x}", vendor_id, device_id)?;
+            }
+        }
+        let invalid_class = match h.header_type {
+            HeaderType::Normal(_) => class == 0x0604,
+            _ => h.class_code.base != 0x06,
+        };
+        if invalid_class {
+            let header_type = match h.header_type {
+                HeaderType::Normal(_) => 0,
+                HeaderType::Bridge(_) => 1,
+                HeaderType::Cardbus(_) => 2,
+            };
+            writeln!(f, "\t!!! Invalid class {:04x} for header type {:02x}", class, header_type)?;
+        }
+
+        let cmd = word(&h.command);
+        writeln!(f,
+            "\tControl: I/O{} Mem{} BusMaster{} SpecCycle{} MemWINV{} VGASnoop{} ParErr{} \
+            Stepping{} SERR{} FastB2B{} DisINTx{}",
+            flag(cmd, 0x1), flag(cmd, 0x2), flag(cmd, 0x4), flag(cmd, 0x8), flag(cmd, 0x10),
+            flag(cmd, 0x20), flag(cmd, 0x40), flag(cmd, 0x80), flag(cmd, 0x100), flag(cmd, 0x200),
+            flag(cmd, 0x400),
+        )?;
+        let status = word(&h.status);
+        writeln!(f,
+            "\tStatus: Cap{} 66MHz{} UDF{} FastB2B{} ParErr{} DEVSEL={} >TAbort{} <TAbort{} \
+            <MAbort{} >SERR{} <PERR{} INTx{}",
+            flag(status, 0x10), flag(status, 0x20), flag(status, 0x40), flag(status, 0x80),
+            flag(status, 0x100), devsel(status), flag(status, 0x800), flag(status, 0x1000),
+            flag(status, 0x2000), flag(status, 0x4000), flag(status, 0x8000), flag(status, 0x8),
+        )?;
+        if cmd & 0x4 != 0 {
+            write!(f, "\tLatency: {}", h.latency_timer)?;
+            if let HeaderType::Normal(normal) = &h.header_type {
+                let (min_gnt, max_lat) = (normal.min_grant, normal.max_latency);
+                if min_gnt != 0 || max_lat != 0 {
+                    write!(f, " (")?;
+                    if min_gnt != 0 {
+                        write!(f, "{}ns min", u32::from(min_gnt) * 250)?;
+                    }
+                    if min_gnt != 0 && max_lat != 0 {
+                        write!(f, ", ")?;
+                    }
+                    if max_lat != 0 {
+                        write!(f, "{}ns max", u32::from(max_lat) * 250)?;
+                    }
+                    write!(f, ")")?;
+                }
+            }
+            if h.cache_line_size != 0 {
+                write!(f, ", Cache Line Size: {} bytes", u32::from(h.cache_line_size) * 4)?;
+            }
+            writeln!(f)?;
+        }
+        let pin = u8::from(h.interrupt_pin);
+        if pin != 0 || h.interrupt_line != 0 {
+            let pin = if pin != 0 { char::from(b'A'.wrapping_add(pin - 1)) } else { '?' };
+            writeln!(f, "\tInterrupt: pin {} routed to IRQ {}", pin, h.interrupt_line)?;
+        }
+        if h.bist.is_capable {
+            if h.bist.is_running {
+                writeln!(f, "\tBIST is running")?;
+            } else {
+                writeln!(f, "\tBIST result: {:02x}", h.bist.completion_code)?;
+            }
+        }
+
+        match &h.header_type {
+            HeaderType::Normal(normal) => {
+                self.fmt_bases(f, &normal.base_addresses.0)?;
+                self.fmt_rom(f, u32::from(normal.expansion_rom.clone()))?;
+                self.fmt_caps(f)?;
+            },
+            HeaderType::Bridge(bridge) => {
+                self.fmt_bases(f, &bridge.base_addresses.0)?;
+                writeln!(f,
+                    "\tBus: primary={:02x}, secondary={:02x}, subordinate={:02x}, sec-latency={}",
+                    bridge.primary_bus_number, bridge.secondary_bus_number,
+                    bridge.subordinate_bus_number, bridge.secondary_latency_timer,
+                )?;
+                let (io_base, io_limit, io_base_upper, io_limit_upper) =
+                    bridge.io_address_range.clone().into();
+                let io_type = io_base & 0x0f;
+                if io_type != io_limit & 0x0f || io_type > 1 {
+                    writeln!(f, "\t!!! Unknown I/O range types {:x}/{:x}", io_base, io_limit)?;
+                } else {
+                    let mut base = u64::from(io_base & 0xf0) << 8;
+                    let mut limit = u64::from(io_limit & 0xf0) << 8;
+                    if io_type == 1 {
+                        base |= u64::from(io_base_upper) << 16;
+                        limit |= u64::from(io_limit_upper) << 16;
+                    }
+                    self.fmt_range(f, "\tI/O behind bridge", base, limit + 0xfff, false)?;
+                }
+                let (mem_base, mem_limit) = (bridge.memory_base, bridge.memory_limit);
+                if mem_base & 0x0f != mem_limit & 0x0f || mem_base & 0x0f != 0 {
+                    writeln!(f,
+                        "\t!!! Unknown memory range types {:x}/{:x}", mem_base, mem_limit
+                    )?;
+                } else {
+                    let base = u64::from(mem_base & 0xfff0) << 16;
+                    let limit = u64::from(mem_limit & 0xfff0) << 16;
+                    self.fmt_range(f, "\tMemory behind bridge", base, limit + 0xfffff, false)?;
+                }
+                let (pref_base, pref_limit, pref_base_upper, pref_limit_upper) =
+                    bridge.prefetchable_memory.clone().into();
+                let pref_type = pref_base & 0x0f;
+                if pref_type != pref_limit & 0x0f || pref_type > 1 {
+                    writeln!(f,
+                        "\t!!! Unknown prefetchable memory range types {:x}/{:x}",
+                        pref_base, pref_limit
+                    )?;
+                } else {
+                    let mut base = u64::from(pref_base & 0xfff0) << 16;
+                    let mut limit = u64::from(pref_limit & 0xfff0) << 16;
+                    if pref_type == 1 {
+                        base |= u64::from(pref_base_upper) << 32;
+                        limit |= u64::from(pref_limit_upper) << 32;
+                    }
+                    self.fmt_range(f,
+                        "\tPrefetchable memory behind bridge", base, limit + 0xfffff, pref_type == 1
+                    )?;
+                }
+                let sec = word(&bridge.secondary_status);
+                writeln!(f,
+                    "\tSecondary status: 66MHz{} FastB2B{} ParErr{} DEVSEL={} >TAbort{} \
+                    <TAbort{} <MAbort{} <SERR{} <PERR{}",
+                    flag(sec, 0x20), flag(sec, 0x80), flag(sec, 0x100), devsel(sec),
+                    flag(sec, 0x800), flag(sec, 0x1000), flag(sec, 0x2000), flag(sec, 0x4000),
+                    flag(sec, 0x8000),
+                )?;
+                self.fmt_rom(f, u32::from(bridge.expansion_rom.clone()))?;
+                let brc = word(&bridge.bridge_control);
+                writeln!(f,
+                    "\tBridgeCtl: Parity{} SERR{} NoISA{} VGA{} VGA16{} MAbort{} >Reset{} \
+                    FastB2B{}",
+                    flag(brc, 0x1), flag(brc, 0x2), flag(brc, 0x4), flag(brc, 0x8),
+                    flag(brc, 0x10), flag(brc, 0x20), flag(brc, 0x40), flag(brc, 0x80),
+                )?;
+                writeln!(f,
+                    "\t\tPriDiscTmr{} SecDiscTmr{} DiscTmrStat{} DiscTmrSERREn{}",
+                    flag(brc, 0x100), flag(brc, 0x200), flag(brc, 0x400), flag(brc, 0x800),
+                )?;
+                self.fmt_caps(f)?;
+            },
+            HeaderType::Cardbus(cardbus) => {
+                self.fmt_bases(f, &cardbus.base_addresses.0)?;
+                writeln!(f,
+                    "\tBus: primary={:02x}, secondary={:02x}, subordinate={:02x}, sec-latency={}",
+                    cardbus.pci_bus_number, cardbus.cardbus_bus_number,
+                    cardbus.subordinate_bus_number, cardbus.cardbus_latency_timer,
+                )?;
+                let brc = word(&cardbus.bridge_control);
+                let vvv = self.verbosity == Verbosity::Vvv;
+                let windows = [
+                    (cardbus.memory_base_address_0, cardbus.memory_limit_address_0),
+                    (cardbus.memory_base_address_1, cardbus.memory_limit_address_1),
+                ];
+                for (i, (base, limit)) in windows.into_iter().enumerate() {
+                    let limit = limit.wrapping_add(0xfff);
+                    if base <= limit || vvv {
+                        writeln!(f,
+                            "\tMemory window {}: {:08x}-{:08x}{}{}",
+                            i, base, limit,
+                            if cmd & 0x2 != 0 { "" } else { " [disabled]" },
+                            if brc & (0x100 << i) != 0 { " (prefetchable)" } else { "" },
+                        )?;
+                    }
+                }
+                let windows = [
+                    cardbus.io_access_address_range_0.clone(),
+                    cardbus.io_access_address_range_1.clone(),
+                ];
+                for (i, range) in windows.into_iter().enumerate() {
+                    let [[base_lower, base_upper], [limit_lower, limit_upper]] = range.into();
+                    let (mut base, mut limit) = (u32::from(base_lower), u32::from(limit_lower));
+                    if base & 0x1 != 0 {
+                        base |= u32::from(base_upper) << 16;
+                        limit |= u32::from(limit_upper) << 16;
+                    }
+                    let base = base & !0x3;
+                    let limit = (limit & !0x3).wrapping_add(3);
+                    if base <= limit || vvv {
+                        writeln!(f,
+                            "\tI/O window {}: {:08x}-{:08x}{}",
+                            i, base, limit,
+                            if cmd & 0x1 != 0 { "" } else { " [disabled]" },
+                        )?;
+                    }
+                }
+                if word(&cardbus.secondary_status) & 0x4000 != 0 {
+                    writeln!(f, "\tSecondary status: SERR")?;
+                }
+                writeln!(f,
+                    "\tBridgeCtl: Parity{} SERR{} ISA{} VGA{} MAbort{} >Reset{} 16bInt{} \
+                    PostWrite{}",
+                    flag(brc, 0x1), flag(brc, 0x2), flag(brc, 0x4), flag(brc, 0x8),
+                    flag(brc, 0x20), flag(brc, 0x40), flag(brc, 0x80), flag(brc, 0x400),
+                )?;
+                if self.capabilities.is_none() {
+                    writeln!(f, "\t<access denied to the rest>")?;
+                } else {
+                    let exca = cardbus.legacy_mode_base_address & 0xffff;
+                    if exca != 0 {
+                        writeln!(f, "\t16-bit legacy interface ports at {:04x}", exca)?;
+                    }
+                    self.fmt_caps(f)?;
+                }
+            },
+        }
+        if let Some(driver) = self.driver {
+            writeln!(f, "\tKernel driver in use: {}", driver)?;
+        }
+        writeln!(f)
+    }
+}
+
+impl<'a> Verbose<'a> {
+    fn size(&self, index: usize) -> u64 {
+        self.sizes.get(index).copied().unwrap_or(0)
+    }
+
+    fn base_addresses(&self) -> &[u32] {
+        match &self.header.header_type {
+            HeaderType::Normal(normal) => &normal.base_addresses.0,
+            HeaderType::Bridge(bridge) => &bridge.base_addresses.0,
+            HeaderType::Cardbus(cardbus) => &cardbus.base_addresses.0,
+        }
+    }
+
+    /// Any assigned memory space region, used by PCI Express AtomicOp capabilities
+    fn has_memory_space_bar(&self) -> bool {
+        self.base_addresses().iter().enumerate()
+            .any(|(i, &bar)| bar != 0 && bar & 0x1 == 0 && self.size(i) != 0)
+    }
+
+    fn fmt_bases(&self, f: &mut fmt::Formatter<'_>, bars: &[u32]) -> fmt::Result {
+        let cmd = word(&self.header.command);
+        let cnt = bars.len();
+        let mut i = 0;
+        while i < cnt {
+            let index = i;
+            let mut flg = bars[i];
+            if flg == 0xffffffff {
+                flg = 0;
+            }
+            let len = self.size(i);
+            let mut hw_upper = 0;
+            let mut broken = false;
+            let hw_lower = if flg & 0x1 != 0 {
+                flg & !0x3
+            } else {
+                if flg & 0x6 == 0x4 {
+                    if i >= cnt - 1 {
+                        broken = true;
+                    } else {
+                        i += 1;
+                        hw_upper = bars[i];
+                    }
+                }
+                flg & !0xf
+            };
+            i += 1;
+            let pos = u64::from(flg) | u64::from(hw_upper) << 32;
+            if pos == 0 && len == 0 {
+                continue;
+            }
+            write!(f, "\tRegion {}: ", index)?;
+            // Region flags without an address are reported by OS, but unassigned in the device
+            let virtual_ = pos != 0 && hw_lower == 0 && hw_upper == 0;
+            if flg & 0x1 != 0 {
+                let a = pos & !0x3;
+                write!(f, "I/O ports at ")?;
+                if a != 0 || cmd & 0x1 != 0 {
+                    write!(f, "{:04x}", a)?;
+                } else if hw_lower != 0 {
+                    write!(f, "<ignored>")?;
+                } else {
+                    write!(f, "<unassigned>")?;
+                }
+                if virtual_ {
+                    write!(f, " [virtual]")?;
+                } else if cmd & 0x1 == 0 {
+                    write!(f, " [disabled]")?;
+                }
+            } else {
+                let a = pos & !0xf;
+                write!(f, "Memory at ")?;
+                if broken {
+                    write!(f, "<broken-64-bit-slot>")?;
+                } else if a != 0 {
+                    write!(f, "{:08x}", a)?;
+                } else if hw_lower != 0 || hw_upper != 0 {
+                    write!(f, "<ignored>")?;
+                } else {
+                    write!(f, "<unassigned>")?;
+                }
+                let width = match flg & 0x6 {
+                    0x0 => "32-bit",
+                    0x4 => "64-bit",
+                    0x2 => "low-1M",
+                    _ => "type 3",
+                };
+                let prefetchable = if flg & 0x8 != 0 { "" } else { "non-" };
+                write!(f, " ({}, {}prefetchable)", width, prefetchable)?;
+                if virtual_ {
+                    write!(f, " [virtual]")?;
+                } else if cmd & 0x2 == 0 {
+                    write!(f, " [disabled]")?;
+                }
+            }
+            fmt_size(f, len)?;
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+
+    fn fmt_rom(&self, f: &mut fmt::Formatter<'_>, flg: u32) -> fmt::Result {
+        let len = self.size(6);
+        if flg == 0 && len == 0 {
+            return Ok(());
+        }
+        write!(f, "\tExpansion ROM at ")?;
+        if flg & !0x7ff != 0 {
+            write!(f, "{:08x}", flg & !0x7ff)?;
+        } else {
+            write!(f, "<unassigned>")?;
+        }
+        if flg & 0x1 == 0 {
+            write!(f, " [disabled]")?;
+        } else if word(&self.header.command) & 0x2 == 0 {
+            write!(f, " [disabled by cmd]")?;
+        }
+        fmt_size(f, len)?;
+        writeln!(f)
+    }
+
+    fn fmt_range(
+        &self, f: &mut fmt::Formatter<'_>, prefix: &str, base: u64, limit: u64, is_64bit: bool,
+    ) -> fmt::Result {
+        if base > limit && self.verbosity == Verbosity::Vv {
+            return Ok(());
+        }
+        write!(f, "{}:", prefix)?;
+        if is_64bit {
+            write!(f, " {:016x}-{:016x}", base, limit)?;
+        } else {
+            write!(f, " {:08x}-{:08x}", base as u32, limit as u32)?;
+        }
+        if base > limit {
+            write!(f, " [disabled]")?;
+        }
+        writeln!(f)
+    }
+
+    fn fmt_caps(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if word(&self.header.status) & 0x10 == 0 {
+            return Ok(());
+        }
+        let mut pointer = self.header.capabilities_pointer & !0x3;
+        let Some(caps) = self.capabilities else {
+            if pointer != 0 {
+                writeln!(f, "\tCapabilities: <access denied>")?;
+            }
+            return Ok(());
+        };
+        let data = caps.data();
+        let mut been_there = [false; 0x100];
+        let mut can_have_ext_caps = false;
+        let mut express_type = None;
+        while pointer != 0 {
+            write!(f, "\tCapabilities: ")?;
+            let start = usize::from(pointer).saturating_sub(crate::DDR_OFFSET);
+            let raw = data.get(start..start + 4).filter(|_| pointer as usize >= crate::DDR_OFFSET);
+            let Some(raw) = raw else {
+                writeln!(f, "<access denied>")?;
+                break;
+            };
+            write!(f, "[{:02x}] ", pointer)?;
+            if been_there[usize::from(pointer)] {
+                writeln!(f, "<chain looped>")?;
+                break;
+            }
+            been_there[usize::from(pointer)] = true;
+            let cap_word = u16::from_le_bytes([raw[2], raw[3]]);
+            // Capability body is not decodable, show it as unknown one and follow the chain
+            let Some(cap) = Capabilities::new(data, pointer).with_header_type(&self.header.header_type).next() else {
+                writeln!(f, "#{:02x} [{:04x}]", raw[0], cap_word)?;
+                pointer = raw[1] & !0x3;
+                continue;
+            };
+            match cap.kind {
+                CapabilityKind::NullCapability => writeln!(f, "Null")?,
+                CapabilityKind::PowerManagementInterface(pm) => fmt_pm(f, &pm)?,
+                CapabilityKind::AcceleratedGraphicsPort(agp) => fmt_agp(f, &agp)?,
+                CapabilityKind::VitalProductData(_) =>
+                    writeln!(f, "Vital Product Data\n\t\tNot readable")?,
+                CapabilityKind::SlotIdentification(sid) => writeln!(f,
+                    "Slot ID: {} slots, First{}, chassis {:02x}",
+                    sid.expansion_slot.expansion_slots_provided,
+                    Flag(sid.expansion_slot.first_in_chassis),
+                    sid.chassis_number,
+                )?,
+                CapabilityKind::MessageSignaledInterrups(msi) => fmt_msi(f, &msi)?,
+                CapabilityKind::CompactPciHotSwap(_) =>
+                    writeln!(f, "CompactPCI hot-swap <?>")?,
+                CapabilityKind::PciX(pcix) => {
+                    fmt_pcix(f, &pcix)?;
+                    can_have_ext_caps = true;
+                },
+                CapabilityKind::Hypertransport(ht) => fmt_ht(f, &ht, cap_word)?,
+                CapabilityKind::VendorSpecific(vs) => self.fmt_vendor_specific(f, &vs)?,
+                CapabilityKind::DebugPort(dp) => writeln!(f,
+                    "Debug port: BAR={} offset={:04x}", dp.bar_number, dp.offset
+                )?,
+                CapabilityKind::CompactPciResourceControl(_) =>
+                    writeln!(f, "CompactPCI central resource control <?>")?,
+                CapabilityKind::PciHotPlug(_) => writeln!(f, "Hot-plug capable")?,
+                CapabilityKind::BridgeSubsystemVendorId(ssvid) => writeln!(f,
+                    "Subsystem: {:04x}:{:04x}", ssvid.subsystem_vendor_id, ssvid.subsystem_id
+                )?,
+                CapabilityKind::Agp8x(_) => writeln!(f, "AGP3 <?>")?,
+                CapabilityKind::SecureDevice(_) => writeln!(f, "Secure device <?>")?,
+                CapabilityKind::PciExpress(pcie) => {
+                    express_type = Some(self.fmt_express(f, &pcie)?);
+                    can_have_ext_caps = true;
+                },
+                CapabilityKind::MsiX(msix) => fmt_msix(f, &msix)?,
+                CapabilityKind::Sata(sata) => fmt_sata(f, &sata)?,
+                CapabilityKind::AdvancedFeatures(af) => {
+                    writeln!(f, "PCI Advanced Features")?;
+                    writeln!(f,
+                        "\t\tAFCap: TP{} FLR{}",
+                        Flag(af.capabilities.transactions_pending),
+                        Flag(af.capabilities.function_level_reset),
+                    )?;
+                    writeln!(f, "\t\tAFCtrl: FLR{}", Flag(af.control.initiate_flr))?;
+                    writeln!(f, "\t\tAFStatus: TP{}", Flag(af.status.transactions_pending))?;
+                },
+                CapabilityKind::EnhancedAllocation(ea) => fmt_ea(f, &ea)?,
+                CapabilityKind::FlatteningPortalBridge(_) =>
+                    writeln!(f, "#{:02x} [{:04x}]", 0x15, cap_word)?,
+                CapabilityKind::Reserved(0xff) => {
+                    writeln!(f, "<chain broken>")?;
+                    break;
+                },
+                CapabilityKind::Reserved(id) => {
+                    // PCI-X
+                    if id == 0x07 {
+                        can_have_ext_caps = true;
+                    }
+                    writeln!(f, "#{:02x} [{:04x}]", id, cap_word)?;
+                },
+            }
+            pointer = raw[1] & !0x3;
+        }
+        if can_have_ext_caps {
+            self.fmt_ext_caps(f, express_type)?;
+        }
+        Ok(())
+    }
+
+    fn fmt_vendor_specific(&self, f: &mut fmt::Formatter<'_>, vs: &VendorSpecific) -> fmt::Result {
+        write!(f, "Vendor Specific Information: ")?;
+        let len = vs.0.len() + 1;
+        let h = self.header;
+        let virtio = match vs.vendor_capability(h.vendor_id, h.device_id) {
+            VendorCapabilty::Virtio(virtio) if len >= 16 => virtio,
+            _ => return writeln!(f, "Len={:02x} <?>", len),
+        };
+        let (name, bar, offset, size, multiplier) = match virtio {
+            Virtio::CommonCfg { bar, offset, size } => ("CommonCfg", bar, offset, size, None),
+            Virtio::Notify { bar, offset, size, multiplier } =>
+                ("Notify", bar, offset, size, multiplier.filter(|_| len >= 20)),
+            Virtio::Isr { bar, offset, size } => ("ISR", bar, offset, size, None),
+            Virtio::DeviceCfg { bar, offset, size } => ("DeviceCfg", bar, offset, size, None),
+            Virtio::Unknown { bar, offset, size } => ("<unknown>", bar, offset, size, None),
+        };
+        writeln!(f, "VirtIO: {}", name)?;
+        write!(f, "\t\tBAR={} offset={:08x} size={:08x}", bar, offset, size)?;
+        if let Some(multiplier) = multiplier {
+            write!(f, " multiplier={:08x}", multiplier)?;
+        }
+        writeln!(f)
+    }
+
+    /// Returns device/port type
+    fn fmt_express(&self, f: &mut fmt::Formatter<'_>, pcie: &PciExpress) -> Result<u8, fmt::Error> {
+        let cap = word(&pcie.capabilities);
+        let ty = ((cap >> 4) & 0xf) as u8;
+        write!(f, "Express (v{}) ", cap & 0xf)?;
+        match ty {
+            EXP_ENDPOINT => write!(f, "Endpoint")?,
+            EXP_LEG_END => write!(f, "Legacy Endpoint")?,
+            EXP_ROOT_PORT => write!(f, "Root Port (Slot{})", flag(cap, 0x100))?,
+            EXP_UPSTREAM => write!(f, "Upstream Port")?,
+            EXP_DOWNSTREAM => write!(f, "Downstream Port (Slot{})", flag(cap, 0x100))?,
+            EXP_PCI_BRIDGE => write!(f, "PCI-Express to PCI/PCI-X Bridge")?,
+            EXP_PCIE_BRIDGE =>
+                write!(f, "PCI/PCI-X to PCI-Express Bridge (Slot{})", flag(cap, 0x100))?,
+            EXP_ROOT_INT_EP => write!(f, "Root Complex Integrated Endpoint")?,
+            EXP_ROOT_EC => write!(f, "Root Complex Event Collector")?,
+            ty => write!(f, "Unknown type {}", ty)?,
+        }
+        writeln!(f, ", MSI {:02x}", (cap & 0x3e00) >> 9)?;
+        fmt_express_dev(f, pcie, ty)?;
+        if let Some(link) = &pcie.link {
+            fmt_express_link(f, link, ty)?;
+        }
+        if let Some(slot) = &pcie.slot {
+            fmt_express_slot(f, slot)?;
+        }
+        if let Some(root) = &pcie.root {
+            fmt_express_root(f, root)?;
+        }
+        if let Some(device_2) = &pcie.device_2 {
+            fmt_express_dev2(f, device_2, ty, self.has_memory_space_bar())?;
+        }
+        if let Some(link_2) = &pcie.link_2 {
+            let Address { device, function, .. } = self.address;
+            fmt_express_link2(f, link_2, ty, device == 0 && function == 0)?;
+        }
+        Ok(ty)
+    }
+
+    fn fmt_ext_caps(&self, f: &mut fmt::Formatter<'_>, express_type: Option<u8>) -> fmt::Result {
+        let Some(ecaps) = self.extended_capabilities else {
+            return Ok(());
+        };
+        let ecs = ecaps.data();
+        let mut been_there = [false; 0x1000];
+        let mut offset = crate::ECS_OFFSET as u16;
+        loop {
+            let start = usize::from(offset).wrapping_sub(crate::ECS_OFFSET);
+            let Some(raw) = ecs.get(start..start.wrapping_add(4)) else {
+                break;
+            };
+            let header = u32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]);
+            if header == 0 {
+                break;
+            }
+            let Some(ecap) = ExtendedCapabilities::with_offset(ecs, offset).next() else {
+                break;
+            };
+            write!(f, "\tCapabilities: [{:03x} v{}] ", offset, ecap.version)?;
+            if been_there[usize::from(offset)] {
+                writeln!(f, "<chain looped>")?;
+                break;
+            }
+            been_there[usize::from(offset)] = true;
+            use ExtendedCapabilityKind as Kind;
+            match ecap.kind {
+                Kind::Null => writeln!(f, "Null")?,
+                Kind::AdvancedErrorReporting(aer) => fmt_aer(f, &aer, express_type)?,
+                Kind::VirtualChannel(vc) => fmt_vc(f, &vc, offset)?,
+                Kind::DeviceSerialNumber(dsn) => {
+                    let [u0, u1, u2, u3] = dsn.upper_dword.to_be_bytes();
+                    let [l0, l1, l2, l3] = dsn.lower_dword.to_be_bytes();
+                    writeln!(f,
+                        "Device Serial Number {:02x}-{:02x}-{:02x}-{:02x}-{:02x}-{:02x}-{:02x}-{:02x}",
+                        u0, u1, u2, u3, l0, l1, l2, l3,
+                    )?;
+                },
+                Kind::PowerBudgeting(_) => writeln!(f, "Power Budgeting <?>")?,
+                Kind::RootComplexLinkDeclaration(rcld) => fmt_rclink(f, &rcld)?,
+                Kind::RootComplexInternalLinkControl(_) =>
+                    writeln!(f, "Root Complex Internal Link <?>")?,
+                Kind::RootComplexEventCollectorEndpointAssociation(rcec) =>
+                    fmt_rcec(f, &rcec, self.verbosity == Verbosity::Vvv)?,
+                Kind::MultiFunctionVirtualChannel(_) =>
+                    writeln!(f, "Multi-Function Virtual Channel <?>")?,
+                Kind::VirtualChannelMfvcPresent(vc) => fmt_vc(f, &vc, offset)?,
+                Kind::RootComplexRegisterBlock => writeln!(f, "Root Bridge Control Block <?>")?,
+                Kind::VendorSpecificExtendedCapability(vsec) => writeln!(f,
+                    "Vendor Specific Information: ID={:04x} Rev={} Len={:03x} <?>",
+                    vsec.header.vsec_id, vsec.header.vsec_rev, vsec.header.vsec_length,
+                )?,
+                Kind::AccessControlServices(acs) => {
+                    writeln!(f, "Access Control Services")?;
+                    fmt_acs(f, "ACSCap", word(&acs.acs_capability))?;
+                    fmt_acs(f, "ACSCtl", word(&acs.acs_control))?;
+                },
+                Kind::AlternativeRoutingIdInterpretation(ari) => {
+                    writeln!(f, "Alternative Routing-ID Interpretation (ARI)")?;
+                    let w = word(&ari.ari_capability);
+                    writeln!(f,
+                        "\t\tARICap:\tMFVC{} ACS{}, Next Function: {}",
+                        flag(w, 0x1), flag(w, 0x2), (w >> 8) & 0xff
+                    )?;
+                    let w = word(&ari.ari_control);
+                    writeln!(f,
+                        "\t\tARICtl:\tMFVC{} ACS{}, Function Group: {}",
+                        flag(w, 0x1), flag(w, 0x2), (w >> 4) & 0x7
+                    )?;
+                },
+                Kind::AddressTranslationServices(ats) => {
+                    writeln!(f, "Address Translation Service (ATS)")?;
+                    let w = word(&ats.ats_capability);
+                    writeln!(f, "\t\tATSCap:\tInvalidate Queue Depth: {:02x}", w & 0x1f)?;
+                    let w = word(&ats.ats_control);
+                    writeln!(f,
+                        "\t\tATSCtl:\tEnable{}, Smallest Translation Unit: {:02x}",
+                        flag(w, 0x8000), w & 0x1f
+                    )?;
+                },
+                Kind::SingleRootIoVirtualization(sriov) => fmt_sriov(f, &sriov)?,
+                Kind::MultiRootIoVirtualization =>
+                    writeln!(f, "Multi-Root I/O Virtualization <?>")?,
+                Kind::Multicast => writeln!(f, "Multicast")?,
+                Kind::PageRequestInterface(pri) => {
+                    writeln!(f, "Page Request Interface (PRI)")?;
+                    let w = word(&pri.page_request_control);
+                    writeln!(f, "\t\tPRICtl: Enable{} Reset{}", flag(w, 0x1), flag(w, 0x2))?;
+                    let w = word(&pri.page_request_status);
+                    writeln!(f,
+                        "\t\tPRISta: RF{} UPRGI{} Stopped{}",
+                        flag(w, 0x1), flag(w, 0x2), flag(w, 0x100)
+                    )?;
+                    writeln!(f,
+                        "\t\tPage Request Capacity: {:08x}, Page Request Allocation: {:08x}",
+                        pri.outstanding_page_request_capacity,
+                        pri.outstanding_page_request_allocation,
+                    )?;
+                },
+                Kind::AmdReserved => writeln!(f, "Reserved for AMD <?>")?,
+                Kind::ResizableBar(rebar) => fmt_rebar(f, &rebar, false)?,
+                Kind::DynamicPowerAllocation => writeln!(f, "Dynamic Power Allocation <?>")?,
+                Kind::TphRequester(tph) => {
+                    writeln!(f, "Transaction Processing Hints")?;
+                    let l = dword(&tph.tph_requester_capability);
+                    if l & 0x2 != 0 {
+                        writeln!(f, "\t\tInterrupt vector mode supported")?;
+                    }
+                    if l & 0x4 != 0 {
+                        writeln!(f, "\t\tDevice specific mode supported")?;
+                    }
+                    if l & 0x100 != 0 {
+                        writeln!(f, "\t\tExtended requester support")?;
+                    }
+                    match l & 0x600 {
+                        0x000 => writeln!(f, "\t\tNo steering table available")?,
+                        0x200 => writeln!(f, "\t\tSteering table in TPH capability structure")?,
+                        0x400 => writeln!(f, "\t\tSteering table in MSI-X table")?,
+                        _ => writeln!(f, "\t\tReserved steering table location")?,
+                    }
+                },
+                Kind::LatencyToleranceReporting(ltr) => {
+                    writeln!(f, "Latency Tolerance Reporting")?;
+                    let latency = |w: u32| u64::from(w & 0x3ff) << (5 * ((w >> 10) & 0x7));
+                    writeln!(f,
+                        "\t\tMax snoop latency: {}ns", latency(word(&ltr.max_snoop_latency))
+                    )?;
+                    writeln!(f,
+                        "\t\tMax no snoop latency: {}ns", latency(word(&ltr.max_no_snoop_latency))
+                    )?;
+                },
+                Kind::SecondaryPciExpress(sec) => {
+                    writeln!(f, "Secondary PCI Express")?;
+                    let l = dword(&sec.link_control_3);
+                    writeln!(f,
+                        "\t\tLnkCtl3: LnkEquIntrruptEn{}, PerformEqu{}",
+                        flag(l, 0x2), flag(l, 0x1)
+                    )?;
+                    write!(f, "\t\tLaneErrStat: ")?;
+                    let l = sec.lane_error_status.0;
+                    if l != 0 {
+                        write!(f, "LaneErr at lane:")?;
+                        for lane in (0..32).filter(|lane| l & (1 << lane) != 0) {
+                            write!(f, " {}", lane)?;
+                        }
+                    } else {
+                        write!(f, "0")?;
+                    }
+                    writeln!(f)?;
+                },
+                Kind::ProtocolMultiplexing => writeln!(f, "Protocol Multiplexing <?>")?,
+                Kind::ProcessAddressSpaceId(pasid) => {
+                    writeln!(f, "Process Address Space ID (PASID)")?;
+                    let w = word(&pasid.pacid_capability);
+                    writeln!(f,
+                        "\t\tPASIDCap: Exec{} Priv{}, Max PASID Width: {:02x}",
+                        flag(w, 0x2), flag(w, 0x4), (w >> 8) & 0x1f
+                    )?;
+                    let w = word(&pasid.pacid_control);
+                    writeln!(f,
+                        "\t\tPASIDCtl: Enable{} Exec{} Priv{}",
+                        flag(w, 0x1), flag(w, 0x2), flag(w, 0x4)
+                    )?;
+                },
+                Kind::LnRequester => writeln!(f, "LN Requester <?>")?,
+                Kind::DownstreamPortContainment(dpc) => {
+                    writeln!(f, "Downstream Port Containment")?;
+                    let l = u32::from(u16::from(&dpc.dpc_capability));
+                    writeln!(f,
+                        "\t\tDpcCap:\tINT Msg #{}, RPExt{} PoisonedTLP{} SwTrigger{} \
+                        RP PIO Log {}, DL_ActiveErr{}",
+                        l & 0x1f, flag(l, 0x20), flag(l, 0x40), flag(l, 0x80), (l >> 8) & 0xf,
+                        flag(l, 0x1000),
+                    )?;
+                    let l = u32::from(u16::from(&dpc.dpc_control));
+                    writeln!(f,
+                        "\t\tDpcCtl:\tTrigger:{:x} Cmpl{} INT{} ErrCor{} PoisonedTLP{} \
+                        SwTrigger{} DL_ActiveErr{}",
+                        l & 0x3, flag(l, 0x4), flag(l, 0x8), flag(l, 0x10), flag(l, 0x20),
+                        flag(l, 0x40), flag(l, 0x80),
+                    )?;
+                    let l = u32::from(u16::from(&dpc.dpc_status));
+                    writeln!(f,
+                        "\t\tDpcSta:\tTrigger{} Reason:{:02x} INT{} RPBusy{} TriggerExt:{:02x} \
+                        RP PIO ErrPtr:{:02x}",
+                        flag(l, 0x1), (l >> 1) & 0x3, flag(l, 0x8), flag(l, 0x10),
+                        (l >> 5) & 0x3, (l >> 8) & 0x1f,
+                    )?;
+                    writeln!(f, "\t\tSource:\t{:04x}", dpc.dpc_error_source_id)?;
+                },
+                Kind::L1PmSubstates(l1pm) => fmt_l1pm(f, &l1pm)?,
+                Kind::PrecisionTimeMeasurement(ptm) => {
+                    writeln!(f, "Precision Time Measurement")?;
+                    let l = dword(&ptm.ptm_capability);
+                    writeln!(f,
+                        "\t\tPTMCap: Requester:{} Responder:{} Root:{}",
+                        flag(l, 0x1), flag(l, 0x2), flag(l, 0x4)
+                    )?;
+                    write!(f, "\t\tPTMClockGranularity: ")?;
+                    match (l >> 8) & 0xff {
+                        0x00 => writeln!(f, "Unimplemented")?,
+                        0xff => writeln!(f, "Greater than 254ns")?,
+                        ns => writeln!(f, "{}ns", ns)?,
+                    }
+                    let l = dword(&ptm.ptm_control);
+                    writeln!(f,
+                        "\t\tPTMControl: Enabled:{} RootSelected:{}", flag(l, 0x1), flag(l, 0x2)
+                    )?;
+                    write!(f, "\t\tPTMEffectiveGranularity: ")?;
+                    match (l >> 8) & 0xff {
+                        0x00 => writeln!(f, "Unknown")?,
+                        0xff => writeln!(f, "Greater than 254ns")?,
+                        ns => writeln!(f, "{}ns", ns)?,
+                    }
+                },
+                Kind::PciExpressOverMphy => writeln!(f, "PCI Express over M_PHY <?>")?,
+                Kind::FrsQueueing => writeln!(f, "FRS Queueing <?>")?,
+                Kind::ReadinessTimeReporting => writeln!(f, "Readiness Time Reporting <?>")?,
+                Kind::DesignatedVendorSpecificExtendedCapability(dvsec) => fmt_dvsec(f, &dvsec)?,
+                Kind::VFResizableBar(vf_rebar) => fmt_rebar(f, &vf_rebar.0, true)?,
+                Kind::DataLinkFeature => writeln!(f, "Data Link Feature <?>")?,
+                Kind::PhysicalLayer16GTps => writeln!(f, "Physical Layer 16.0 GT/s <?>")?,
+                Kind::ReceiverLaneMargining => writeln!(f, "Lane Margining at the Receiver <?>")?,
+                Kind::HierarchyId => writeln!(f, "Hierarchy ID <?>")?,
+                Kind::NativePcieEnclosureManagement =>
+                    writeln!(f, "Native PCIe Enclosure Management <?>")?,
+                kind => writeln!(f, "Extended Capability ID {:#x}", kind.id())?,
+            }
+            offset = ((header >> 20) & !0x3) as u16;
+            if offset == 0 {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+
+/// PCI Express Device/Port Type
+const EXP_ENDPOINT: u8 = 0x0;
+const EXP_LEG_END: u8 = 0x1;
+const EXP_ROOT_PORT: u8 = 0x4;
+const EXP_UPSTREAM: u8 = 0x5;
+const EXP_DOWNSTREAM: u8 = 0x6;
+const EXP_PCI_BRIDGE: u8 = 0x7;
+const EXP_PCIE_BRIDGE: u8 = 0x8;
+const EXP_ROOT_INT_EP: u8 = 0x9;
+const EXP_ROOT_EC: u8 = 0xa;
+
+/// `+` or `-` sign after flag name
+struct Flag(bool);
+impl fmt::Display for Flag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.0 { "+" } else { "-" })
+    }
+}
+
+fn flag(bits: u32, mask: u32) -> Flag {
+    Flag(bits & mask != 0)
+}
+
+/// Name from the table or `??N` for out of range value
+struct Table<'t>(&'t [&'t str], u32);
+impl<'t> fmt::Display for Table<'t> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0.get(self.1 as usize) {
+            Some(name) => f.write_str(name),
+            None => write!(f, "??{}", self.1),
+        }
+    }
+}
+
+/// Raw word register value
+fn word<T: Clone + Into<u16>>(register: &T) -> u32 {
+    u32::from(register.clone().into())
+}
+
+/// Raw dword register value
+fn dword<T: Clone + Into<u32>>(register: &T) -> u32 {
+    register.clone().into()
+}
+
+fn devsel(status: u32) -> &'static str {
+    ["fast", "medium", "slow", "??"][(status >> 9) as usize & 0x3]
+}
+
+fn fmt_size(f: &mut fmt::Formatter<'_>, mut size: u64) -> fmt::Result {
+    if size == 0 {
+        return Ok(());
+    }
+    let suffixes = ["", "K", "M", "G", "T"];
+    let mut i = 0;
+    while i < suffixes.len() - 1 && size.is_multiple_of(1024) {
+        size /= 1024;
+        i += 1;
+    }
+    write!(f, " [size={}{}]", size as u32, suffixes[i])
+}
+
+fn fmt_pm(f: &mut fmt::Formatter<'_>, pm: &PowerManagementInterface) -> fmt::Result {
+    let cap = word(&pm.capabilities);
+    writeln!(f, "Power Management version {}", cap & 0x7)?;
+    let aux_current = [0, 55, 100, 160, 220, 270, 320, 375][(cap as usize >> 6) & 0x7];
+    writeln!(f,
+        "\t\tFlags: PMEClk{} DSI{} D1{} D2{} AuxCurrent={}mA \
+        PME(D0{},D1{},D2{},D3hot{},D3cold{})",
+        flag(cap, 0x8), flag(cap, 0x20), flag(cap, 0x200), flag(cap, 0x400), aux_current,
+        flag(cap, 0x800), flag(cap, 0x1000), flag(cap, 0x2000), flag(cap, 0x4000),
+        flag(cap, 0x8000),
+    )?;
+    let ctl = word(&pm.control);
+    writeln!(f,
+        "\t\tStatus: D{} NoSoftRst{} PME-Enable{} DSel={} DScale={} PME{}",
+        ctl & 0x3, flag(ctl, 0x8), flag(ctl, 0x100), (ctl >> 9) & 0xf, (ctl >> 13) & 0x3,
+        flag(ctl, 0x8000),
+    )?;
+    let bridge = u32::from(u8::from(pm.bridge.clone()));
+    if bridge != 0 {
+        writeln!(f, "\t\tBridge: PM{} B3{}", flag(bridge, 0x80), flag(!bridge, 0x40))?;
+    }
+    Ok(())
+}
+
+fn fmt_agp(f: &mut fmt::Formatter<'_>, agp: &AcceleratedGraphicsPort) -> fmt::Result {
+    writeln!(f, "AGP version {:x}.{:x}", agp.version.major, agp.version.minor)?;
+    let agp3 = agp.is_agp3_mode();
+    let st = &agp.status;
+    writeln!(f,
+        "\t\tStatus: RQ={} Iso{} ArqSz={} Cal={} SBA{} ITACoh{} GART64{} HTrans{} 64bit{} FW{} \
+        AGP3{} Rate={}",
+        u32::from(st.request_queue) + 1, Flag(st.isochronous), st.async_request_size,
+        u8::from(st.calibration_cycle), Flag(st.side_band_addressing), Flag(st.ita_coherent),
+        Flag(st.gart64), Flag(st.no_host_translation), Flag(st.over_4g), Flag(st.fast_writes),
+        Flag(st.agp3_mode), AgpRate(st.data_rate, agp3),
+    )?;
+    let cmd = &agp.command;
+    writeln!(f,
+        "\t\tCommand: RQ={} ArqSz={} Cal={} SBA{} AGP{} GART64{} 64bit{} FW{} Rate={}",
+        u32::from(cmd.request_queue) + 1, cmd.async_request_size,
+        u8::from(cmd.calibration_cycle), Flag(cmd.side_band_addressing_enable),
+        Flag(cmd.agp_enable), Flag(cmd.gart64_enable), Flag(cmd.over_4g_enable),
+        Flag(cmd.fast_writes_enable), AgpRate(cmd.data_rate, agp3),
+    )
+}
+
+struct AgpRate(DataRate, bool);
+impl fmt::Display for AgpRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut multipliers = self.0.multipliers(self.1).peekable();
+        if multipliers.peek().is_none() {
+            return f.write_str("<none>");
+        }
+        for (i, multiplier) in multipliers.enumerate() {
+            write!(f, "{}x{}", if i == 0 { "" } else { "," }, multiplier)?;
+        }
+        Ok(())
+    }
+}
+
+fn fmt_pcix(f: &mut fmt::Formatter<'_>, pcix: &PciX) -> fmt::Result {
+    match pcix {
+        PciX::Device(dev) => {
+            writeln!(f, "PCI-X non-bridge device")?;
+            let cmd = &dev.command;
+            writeln!(f,
+                "\t\tCommand: DPERE{} ERO{} RBC={} OST={}",
+                Flag(cmd.data_parity_error_recovery_enable), Flag(cmd.enable_relaxed_ordering),
+                cmd.max_memory_read_byte_count.value(),
+                cmd.max_outstanding_split_transactions.value(),
+            )?;
+            let st = &dev.status;
+            let dc = match st.device_complexity {
+                DeviceComplexity::Simple => "simple",
+                DeviceComplexity::Bridge => "bridge",
+            };
+            writeln!(f,
+                "\t\tStatus: Dev={:02x}:{:02x}.{} 64bit{} 133MHz{} SCD{} USC{} DC={} DMMRBC={} \
+                DMOST={} DMCRS={} RSCEM{} 266MHz{} 533MHz{}",
+                st.bus_number, st.device_number, st.function_number, Flag(st.device_64bit),
+                Flag(st.capable_133mhz), Flag(st.split_completion_discarded),
+                Flag(st.unexpected_split_completion), dc,
+                st.designed_max_memory_read_byte_count.value(),
+                st.designed_max_outstanding_split_transactions.value(),
+                st.designed_max_cumulative_read_size.value(),
+                Flag(st.received_split_completion_error_message), Flag(st.capable_266mhz),
+                Flag(st.capable_533mhz),
+            )
+        },
+        PciX::Bridge(bridge) => {
+            writeln!(f, "PCI-X bridge device")?;
+            let sec = &bridge.secondary_status;
+            let freq = ["conv", "66MHz", "100MHz", "133MHz", "?4", "?5", "?6", "?7"]
+                [usize::from(sec.secondary_bus_mode_and_frequency & 0x7)];
+            writeln!(f,
+                "\t\tSecondary Status: 64bit{} 133MHz{} SCD{} USC{} SCO{} SRD{} Freq={}",
+                Flag(sec.is_64bit), Flag(sec.capable_133mhz), Flag(sec.split_completion_discarded),
+                Flag(sec.unexpected_split_completion), Flag(sec.split_completion_overrun),
+                Flag(sec.split_request_delayed), freq,
+            )?;
+            let st = &bridge.bridge_status;
+            writeln!(f,
+                "\t\tStatus: Dev={:02x}:{:02x}.{} 64bit{} 133MHz{} SCD{} USC{} SCO{} SRD{}",
+                st.bus_number, st.device_number, st.function_number, Flag(st.is_64bit),
+                Flag(st.capable_133mhz), Flag(st.split_completion_discarded),
+                Flag(st.unexpected_split_completion), Flag(st.split_completion_overrun),
+                Flag(st.split_request_delayed),
+            )?;
+            for (name, stc) in [
+                ("Upstream", &bridge.upstream_split_transaction_control),
+                ("Downstream", &bridge.downstream_split_transaction_control),
+            ] {
+                writeln!(f,
+                    "\t\t{}: Capacity={} CommitmentLimit={}",
+                    name, stc.split_transaction_capacity, stc.split_transaction_commitment_limit,
+                )?;
+            }
+            Ok(())
+        },
+    }
+}
+
+fn fmt_ea(f: &mut fmt::Formatter<'_>, ea: &EnhancedAllocation) -> fmt::Result {
+    write!(f, "Enhanced Allocation (EA): NumEntries={}", ea.num_entries)?;
+    if let Some(fixed) = &ea.fixed_bus_numbers {
+        write!(f, ", secondary={}, subordinate={}", fixed.secondary, fixed.subordinate)?;
+    }
+    writeln!(f)?;
+    for (n, entry) in ea.entries().enumerate() {
+        writeln!(f,
+            "\t\tEntry {}: Enable{} Writable{} EntrySize={}",
+            n, Flag(entry.enable), Flag(entry.writable), entry.entry_size,
+        )?;
+        write!(f, "\t\t\t BAR Equivalent Indicator: ")?;
+        match entry.bar_equivalent_indicator {
+            BarEquivalentIndicator::Bar(n) => writeln!(f, "BAR {}", n)?,
+            BarEquivalentIndicator::ResourceBehindFunction => writeln!(f, "resource behind function")?,
+            BarEquivalentIndicator::NotIndicated => writeln!(f, "not indicated")?,
+            BarEquivalentIndicator::ExpansionRom => writeln!(f, "expansion ROM")?,
+            BarEquivalentIndicator::VfBar(n) => writeln!(f, "VF-BAR {}", n)?,
+            BarEquivalentIndicator::Reserved(_) => writeln!(f, "reserved")?,
+        }
+        for (name, props, is_secondary) in [
+            ("PrimaryProperties", entry.primary_properties, false),
+            ("SecondaryProperties", entry.secondary_properties, true),
+        ] {
+            let desc = match props {
+                EntryProperties::MemoryNonPrefetchable => "memory space, non-prefetchable",
+                EntryProperties::MemoryPrefetchable => "memory space, prefetchable",
+                EntryProperties::Io => "I/O space",
+                EntryProperties::VfMemoryPrefetchable => "VF memory space, prefetchable",
+                EntryProperties::VfMemoryNonPrefetchable => "VF memory space, non-prefetchable",
+                EntryProperties::BridgeMemoryNonPrefetchable =>
+                    "memory behind bridge, non-prefetchable",
+                EntryProperties::BridgeMemoryPrefetchable => "memory behind bridge, prefetchable",
+                EntryProperties::BridgeIo => "I/O space behind bridge",
+                EntryProperties::MemoryUnavailable => "memory space resource unavailable for use",
+                EntryProperties::IoUnavailable => "I/O space resource unavailable for use",
+                EntryProperties::Unavailable if is_secondary =>
+                    "entry unavailable for use, PrimaryProperties should be used",
+                EntryProperties::Unavailable => "entry unavailable for use",
+                EntryProperties::Reserved(_) => "reserved",
+            };
+            writeln!(f, "\t\t\t {}: {}", name, desc)?;
+        }
+        write!(f, "\t\t\t Base: ")?;
+        if entry.is_base_64bit {
+            write!(f, "{:x}", entry.base >> 32)?;
+        }
+        writeln!(f, "{:08x}", entry.base as u32)?;
+        write!(f, "\t\t\t MaxOffset: ")?;
+        if entry.is_max_offset_64bit {
+            write!(f, "{:x}", entry.max_offset >> 32)?;
+        }
+        writeln!(f, "{:08x}", entry.max_offset as u32)?;
+    }
+    Ok(())
+}
+
+fn fmt_msi(f: &mut fmt::Formatter<'_>, msi: &MessageSignaledInterrups) -> fmt::Result {
+    let ctl = &msi.message_control;
+    let is_64bit = matches!(msi.message_address, MessageAddress::Qword(_));
+    writeln!(f,
+        "MSI: Enable{} Count={}/{} Maskable{} 64bit{}",
+        Flag(ctl.enable),
+        1u32 << u8::from(ctl.multiple_message_enable),
+        1u32 << u8::from(ctl.multiple_message_capable),
+        Flag(ctl.per_vector_masking_capable),
+        Flag(is_64bit),
+    )?;
+    write!(f, "\t\tAddress: ")?;
+    match msi.message_address {
+        MessageAddress::Dword(address) => write!(f, "{:08x}", address)?,
+        MessageAddress::Qword(address) =>
+            write!(f, "{:08x}{:08x}", address >> 32, address as u32)?,
+    }
+    writeln!(f, "  Data: {:04x}", msi.message_data)?;
+    if let (Some(mask), Some(pending)) = (msi.mask_bits, msi.pending_bits) {
+        writeln!(f, "\t\tMasking: {:08x}  Pending: {:08x}", mask, pending)?;
+    }
+    Ok(())
+}
+
+fn fmt_msix(f: &mut fmt::Formatter<'_>, msix: &MsiX) -> fmt::Result {
+    let ctl = &msix.message_control;
+    writeln!(f,
+        "MSI-X: Enable{} Count={} Masked{}",
+        Flag(ctl.msi_x_enable), u32::from(ctl.table_size) + 1, Flag(ctl.function_mask),
+    )?;
+    let table = dword(&msix.table);
+    writeln!(f, "\t\tVector table: BAR={} offset={:08x}", table & 0x7, table & !0x7)?;
+    let pba = dword(&msix.pending_bit_array);
+    writeln!(f, "\t\tPBA: BAR={} offset={:08x}", pba & 0x7, pba & !0x7)
+}
+
+fn fmt_sata(f: &mut fmt::Formatter<'_>, sata: &Sata) -> fmt::Result {
+    write!(f, "SATA HBA v{}.{}", sata.revision.major, sata.revision.minor)?;
+    match u8::from(sata.bar_location.clone()) {
+        bar @ 4..=9 => writeln!(f, " BAR{} Offset={:08x}", bar - 4, sata.bar_offset.0),
+        15 => writeln!(f, " InCapability"),
+        bar => writeln!(f, " BAR??{}", bar),
+    }
+}
+
+const HT_LINK_WIDTH: [&str; 8] = ["8bit", "16bit", "[2]", "32bit", "2bit", "4bit", "[6]", "N/C"];
+const HT_LINK_FREQ: [&str; 16] = [
+    "200MHz", "300MHz", "400MHz", "500MHz", "600MHz", "800MHz", "1.0GHz", "1.2GHz",
+    "1.4GHz", "1.6GHz", "[a]", "[b]", "[c]", "[d]", "[e]", "Vend",
+];
+
+fn fmt_ht(f: &mut fmt::Formatter<'_>, ht: &Hypertransport, cmd: u16) -> fmt::Result {
+    let cmd = u32::from(cmd);
+    match ht {
+        Hypertransport::SlaveOrPrimaryInterface(pri) => {
+            writeln!(f, "HyperTransport: Slave or Primary Interface")?;
+            let rid = u8::from(&pri.revision_id);
+            if rid < 0x22 && rid > 0x11 {
+                writeln!(f, "\t\t!!! Possibly incomplete decoding")?;
+            }
+            write!(f,
+                "\t\tCommand: BaseUnitID={} UnitCnt={} MastHost{} DefDir{}",
+                cmd & 0x1f, (cmd >> 5) & 0x1f, flag(cmd, 0x400), flag(cmd, 0x800),
+            )?;
+            if rid >= 0x22 {
+                write!(f, " DUL{}", flag(cmd, 0x1000))?;
+            }
+            writeln!(f)?;
+            fmt_ht_link_control(f, " 0", &pri.link_control_0, rid)?;
+            fmt_ht_link_config(f, " 0", &pri.link_config_0, rid)?;
+            fmt_ht_link_control(f, " 1", &pri.link_control_1, rid)?;
+            fmt_ht_link_config(f, " 1", &pri.link_config_1, rid)?;
+            writeln!(f, "\t\tRevision ID: {}.{:02}", rid >> 5, rid & 0x1f)?;
+            if rid < 0x22 {
+                return Ok(());
+            }
+            fmt_ht_link_freq_err(f, " 0", pri.link_freq_0, &pri.link_error_0)?;
+            // Primary interface capability is one byte wide
+            fmt_ht_link_freq_cap(f, " 0", word(&pri.link_freq_cap_0) & 0xff)?;
+            let ftr = word(&pri.feature) & 0xff;
+            writeln!(f,
+                "\t\tFeature Capability: IsocFC{} LDTSTOP{} CRCTM{} ECTLT{} 64bA{} UIDRD{}",
+                flag(ftr, 0x1), flag(ftr, 0x2), flag(ftr, 0x4), flag(ftr, 0x8), flag(ftr, 0x10),
+                flag(ftr, 0x20),
+            )?;
+            fmt_ht_link_freq_err(f, " 1", pri.link_freq_1, &pri.link_error_1)?;
+            fmt_ht_link_freq_cap(f, " 1", word(&pri.link_freq_cap_1) & 0xff)?;
+            fmt_ht_error_handling(f, word(&pri.error_handling))?;
+            writeln!(f,
+                "\t\tPrefetchable memory behind bridge Upper: {:02x}-{:02x}",
+                pri.mem_base_upper, pri.mem_limit_upper,
+            )?;
+            writeln!(f, "\t\tBus Number: {:02x}", pri.bus_number)
+        },
+        Hypertransport::HostOrSecondaryInterface(sec) => {
+            writeln!(f, "HyperTransport: Host or Secondary Interface")?;
+            let rid = u8::from(&sec.revision_id);
+            if rid < 0x22 && rid > 0x11 {
+                writeln!(f, "\t\t!!! Possibly incomplete decoding")?;
+            }
+            write!(f, "\t\tCommand: WarmRst{} DblEnd{}", flag(cmd, 0x1), flag(cmd, 0x2))?;
+            if rid >= 0x22 {
+                write!(f,
+                    " DevNum={} ChainSide{} HostHide{} Slave{} <EOCErr{} DUL{}",
+                    (cmd & 0x7c) >> 2, flag(cmd, 0x80), flag(cmd, 0x100), flag(cmd, 0x400),
+                    flag(cmd, 0x800), flag(cmd, 0x1000),
+                )?;
+            }
+            writeln!(f)?;
+            fmt_ht_link_control(f, "", &sec.link_control, rid)?;
+            fmt_ht_link_config(f, "", &sec.link_config, rid)?;
+            writeln!(f, "\t\tRevision ID: {}.{:02}", rid >> 5, rid & 0x1f)?;
+            if rid < 0x22 {
+                return Ok(());
+            }
+            fmt_ht_link_freq_err(f, "", sec.link_freq, &sec.link_error)?;
+            fmt_ht_link_freq_cap(f, "", word(&sec.link_freq_cap))?;
+            let ftr = word(&sec.feature);
+            writeln!(f,
+                "\t\tFeature Capability: IsocFC{} LDTSTOP{} CRCTM{} ECTLT{} 64bA{} UIDRD{} \
+                ExtRS{} UCnfE{}",
+                flag(ftr, 0x1), flag(ftr, 0x2), flag(ftr, 0x4), flag(ftr, 0x8), flag(ftr, 0x10),
+                flag(ftr, 0x20), flag(ftr, 0x100), flag(ftr, 0x200),
+            )?;
+            if ftr & 0x100 != 0 {
+                fmt_ht_error_handling(f, word(&sec.error_handling))?;
+                writeln!(f,
+                    "\t\tPrefetchable memory behind bridge Upper: {:02x}-{:02x}",
+                    sec.mem_base_upper, sec.mem_limit_upper,
+                )?;
+            }
+            Ok(())
+        },
+        Hypertransport::Switch(_) => writeln!(f, "HyperTransport: Switch"),
+        Hypertransport::InterruptDiscoveryAndConfiguration(_) =>
+            writeln!(f, "HyperTransport: Interrupt Discovery and Configuration"),
+        Hypertransport::RevisionId(rid, _) =>
+            writeln!(f, "HyperTransport: Revision ID: {}.{:02}", rid.major, rid.minor),
+        Hypertransport::UnitIdClumping(_) => writeln!(f, "HyperTransport: UnitID Clumping"),
+        Hypertransport::ExtendedConfigurationSpaceAccess(_) =>
+            writeln!(f, "HyperTransport: Extended Configuration Space Access"),
+        Hypertransport::AddressMapping(_) => writeln!(f, "HyperTransport: Address Mapping"),
+        Hypertransport::MsiMapping(msim) => {
+            writeln!(f,
+                "HyperTransport: MSI Mapping Enable{} Fixed{}",
+                Flag(msim.enabled), Flag(msim.fixed),
+            )?;
+            if !msim.fixed {
+                let base = u64::from(msim.base_address_upper) << 32
+                    | u64::from(msim.base_address_lower & !0xfffff);
+                writeln!(f, "\t\tMapping Address Base: {:016x}", base)?;
+            }
+            Ok(())
+        },
+        Hypertransport::DirectRoute(_) => writeln!(f, "HyperTransport: DirectRoute"),
+        Hypertransport::VCSet(_) => writeln!(f, "HyperTransport: VCSet"),
+        Hypertransport::RetryMode(_) => writeln!(f, "HyperTransport: Retry Mode"),
+        Hypertransport::X86Encoding(_) => writeln!(f, "HyperTransport: X86 (reserved)"),
+        ht => writeln!(f, "HyperTransport: #{:02x}", u8::from(ht)),
+    }
+}
+
+fn fmt_ht_link_control(
+    f: &mut fmt::Formatter<'_>, n: &str, lctr: &LinkControl, rid: u8,
+) -> fmt::Result {
+    let lctr = word(lctr);
+    write!(f,
+        "\t\tLink Control{}: CFlE{} CST{} CFE{} <LkFail{} Init{} EOC{} TXO{} <CRCErr={:x}",
+        n, flag(lctr, 0x2), flag(lctr, 0x4), flag(lctr, 0x8), flag(lctr, 0x10),
+        flag(lctr, 0x20), flag(lctr, 0x40), flag(lctr, 0x80), (lctr >> 8) & 0xf,
+    )?;
+    if rid >= 0x22 {
+        write!(f,
+            " IsocEn{} LSEn{} ExtCTL{} 64b{}",
+            flag(lctr, 0x1000), flag(lctr, 0x2000), flag(lctr, 0x4000), flag(lctr, 0x8000),
+        )?;
+    }
+    writeln!(f)
+}
+
+fn fmt_ht_link_config(
+    f: &mut fmt::Formatter<'_>, n: &str, lcnf: &LinkConfiguration, rid: u8,
+) -> fmt::Result {
+    let lcnf = word(lcnf);
+    let width = |shift: u32| HT_LINK_WIDTH[(lcnf >> shift) as usize & 0x7];
+    if rid >= 0x22 {
+        writeln!(f,
+            "\t\tLink Config{}: MLWI={} DwFcIn{} MLWO={} DwFcOut{} LWI={} DwFcInEn{} \
+            LWO={} DwFcOutEn{}",
+            n, width(0), flag(lcnf, 0x8), width(4), flag(lcnf, 0x80), width(8),
+            flag(lcnf, 0x800), width(12), flag(lcnf, 0x8000),
+        )
+    } else {
+        writeln!(f,
+            "\t\tLink Config{}: MLWI={} MLWO={} LWI={} LWO={}",
+            n, width(0), width(4), width(8), width(12),
+        )
+    }
+}
+
+fn fmt_ht_link_freq_err(
+    f: &mut fmt::Formatter<'_>, n: &str, freq: u8, err: &LinkError,
+) -> fmt::Result {
+    writeln!(f, "\t\tLink Frequency{}: {}", n, HT_LINK_FREQ[usize::from(freq & 0xf)])?;
+    writeln!(f,
+        "\t\tLink Error{}: <Prot{} <Ovfl{} <EOC{} CTLTm{}",
+        n, Flag(err.protocol_error), Flag(err.overflow_error), Flag(err.end_of_chain_error),
+        Flag(err.ctl_timeout),
+    )
+}
+
+fn fmt_ht_link_freq_cap(f: &mut fmt::Formatter<'_>, n: &str, lfcap: u32) -> fmt::Result {
+    writeln!(f,
+        "\t\tLink Frequency Capability{}: 200MHz{} 300MHz{} 400MHz{} 500MHz{} 600MHz{} 800MHz{} \
+        1.0GHz{} 1.2GHz{} 1.4GHz{} 1.6GHz{} Vend{}",
+        n, flag(lfcap, 0x1), flag(lfcap, 0x2), flag(lfcap, 0x4), flag(lfcap, 0x8),
+        flag(lfcap, 0x10), flag(lfcap, 0x20), flag(lfcap, 0x40), flag(lfcap, 0x80),
+        flag(lfcap, 0x100), flag(lfcap, 0x200), flag(lfcap, 0x8000),
+    )
+}
+
+fn fmt_ht_error_handling(f: &mut fmt::Formatter<'_>, eh: u32) -> fmt::Result {
+    writeln!(f,
+        "\t\tError Handling: PFlE{} OFlE{} PFE{} OFE{} EOCFE{} RFE{} CRCFE{} SERRFE{} CF{} RE{} \
+        PNFE{} ONFE{} EOCNFE{} RNFE{} CRCNFE{} SERRNFE{}",
+        flag(eh, 0x1), flag(eh, 0x2), flag(eh, 0x4), flag(eh, 0x8), flag(eh, 0x10),
+        flag(eh, 0x20), flag(eh, 0x40), flag(eh, 0x80), flag(eh, 0x100), flag(eh, 0x200),
+        flag(eh, 0x400), flag(eh, 0x800), flag(eh, 0x1000), flag(eh, 0x2000), flag(eh, 0x4000),
+        flag(eh, 0x8000),
+    )
+}
+
+const LATENCY_L0S: [&str; 8] =
+    ["<64ns", "<128ns", "<256ns", "<512ns", "<1us", "<2us", "<4us", "unlimited"];
+const LATENCY_L1: [&str; 8] =
+    ["<1us", "<2us", "<4us", "<8us", "<16us", "<32us", "<64us", "unlimited"];
+
+/// Slot power limit in watts
+fn power_limit(value: u32, scale: u32) -> f32 {
+    if scale == 0 && value >= 0xf0 {
+        return match value {
+            0xf0 => 250.0,
+            0xf1 => 275.0,
+            0xf2 => 300.0,
+            _ => -1.0,
+        };
+    }
+    value as f32 * [1.0, 0.1, 0.01, 0.001][scale as usize & 0x3]
+}
+
+fn link_speed(speed: u32) -> &'static str {
+    match speed {
+        1 => "2.5GT/s",
+        2 => "5GT/s",
+        3 => "8GT/s",
+        4 => "16GT/s",
+        _ => "unknown",
+    }
+}
+
+fn link_compare(sta: u32, cap: u32) -> &'static str {
+    if sta < cap {
+        "downgraded"
+    } else if sta > cap {
+        "strange"
+    } else {
+        "ok"
+    }
+}
+
+fn fmt_express_dev(f: &mut fmt::Formatter<'_>, pcie: &PciExpress, ty: u8) -> fmt::Result {
+    let t = dword(&pcie.device.capabilities);
+    write!(f,
+        "\t\tDevCap:\tMaxPayload {} bytes, PhantFunc {}",
+        128 << (t & 0x7), (1 << ((t & 0x18) >> 3)) - 1,
+    )?;
+    if matches!(ty, EXP_ENDPOINT | EXP_LEG_END) {
+        write!(f,
+            ", Latency L0s {}, L1 {}",
+            LATENCY_L0S[(t as usize & 0x1c0) >> 6], LATENCY_L1[(t as usize & 0xe00) >> 9],
+        )?;
+    }
+    write!(f, "\n\t\t\tExtTag{}", flag(t, 0x20))?;
+    if matches!(ty, EXP_ENDPOINT | EXP_LEG_END | EXP_UPSTREAM | EXP_PCI_BRIDGE) {
+        write!(f,
+            " AttnBtn{} AttnInd{} PwrInd{}", flag(t, 0x1000), flag(t, 0x2000), flag(t, 0x4000)
+        )?;
+    }
+    write!(f, " RBE{}", flag(t, 0x8000))?;
+    if matches!(ty, EXP_ENDPOINT | EXP_LEG_END | EXP_ROOT_INT_EP) {
+        write!(f, " FLReset{}", flag(t, 0x10000000))?;
+    }
+    if matches!(ty, EXP_ENDPOINT | EXP_UPSTREAM | EXP_PCI_BRIDGE) {
+        write!(f,
+            " SlotPowerLimit {:.3}W", power_limit((t >> 18) & 0xff, (t >> 26) & 0x3)
+        )?;
+    }
+    writeln!(f)?;
+
+    let w = word(&pcie.device.control);
+    writeln!(f,
+        "\t\tDevCtl:\tCorrErr{} NonFatalErr{} FatalErr{} UnsupReq{}",
+        flag(w, 0x1), flag(w, 0x2), flag(w, 0x4), flag(w, 0x8),
+    )?;
+    write!(f,
+        "\t\t\tRlxdOrd{} ExtTag{} PhantFunc{} AuxPwr{} NoSnoop{}",
+        flag(w, 0x10), flag(w, 0x100), flag(w, 0x200), flag(w, 0x400), flag(w, 0x800),
+    )?;
+    if ty == EXP_PCI_BRIDGE {
+        write!(f, " BrConfRtry{}", flag(w, 0x8000))?;
+    }
+    if matches!(ty, EXP_ENDPOINT | EXP_LEG_END | EXP_ROOT_INT_EP) && t & 0x10000000 != 0 {
+        write!(f, " FLReset{}", flag(w, 0x8000))?;
+    }
+    writeln!(f,
+        "\n\t\t\tMaxPayload {} bytes, MaxReadReq {} bytes",
+        128 << ((w & 0xe0) >> 5), 128 << ((w & 0x7000) >> 12),
+    )?;
+
+    let w = word(&pcie.device.status);
+    writeln!(f,
+        "\t\tDevSta:\tCorrErr{} NonFatalErr{} FatalErr{} UnsupReq{} AuxPwr{} TransPend{}",
+        flag(w, 0x1), flag(w, 0x2), flag(w, 0x4), flag(w, 0x8), flag(w, 0x10), flag(w, 0x20),
+    )
+}
+
+fn fmt_express_link(f: &mut fmt::Formatter<'_>, link: &Link, ty: u8) -> fmt::Result {
+    let t = dword(&link.capabilities);
+    let aspm = (t & 0xc00) >> 10;
+    let (cap_speed, cap_width) = (t & 0xf, (t & 0x3f0) >> 4);
+    write!(f,
+        "\t\tLnkCap:\tPort #{}, Speed {}, Width x{}, ASPM {}",
+        t >> 24, link_speed(cap_speed), cap_width,
+        ["not supported", "L0s", "L1", "L0s L1"][aspm as usize],
+    )?;
+    if aspm != 0 {
+        write!(f, ", Exit Latency ")?;
+        if aspm & 0x1 != 0 {
+            write!(f, "L0s {}", LATENCY_L0S[(t as usize & 0x7000) >> 12])?;
+        }
+        if aspm & 0x2 != 0 {
+            write!(f,
+                "{}L1 {}",
+                if aspm & 0x1 != 0 { ", " } else { "" },
+                LATENCY_L1[(t as usize & 0x38000) >> 15],
+            )?;
+        }
+    }
+    writeln!(f)?;
+    writeln!(f,
+        "\t\t\tClockPM{} Surprise{} LLActRep{} BwNot{} ASPMOptComp{}",
+        flag(t, 0x40000), flag(t, 0x80000), flag(t, 0x100000), flag(t, 0x200000),
+        flag(t, 0x400000),
+    )?;
+
+    let w = word(&link.control);
+    write!(f,
+        "\t\tLnkCtl:\tASPM {};",
+        ["Disabled", "L0s Enabled", "L1 Enabled", "L0s L1 Enabled"][w as usize & 0x3],
+    )?;
+    if matches!(ty, EXP_ROOT_PORT | EXP_ENDPOINT | EXP_LEG_END | EXP_PCI_BRIDGE) {
+        write!(f, " RCB {} bytes", if w & 0x8 != 0 { 128 } else { 64 })?;
+    }
+    writeln!(f, " Disabled{} CommClk{}", flag(w, 0x10), flag(w, 0x40))?;
+    writeln!(f,
+        "\t\t\tExtSynch{} ClockPM{} AutWidDis{} BWInt{} AutBWInt{}",
+        flag(w, 0x80), flag(w, 0x100), flag(w, 0x200), flag(w, 0x400), flag(w, 0x800),
+    )?;
+
+    let w = word(&link.status);
+    let (sta_speed, sta_width) = (w & 0xf, (w & 0x3f0) >> 4);
+    writeln!(f,
+        "\t\tLnkSta:\tSpeed {} ({}), Width x{} ({})",
+        link_speed(sta_speed), link_compare(sta_speed, cap_speed),
+        sta_width, link_compare(sta_width, cap_width),
+    )?;
+    writeln!(f,
+        "\t\t\tTrErr{} Train{} SlotClk{} DLActive{} BWMgmt{} ABWMgmt{}",
+        flag(w, 0x400), flag(w, 0x800), flag(w, 0x1000), flag(w, 0x2000), flag(w, 0x4000),
+        flag(w, 0x8000),
+    )
+}
+
+fn fmt_express_slot(f: &mut fmt::Formatter<'_>, slot: &Slot) -> fmt::Result {
+    let t = dword(&slot.capabilities);
+    writeln!(f,
+        "\t\tSltCap:\tAttnBtn{} PwrCtrl{} MRL{} AttnInd{} PwrInd{} HotPlug{} Surprise{}",
+        flag(t, 0x1), flag(t, 0x2), flag(t, 0x4), flag(t, 0x8), flag(t, 0x10), flag(t, 0x40),
+        flag(t, 0x20),
+    )?;
+    writeln!(f,
+        "\t\t\tSlot #{}, PowerLimit {:.3}W; Interlock{} NoCompl{}",
+        t >> 19, power_limit((t & 0x7f80) >> 7, (t & 0x18000) >> 15),
+        flag(t, 0x20000), flag(t, 0x40000),
+    )?;
+
+    let w = word(&slot.control);
+    writeln!(f,
+        "\t\tSltCtl:\tEnable: AttnBtn{} PwrFlt{} MRL{} PresDet{} CmdCplt{} HPIrq{} LinkChg{}",
+        flag(w, 0x1), flag(w, 0x2), flag(w, 0x4), flag(w, 0x8), flag(w, 0x10), flag(w, 0x20),
+        flag(w, 0x1000),
+    )?;
+    let indicator = |shift: u32| ["Unknown", "On", "Blink", "Off"][(w >> shift) as usize & 0x3];
+    writeln!(f,
+        "\t\t\tControl: AttnInd {}, PwrInd {}, Power{} Interlock{}",
+        indicator(6), indicator(8), flag(w, 0x400), flag(w, 0x800),
+    )?;
+
+    let w = word(&slot.status);
+    writeln!(f,
+        "\t\tSltSta:\tStatus: AttnBtn{} PowerFlt{} MRL{} CmdCplt{} PresDet{} Interlock{}",
+        flag(w, 0x1), flag(w, 0x2), flag(w, 0x20), flag(w, 0x10), flag(w, 0x40), flag(w, 0x80),
+    )?;
+    writeln!(f,
+        "\t\t\tChanged: MRL{} PresDet{} LinkState{}",
+        flag(w, 0x4), flag(w, 0x8), flag(w, 0x100),
+    )
+}
+
+fn fmt_express_root(f: &mut fmt::Formatter<'_>, root: &Root) -> fmt::Result {
+    let w = word(&root.control);
+    writeln!(f,
+        "\t\tRootCtl: ErrCorrectable{} ErrNon-Fatal{} ErrFatal{} PMEIntEna{} CRSVisible{}",
+        flag(w, 0x1), flag(w, 0x2), flag(w, 0x4), flag(w, 0x8), flag(w, 0x10),
+    )?;
+    let w = word(&root.capabilities);
+    writeln!(f, "\t\tRootCap: CRSVisible{}", flag(w, 0x1))?;
+    let l = dword(&root.status);
+    writeln!(f,
+        "\t\tRootSta: PME ReqID {:04x}, PMEStatus{} PMEPending{}",
+        l & 0xffff, flag(l, 0x10000), flag(l, 0x20000),
+    )
+}
+
+fn fmt_express_dev2(
+    f: &mut fmt::Formatter<'_>, device_2: &Device2, ty: u8, has_mem_bar: bool,
+) -> fmt::Result {
+    let l = u32::from(&device_2.capabilities);
+    let timeout_range = match l & 0xf {
+        0 => "Not Supported",
+        1 => "Range A",
+        2 => "Range B",
+        3 => "Range AB",
+        6 => "Range BC",
+        7 => "Range ABC",
+        14 => "Range BCD",
+        15 => "Range ABCD",
+        _ => "Unknown",
+    };
+    write!(f,
+        "\t\tDevCap2: Completion Timeout: {}, TimeoutDis{}, NROPrPrP{}, LTR{}",
+        timeout_range, flag(l, 0x10), flag(l, 0x400), flag(l, 0x800),
+    )?;
+    let obff = match (l >> 18) & 0x3 {
+        1 => "Via message",
+        2 => "Via WAKE#",
+        3 => "Via message/WAKE#",
+        _ => "Not Supported",
+    };
+    write!(f,
+        "\n\t\t\t 10BitTagComp{}, 10BitTagReq{}, OBFF {}, ExtFmt{}, EETLPPrefix{}",
+        flag(l, 0x10000), flag(l, 0x20000), obff, flag(l, 0x100000), flag(l, 0x200000),
+    )?;
+    if l & 0x200000 != 0 {
+        let prefixes = match (l >> 22) & 0x3 {
+            0 => 4,
+            n => n,
+        };
+        write!(f, ", MaxEETLPPrefixes {}", prefixes)?;
+    }
+    let epr = ["Not Supported", "Dev Specific", "Form Factor Dev Specific", "Reserved"];
+    write!(f,
+        "\n\t\t\t EmergencyPowerReduction {}, EmergencyPowerReductionInit{}",
+        epr[(l as usize >> 24) & 0x3], flag(l, 0x4000000),
+    )?;
+    write!(f, "\n\t\t\t FRS{}", flag(l, 0x80000000))?;
+    if ty == EXP_ROOT_PORT {
+        let cls = ["Not Supported", "64byte cachelines", "128byte cachelines", "Reserved"];
+        write!(f, ", LN System CLS {}", cls[(l as usize >> 14) & 0x3])?;
+    }
+    if matches!(ty, EXP_ROOT_PORT | EXP_ENDPOINT) {
+        write!(f, ", TPHComp{}, ExtTPHComp{}", flag(l, 0x1000), flag(l, 0x2000))?;
+    }
+    if matches!(ty, EXP_ROOT_PORT | EXP_DOWNSTREAM) {
+        write!(f, ", ARIFwd{}", flag(l, 0x20))?;
+    }
+    writeln!(f)?;
+    let is_switch_or_root = matches!(ty, EXP_ROOT_PORT | EXP_UPSTREAM | EXP_DOWNSTREAM);
+    if is_switch_or_root || has_mem_bar {
+        write!(f, "\t\t\t AtomicOpsCap:")?;
+        if is_switch_or_root {
+            write!(f, " Routing{}", flag(l, 0x40))?;
+        }
+        if ty == EXP_ROOT_PORT || has_mem_bar {
+            write!(f,
+                " 32bit{} 64bit{} 128bitCAS{}", flag(l, 0x80), flag(l, 0x100), flag(l, 0x200)
+            )?;
+        }
+        writeln!(f)?;
+    }
+
+    let w = u32::from(u16::from(&device_2.control));
+    let timeout_value = match w & 0xf {
+        0 => "50us to 50ms",
+        1 => "50us to 100us",
+        2 => "1ms to 10ms",
+        5 => "16ms to 55ms",
+        6 => "65ms to 210ms",
+        9 => "260ms to 900ms",
+        10 => "1s to 3.5s",
+        13 => "4s to 13s",
+        14 => "17s to 64s",
+        _ => "Unknown",
+    };
+    let obff = ["Disabled", "Via message A", "Via message B", "Via WAKE#"];
+    write!(f,
+        "\t\tDevCtl2: Completion Timeout: {}, TimeoutDis{}, LTR{}, OBFF {}",
+        timeout_value, flag(w, 0x10), flag(w, 0x400), obff[(w as usize >> 13) & 0x3],
+    )?;
+    if matches!(ty, EXP_ROOT_PORT | EXP_DOWNSTREAM) {
+        write!(f, " ARIFwd{}", flag(w, 0x20))?;
+    }
+    writeln!(f)?;
+    let is_requester = matches!(ty, EXP_ROOT_PORT | EXP_ENDPOINT | EXP_ROOT_INT_EP | EXP_LEG_END);
+    if is_switch_or_root || is_requester {
+        write!(f, "\t\t\t AtomicOpsCtl:")?;
+        if is_requester {
+            write!(f, " ReqEn{}", flag(w, 0x40))?;
+        }
+        if is_switch_or_root {
+            write!(f, " EgressBlck{}", flag(w, 0x80))?;
+        }
+        writeln!(f)?;
+    }
+    Ok(())
+}
+
+fn fmt_express_link2(
+    f: &mut fmt::Formatter<'_>, link_2: &Link2, ty: u8, is_first_function: bool,
+) -> fmt::Result {
+    let deemphasis = |value: u32| match value {
+        0 => "-6dB",
+        1 => "-3.5dB",
+        _ => "Unknown",
+    };
+    if !matches!(ty, EXP_ENDPOINT | EXP_LEG_END) || is_first_function {
+        let w = word(&link_2.control);
+        let speed = match w & 0xf {
+            0 | 1 => "2.5GT/s",
+            2 => "5GT/s",
+            3 => "8GT/s",
+            4 => "16GT/s",
+            _ => "Unknown",
+        };
+        write!(f,
+            "\t\tLnkCtl2: Target Link Speed: {}, EnterCompliance{} SpeedDis{}",
+            speed, flag(w, 0x10), flag(w, 0x20),
+        )?;
+        if ty == EXP_DOWNSTREAM {
+            write!(f, ", Selectable De-emphasis: {}", deemphasis((w >> 6) & 0x1))?;
+        }
+        let margin = match (w >> 7) & 0x7 {
+            0 => "Normal Operating Range",
+            1 => "800-1200mV(full-swing)/400-700mV(half-swing)",
+            2..=5 => "200-400mV(full-swing)/100-200mV(half-swing)",
+            _ => "Reserved",
+        };
+        writeln!(f,
+            "\n\t\t\t Transmit Margin: {}, EnterModifiedCompliance{} ComplianceSOS{}",
+            margin, flag(w, 0x400), flag(w, 0x800),
+        )?;
+        writeln!(f, "\t\t\t Compliance De-emphasis: {}", deemphasis((w >> 12) & 0xf))?;
+    }
+    let w = word(&link_2.status);
+    writeln!(f,
+        "\t\tLnkSta2: Current De-emphasis Level: {}, EqualizationComplete{}, \
+        EqualizationPhase1{}",
+        deemphasis(w & 0x1), flag(w, 0x2), flag(w, 0x4),
+    )?;
+    writeln!(f,
+        "\t\t\t EqualizationPhase2{}, EqualizationPhase3{}, LinkEqualizationRequest{}",
+        flag(w, 0x8), flag(w, 0x10), flag(w, 0x20),
+    )
+}
+
+fn fmt_aer(
+    f: &mut fmt::Formatter<'_>, aer: &AdvancedErrorReporting, express_type: Option<u8>,
+) -> fmt::Result {
+    writeln!(f, "Advanced Error Reporting")?;
+    let uncorrectable = [
+        ("UESta", dword(&aer.uncorrectable_error_status)),
+        ("UEMsk", dword(&aer.uncorrectable_error_mask)),
+        ("UESvrt", dword(&aer.uncorrectable_error_severity)),
+    ];
+    for (name, l) in uncorrectable {
+        writeln!(f,
+            "\t\t{}:\tDLP{} SDES{} TLP{} FCP{} CmpltTO{} CmpltAbrt{} UnxCmplt{} RxOF{} \
+            MalfTLP{} ECRC{} UnsupReq{} ACSViol{}",
+            name, flag(l, 0x10), flag(l, 0x20), flag(l, 0x1000), flag(l, 0x2000),
+            flag(l, 0x4000), flag(l, 0x8000), flag(l, 0x10000), flag(l, 0x20000),
+            flag(l, 0x40000), flag(l, 0x80000), flag(l, 0x100000), flag(l, 0x200000),
+        )?;
+    }
+    let correctable = [
+        ("CESta", dword(&aer.correctable_error_status)),
+        ("CEMsk", dword(&aer.correctable_error_mask)),
+    ];
+    for (name, l) in correctable {
+        writeln!(f,
+            "\t\t{}:\tRxErr{} BadTLP{} BadDLLP{} Rollover{} Timeout{} AdvNonFatalErr{}",
+            name, flag(l, 0x1), flag(l, 0x40), flag(l, 0x80), flag(l, 0x100), flag(l, 0x1000),
+            flag(l, 0x2000),
+        )?;
+    }
+    let l = dword(&aer.advanced_error_capabilities_and_control);
+    writeln!(f,
+        "\t\tAERCap:\tFirst Error Pointer: {:02x}, ECRCGenCap{} ECRCGenEn{} ECRCChkCap{} \
+        ECRCChkEn{}\n\t\t\tMultHdrRecCap{} MultHdrRecEn{} TLPPfxPres{} HdrLogCap{}",
+        l & 0x1f, flag(l, 0x20), flag(l, 0x40), flag(l, 0x80), flag(l, 0x100), flag(l, 0x200),
+        flag(l, 0x400), flag(l, 0x800), flag(l, 0x1000),
+    )?;
+    let [l0, l1, l2, l3] = aer.header_log.0;
+    writeln!(f, "\t\tHeaderLog: {:08x} {:08x} {:08x} {:08x}", l0, l1, l2, l3)?;
+    if matches!(express_type, Some(EXP_ROOT_PORT | EXP_ROOT_EC)) {
+        let l = dword(&aer.root_error_command);
+        writeln!(f,
+            "\t\tRootCmd: CERptEn{} NFERptEn{} FERptEn{}", flag(l, 0x1), flag(l, 0x2), flag(l, 0x4)
+        )?;
+        let l = dword(&aer.root_error_status);
+        writeln!(f,
+            "\t\tRootSta: CERcvd{} MultCERcvd{} UERcvd{} MultUERcvd{}\n\t\t\t FirstFatal{} \
+            NonFatalMsg{} FatalMsg{} IntMsg {}",
+            flag(l, 0x1), flag(l, 0x2), flag(l, 0x4), flag(l, 0x8), flag(l, 0x10),
+            flag(l, 0x20), flag(l, 0x40), (l >> 27) & 0x1f,
+        )?;
+        writeln!(f,
+            "\t\tErrorSrc: ERR_COR: {:04x} ERR_FATAL/NONFATAL: {:04x}",
+            aer.error_source_identification.err_cor_source_identification,
+            aer.error_source_identification.err_fatal_or_nonfatal_source_identification,
+        )?;
+    }
+    Ok(())
+}
+
+fn fmt_vc(f: &mut fmt::Formatter<'_>, vc: &VirtualChannel, offset: u16) -> fmt::Result {
+    writeln!(f, "Virtual Channel")?;
+    let cr1 = dword(&vc.port_vc_capability_1);
+    let cr2 = dword(&vc.port_vc_capability_2);
+    let ctrl = word(&vc.port_vc_control);
+    let status = word(&vc.port_vc_status);
+    writeln!(f,
+        "\t\tCaps:\tLPEVC={} RefClk={} PATEntryBits={}",
+        (cr1 >> 4) & 0x7, Table(&["100ns"], (cr1 >> 8) & 0x3), 1 << ((cr1 >> 10) & 0x3),
+    )?;
+    let arb_selects = ["Fixed", "WRR32", "WRR64", "WRR128", "??4", "??5", "??6", "??7"];
+    write!(f, "\t\tArb:")?;
+    fmt_arb_selects(f, &arb_selects, cr2)?;
+    writeln!(f, "\n\t\tCtrl:\tArbSelect={}", Table(&arb_selects, (ctrl >> 1) & 0x7))?;
+    writeln!(f, "\t\tStatus:\tInProgress{}", flag(status, 0x1))?;
+    let arb_table_pos = (cr2 >> 24) & 0xff;
+    if arb_table_pos != 0 {
+        writeln!(f,
+            "\t\tPort Arbitration Table [{:x}] <?>", u32::from(offset) + 16 * arb_table_pos
+        )?;
+    }
+    let vc_arb_selects = ["Fixed", "WRR32", "WRR64", "WRR128", "TWRR128", "WRR256", "??6", "??7"];
+    let mut evcs = vc.extended_virtual_channels();
+    for i in 0..=(cr1 & 0x7) {
+        write!(f, "\t\tVC{}:\t", i)?;
+        let Some(evc) = evcs.next() else {
+            writeln!(f, "<unreadable>")?;
+            continue;
+        };
+        let rcap = dword(&evc.vc_resource_capability);
+        let rctrl = dword(&evc.vc_resource_control);
+        let rstatus = word(&evc.vc_resource_status);
+        let pat_pos = (rcap >> 24) & 0xff;
+        writeln!(f,
+            "Caps:\tPATOffset={:02x} MaxTimeSlots={} RejSnoopTrans{}",
+            pat_pos, ((rcap >> 16) & 0x3f) + 1, flag(rcap, 0x8000),
+        )?;
+        write!(f, "\t\t\tArb:")?;
+        fmt_arb_selects(f, &vc_arb_selects, rcap)?;
+        writeln!(f,
+            "\n\t\t\tCtrl:\tEnable{} ID={} ArbSelect={} TC/VC={:02x}",
+            flag(rctrl, 0x80000000), (rctrl >> 24) & 0x7,
+            Table(&vc_arb_selects, (rctrl >> 17) & 0x7), rctrl & 0xff,
+        )?;
+        writeln!(f,
+            "\t\t\tStatus:\tNegoPending{} InProgress{}", flag(rstatus, 0x2), flag(rstatus, 0x1)
+        )?;
+        if pat_pos != 0 {
+            writeln!(f, "\t\t\tPort Arbitration Table <?>")?;
+        }
+    }
+    Ok(())
+}
+
+fn fmt_arb_selects(f: &mut fmt::Formatter<'_>, names: &[&str; 8], bits: u32) -> fmt::Result {
+    for (i, name) in names.iter().enumerate() {
+        if !name.starts_with('?') || bits & (1 << i) != 0 {
+            let separator = if i == 0 { '\t' } else { ' ' };
+            write!(f, "{}{}{}", separator, name, flag(bits, 1 << i))?;
+        }
+    }
+    Ok(())
+}
+
+fn fmt_acs(f: &mut fmt::Formatter<'_>, name: &str, w: u32) -> fmt::Result {
+    writeln!(f,
+        "\t\t{}:\tSrcValid{} TransBlk{} ReqRedir{} CmpltRedir{} UpstreamFwd{} EgressCtrl{} \
+        DirectTrans{}",
+        name, flag(w, 0x1), flag(w, 0x2), flag(w, 0x4), flag(w, 0x8), flag(w, 0x10),
+        flag(w, 0x20), flag(w, 0x40),
+    )
+}
+
+fn fmt_rclink(f: &mut fmt::Formatter<'_>, rcld: &RootComplexLinkDeclaration) -> fmt::Result {
+    writeln!(f, "Root Complex Link")?;
+    let esd = dword(&rcld.element_self_description);
+    writeln!(f,
+        "\t\tDesc:\tPortNumber={:02x} ComponentID={:02x} EltType={}",
+        esd >> 24, (esd >> 16) & 0xff, Table(&["Config", "Egress", "Internal"], esd & 0xff),
+    )?;
+    for (i, entry) in rcld.link_entries().enumerate() {
+        let desc = &entry.link_description;
+        let link_type = match entry.link_address {
+            LinkAddress::MemoryMappedSpace(_) => "MemMappedRCRB",
+            LinkAddress::ConfigurationSpace(_) => "Config",
+        };
+        writeln!(f,
+            "\t\tLink{}:\tDesc:\tTargetPort={:02x} TargetComponent={:02x} AssocRCRB{} \
+            LinkType={} LinkValid{}",
+            i, desc.target_port_number, desc.target_component_id,
+            Flag(desc.associate_rcrb_header), link_type, Flag(desc.link_valid),
+        )?;
+        if !desc.link_valid {
+            continue;
+        }
+        match entry.link_address {
+            LinkAddress::MemoryMappedSpace(addr) => writeln!(f, "\t\t\tAddr:\t{:016x}", addr)?,
+            LinkAddress::ConfigurationSpace(csa) => writeln!(f,
+                "\t\t\tAddr:\t{:02x}:{:02x}.{}  CfgSpace={:016x}",
+                csa.bus, csa.device, csa.function, u64::from(csa.clone()),
+            )?,
+        }
+    }
+    Ok(())
+}
+
+fn fmt_rcec(
+    f: &mut fmt::Formatter<'_>,
+    rcec: &RootComplexEventCollectorEndpointAssociation,
+    vvv: bool,
+) -> fmt::Result {
+    writeln!(f, "Root Complex Event Collector Endpoint Association")?;
+    let bitmap = rcec.association_bitmap_for_rcieps;
+    write!(f, "\t\tRCiEPBitmap: ")?;
+    if bitmap == 0 {
+        write!(f, "{}", if vvv { "00000000 [none]" } else { "[none]" })?;
+    } else {
+        write!(f, "RCiEP at Device(s):")?;
+        let mut devices = (0..32).filter(|n| bitmap & (1 << n) != 0).peekable();
+        let mut separator = "";
+        while let Some(first) = devices.next() {
+            let mut last = first;
+            while devices.next_if_eq(&(last + 1)).is_some() {
+                last += 1;
+            }
+            write!(f, "{} {}", separator, first)?;
+            if last > first {
+                write!(f, "-{}", last)?;
+            }
+            separator = ",";
+        }
+    }
+    writeln!(f)?;
+    if let Some(abn) = &rcec.associated_bus_numbers {
+        write!(f, "\t\tAssociatedBusNumbers: ")?;
+        if abn.next_bus == 0xff && abn.last_bus == 0x00 {
+            writeln!(f, "{}", if vvv { "ff-00 [none]" } else { "[none]" })?;
+        } else {
+            writeln!(f, "{:02x}-{:02x}", abn.next_bus, abn.last_bus)?;
+        }
+    }
+    Ok(())
+}
+
+/// Resizable BAR size from 1MB (0) to 8EB (43)
+struct RebarSize(u64);
+impl fmt::Display for RebarSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            n @ 0..=43 => {
+                let unit = ["MB", "GB", "TB", "PB", "EB"][n as usize / 10];
+                write!(f, "{}{}", 1 << (n % 10), unit)
+            },
+            _ => write!(f, "<unknown>"),
+        }
+    }
+}
+
+fn fmt_rebar(f: &mut fmt::Formatter<'_>, rebar: &ResizableBar, is_virtual: bool) -> fmt::Result {
+    writeln!(f, "{} Resizable BAR", if is_virtual { "Virtual" } else { "Physical" })?;
+    let count = rebar.entries().next()
+        .map(|entry| entry.control.number_of_resizable_bars)
+        .unwrap_or(0);
+    if !(1..=6).contains(&count) {
+        return writeln!(f, "\t\t<error in resizable BAR: BAR count={}>", count);
+    }
+    for entry in rebar.entries() {
+        write!(f,
+            "\t\tBAR {}: current size: {}, supported:",
+            entry.control.bar_index, RebarSize(entry.control.bar_size.0.into()),
+        )?;
+        let bitmap = entry.supported_sizes_bitmap();
+        for n in (0..44).filter(|n| bitmap & (1 << n) != 0) {
+            write!(f, " {}", RebarSize(n))?;
+        }
+        writeln!(f)?;
+    }
+    Ok(())
+}
+
+fn fmt_dvsec(
+    f: &mut fmt::Formatter<'_>,
+    dvsec: &DesignatedVendorSpecificExtendedCapability,
+) -> fmt::Result {
+    let header = &dvsec.header;
+    write!(f,
+        "Designated Vendor-Specific: Vendor={:04x} ID={:04x} Rev={} Len={}",
+        header.dvsec_vendor_id, header.dvsec_id, header.dvsec_revision, header.dvsec_length,
+    )?;
+    let cxl = match dvsec.dvsec_capability() {
+        Ok(DvsecCapability::ComputeExpressLink(cxl)) => cxl,
+        _ => return writeln!(f, " <?>"),
+    };
+    writeln!(f, ": CXL")?;
+    if let ComputeExpressLink::Device(device) = cxl {
+        let cap = &device.capability;
+        writeln!(f,
+            "\t\tCXLCap:\tCache{} IO{} Mem{} Mem HW Init{} HDMCount {} Viral{}",
+            Flag(cap.cache_capable), Flag(cap.io_capable), Flag(cap.mem_capable),
+            Flag(cap.mem_hwinit_mode), cap.hdm_count, Flag(cap.viral_capable),
+        )?;
+        let ctl = &device.control;
+        writeln!(f,
+            "\t\tCXLCtl:\tCache{} IO{} Mem{} CacheSFCov {} CacheSFGran {} CacheClean{} Viral{}",
+            Flag(ctl.cache_enable), Flag(ctl.io_enable), Flag(ctl.mem_enable),
+            ctl.cache_sf_coverage, ctl.cache_sf_granularity, Flag(ctl.cache_clean_eviction),
+            Flag(ctl.viral_enable),
+        )?;
+    }
+    Ok(())
+}
+
+fn fmt_sriov(f: &mut fmt::Formatter<'_>, sriov: &SingleRootIoVirtualization) -> fmt::Result {
+    writeln!(f, "Single Root I/O Virtualization (SR-IOV)")?;
+    let l = dword(&sriov.sriov_capability);
+    writeln!(f,
+        "\t\tIOVCap:\tMigration{}, Interrupt Message Number: {:03x}", flag(l, 0x1), l >> 21
+    )?;
+    let w = word(&sriov.sriov_control);
+    writeln!(f,
+        "\t\tIOVCtl:\tEnable{} Migration{} Interrupt{} MSE{} ARIHierarchy{}",
+        flag(w, 0x1), flag(w, 0x2), flag(w, 0x4), flag(w, 0x8), flag(w, 0x10),
+    )?;
+    let w = word(&sriov.sriov_status);
+    writeln!(f, "\t\tIOVSta:\tMigration{}", flag(w, 0x1))?;
+    writeln!(f,
+        "\t\tInitial VFs: {}, Total VFs: {}, Number of VFs: {}, Function Dependency Link: {:02x}",
+        sriov.sriov_initial_vfs, sriov.sriov_total_vfs, sriov.sriov_num_vfs,
+        sriov.sriov_function_denpendency_link.function_dependency_link,
+    )?;
+    writeln!(f,
+        "\t\tVF offset: {}, stride: {}, Device ID: {:04x}",
+        sriov.sriov_first_vf_offset, sriov.sriov_vf_stride,
+        sriov.sriov_vf_device_id.vf_device_id,
+    )?;
+    writeln!(f,
+        "\t\tSupported Page Size: {:08x}, System Page Size: {:08x}",
+        sriov.sriov_supported_page_sizes, sriov.sriov_system_page_size,
+    )?;
+    let migration = dword(&sriov.sriov_vf_migration_state_array_offset);
+    let bars = sriov.sriov_vf_bar.0;
+    let mut i = 0;
+    while i < bars.len() {
+        let index = i;
+        let l = match bars[i] {
+            0xffffffff => 0,
+            l => l,
+        };
+        i += 1;
+        if l == 0 {
+            continue;
+        }
+        write!(f, "\t\tRegion {}: Memory at ", index)?;
+        let is_64bit = l & 0x6 == 0x4;
+        if is_64bit {
+            // The last BAR upper half overlaps VF Migration State Array Offset
+            let upper = bars.get(i).copied().unwrap_or(migration);
+            i += 1;
+            write!(f, "{:08x}", upper)?;
+        }
+        writeln!(f,
+            "{:08x} ({}-bit, {}prefetchable)",
+            l & !0xf,
+            if l & 0x6 == 0 { "32" } else { "64" },
+            if l & 0x8 != 0 { "" } else { "non-" },
+        )?;
+    }
+    writeln!(f, "\t\tVF Migration: offset: {:08x}, BIR: {}", migration & !0x7, migration & 0x7)
+}
+
+fn fmt_l1pm(f: &mut fmt::Formatter<'_>, l1pm: &L1PmSubstates) -> fmt::Result {
+    writeln!(f, "L1 PM Substates")?;
+    let calc_time = |value: u32, scale: u32| match scale {
+        0 => Some(value * 2),
+        1 => Some(value * 10),
+        2 => Some(value * 100),
+        _ => None,
+    };
+    let l = dword(&l1pm.l1_pm_substates_capabilities);
+    writeln!(f,
+        "\t\tL1SubCap: PCI-PM_L1.2{} PCI-PM_L1.1{} ASPM_L1.2{} ASPM_L1.1{} L1_PM_Substates{}",
+        flag(l, 0x1), flag(l, 0x2), flag(l, 0x4), flag(l, 0x8), flag(l, 0x10),
+    )?;
+    let is_l1_2_capable = l & 0x5 != 0;
+    if is_l1_2_capable {
+        write!(f, "\t\t\t  PortCommonModeRestoreTime={}us ", (l >> 8) & 0xff)?;
+        match calc_time((l >> 19) & 0x1f, (l >> 16) & 0x3) {
+            Some(time) => writeln!(f, "PortTPowerOnTime={}us", time)?,
+            None => writeln!(f, "PortTPowerOnTime=<error>")?,
+        }
+    }
+    let val = dword(&l1pm.l1_pm_substates_control_1);
+    writeln!(f,
+        "\t\tL1SubCtl1: PCI-PM_L1.2{} PCI-PM_L1.1{} ASPM_L1.2{} ASPM_L1.1{}",
+        flag(val, 0x1), flag(val, 0x2), flag(val, 0x4), flag(val, 0x8),
+    )?;
+    if is_l1_2_capable {
+        write!(f, "\t\t\t   T_CommonMode={}us", (val >> 8) & 0xff)?;
+        if l & 0x4 != 0 {
+            let scale = val >> 29;
+            if scale > 5 {
+                write!(f, " LTR1.2_Threshold=<error>")?;
+            } else {
+                let threshold = u64::from((val >> 16) & 0x3ff) << (5 * scale);
+                write!(f, " LTR1.2_Threshold={}ns", threshold)?;
+            }
+        }
+        writeln!(f)?;
+    }
+    let val = dword(&l1pm.l1_pm_substates_control_2);
+    write!(f, "\t\tL1SubCtl2:")?;
+    if is_l1_2_capable {
+        match calc_time((val >> 3) & 0x1f, val & 0x3) {
+            Some(time) => write!(f, " T_PwrOn={}us", time)?,
+            None => write!(f, " T_PwrOn=<error>")?,
+        }
+    }
+    writeln!(f)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use pretty_assertions::assert_eq;
+    use crate::{DDR_OFFSET, DDR_LENGTH, ECS_OFFSET};
+    use super::*;
+
+    const DATA_2030: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/device/8086_2030/config"
+    ));
+
+    /// Normal header with capabilities list at `pointer`
+    fn header(pointer: u8) -> [u8; DDR_OFFSET] {
+        let mut data = [0; DDR_OFFSET];
+        data[..4].copy_from_slice(&[0x86, 0x80, 0xc8, 0x9d]);
+        data[0x04] = 0x06;
+        data[0x06] = 0x10;
+        data[0x08] = 0x21;
+        data[0x09] = 0x00;
+        data[0x0a] = 0x03;
+        data[0x0b] = 0x04;
+        data[0x10..0x14].copy_from_slice(&0xb4418004u32.to_le_bytes());
+        data[0x14..0x18].copy_from_slice(&0u32.to_le_bytes());
+        data[0x2c..0x30].copy_from_slice(&[0x43, 0x10, 0xa1, 0x16]);
+        data[0x34] = pointer;
+        data[0x3c] = 0xff;
+        data[0x3d] = 0x01;
+        data
+    }
+
+    /// Extended capabilities part of PCI Express Endpoint description
+    fn express_extended_capabilities(ecs: &[u8]) -> String {
+        let mut ddr = [0u8; DDR_LENGTH];
+        ddr[..4].copy_from_slice(&[0x10, 0x00, 0x02, 0x00]);
+        let header = Header::try_from(&header(0x40)[..]).unwrap();
+        let verbose = Verbose {
+            capabilities: Some(Capabilities::new(&ddr, 0x40)),
+            extended_capabilities: Some(ExtendedCapabilities::new(ecs)),
+            ..Verbose::new("00:17.0".parse().unwrap(), &header)
+        };
+        let result = verbose.to_string();
+        let (_, result) = result.split_once("\tCapabilities: [100").unwrap();
+        format!("\tCapabilities: [100{}", result)
+    }
+
+    #[test]
+    fn msi_pm_sata() {
+        // Capabilities list from capabilities module documentation
+        let mut ddr = [0u8; DDR_LENGTH];
+        ddr[0x30..0x38].copy_from_slice(&[0x01, 0xa8, 0x03, 0x40, 0x08, 0x00, 0x00, 0x00]);
+        ddr[0x40..0x4a].copy_from_slice(&[0x05, 0x70, 0x01, 0x00, 0x58, 0x03, 0xe0, 0xfe, 0x00, 0x00]);
+        ddr[0x68..0x70].copy_from_slice(&[0x12, 0x00, 0x10, 0x00, 0x48, 0x00, 0x00, 0x00]);
+        let header = Header::try_from(&header(0x80)[..]).unwrap();
+        let verbose = Verbose {
+            capabilities: Some(Capabilities::new(&ddr, 0x80)),
+            driver: Some("ahci"),
+            sizes: &[0x2000],
+            ..Verbose::new("00:17.0".parse().unwrap(), &header)
+        };
+        let sample = "\
+00:17.0 0403: 8086:9dc8 (rev 21)
+\tSubsystem: 1043:16a1
+\tControl: I/O- Mem+ BusMaster+ SpecCycle- MemWINV- VGASnoop- ParErr- Stepping- SERR- FastB2B- DisINTx-
+\tStatus: Cap+ 66MHz- UDF- FastB2B- ParErr- DEVSEL=fast >TAbort- <TAbort- <MAbort- >SERR- <PERR- INTx-
+\tLatency: 0
+\tInterrupt: pin A routed to IRQ 255
+\tRegion 0: Memory at b4418000 (64-bit, non-prefetchable) [size=8K]
+\tCapabilities: [80] MSI: Enable+ Count=1/1 Maskable- 64bit-
+\t\tAddress: fee00358  Data: 0000
+\tCapabilities: [70] Power Management version 3
+\t\tFlags: PMEClk- DSI- D1- D2- AuxCurrent=0mA PME(D0-,D1-,D2-,D3hot+,D3cold-)
+\t\tStatus: D0 NoSoftRst+ PME-Enable- DSel=0 DScale=0 PME-
+\tCapabilities: [a8] SATA HBA v1.0 BAR4 Offset=00000004
+\tKernel driver in use: ahci
+
+";
+        assert_eq!(sample, verbose.to_string());
+    }
+
+    #[test]
+    fn express_endpoint() {
+        // lspci -vv quote from pci_express module tests
+        let mut ddr = [0u8; DDR_LENGTH];
+        let cap = [
+            0x10,0x00,0x02,0x00,0xc2,0x8c,0x00,0x10,0x3e,0x20,0x09,0x00,0x43,0x5c,0x42,0x00,
+            0x40,0x00,0x43,0x10,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,
+            0x00,0x00,0x00,0x00,0x1f,0x08,0x00,0x00,0x06,0x00,0x00,0x00,0x0e,0x00,0x00,0x00,
+            0x01,0x00,0x1f,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,
+        ];
+        ddr[0x60..0x60 + cap.len()].copy_from_slice(&cap);
+        let header = Header::try_from(&header(0xa0)[..]).unwrap();
+        let verbose = Verbose {
+            capabilities: Some(Capabilities::new(&ddr, 0xa0)),
+            sizes: &[0x2000],
+            ..Verbose::new("01:00.0".parse().unwrap(), &header)
+        };
+        let sample = "\
+\tCapabilities: [a0] Express (v2) Endpoint, MSI 00
+\t\tDevCap:\tMaxPayload 512 bytes, PhantFunc 0, Latency L0s <512ns, L1 <64us
+\t\t\tExtTag- AttnBtn- AttnInd- PwrInd- RBE+ FLReset+ SlotPowerLimit 0.000W
+\t\tDevCtl:\tCorrErr- NonFatalErr+ FatalErr+ UnsupReq+
+\t\t\tRlxdOrd+ ExtTag- PhantFunc- AuxPwr- NoSnoop- FLReset-
+\t\t\tMaxPayload 256 bytes, MaxReadReq 512 bytes
+\t\tDevSta:\tCorrErr+ NonFatalErr- FatalErr- UnsupReq+ AuxPwr- TransPend-
+\t\tLnkCap:\tPort #0, Speed 8GT/s, Width x4, ASPM L0s L1, Exit Latency L0s <2us, L1 <16us
+\t\t\tClockPM- Surprise- LLActRep- BwNot- ASPMOptComp+
+\t\tLnkCtl:\tASPM Disabled; RCB 64 bytes Disabled- CommClk+
+\t\t\tExtSynch- ClockPM- AutWidDis- BWInt- AutBWInt-
+\t\tLnkSta:\tSpeed 8GT/s (ok), Width x4 (ok)
+\t\t\tTrErr- Train- SlotClk+ DLActive- BWMgmt- ABWMgmt-
+\t\tDevCap2: Completion Timeout: Range ABCD, TimeoutDis+, NROPrPrP-, LTR+
+\t\t\t 10BitTagComp-, 10BitTagReq-, OBFF Not Supported, ExtFmt-, EETLPPrefix-
+\t\t\t EmergencyPowerReduction Not Supported, EmergencyPowerReductionInit-
+\t\t\t FRS-, TPHComp-, ExtTPHComp-
+\t\t\t AtomicOpsCap: 32bit- 64bit- 128bitCAS-
+\t\tDevCtl2: Completion Timeout: 65ms to 210ms, TimeoutDis-, LTR-, OBFF Disabled
+\t\t\t AtomicOpsCtl: ReqEn-
+\t\tLnkCtl2: Target Link Speed: 2.5GT/s, EnterCompliance- SpeedDis-
+\t\t\t Transmit Margin: Normal Operating Range, EnterModifiedCompliance- ComplianceSOS-
+\t\t\t Compliance De-emphasis: -6dB
+\t\tLnkSta2: Current De-emphasis Level: -3.5dB, EqualizationComplete+, EqualizationPhase1+
+\t\t\t EqualizationPhase2+, EqualizationPhase3+, LinkEqualizationRequest-
+
+";
+        let result = verbose.to_string();
+        let (_, result) = result.split_once("\tCapabilities: ").unwrap();
+        assert_eq!(sample, format!("\tCapabilities: {}", result));
+
+        // Other functions of multi-function endpoint have no Link Control 2
+        let verbose = Verbose { address: "01:00.1".parse().unwrap(), ..verbose };
+        assert!(!verbose.to_string().contains("LnkCtl2"));
+        // AtomicOp completer capabilities are shown only for functions with memory space
+        let verbose = Verbose { sizes: &[], ..verbose };
+        assert!(!verbose.to_string().contains("AtomicOpsCap"));
+    }
+
+    #[test]
+    fn extended_capabilities() {
+        let mut ecs = [0u8; 0x100];
+        // L1 PM Substates and Secondary PCI Express from extended capabilities modules tests
+        ecs[0x00..0x10].copy_from_slice(&[
+            0x1e,0x00,0x01,0x11,0x1f,0xff,0x28,0x00,0x03,0x00,0x32,0x40,0xb0,0x00,0x00,0x00,
+        ]);
+        ecs[0x10..0x1c].copy_from_slice(&[
+            0x19,0x00,0x01,0x00,0x00,0x00,0x00,0x00,0xfb,0x0c,0x00,0x00,
+        ]);
+        let mut ddr = [0u8; DDR_LENGTH];
+        ddr[..4].copy_from_slice(&[0x10, 0x00, 0x02, 0x00]);
+        let header = Header::try_from(&header(0x40)[..]).unwrap();
+        let verbose = Verbose {
+            capabilities: Some(Capabilities::new(&ddr, 0x40)),
+            extended_capabilities: Some(ExtendedCapabilities::new(&ecs)),
+            ..Verbose::new("00:17.0".parse().unwrap(), &header)
+        };
+        let sample = "\
+\tCapabilities: [100 v1] L1 PM Substates
+\t\tL1SubCap: PCI-PM_L1.2+ PCI-PM_L1.1+ ASPM_L1.2+ ASPM_L1.1+ L1_PM_Substates+
+\t\t\t  PortCommonModeRestoreTime=255us PortTPowerOnTime=10us
+\t\tL1SubCtl1: PCI-PM_L1.2+ PCI-PM_L1.1+ ASPM_L1.2- ASPM_L1.1-
+\t\t\t   T_CommonMode=0us LTR1.2_Threshold=51200ns
+\t\tL1SubCtl2: T_PwrOn=44us
+\tCapabilities: [110 v1] Secondary PCI Express
+\t\tLnkCtl3: LnkEquIntrruptEn-, PerformEqu-
+\t\tLaneErrStat: LaneErr at lane: 0 1 3 4 5 6 7 10 11
+
+";
+        let result = verbose.to_string();
+        let (_, result) = result.split_once("\tCapabilities: [100").unwrap();
+        assert_eq!(sample, format!("\tCapabilities: [100{}", result));
+
+        // Next pointer of Secondary PCI Express refers to L1 PM Substates
+        let mut looped = ecs;
+        looped[0x13] = 0x10;
+        let verbose = Verbose {
+            extended_capabilities: Some(ExtendedCapabilities::new(&looped)),
+            ..verbose
+        };
+        assert!(verbose.to_string().ends_with("\tCapabilities: [100 v1] <chain looped>\n\n"));
+    }
+
+    #[test]
+    fn resizable_bar() {
+        // Resizable BAR module tests data
+        let mut ecs = [0u8; 0x100];
+        ecs[..4].copy_from_slice(&[0x15, 0x00, 0x01, 0x00]);
+        ecs[4..20].copy_from_slice(&[
+            0x00, 0xf0, 0x07, 0x00, 0x40, 0x08, 0x00, 0x00,
+            0x00, 0x02, 0x00, 0x00, 0x02, 0x05, 0x02, 0x00,
+        ]);
+        let sample = "\
+\tCapabilities: [100 v1] Physical Resizable BAR
+\t\tBAR 0: current size: 256MB, supported: 256MB 512MB 1GB 2GB 4GB 8GB 16GB
+\t\tBAR 2: current size: 32MB, supported: 32MB 512TB
+
+";
+        assert_eq!(sample, express_extended_capabilities(&ecs));
+        // Same entries in VF Resizable BAR
+        ecs[0] = 0x24;
+        let result = express_extended_capabilities(&ecs);
+        assert!(result.starts_with("\tCapabilities: [100 v1] Virtual Resizable BAR\n\t\tBAR 0:"));
+        ecs[0] = 0x15;
+        // Number of Resizable BARs is out of range
+        ecs[8] = 0xe0;
+        let sample = "\
+\tCapabilities: [100 v1] Physical Resizable BAR
+\t\t<error in resizable BAR: BAR count=7>
+
+";
+        assert_eq!(sample, express_extended_capabilities(&ecs));
+    }
+
+    #[test]
+    fn root_complex_link() {
+        // Root Complex Link Declaration module tests data followed by invalid link
+        let mut ecs = [0u8; 0x100];
+        ecs[..4].copy_from_slice(&[0x05, 0x00, 0x01, 0x00]);
+        ecs[4..0x40].copy_from_slice(&[
+            0x00, 0x03, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x90, 0xd1, 0xfe, 0x00, 0x00, 0x00, 0x00,
+            0x03, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0xf0, 0x0f, 0xe0, 0x00, 0x00, 0x00, 0x00,
+            0x04, 0x00, 0x03, 0x01, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0xa0, 0xd1, 0xfe, 0x00, 0x00, 0x00, 0x00,
+        ]);
+        let sample = "\
+\tCapabilities: [100 v1] Root Complex Link
+\t\tDesc:\tPortNumber=02 ComponentID=01 EltType=Config
+\t\tLink0:\tDesc:\tTargetPort=00 TargetComponent=01 AssocRCRB- LinkType=MemMappedRCRB LinkValid+
+\t\t\tAddr:\t00000000fed19000
+\t\tLink1:\tDesc:\tTargetPort=00 TargetComponent=02 AssocRCRB- LinkType=Config LinkValid+
+\t\t\tAddr:\t00:1f.7  CfgSpace=00000000e00ff000
+\t\tLink2:\tDesc:\tTargetPort=01 TargetComponent=03 AssocRCRB+ LinkType=MemMappedRCRB LinkValid-
+
+";
+        assert_eq!(sample, express_extended_capabilities(&ecs));
+    }
+
+    #[test]
+    fn root_complex_event_collector() {
+        let mut ecs = [0u8; 0x100];
+        ecs[..12].copy_from_slice(&[
+            0x07, 0x00, 0x02, 0x00, 0x07, 0x01, 0x00, 0x80, 0x00, 0xe1, 0xe4, 0x00,
+        ]);
+        let sample = "\
+\tCapabilities: [100 v2] Root Complex Event Collector Endpoint Association
+\t\tRCiEPBitmap: RCiEP at Device(s): 0-2, 8, 31
+\t\tAssociatedBusNumbers: e1-e4
+
+";
+        assert_eq!(sample, express_extended_capabilities(&ecs));
+        // Version 1 without bus numbers
+        ecs[2] = 0x01;
+        ecs[4..8].copy_from_slice(&[0x00; 4]);
+        let sample = "\
+\tCapabilities: [100 v1] Root Complex Event Collector Endpoint Association
+\t\tRCiEPBitmap: [none]
+
+";
+        assert_eq!(sample, express_extended_capabilities(&ecs));
+    }
+
+    #[test]
+    fn designated_vendor_specific() {
+        let mut ecs = [0u8; 0x100];
+        // PCIe DVSEC for CXL Devices followed by unknown DVSEC
+        ecs[..0x0e].copy_from_slice(&[
+            0x23, 0x00, 0x01, 0x12, 0x98, 0x1e, 0x81, 0x03, 0x00, 0x00, 0x1e, 0x00, 0x06, 0x00,
+        ]);
+        ecs[0x20..0x2a].copy_from_slice(&[
+            0x23, 0x00, 0x01, 0x00, 0x86, 0x80, 0xa1, 0x00, 0x05, 0x00,
+        ]);
+        let sample = "\
+\tCapabilities: [100 v1] Designated Vendor-Specific: Vendor=1e98 ID=0000 Rev=1 Len=56: CXL
+\t\tCXLCap:\tCache- IO+ Mem+ Mem HW Init+ HDMCount 1 Viral-
+\t\tCXLCtl:\tCache- IO+ Mem+ CacheSFCov 0 CacheSFGran 0 CacheClean- Viral-
+\tCapabilities: [120 v1] Designated Vendor-Specific: Vendor=8086 ID=0005 Rev=1 Len=10 <?>
+
+";
+        assert_eq!(sample, express_extended_capabilities(&ecs));
+    }
+
+    #[test]
+    fn root_port() {
+        let header = Header::try_from(&DATA_2030[..DDR_OFFSET]).unwrap();
+        let verbose = Verbose {
+            capabilities: Some(Capabilities::new(
+                &DATA_2030[DDR_OFFSET..ECS_OFFSET], header.capabilities_pointer
+            )),
+            extended_capabilities: Some(ExtendedCapabilities::new(&DATA_2030[ECS_OFFSET..])),
+            driver: Some("pcieport"),
+            ..Verbose::new("00:03.0".parse().unwrap(), &header)
+        };
+        let result = verbose.to_string();
+        let titles = result.lines()
+            .filter_map(|line| line.strip_prefix("\tCapabilities: "))
+            .collect::<Vec<_>>();
+        let sample = vec![
+            "[40] Subsystem: 8086:0000",
+            "[60] MSI: Enable+ Count=1/2 Maskable+ 64bit-",
+            "[90] Express (v2) Root Port (Slot+), MSI 00",
+            "[e0] Power Management version 3",
+            "[100 v1] Vendor Specific Information: ID=0002 Rev=0 Len=00c <?>",
+            "[110 v1] Access Control Services",
+            "[148 v1] Advanced Error Reporting",
+            "[1d0 v1] Vendor Specific Information: ID=0003 Rev=1 Len=00a <?>",
+            "[250 v1] Secondary PCI Express",
+            "[280 v1] Vendor Specific Information: ID=0005 Rev=3 Len=018 <?>",
+            "[298 v1] Vendor Specific Information: ID=0007 Rev=0 Len=024 <?>",
+            "[300 v1] Vendor Specific Information: ID=0008 Rev=0 Len=038 <?>",
+        ];
+        assert_eq!(sample, titles);
+        assert!(result.starts_with("00:03.0 0604: 8086:2030 (rev 04)\n\tControl: "));
+        assert!(result.contains("\n\tBus: primary=ae, secondary=af, subordinate=af, sec-latency=0\n"));
+        assert!(result.contains("\n\t\tRootCmd: CERptEn- NFERptEn- FERptEn-\n"));
+        assert!(result.ends_with("\tKernel driver in use: pcieport\n\n"));
+    }
+
+    #[test]
+    fn agp() {
+        let mut ddr = [0u8; DDR_LENGTH];
+        ddr[0x18..0x22].copy_from_slice(&[0x02, 0x68, 0x20, 0x00, 0x17, 0x02, 0x00, 0x1f, 0x04, 0x03]);
+        ddr[0x28..0x34].copy_from_slice(&[0x0e, 0x00, 0x30, 0x00, 0x0b, 0x02, 0x00, 0x1f, 0x02, 0x01, 0x00, 0x1f]);
+        let header = Header::try_from(&header(0x58)[..]).unwrap();
+        let verbose = Verbose {
+            capabilities: Some(Capabilities::new(&ddr, 0x58)),
+            ..Verbose::new("01:00.0".parse().unwrap(), &header)
+        };
+        let sample = "\
+\tCapabilities: [58] AGP version 2.0
+\t\tStatus: RQ=32 Iso- ArqSz=0 Cal=0 SBA+ ITACoh- GART64- HTrans- 64bit- FW+ AGP3- Rate=x1,x2,x4
+\t\tCommand: RQ=1 ArqSz=0 Cal=0 SBA+ AGP+ GART64- 64bit- FW- Rate=x4
+\tCapabilities: [68] AGP3 <?>
+
+";
+        let result = verbose.to_string();
+        assert!(result.ends_with(sample), "{}", result);
+    }
+
+    #[test]
+    fn pci_x_bridge() {
+        let mut ddr = [0u8; DDR_LENGTH];
+        ddr[0x98..0xa8].copy_from_slice(&[
+            0x07, 0x00, 0xc3, 0x00, 0xe0, 0x00, 0x03, 0x00, 0xff, 0xff, 0xff, 0xff, 0x40, 0x00, 0x80, 0x00,
+        ]);
+        let mut data = DATA_2030[..DDR_OFFSET].to_vec();
+        data[0x34] = 0xd8;
+        let header = Header::try_from(&data[..]).unwrap();
+        let verbose = Verbose {
+            capabilities: Some(Capabilities::new(&ddr, 0xd8)),
+            ..Verbose::new("00:1c.0".parse().unwrap(), &header)
+        };
+        let sample = "\
+\tCapabilities: [d8] PCI-X bridge device
+\t\tSecondary Status: 64bit+ 133MHz+ SCD- USC- SCO- SRD- Freq=133MHz
+\t\tStatus: Dev=00:1c.0 64bit+ 133MHz+ SCD- USC- SCO- SRD-
+\t\tUpstream: Capacity=65535 CommitmentLimit=65535
+\t\tDownstream: Capacity=64 CommitmentLimit=128
+";
+        let result = verbose.to_string();
+        assert!(result.contains(sample), "{}", result);
+    }
+
+    #[test]
+    fn enhanced_allocation() {
+        let mut ddr = [0u8; DDR_LENGTH];
+        ddr[0x40..0x68].copy_from_slice(&[
+            0x14, 0x00, 0x02, 0x00,
+            0x03, 0x00, 0xff, 0x80, 0x02, 0x00, 0x00, 0x72, 0xfc, 0xff, 0x0f, 0x00, 0x08, 0x00, 0x00, 0x00,
+            0x94, 0x04, 0xff, 0x80, 0x02, 0x00, 0x00, 0x40, 0xfe, 0xff, 0x1f, 0x00, 0x08, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+        ]);
+        let mut data = DATA_2030[..DDR_OFFSET].to_vec();
+        data[0x0e] = 0x00;
+        data[0x34] = 0x80;
+        let header = Header::try_from(&data[..]).unwrap();
+        let verbose = Verbose {
+            capabilities: Some(Capabilities::new(&ddr, 0x80)),
+            ..Verbose::new("00:1f.3".parse().unwrap(), &header)
+        };
+        let sample = "\
+\tCapabilities: [80] Enhanced Allocation (EA): NumEntries=2
+\t\tEntry 0: Enable+ Writable- EntrySize=3
+\t\t\t BAR Equivalent Indicator: BAR 0
+\t\t\t PrimaryProperties: memory space, non-prefetchable
+\t\t\t SecondaryProperties: entry unavailable for use, PrimaryProperties should be used
+\t\t\t Base: 872000000
+\t\t\t MaxOffset: 000fffff
+\t\tEntry 1: Enable+ Writable- EntrySize=4
+\t\t\t BAR Equivalent Indicator: VF-BAR 0
+\t\t\t PrimaryProperties: VF memory space, non-prefetchable
+\t\t\t SecondaryProperties: entry unavailable for use, PrimaryProperties should be used
+\t\t\t Base: 840000000
+\t\t\t MaxOffset: 0001fffff
+";
+        let result = verbose.to_string();
+        assert!(result.contains(sample), "{}", result);
+    }
+
+    #[test]
+    fn undecodable_capability() {
+        // MSI capability does not fit into the configuration space
+        let mut ddr = [0u8; DDR_LENGTH];
+        ddr[0x30..0x38].copy_from_slice(&[0x01, 0x00, 0x03, 0x40, 0x08, 0x00, 0x00, 0x00]);
+        ddr[0xb8..0xbc].copy_from_slice(&[0x05, 0x70, 0x80, 0x00]);
+        let header = Header::try_from(&header(0xf8)[..]).unwrap();
+        let verbose = Verbose {
+            capabilities: Some(Capabilities::new(&ddr, 0xf8)),
+            ..Verbose::new("00:17.0".parse().unwrap(), &header)
+        };
+        let sample = "\
+\tCapabilities: [f8] #05 [0080]
+\tCapabilities: [70] Power Management version 3
+\t\tFlags: PMEClk- DSI- D1- D2- AuxCurrent=0mA PME(D0-,D1-,D2-,D3hot+,D3cold-)
+\t\tStatus: D0 NoSoftRst+ PME-Enable- DSel=0 DScale=0 PME-
+
+";
+        let result = verbose.to_string();
+        assert!(result.ends_with(sample), "{}", result);
+    }
+
+    #[test]
+    fn access_denied() {
+        let header = Header::try_from(&DATA_2030[..DDR_OFFSET]).unwrap();
+        let result = Verbose::new("0001:00:03.0".parse().unwrap(), &header).to_string();
+        assert!(result.starts_with("0001:00:03.0 0604: "));
+        assert!(result.ends_with("\tCapabilities: <access denied>\n\n"));
+    }
+
+    #[test]
+    fn size() {
+        struct Size(u64);
+        impl fmt::Display for Size {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt_size(f, self.0)
+            }
+        }
+        assert_eq!("", Size(0).to_string());
+        assert_eq!(" [size=256]", Size(256).to_string());
+        assert_eq!(" [size=1536K]", Size(1536 << 10).to_string());
+        assert_eq!(" [size=16G]", Size(16 << 30).to_string());
+    }
+}
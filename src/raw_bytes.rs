@@ -0,0 +1,113 @@
+//! Variable-length register blocks
+//!
+//! Capabilities borrow their variable-length register blocks from configuration space. With the
+//! `serde` feature a block may also be owned by a fixed-size buffer, so deserialized capabilities
+//! do not borrow from the input and no allocator is required.
+
+use core::{fmt, ops::Deref};
+
+#[cfg(feature = "serde")]
+use crate::ECS_LENGTH;
+
+
+/// Register block borrowed from configuration space or owned by a deserialized capability,
+/// dereferences to its bytes
+#[derive(Clone)]
+pub struct RawBytes<'a>(Inner<'a>);
+
+// Owned block is kept inline, there is no allocator to box it
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone)]
+enum Inner<'a> {
+    Borrowed(&'a [u8]),
+    /// Register block is never longer than extended configuration space
+    #[cfg(feature = "serde")]
+    Owned { data: [u8; ECS_LENGTH], len: usize },
+}
+
+impl<'a> RawBytes<'a> {
+    pub const fn new(data: &'a [u8]) -> Self {
+        Self(Inner::Borrowed(data))
+    }
+    /// Copy of `data`, `None` if it does not fit in extended configuration space
+    #[cfg(feature = "serde")]
+    pub fn owned(data: &[u8]) -> Option<Self> {
+        let mut buf = [0; ECS_LENGTH];
+        buf.get_mut(..data.len())?.copy_from_slice(data);
+        Some(Self(Inner::Owned { data: buf, len: data.len() }))
+    }
+}
+/// Empty register block
+impl<'a> Default for RawBytes<'a> {
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+impl<'a> Deref for RawBytes<'a> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        match &self.0 {
+            Inner::Borrowed(data) => data,
+            #[cfg(feature = "serde")]
+            Inner::Owned { data, len } => &data[..*len],
+        }
+    }
+}
+impl<'a> AsRef<[u8]> for RawBytes<'a> {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+impl<'a> From<&'a [u8]> for RawBytes<'a> {
+    fn from(data: &'a [u8]) -> Self {
+        Self::new(data)
+    }
+}
+impl<'a, const N: usize> From<&'a [u8; N]> for RawBytes<'a> {
+    fn from(data: &'a [u8; N]) -> Self {
+        Self::new(data)
+    }
+}
+impl<'a> fmt::Debug for RawBytes<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+impl<'a, 'b> PartialEq<RawBytes<'b>> for RawBytes<'a> {
+    fn eq(&self, other: &RawBytes<'b>) -> bool {
+        **self == **other
+    }
+}
+impl<'a> Eq for RawBytes<'a> {}
+impl<'a> PartialEq<[u8]> for RawBytes<'a> {
+    fn eq(&self, other: &[u8]) -> bool {
+        **self == *other
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use super::*;
+
+    #[test]
+    fn borrowed() {
+        let data = [0xaa, 0x55];
+        let raw = RawBytes::new(&data);
+        assert_eq!(&data[..], &*raw);
+        assert_eq!(RawBytes::from(&[0xaa, 0x55]), raw);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn owned() {
+        let data = [0xaa, 0x55];
+        let raw = RawBytes::owned(&data).unwrap();
+        assert_eq!(&data[..], &*raw);
+        assert_eq!(RawBytes::new(&data), raw);
+        assert!(RawBytes::owned(&[0; ECS_LENGTH + 1]).is_none());
+    }
+}
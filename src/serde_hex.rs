@@ -0,0 +1,282 @@
+//! Serialization of raw register blocks as hex strings
+//!
+//! Deserialized register blocks are [owned](RawBytes::owned), register tables serialized as
+//! decoded entries are collected into fixed-size [Seq]s, so no allocator is required.
+
+use core::{fmt, marker::PhantomData};
+
+use serde::{
+    de::{self, Deserialize, Deserializer, SeqAccess, Unexpected, Visitor},
+    Serialize,
+    Serializer,
+};
+
+use crate::{RawBytes, ECS_LENGTH};
+
+
+/// Serializes borrowed bytes as a lowercase hex string without separators
+pub(crate) fn serialize<T, S>(data: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: AsRef<[u8]> + ?Sized,
+    S: Serializer,
+{
+    serializer.collect_str(&Hex(data.as_ref()))
+}
+
+struct Hex<'a>(&'a [u8]);
+impl<'a> fmt::Display for Hex<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+    }
+}
+
+impl<'a> Serialize for RawBytes<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self, serializer)
+    }
+}
+/// Hex string is case insensitive
+impl<'de, 'a> Deserialize<'de> for RawBytes<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(HexVisitor)
+    }
+}
+
+struct HexVisitor;
+impl<'de> Visitor<'de> for HexVisitor {
+    type Value = RawBytes<'static>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a hex string of at most {} bytes", ECS_LENGTH)
+    }
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        let mut buf = [0; ECS_LENGTH];
+        if !v.len().is_multiple_of(2) || v.len() / 2 > buf.len() {
+            return Err(E::invalid_length(v.len(), &self));
+        }
+        let nibble = |c: u8| char::from(c).to_digit(16)
+            .ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self));
+        for (byte, pair) in buf.iter_mut().zip(v.as_bytes().chunks(2)) {
+            *byte = (nibble(pair[0])? << 4 | nibble(pair[1])?) as u8;
+        }
+        RawBytes::owned(&buf[..v.len() / 2]).ok_or_else(|| E::invalid_length(v.len(), &self))
+    }
+}
+
+/// Up to `N` entries of a register table serialized as a sequence of decoded entries
+pub(crate) struct Seq<T, const N: usize>([Option<T>; N]);
+impl<T, const N: usize> Seq<T, N> {
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &T> {
+        self.0.iter().map_while(Option::as_ref)
+    }
+}
+impl<'de, T: Deserialize<'de>, const N: usize> Deserialize<'de> for Seq<T, N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(SeqVisitor(PhantomData))
+    }
+}
+
+struct SeqVisitor<T, const N: usize>(PhantomData<T>);
+impl<'de, T: Deserialize<'de>, const N: usize> Visitor<'de> for SeqVisitor<T, N> {
+    type Value = Seq<T, N>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a sequence of at most {} entries", N)
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut entries = core::array::from_fn(|_| None);
+        for entry in entries.iter_mut() {
+            match seq.next_element()? {
+                Some(value) => *entry = Some(value),
+                None => return Ok(Seq(entries)),
+            }
+        }
+        if seq.next_element::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(N + 1, &self));
+        }
+        Ok(Seq(entries))
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use crate::{
+        DDR_OFFSET, ECS_OFFSET, Header, Capabilities, ExtendedCapabilities,
+        capabilities::{
+            CapabilityKind,
+            pci_express::LinkSpeed,
+            power_management_interface::PowerState,
+        },
+        extended_capabilities::ExtendedCapabilityKind,
+    };
+
+    const DATA_2030: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/device/8086_2030/config"
+    ));
+    const DATA_9DC8: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/device/8086_9dc8/config"
+    ));
+
+    #[test]
+    fn header_round_trip() {
+        let header = Header::try_from(&DATA_2030[..DDR_OFFSET]).unwrap();
+        let value = serde_json::to_value(&header).unwrap();
+        assert_eq!(json!(0x8086), value["vendor_id"]);
+        assert_eq!(json!(0x2030), value["device_id"]);
+        let result: Header = serde_json::from_value(value).unwrap();
+        assert_eq!(header, result);
+    }
+
+    #[test]
+    fn capabilities_round_trip() {
+        let header = Header::try_from(&DATA_2030[..DDR_OFFSET]).unwrap();
+        let caps = Capabilities::new(&DATA_2030[DDR_OFFSET..ECS_OFFSET], header.capabilities_pointer);
+        for cap in caps {
+            let json = serde_json::to_string(&cap).unwrap();
+            let result: crate::capabilities::Capability = serde_json::from_str(&json).unwrap();
+            assert_eq!(cap, result, "{}", json);
+        }
+    }
+
+    #[test]
+    fn enum_strings() {
+        assert_eq!("\"Rate8GTps\"", serde_json::to_string(&LinkSpeed::Rate8GTps).unwrap());
+        assert_eq!("\"D3Hot\"", serde_json::to_string(&PowerState::D3Hot).unwrap());
+        let result: LinkSpeed = serde_json::from_str("\"Rate16GTps\"").unwrap();
+        assert_eq!(LinkSpeed::Rate16GTps, result);
+    }
+
+    #[test]
+    fn borrowed_slices_as_hex() {
+        let vs = CapabilityKind::VendorSpecific(crate::capabilities::VendorSpecific::new(&[0x0c, 0xab, 0x01]));
+        assert_eq!(json!({ "VendorSpecific": "0cab01" }), serde_json::to_value(&vs).unwrap());
+
+        let vsec = ExtendedCapabilities::new(&DATA_2030[ECS_OFFSET..])
+            .find_map(|ecap| match ecap.kind {
+                kind @ ExtendedCapabilityKind::VendorSpecificExtendedCapability(_) => Some(kind),
+                _ => None,
+            })
+            .unwrap();
+        let value = serde_json::to_value(&vsec).unwrap();
+        let vsec = &value["VendorSpecificExtendedCapability"];
+        assert_eq!(json!(2), vsec["header"]["vsec_id"]);
+        assert_eq!(json!("07380000"), vsec["registers"]);
+    }
+
+    #[test]
+    fn tables_are_decoded() {
+        use byte::{BytesExt, LE};
+        use crate::extended_capabilities::{AccessControlServices, TphRequester, VirtualChannel};
+        // Trailing bytes belong to the next capability and should not be serialized
+        let data = [0x20, 0x08, 0x00, 0x00, 0x05, 0xaa, 0xbb, 0xcc, 0xdd];
+        let acs: AccessControlServices = data.read_with(&mut 0, LE).unwrap();
+        let value = serde_json::to_value(&acs).unwrap();
+        assert_eq!(
+            json!([true, false, true, false, false, false, false, false]),
+            value["egress_control_vectors"]
+        );
+        assert_eq!(None, value.get("data"));
+
+        let data = [0x00, 0x02, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x11, 0x22, 0x33, 0x44, 0xaa, 0xbb];
+        let tphr: TphRequester = data.read_with(&mut 0, LE).unwrap();
+        let value = serde_json::to_value(&tphr).unwrap();
+        let sample = json!([
+            { "st_lower": 0x11, "st_upper": 0x22 },
+            { "st_lower": 0x33, "st_upper": 0x44 },
+        ]);
+        assert_eq!(sample, value["tph_st_table"]);
+
+        let mut data = [0u8; 0x20];
+        data[0x0c..0x10].copy_from_slice(&[0xaa, 0xbb, 0xcc, 0xdd]);
+        let vc: VirtualChannel = data.read_with(&mut 0, LE).unwrap();
+        let value = serde_json::to_value(&vc).unwrap();
+        assert_eq!(1, value["extended_virtual_channels"].as_array().unwrap().len());
+        assert_eq!(json!([]), value["vc_arbitration_table"]);
+        assert_eq!(json!([[]]), value["port_arbitration_tables"]);
+        assert_eq!(None, value.get("data"));
+    }
+
+    #[test]
+    fn tables_round_trip() {
+        use byte::{BytesExt, LE};
+        use crate::extended_capabilities::{AccessControlServices, TphRequester, VirtualChannel};
+        let data = [0x20, 0x08, 0x00, 0x00, 0x05, 0xaa, 0xbb, 0xcc, 0xdd];
+        let acs: AccessControlServices = data.read_with(&mut 0, LE).unwrap();
+        let result: AccessControlServices = serde_json::from_value(serde_json::to_value(&acs).unwrap()).unwrap();
+        assert_eq!(acs, result);
+        let mut buf = [0u8; 8];
+        buf.write_with(&mut 0, result, LE).unwrap();
+        // Bits above Egress Control Vector Size are not kept
+        assert_eq!([0x20, 0x08, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00], buf);
+
+        let data = [0x00, 0x02, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x11, 0x22, 0x33, 0x44, 0xaa, 0xbb];
+        let tphr: TphRequester = data.read_with(&mut 0, LE).unwrap();
+        let result: TphRequester = serde_json::from_value(serde_json::to_value(&tphr).unwrap()).unwrap();
+        assert_eq!(tphr, result);
+
+        let data = [
+            // Port VC Capability 1: Port Arbitration Table Entry Size 4 bits
+            0x00, 0x08, 0x00, 0x00,
+            // Port VC Capability 2: VC Arbitration Table Offset 02h
+            0x00, 0x00, 0x00, 0x02,
+            // Port VC Control: WRR 32 phases, Port VC Status
+            0x02, 0x00, 0x00, 0x00,
+            // VC0: Port Arbitration Table Offset 03h, WRR 32 phases
+            0x02, 0x00, 0x00, 0x03, 0xff, 0x00, 0x02, 0x80, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            // VC Arbitration Table
+            0x10, 0x01, 0x10, 0x01, 0x10, 0x01, 0x10, 0x01, 0x10, 0x01, 0x10, 0x01, 0x10, 0x01, 0x10, 0x01,
+            // Port Arbitration Table
+            0x10, 0x32, 0x10, 0x32, 0x10, 0x32, 0x10, 0x32, 0x10, 0x32, 0x10, 0x32, 0x10, 0x32, 0x10, 0x32,
+            // Next capability
+            0xaa, 0xbb,
+        ];
+        let vc: VirtualChannel = data.read_with(&mut 0, LE).unwrap();
+        let kind = ExtendedCapabilityKind::VirtualChannel(vc);
+        let value = serde_json::to_value(&kind).unwrap();
+        assert_eq!(32, value["VirtualChannel"]["port_arbitration_tables"][0].as_array().unwrap().len());
+        let result: ExtendedCapabilityKind = serde_json::from_value(value).unwrap();
+        assert_eq!(kind, result);
+        let mut buf = [0u8; 0x3c];
+        let vc = match result {
+            ExtendedCapabilityKind::VirtualChannel(vc) => vc,
+            other => std::panic!("{:?}", other),
+        };
+        buf.write_with(&mut 0, vc, LE).unwrap();
+        assert_eq!(data[..0x3c], buf);
+    }
+
+    #[test]
+    fn borrowed_variants_round_trip() {
+        let result: CapabilityKind = serde_json::from_str(r#"{ "VendorSpecific": "0cAB01" }"#).unwrap();
+        let sample = CapabilityKind::VendorSpecific(crate::capabilities::VendorSpecific::new(&[0x0c, 0xab, 0x01]));
+        assert_eq!(sample, result);
+        assert!(serde_json::from_str::<CapabilityKind>(r#"{ "VendorSpecific": "0cab0" }"#).is_err());
+        assert!(serde_json::from_str::<CapabilityKind>(r#"{ "VendorSpecific": "0cab0x" }"#).is_err());
+
+        for data in [DATA_2030, DATA_9DC8] {
+            let header = Header::try_from(&data[..DDR_OFFSET]).unwrap();
+            let caps = Capabilities::new(&data[DDR_OFFSET..ECS_OFFSET], header.capabilities_pointer);
+            let ecaps = ExtendedCapabilities::new(data.get(ECS_OFFSET..).unwrap_or_default());
+            for cap in caps {
+                let json = serde_json::to_string(&cap).unwrap();
+                let result: crate::capabilities::Capability = serde_json::from_str(&json).unwrap();
+                assert_eq!(cap, result, "{}", json);
+            }
+            for ecap in ecaps {
+                let json = serde_json::to_string(&ecap).unwrap();
+                let result: crate::extended_capabilities::ExtendedCapability =
+                    serde_json::from_str(&json).unwrap();
+                assert_eq!(ecap, result, "{}", json);
+                assert_eq!(json, serde_json::to_string(&result).unwrap());
+            }
+        }
+    }
+}
@@ -0,0 +1,314 @@
+/*!
+Linux sysfs backend
+
+Enumerates PCI devices exposed by Linux in `/sys/bus/pci/devices`. Each device directory is named
+after the device [Address] and contains `config` (64, 256 or 4096 bytes depending on privileges
+and device type), `resource`, `vendor`, `device` files and an optional `driver` link.
+
+Available with `std` feature only.
+
+```no_run
+# use pcics::sysfs::Sysfs;
+for device in Sysfs::new().devices().unwrap() {
+    let (address, header, caps, ecaps) = device.parse().unwrap();
+    println!("{} {:04x}:{:04x} {:?}", address, header.vendor_id, header.device_id, device.driver);
+    for cap in caps {
+        println!("  [{:02x}] {:02x}", cap.pointer, cap.kind.id());
+    }
+    for ecap in ecaps {
+        println!("  [{:03x}] {:04x}", ecap.offset, ecap.id());
+    }
+}
+```
+*/
+
+use std::{
+    fs,
+    io,
+    path::{Path, PathBuf},
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use super::{
+    DDR_OFFSET, ECS_OFFSET, Error,
+    address::Address,
+    header::{Header, HEADER_TYPE_OFFSET},
+    capabilities::Capabilities,
+    extended_capabilities::ExtendedCapabilities,
+};
+
+
+/// sysfs devices tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sysfs {
+    root: PathBuf,
+}
+impl Sysfs {
+    pub const DEFAULT_ROOT: &'static str = "/sys/bus/pci/devices";
+    pub fn new() -> Self {
+        Self::with_root(Self::DEFAULT_ROOT)
+    }
+    /// Devices directory other than [DEFAULT_ROOT](Self::DEFAULT_ROOT)
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+    /// All readable devices sorted by address. Entries with names that are not PCI addresses and
+    /// devices which files can not be read (e.g. removed while enumerating) are skipped, use
+    /// [device](Self::device) to get the reason.
+    pub fn devices(&self) -> io::Result<Vec<SysfsDevice>> {
+        let mut devices = Vec::new();
+        for entry in fs::read_dir(&self.root)? {
+            let entry = entry?;
+            let address = entry.file_name().to_str().and_then(|name| name.parse().ok());
+            let device = address.and_then(|address| SysfsDevice::read(address, &entry.path()).ok());
+            devices.extend(device);
+        }
+        devices.sort_by_key(|device| device.address);
+        Ok(devices)
+    }
+    pub fn device(&self, address: Address) -> io::Result<SysfsDevice> {
+        SysfsDevice::read(address, &self.root.join(address.to_string()))
+    }
+}
+impl Default for Sysfs {
+    fn default() -> Self { Self::new() }
+}
+
+/// Device files content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SysfsDevice {
+    pub address: Address,
+    pub path: PathBuf,
+    /// Raw configuration space
+    pub config: Vec<u8>,
+    /// Regions from `resource` file
+    pub resources: Vec<Resource>,
+    pub vendor_id: u16,
+    pub device_id: u16,
+    /// Bound kernel driver name
+    pub driver: Option<String>,
+}
+impl SysfsDevice {
+    pub fn read(address: Address, path: &Path) -> io::Result<Self> {
+        let config = fs::read(path.join("config"))?;
+        let resources = fs::read_to_string(path.join("resource"))?
+            .lines()
+            .map(str::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        let vendor_id = read_hex_file(&path.join("vendor"))?;
+        let device_id = read_hex_file(&path.join("device"))?;
+        let driver = match fs::read_link(path.join("driver")) {
+            Ok(link) => link.file_name().and_then(|name| name.to_str()).map(String::from),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        Ok(Self {
+            address,
+            path: path.to_path_buf(),
+            config,
+            resources,
+            vendor_id,
+            device_id,
+            driver,
+        })
+    }
+    pub fn header(&self) -> Result<Header, Error> {
+        let length = self.config.get(HEADER_TYPE_OFFSET).map_or(DDR_OFFSET, |&ht| Header::length(ht));
+        Header::try_from(self.config.get(..length).unwrap_or(&self.config))
+    }
+    /// Empty if configuration space is not readable beyond header
+    pub fn capabilities(&self) -> Result<Capabilities<'_>, Error> {
+        let header = self.header()?;
+        let end = self.config.len().min(ECS_OFFSET);
+        let data = self.config.get(DDR_OFFSET..end).unwrap_or_default();
+        Ok(Capabilities::new(data, header.capabilities_pointer).with_header_type(&header.header_type))
+    }
+    /// Empty if extended configuration space is not readable
+    pub fn extended_capabilities(&self) -> ExtendedCapabilities<'_> {
+        ExtendedCapabilities::new(self.config.get(ECS_OFFSET..).unwrap_or_default())
+    }
+    pub fn parse(&self) -> Result<(Address, Header, Capabilities<'_>, ExtendedCapabilities<'_>), Error> {
+        Ok((self.address, self.header()?, self.capabilities()?, self.extended_capabilities()))
+    }
+}
+
+/// Line of `resource` file: start, end and flags of a region
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Resource {
+    pub start: u64,
+    pub end: u64,
+    /// Kernel IORESOURCE_* flags
+    pub flags: u64,
+}
+impl Resource {
+    /// Unused regions have all fields zero
+    pub fn is_empty(&self) -> bool {
+        self.start == 0 && self.end == 0
+    }
+    /// Zero for empty regions and regions ending below start
+    pub fn size(&self) -> u64 {
+        if self.is_empty() {
+            0
+        } else {
+            self.end.checked_sub(self.start).map_or(0, |len| len.saturating_add(1))
+        }
+    }
+}
+impl core::str::FromStr for Resource {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.split_whitespace().map(parse_hex);
+        let mut next = || fields.next()
+            .unwrap_or_else(|| Err(invalid_data("resource line has less than 3 fields")));
+        let resource = Self { start: next()?, end: next()?, flags: next()? };
+        if resource.end < resource.start {
+            return Err(invalid_data("resource end is below start"));
+        }
+        Ok(resource)
+    }
+}
+
+fn read_hex_file<T: TryFrom<u64>>(path: &Path) -> io::Result<T> {
+    let value = parse_hex(fs::read_to_string(path)?.trim())?;
+    T::try_from(value).map_err(|_| invalid_data("value is out of range"))
+}
+
+fn parse_hex(s: &str) -> io::Result<u64> {
+    let digits = s.strip_prefix("0x").unwrap_or(s);
+    u64::from_str_radix(digits, 16).map_err(|_| invalid_data("not a hex number"))
+}
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process, format};
+    use pretty_assertions::assert_eq;
+    use super::*;
+
+    const DATA_2030: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/device/8086_2030/config"
+    ));
+    const DATA_9DC8: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/device/8086_9dc8/config"
+    ));
+
+    /// Temporary sysfs-like tree removed on drop
+    struct FakeSysfs(PathBuf);
+    impl FakeSysfs {
+        fn new(name: &str) -> Self {
+            let root = env::temp_dir().join(format!("pcics-{}-{}", name, process::id()));
+            let _ = fs::remove_dir_all(&root);
+            fs::create_dir_all(root.join("drivers/pcieport")).unwrap();
+            fs::create_dir_all(root.join("devices")).unwrap();
+            Self(root)
+        }
+        fn devices(&self) -> PathBuf {
+            self.0.join("devices")
+        }
+        fn add(&self, address: &str, config: &[u8], driver: Option<&str>) {
+            let path = self.devices().join(address);
+            fs::create_dir(&path).unwrap();
+            fs::write(path.join("config"), config).unwrap();
+            fs::write(path.join("vendor"), format!("0x{:02x}{:02x}\n", config[1], config[0])).unwrap();
+            fs::write(path.join("device"), format!("0x{:02x}{:02x}\n", config[3], config[2])).unwrap();
+            fs::write(path.join("resource"), concat!(
+                "0x00000000fd000000 0x00000000fdffffff 0x0000000000040200\n",
+                "0x0000000000000000 0x0000000000000000 0x0000000000000000\n",
+            )).unwrap();
+            if let Some(driver) = driver {
+                std::os::unix::fs::symlink(self.0.join("drivers").join(driver), path.join("driver"))
+                    .unwrap();
+            }
+        }
+    }
+    impl Drop for FakeSysfs {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn devices() {
+        let fake = FakeSysfs::new("devices");
+        fake.add("0000:00:1f.3", DATA_9DC8, None);
+        fake.add("0000:00:03.0", DATA_2030, Some("pcieport"));
+        // Unprivileged users can read only the header
+        fake.add("0000:01:00.0", &DATA_2030[..DDR_OFFSET], None);
+        fs::create_dir(fake.devices().join("not-a-device")).unwrap();
+        // Device removed while enumerating
+        fake.add("0000:02:00.0", DATA_2030, None);
+        fs::remove_file(fake.devices().join("0000:02:00.0/resource")).unwrap();
+
+        let devices = Sysfs::with_root(fake.devices()).devices().unwrap();
+        let result = devices.iter()
+            .map(|d| (d.address.to_string(), d.vendor_id, d.device_id, d.driver.clone()))
+            .collect::<Vec<_>>();
+        let sample = vec![
+            ("0000:00:03.0".into(), 0x8086, 0x2030, Some("pcieport".into())),
+            ("0000:00:1f.3".into(), 0x8086, 0x9dc8, None),
+            ("0000:01:00.0".into(), 0x8086, 0x2030, None),
+        ];
+        assert_eq!(sample, result);
+
+        let resource = Resource { start: 0xfd000000, end: 0xfdffffff, flags: 0x40200 };
+        assert_eq!(vec![resource, Resource { start: 0, end: 0, flags: 0 }], devices[0].resources);
+        assert_eq!(0x1000000, resource.size());
+
+        let (address, header, caps, ecaps) = devices[0].parse().unwrap();
+        assert_eq!("0000:00:03.0".parse(), Ok(address));
+        assert_eq!(Header::try_from(&DATA_2030[..DDR_OFFSET]).unwrap(), header);
+        assert_eq!(4, caps.count());
+        assert_eq!(8, ecaps.count());
+
+        let (_, _, caps, ecaps) = devices[2].parse().unwrap();
+        assert_eq!((0, 0), (caps.count(), ecaps.count()));
+    }
+
+    #[test]
+    fn device() {
+        let fake = FakeSysfs::new("device");
+        fake.add("0000:00:1f.3", DATA_9DC8, None);
+        let sysfs = Sysfs::with_root(fake.devices());
+        let device = sysfs.device("00:1f.3".parse().unwrap()).unwrap();
+        assert_eq!(0x9dc8, device.header().unwrap().device_id);
+        let result = sysfs.device("00:1f.4".parse().unwrap());
+        assert_eq!(io::ErrorKind::NotFound, result.unwrap_err().kind());
+    }
+
+    #[test]
+    fn cardbus() {
+        let fake = FakeSysfs::new("cardbus");
+        let mut config = DATA_9DC8.to_vec();
+        config[HEADER_TYPE_OFFSET] = 0x02;
+        fake.add("0000:03:00.0", &config, None);
+        fake.add("0000:03:00.1", &config[..0x44], None);
+        let sysfs = Sysfs::with_root(fake.devices());
+        let device = sysfs.device("03:00.0".parse().unwrap()).unwrap();
+        let header = device.header().unwrap();
+        assert!(matches!(header.header_type, crate::header::HeaderType::Cardbus(_)));
+        assert_eq!(Header::try_from(&config[..0x48]), Ok(header));
+        let device = sysfs.device("03:00.1".parse().unwrap()).unwrap();
+        assert_eq!(Err(Error::Truncated { offset: 0 }), device.header());
+    }
+
+    #[test]
+    fn resource() {
+        assert!("0x1 0x2".parse::<Resource>().is_err());
+        assert!("0x1 0x2 0xz".parse::<Resource>().is_err());
+        assert!("0x2 0x1 0x0".parse::<Resource>().is_err());
+        assert_eq!(0, Resource { start: 0x2, end: 0x1, flags: 0 }.size());
+        assert_eq!(u64::MAX, Resource { start: 0, end: u64::MAX, flags: 0 }.size());
+    }
+}
@@ -0,0 +1,44 @@
+//! Configuration spaces shared by tests
+//!
+//! Real devices dumps are taken from `tests/data/device/*/config`, arbitrary contents come from a
+//! deterministic pseudo-random generator so failures are reproducible.
+
+use std::prelude::v1::*;
+use std::{fs, path::Path};
+
+
+/// Configuration spaces of all devices from `tests/data/device`, paired with the device directory
+pub fn device_configs() -> Vec<(String, Vec<u8>)> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data/device");
+    let mut result = fs::read_dir(root).unwrap()
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            (name, fs::read(path.join("config")).unwrap())
+        })
+        .collect::<Vec<_>>();
+    result.sort();
+    assert!(!result.is_empty(), "no device configuration spaces");
+    result
+}
+
+/// Xorshift pseudo-random generator
+#[derive(Debug, Clone)]
+pub struct Random(u64);
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Self(seed | 1)
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+    pub fn fill(&mut self, buf: &mut [u8]) {
+        for chunk in buf.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
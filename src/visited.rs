@@ -0,0 +1,92 @@
+//! Bookkeeping of linked capability list walks
+//!
+//! Next pointers are read from configuration space which may come from an untrusted source, so
+//! neither termination nor sanity of the list can be taken for granted.
+
+use super::Error;
+
+
+/// Structures already visited while walking a list
+///
+/// Configuration space is split into units of `1 << SHIFT` bytes, `N` 64-bit words cover the
+/// whole space the list resides in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Visited<const N: usize, const SHIFT: u32> {
+    /// Units holding start of a visited structure
+    starts: [u64; N],
+    /// Units occupied by visited structures
+    claimed: [u64; N],
+    /// Number of structures that still may be visited
+    remaining: u16,
+}
+impl<const N: usize, const SHIFT: u32> Visited<N, SHIFT> {
+    /// At most `limit` structures will be visited
+    pub const fn new(limit: u16) -> Self {
+        Self { starts: [0; N], claimed: [0; N], remaining: limit }
+    }
+    /// Checks structure at `pointer` referenced by register at `offset` may be visited
+    pub fn enter(&mut self, offset: u16, pointer: u16) -> Result<(), Error> {
+        let unit = usize::from(pointer) >> SHIFT;
+        if Self::get(&self.starts, unit) {
+            return Err(Error::PointerLoop { offset, pointer });
+        }
+        if Self::get(&self.claimed, unit) {
+            return Err(Error::Overlap { offset: pointer });
+        }
+        self.remaining = self.remaining.checked_sub(1)
+            .ok_or(Error::IterationLimit { offset: pointer })?;
+        Ok(())
+    }
+    /// Claims `len` bytes of structure at `pointer`
+    pub fn claim(&mut self, pointer: u16, len: usize) -> Result<(), Error> {
+        let start = usize::from(pointer);
+        let units = (start >> SHIFT)..=((start + len.max(1) - 1) >> SHIFT);
+        if units.clone().any(|unit| Self::get(&self.claimed, unit)) {
+            return Err(Error::Overlap { offset: pointer });
+        }
+        units.for_each(|unit| Self::set(&mut self.claimed, unit));
+        Self::set(&mut self.starts, start >> SHIFT);
+        Ok(())
+    }
+    /// Units beyond the covered space are treated as free
+    fn get(bits: &[u64; N], unit: usize) -> bool {
+        bits.get(unit / 64).is_some_and(|word| word & (1 << (unit % 64)) != 0)
+    }
+    fn set(bits: &mut [u64; N], unit: usize) {
+        if let Some(word) = bits.get_mut(unit / 64) {
+            *word |= 1 << (unit % 64);
+        }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use super::*;
+
+    #[test]
+    fn bytes() {
+        let mut visited = Visited::<4, 0>::new(3);
+        assert_eq!(Ok(()), visited.enter(0x34, 0x40));
+        assert_eq!(Ok(()), visited.claim(0x40, 8));
+        assert_eq!(Err(Error::PointerLoop { offset: 0x41, pointer: 0x40 }), visited.enter(0x41, 0x40));
+        assert_eq!(Err(Error::Overlap { offset: 0x44 }), visited.enter(0x41, 0x44));
+        assert_eq!(Ok(()), visited.enter(0x41, 0x48));
+        assert_eq!(Err(Error::Overlap { offset: 0x3e }), visited.claim(0x3e, 4));
+        assert_eq!(Ok(()), visited.enter(0x41, 0x50));
+        assert_eq!(Err(Error::IterationLimit { offset: 0x60 }), visited.enter(0x51, 0x60));
+    }
+
+    #[test]
+    fn dwords() {
+        let mut visited = Visited::<16, 2>::new(480);
+        assert_eq!(Ok(()), visited.claim(0x100, 4));
+        assert_eq!(Ok(()), visited.claim(0x104, 0x0c));
+        assert_eq!(Err(Error::PointerLoop { offset: 0x104, pointer: 0x104 }), visited.enter(0x104, 0x104));
+        assert_eq!(Err(Error::Overlap { offset: 0x10c }), visited.enter(0x104, 0x10c));
+        assert_eq!(Ok(()), visited.enter(0x104, 0x110));
+        assert_eq!(Ok(()), visited.claim(0xffc, 4));
+    }
+}